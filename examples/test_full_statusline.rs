@@ -1,37 +1,52 @@
-use ccometixline::config::{Config, InputData, Model, Workspace};
-use ccometixline::core::StatusLineGenerator;
+use ccometixline::config::{Config, InputData, Model, SegmentId, Workspace};
+use ccometixline::core::{collect_all_segments, StatusLineGenerator};
+
+/// Build a config from the default theme with only the given segments enabled
+fn config_with(enabled: &[SegmentId]) -> Config {
+    let mut config = Config::default();
+    for segment in config.segments.iter_mut() {
+        segment.enabled = enabled.contains(&segment.id);
+    }
+    config
+}
+
+fn render(config: Config, input: &InputData) -> String {
+    let segments_data = collect_all_segments(&config, input);
+    StatusLineGenerator::new(config).generate(segments_data)
+}
 
 fn main() {
     println!("Testing Full Statusline with Cost Tracking");
     println!("===========================================\n");
 
     // Create test configuration with all segments enabled
-    let config = Config {
-        segments: ccometixline::config::SegmentsConfig {
-            model: true,
-            directory: true,
-            git: true,
-            usage: true,
-            cost: true,
-            burn_rate: true,
-        },
-        theme: "nerdfonts".to_string(),
-    };
+    let config = config_with(&[
+        SegmentId::Model,
+        SegmentId::Directory,
+        SegmentId::Git,
+        SegmentId::Usage,
+        SegmentId::Cost,
+        SegmentId::BurnRate,
+    ]);
 
     // Create test input data
     let input = InputData {
         model: Model {
+            id: String::new(),
             display_name: "claude-3-5-sonnet-20241022".to_string(),
         },
         workspace: Workspace {
             current_dir: "/home/user/projects/test-project".to_string(),
+            ..Default::default()
         },
         transcript_path: "/home/user/.claude/projects/test/session-123.jsonl".to_string(),
+        session_id: None,
+        cost: None,
+        ..Default::default()
     };
 
     // Generate statusline
-    let generator = StatusLineGenerator::new(config.clone());
-    let statusline = generator.generate(&input);
+    let statusline = render(config, &input);
 
     println!("Generated Statusline:");
     println!("{}", statusline);
@@ -42,54 +57,21 @@ fn main() {
     println!("-----------------------------------------");
 
     // Test with only model and usage
-    let minimal_config = Config {
-        segments: ccometixline::config::SegmentsConfig {
-            model: true,
-            directory: false,
-            git: false,
-            usage: true,
-            cost: false,
-            burn_rate: false,
-        },
-        theme: "nerdfonts".to_string(),
-    };
-
-    let minimal_generator = StatusLineGenerator::new(minimal_config);
-    let minimal_statusline = minimal_generator.generate(&input);
+    let minimal_statusline = render(config_with(&[SegmentId::Model, SegmentId::Usage]), &input);
     println!("Minimal (Model + Usage): {}", minimal_statusline);
 
     // Test with cost tracking only
-    let cost_config = Config {
-        segments: ccometixline::config::SegmentsConfig {
-            model: true,
-            directory: true,
-            git: false,
-            usage: false,
-            cost: true,
-            burn_rate: false,
-        },
-        theme: "nerdfonts".to_string(),
-    };
-
-    let cost_generator = StatusLineGenerator::new(cost_config);
-    let cost_statusline = cost_generator.generate(&input);
+    let cost_statusline = render(
+        config_with(&[SegmentId::Model, SegmentId::Directory, SegmentId::Cost]),
+        &input,
+    );
     println!("Cost Tracking: {}", cost_statusline);
 
     // Test with burn rate only
-    let burn_config = Config {
-        segments: ccometixline::config::SegmentsConfig {
-            model: true,
-            directory: true,
-            git: false,
-            usage: false,
-            cost: false,
-            burn_rate: true,
-        },
-        theme: "nerdfonts".to_string(),
-    };
-
-    let burn_generator = StatusLineGenerator::new(burn_config);
-    let burn_statusline = burn_generator.generate(&input);
+    let burn_statusline = render(
+        config_with(&[SegmentId::Model, SegmentId::Directory, SegmentId::BurnRate]),
+        &input,
+    );
     println!("Burn Rate: {}", burn_statusline);
 
     // Test segment ordering
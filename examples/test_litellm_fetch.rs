@@ -135,21 +135,15 @@ async fn main() {
             // Show some pricing data for Claude models with valid pricing
             let mut shown = 0;
             for (name, pricing) in data.iter() {
-                if name.contains("claude")
-                    && pricing.input_cost_per_token.is_some()
-                    && pricing.output_cost_per_token.is_some()
-                    && shown < 3
+                if let (Some(input), Some(output)) =
+                    (pricing.input_cost_per_token, pricing.output_cost_per_token)
                 {
-                    println!("\n   Model: {}", name);
-                    println!(
-                        "   - Input: ${:.6}/token",
-                        pricing.input_cost_per_token.unwrap()
-                    );
-                    println!(
-                        "   - Output: ${:.6}/token",
-                        pricing.output_cost_per_token.unwrap()
-                    );
-                    shown += 1;
+                    if name.contains("claude") && shown < 3 {
+                        println!("\n   Model: {}", name);
+                        println!("   - Input: ${:.6}/token", input);
+                        println!("   - Output: ${:.6}/token", output);
+                        shown += 1;
+                    }
                 }
             }
         }
@@ -1,7 +1,8 @@
 use crate::billing::types::BlockStartSource;
 use crate::billing::{BillingBlock, UsageEntry};
-use crate::config::{floor_to_hour, BlockOverrideManager};
-use chrono::{DateTime, Duration, Timelike, Utc};
+use crate::config::{floor_to_hour, BlockOverrideManager, SCHEDULE_SOURCE};
+use crate::utils::timezone;
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
 use std::collections::HashMap;
 
 /// Identify 5-hour billing blocks from usage entries
@@ -27,7 +28,7 @@ pub fn identify_session_blocks(entries: &[UsageEntry]) -> Vec<BillingBlock> {
 
             // Calculate total tokens and sessions
             let mut session_ids = std::collections::HashSet::new();
-            let mut total_tokens = 0u32;
+            let mut total_tokens = 0u64;
             let mut total_cost = 0.0;
 
             for entry in &block_entries {
@@ -182,9 +183,9 @@ fn identify_dynamic_blocks(
 
         if current_block_start.is_none() {
             // First entry - check for override or start new block (floored to hour)
-            let entry_date = entry_time.date_naive();
+            let entry_date = timezone::date_of(entry_time);
             current_block_start =
-                if let Some(override_config) = override_manager.get_override(entry_date) {
+                if let Some(override_config) = override_manager.effective_override(entry_date) {
                     // Only use override if entry is within 5 hours of override time
                     let time_since_override = entry_time - override_config.start_time;
                     if time_since_override >= Duration::zero()
@@ -210,16 +211,9 @@ fn identify_dynamic_blocks(
                 || time_since_last_entry > session_duration_ms
             {
                 // Close current block
-                let entry_date = current_block_entries
-                    .first()
-                    .unwrap()
-                    .timestamp
-                    .date_naive();
-                let start_source = if override_manager.get_override(entry_date).is_some() {
-                    BlockStartSource::Manual
-                } else {
-                    BlockStartSource::Auto
-                };
+                let entry_date =
+                    timezone::date_of(current_block_entries.first().unwrap().timestamp);
+                let start_source = start_source(override_manager, entry_date);
 
                 let block = create_dynamic_block(
                     block_start,
@@ -240,22 +234,23 @@ fn identify_dynamic_blocks(
                 }
 
                 // Start new block (floored to hour or use override)
-                let entry_date = entry_time.date_naive();
-                current_block_start =
-                    if let Some(override_config) = override_manager.get_override(entry_date) {
-                        // Only use override if entry is within 5 hours of override time
-                        let time_since_override = entry_time - override_config.start_time;
-                        if time_since_override >= Duration::zero()
-                            && time_since_override < session_duration_ms
-                        {
-                            Some(override_config.start_time)
-                        } else {
-                            // Override expired, start new block from current activity
-                            Some(floor_to_hour(entry_time))
-                        }
+                let entry_date = timezone::date_of(entry_time);
+                current_block_start = if let Some(override_config) =
+                    override_manager.effective_override(entry_date)
+                {
+                    // Only use override if entry is within 5 hours of override time
+                    let time_since_override = entry_time - override_config.start_time;
+                    if time_since_override >= Duration::zero()
+                        && time_since_override < session_duration_ms
+                    {
+                        Some(override_config.start_time)
                     } else {
+                        // Override expired, start new block from current activity
                         Some(floor_to_hour(entry_time))
-                    };
+                    }
+                } else {
+                    Some(floor_to_hour(entry_time))
+                };
                 current_block_entries = vec![entry.clone()];
             } else {
                 // Add to current block
@@ -267,16 +262,8 @@ fn identify_dynamic_blocks(
     // Close the last block
     if let Some(block_start) = current_block_start {
         if !current_block_entries.is_empty() {
-            let entry_date = current_block_entries
-                .first()
-                .unwrap()
-                .timestamp
-                .date_naive();
-            let start_source = if override_manager.get_override(entry_date).is_some() {
-                BlockStartSource::Manual
-            } else {
-                BlockStartSource::Auto
-            };
+            let entry_date = timezone::date_of(current_block_entries.first().unwrap().timestamp);
+            let start_source = start_source(override_manager, entry_date);
 
             let block = create_dynamic_block(
                 block_start,
@@ -292,6 +279,17 @@ fn identify_dynamic_blocks(
     blocks
 }
 
+/// Where the start of a block beginning on `date` came from
+fn start_source(override_manager: &BlockOverrideManager, date: NaiveDate) -> BlockStartSource {
+    match override_manager.effective_override(date) {
+        Some(override_config) if override_config.source == SCHEDULE_SOURCE => {
+            BlockStartSource::Schedule
+        }
+        Some(_) => BlockStartSource::Manual,
+        None => BlockStartSource::Auto,
+    }
+}
+
 /// Create a dynamic block using ccusage approach
 fn create_dynamic_block(
     start_time: DateTime<Utc>,
@@ -324,7 +322,7 @@ fn create_dynamic_block(
 
     // Aggregate entries for this block
     let mut session_ids = std::collections::HashSet::new();
-    let mut total_tokens = 0u32;
+    let mut total_tokens = 0u64;
     let mut total_cost = 0.0;
 
     for entry in entries {
@@ -422,6 +420,8 @@ mod tests {
                 model: "test".to_string(),
                 cost: Some(1.0),
                 session_id: "session1".to_string(),
+                subagent: false,
+                project: String::new(),
             },
             UsageEntry {
                 timestamp: now - Duration::hours(1),
@@ -432,6 +432,8 @@ mod tests {
                 model: "test".to_string(),
                 cost: Some(2.0),
                 session_id: "session1".to_string(),
+                subagent: false,
+                project: String::new(),
             },
         ];
 
@@ -487,4 +489,37 @@ mod tests {
         assert_eq!(merged[0].total_tokens, 3000);
         assert_eq!(merged[0].end_time, start1 + Duration::hours(10));
     }
+
+    #[test]
+    fn test_schedule_start_source() {
+        let dir = std::env::temp_dir().join(format!("ccline-block-src-{}", std::process::id()));
+        let manager = BlockOverrideManager::with_path(dir.join("block_overrides.json"))
+            .with_schedule(vec![crate::config::BlockScheduleRule {
+                days: "weekdays".to_string(),
+                start: "09:00".to_string(),
+            }]);
+        let monday = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let start = manager.effective_override(monday).unwrap().start_time;
+        let entries = vec![UsageEntry {
+            timestamp: start + Duration::minutes(30),
+            input_tokens: 100,
+            output_tokens: 50,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            model: "test".to_string(),
+            cost: Some(1.0),
+            session_id: "session1".to_string(),
+            subagent: false,
+            project: String::new(),
+        }];
+
+        let blocks = identify_dynamic_blocks(&entries, &manager);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].start_time, start);
+        assert_eq!(blocks[0].start_time_source, BlockStartSource::Schedule);
+
+        let no_schedule = BlockOverrideManager::with_path(dir.join("block_overrides.json"));
+        let blocks = identify_dynamic_blocks(&entries, &no_schedule);
+        assert_eq!(blocks[0].start_time_source, BlockStartSource::Auto);
+    }
 }
@@ -0,0 +1,121 @@
+use crate::utils::state_file;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+const HISTORY_FILE: &str = "burn_history.json";
+
+/// Samples kept, enough for a five hour block at the sampling interval
+const MAX_SAMPLES: usize = 120;
+
+/// Renders closer together than this replace the latest sample instead of
+/// adding one, since Claude Code refreshes the statusline every few seconds
+const SAMPLE_INTERVAL_SECS: i64 = 150;
+
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One burn-rate reading taken by the BurnRate segment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BurnSample {
+    pub timestamp: DateTime<Utc>,
+    pub cost_per_hour: f64,
+    pub tokens_per_minute: f64,
+}
+
+/// Recent burn-rate readings of the active billing block
+/// (~/.claude/ccline/burn_history.json), so the segment can show a trend
+/// rather than a single value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BurnHistory {
+    /// Start of the block the samples belong to; a new block starts over
+    #[serde(default)]
+    pub block_start: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub samples: Vec<BurnSample>,
+}
+
+impl BurnHistory {
+    pub fn load() -> Self {
+        state_file::load(HISTORY_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        state_file::save(HISTORY_FILE, &serde_json::to_string(self)?)
+    }
+
+    /// Add a reading for the block starting at `block_start`. Returns
+    /// whether it started a new sample, i.e. whether the history needs saving;
+    /// otherwise it only updates the latest sample in memory
+    pub fn record(&mut self, block_start: DateTime<Utc>, sample: BurnSample) -> bool {
+        if self.block_start != Some(block_start) {
+            self.block_start = Some(block_start);
+            self.samples.clear();
+        }
+        match self.samples.last_mut() {
+            Some(last)
+                if sample.timestamp - last.timestamp < Duration::seconds(SAMPLE_INTERVAL_SECS) =>
+            {
+                // Keep the slot's original time so the interval still elapses
+                last.cost_per_hour = sample.cost_per_hour;
+                last.tokens_per_minute = sample.tokens_per_minute;
+                return false;
+            }
+            _ => self.samples.push(sample),
+        }
+        if self.samples.len() > MAX_SAMPLES {
+            self.samples.drain(..self.samples.len() - MAX_SAMPLES);
+        }
+        true
+    }
+
+    /// The last `count` samples, oldest first
+    pub fn recent(&self, count: usize) -> &[BurnSample] {
+        &self.samples[self.samples.len().saturating_sub(count)..]
+    }
+}
+
+/// Block-character sparkline of `values`, scaled from zero to the largest
+pub fn sparkline(values: &[f64]) -> String {
+    let max = values.iter().copied().fold(0.0, f64::max);
+    values
+        .iter()
+        .map(|value| {
+            if max <= 0.0 {
+                return LEVELS[0];
+            }
+            let level = (value.max(0.0) / max * (LEVELS.len() - 1) as f64).round();
+            LEVELS[level as usize]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burn_history() {
+        let start = Utc::now() - Duration::hours(1);
+        let sample = |minutes: i64, cost_per_hour: f64| BurnSample {
+            timestamp: start + Duration::minutes(minutes),
+            cost_per_hour,
+            tokens_per_minute: cost_per_hour * 1000.0,
+        };
+
+        let mut history = BurnHistory::default();
+        assert!(history.record(start, sample(0, 1.0)));
+        // Within the interval: replaces the latest reading, nothing to save
+        assert!(!history.record(start, sample(1, 2.0)));
+        assert!(history.record(start, sample(5, 4.0)));
+        assert_eq!(history.samples.len(), 2);
+        assert_eq!(history.samples[0].cost_per_hour, 2.0);
+        assert_eq!(history.recent(1)[0].cost_per_hour, 4.0);
+        assert_eq!(history.recent(10).len(), 2);
+
+        // A new block starts a new history
+        assert!(history.record(start + Duration::hours(5), sample(300, 1.0)));
+        assert_eq!(history.samples.len(), 1);
+
+        assert_eq!(sparkline(&[0.0, 2.0, 4.0, 8.0]), "▁▃▅█");
+        assert_eq!(sparkline(&[0.0, 0.0]), "▁▁");
+    }
+}
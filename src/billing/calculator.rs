@@ -1,5 +1,6 @@
 use crate::billing::{BillingBlock, BurnRate, BurnRateTrend, ModelPricing, UsageEntry};
-use chrono::{Duration, Local, Utc};
+use crate::utils::timezone;
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
 use std::collections::HashMap;
 
 /// Calculate cost for a single usage entry
@@ -36,11 +37,11 @@ pub fn calculate_daily_total(
     entries: &[UsageEntry],
     pricing_map: &HashMap<String, ModelPricing>,
 ) -> f64 {
-    let today = Local::now().date_naive();
+    let today = timezone::today();
 
     entries
         .iter()
-        .filter(|e| e.timestamp.with_timezone(&Local).date_naive() == today)
+        .filter(|e| timezone::date_of(e.timestamp) == today)
         .filter_map(|entry| {
             // Find pricing for this model
             ModelPricing::get_model_pricing(pricing_map, &entry.model)
@@ -49,6 +50,52 @@ pub fn calculate_daily_total(
         .sum()
 }
 
+/// Calculate month-to-date cost for the calendar month containing `now`,
+/// with month boundaries taken in `now`'s timezone
+pub fn calculate_monthly_total<Tz: TimeZone>(
+    entries: &[UsageEntry],
+    pricing_map: &HashMap<String, ModelPricing>,
+    now: &DateTime<Tz>,
+) -> f64 {
+    let timezone = now.timezone();
+    let month = (now.year(), now.month());
+
+    entries
+        .iter()
+        .filter(|e| {
+            let timestamp = e.timestamp.with_timezone(&timezone);
+            (timestamp.year(), timestamp.month()) == month && e.timestamp <= *now
+        })
+        .filter_map(|entry| {
+            ModelPricing::get_model_pricing(pricing_map, &entry.model)
+                .map(|pricing| calculate_entry_cost(entry, pricing))
+        })
+        .sum()
+}
+
+/// Rolling windows reported next to calendar totals, in days
+pub const ROLLING_WINDOWS_DAYS: [i64; 2] = [7, 30];
+
+/// Calculate cost over the `days` days up to `now`, a rolling window rather
+/// than calendar days
+pub fn calculate_rolling_total(
+    entries: &[UsageEntry],
+    pricing_map: &HashMap<String, ModelPricing>,
+    days: i64,
+    now: DateTime<Utc>,
+) -> f64 {
+    let start = now - Duration::days(days);
+
+    entries
+        .iter()
+        .filter(|e| e.timestamp > start && e.timestamp <= now)
+        .filter_map(|entry| {
+            ModelPricing::get_model_pricing(pricing_map, &entry.model)
+                .map(|pricing| calculate_entry_cost(entry, pricing))
+        })
+        .sum()
+}
+
 /// Calculate burn rate based on recent activity
 pub fn calculate_burn_rate(block: &BillingBlock, entries: &[UsageEntry]) -> Option<BurnRate> {
     let now = Utc::now();
@@ -83,13 +130,13 @@ pub fn calculate_burn_rate(block: &BillingBlock, entries: &[UsageEntry]) -> Opti
     }
 
     // Calculate total tokens (all types)
-    let total_tokens: u32 = recent_entries
+    let total_tokens: u64 = recent_entries
         .iter()
         .map(|e| e.input_tokens + e.output_tokens + e.cache_creation_tokens + e.cache_read_tokens)
         .sum();
 
     // Calculate tokens excluding cache (for indicator thresholds)
-    let non_cache_tokens: u32 = recent_entries
+    let non_cache_tokens: u64 = recent_entries
         .iter()
         .map(|e| e.input_tokens + e.output_tokens)
         .sum();
@@ -103,13 +150,13 @@ pub fn calculate_burn_rate(block: &BillingBlock, entries: &[UsageEntry]) -> Opti
     // Determine trend (simplified)
     let trend = if recent_entries.len() >= 2 {
         let mid_point = recent_entries.len() / 2;
-        let first_half_tokens: u32 = recent_entries[..mid_point]
+        let first_half_tokens: u64 = recent_entries[..mid_point]
             .iter()
             .map(|e| {
                 e.input_tokens + e.output_tokens + e.cache_creation_tokens + e.cache_read_tokens
             })
             .sum();
-        let second_half_tokens: u32 = recent_entries[mid_point..]
+        let second_half_tokens: u64 = recent_entries[mid_point..]
             .iter()
             .map(|e| {
                 e.input_tokens + e.output_tokens + e.cache_creation_tokens + e.cache_read_tokens
@@ -132,6 +179,7 @@ pub fn calculate_burn_rate(block: &BillingBlock, entries: &[UsageEntry]) -> Opti
         tokens_per_minute_for_indicator,
         cost_per_hour,
         trend,
+        projection: block.project(tokens_per_minute, cost_per_hour),
     })
 }
 
@@ -167,6 +215,8 @@ mod tests {
             model: "claude-3-5-sonnet".to_string(),
             cost: None,
             session_id: "test".to_string(),
+            subagent: false,
+            project: String::new(),
         };
 
         let pricing = ModelPricing {
@@ -205,6 +255,8 @@ mod tests {
                 model: "claude-3-5-sonnet".to_string(),
                 cost: None,
                 session_id: "test1".to_string(),
+                subagent: false,
+                project: String::new(),
             },
             UsageEntry {
                 timestamp: now - Duration::days(1), // Yesterday
@@ -215,6 +267,8 @@ mod tests {
                 model: "claude-3-5-sonnet".to_string(),
                 cost: None,
                 session_id: "test2".to_string(),
+                subagent: false,
+                project: String::new(),
             },
         ];
 
@@ -234,4 +288,103 @@ mod tests {
         // Only today's entry: 1000/1000 * 3.0 + 500/1000 * 15.0 = 3.0 + 7.5 = 10.5
         assert!((total - 10.5).abs() < 0.001);
     }
+
+    #[test]
+    fn test_calculate_monthly_total() {
+        let entry = |timestamp: &str| UsageEntry {
+            timestamp: timestamp.parse().unwrap(),
+            input_tokens: 1000,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            model: "claude-3-5-sonnet".to_string(),
+            cost: None,
+            session_id: "test".to_string(),
+            subagent: false,
+            project: String::new(),
+        };
+        // The first entry falls in May in UTC but in June at UTC+9
+        let entries = vec![
+            entry("2025-05-31T20:00:00Z"),
+            entry("2025-06-15T12:00:00Z"),
+            entry("2025-07-01T12:00:00Z"),
+        ];
+        let mut pricing_map = HashMap::new();
+        pricing_map.insert(
+            "claude-3-5-sonnet".to_string(),
+            ModelPricing {
+                model_name: "claude-3-5-sonnet".to_string(),
+                input_cost_per_1k: 1.0,
+                output_cost_per_1k: 0.0,
+                cache_creation_cost_per_1k: 0.0,
+                cache_read_cost_per_1k: 0.0,
+            },
+        );
+
+        let now: DateTime<Utc> = "2025-06-20T00:00:00Z".parse().unwrap();
+        assert_eq!(calculate_monthly_total(&entries, &pricing_map, &now), 1.0);
+        let tokyo = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        let now = now.with_timezone(&tokyo);
+        assert_eq!(calculate_monthly_total(&entries, &pricing_map, &now), 2.0);
+    }
+
+    #[test]
+    fn test_calculate_rolling_total() {
+        let now = Utc::now();
+        let entry = |hours_ago: i64| UsageEntry {
+            timestamp: now - Duration::hours(hours_ago),
+            input_tokens: 1000,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            model: "claude-3-5-sonnet".to_string(),
+            cost: None,
+            session_id: "test".to_string(),
+            subagent: false,
+            project: String::new(),
+        };
+        let entries = vec![entry(1), entry(6 * 24 + 12), entry(29 * 24), entry(31 * 24)];
+        let mut pricing_map = HashMap::new();
+        pricing_map.insert(
+            "claude-3-5-sonnet".to_string(),
+            ModelPricing {
+                model_name: "claude-3-5-sonnet".to_string(),
+                input_cost_per_1k: 1.0,
+                output_cost_per_1k: 0.0,
+                cache_creation_cost_per_1k: 0.0,
+                cache_read_cost_per_1k: 0.0,
+            },
+        );
+
+        assert_eq!(calculate_rolling_total(&entries, &pricing_map, 1, now), 1.0);
+        assert_eq!(calculate_rolling_total(&entries, &pricing_map, 7, now), 2.0);
+        assert_eq!(
+            calculate_rolling_total(&entries, &pricing_map, 30, now),
+            3.0
+        );
+    }
+
+    #[test]
+    fn test_block_projection() {
+        let start = Utc::now() - Duration::hours(4);
+        let block = BillingBlock {
+            start_time: start,
+            end_time: start + Duration::hours(5),
+            cost: 2.0,
+            remaining_minutes: 60,
+            is_active: true,
+            session_count: 1,
+            total_tokens: 10_000,
+            start_time_source: crate::billing::types::BlockStartSource::Auto,
+            is_gap: false,
+        };
+
+        let projection = block.project(100.0, 1.5);
+        assert_eq!(projection.total_tokens, 16_000);
+        assert!((projection.total_cost - 3.5).abs() < 0.001);
+
+        // A block without priced tokens yields a NaN hourly cost
+        let projection = block.project(100.0, f64::NAN);
+        assert!((projection.total_cost - 2.0).abs() < 0.001);
+    }
 }
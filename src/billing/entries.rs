@@ -0,0 +1,55 @@
+use crate::billing::{ModelPricing, UsageEntry};
+use crate::config::SubagentMode;
+use crate::utils::data_loader::other_tool_entries;
+use crate::utils::{DataLoader, FastDataLoader};
+use std::collections::HashMap;
+
+/// Load usage entries from all Claude project directories and attach calculated costs
+///
+/// `include_other_tools` adds the usage of other enabled tools (Codex CLI,
+/// Gemini CLI) for spend reports; leave it off wherever entries are grouped
+/// into Claude's billing blocks.
+pub fn load_priced_entries(
+    use_fast_loader: bool,
+    thread_multiplier: Option<f64>,
+    include_other_tools: bool,
+) -> (Vec<UsageEntry>, HashMap<String, ModelPricing>) {
+    let mut entries = if use_fast_loader {
+        let mut loader = match thread_multiplier {
+            Some(multiplier) => FastDataLoader::with_thread_multiplier(multiplier),
+            None => FastDataLoader::new(),
+        };
+        loader.load_all_projects()
+    } else {
+        DataLoader::new().load_all_projects()
+    };
+    if include_other_tools {
+        entries.extend(other_tool_entries(None, None));
+        entries.sort_by_key(|entry| entry.timestamp);
+    }
+
+    let pricing_map = price_entries(&mut entries);
+    (entries, pricing_map)
+}
+
+/// Attach calculated costs to `entries`, returning the pricing used
+pub fn price_entries(entries: &mut [UsageEntry]) -> HashMap<String, ModelPricing> {
+    let pricing_map =
+        crate::utils::block_on(async { ModelPricing::get_pricing_with_fallback().await });
+
+    for entry in entries {
+        if let Some(pricing) = ModelPricing::get_model_pricing(&pricing_map, &entry.model) {
+            entry.cost = Some(pricing.calculate_cost(entry));
+        }
+    }
+
+    pricing_map
+}
+
+/// Drop subagent usage when `global.subagents` excludes it; the other modes
+/// keep every entry and differ only in how costs are presented
+pub fn apply_subagent_mode(entries: &mut Vec<UsageEntry>, mode: SubagentMode) {
+    if mode == SubagentMode::Exclude {
+        entries.retain(|entry| !entry.subagent);
+    }
+}
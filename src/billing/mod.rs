@@ -1,9 +1,13 @@
 pub mod block;
+pub mod burn_history;
 pub mod calculator;
+pub mod entries;
+pub mod price_changes;
 pub mod pricing;
 pub mod types;
+pub mod weekly;
 
 pub use types::{
-    BillingBlock, BurnRate, BurnRateThresholds, BurnRateTrend, ModelPricing, SessionUsage,
-    UsageEntry,
+    BillingBlock, BlockProjection, BurnRate, BurnRateThresholds, BurnRateTrend, ModelPricing,
+    SessionUsage, UsageEntry,
 };
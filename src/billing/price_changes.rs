@@ -0,0 +1,175 @@
+use super::ModelPricing;
+use crate::utils::state_file;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Refreshes with changes kept in the log, oldest dropped first
+const MAX_EVENTS: usize = 20;
+const LOG_FILE: &str = "price_changes.json";
+
+/// How long the Cost segment flags a price change
+pub const RECENT_HOURS: i64 = 24;
+
+/// One price of one model that differs between two pricing tables
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceChange {
+    pub model: String,
+    /// `input`, `output`, `cache_write` or `cache_read`
+    pub price: String,
+    /// USD per million tokens
+    pub old_per_m: f64,
+    pub new_per_m: f64,
+}
+
+impl PriceChange {
+    /// Relative change in percent, e.g. `25.0` for a 25% hike
+    pub fn percent(&self) -> Option<f64> {
+        (self.old_per_m > 0.0).then(|| (self.new_per_m / self.old_per_m - 1.0) * 100.0)
+    }
+}
+
+/// Price changes found by one pricing refresh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceChangeEvent {
+    pub detected_at: DateTime<Utc>,
+    pub changes: Vec<PriceChange>,
+}
+
+/// Price changes seen when refreshing pricing data
+/// (~/.claude/ccline/price_changes.json)
+///
+/// Costs are recalculated with the current prices, so without this a price
+/// change would silently shift every cost figure.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PriceChangeLog {
+    #[serde(default)]
+    pub events: Vec<PriceChangeEvent>,
+}
+
+impl PriceChangeLog {
+    pub fn load() -> Self {
+        state_file::load(LOG_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        state_file::save(LOG_FILE, &serde_json::to_string_pretty(self)?)
+    }
+
+    /// Append the changes found by a refresh
+    pub fn record(&mut self, changes: Vec<PriceChange>, detected_at: DateTime<Utc>) {
+        if changes.is_empty() {
+            return;
+        }
+        self.events.push(PriceChangeEvent {
+            detected_at,
+            changes,
+        });
+        let excess = self.events.len().saturating_sub(MAX_EVENTS);
+        self.events.drain(..excess);
+    }
+
+    /// Changes detected within the last `hours`
+    pub fn recent(&self, hours: i64) -> Vec<&PriceChange> {
+        let since = Utc::now() - Duration::hours(hours);
+        self.events
+            .iter()
+            .filter(|event| event.detected_at >= since)
+            .flat_map(|event| &event.changes)
+            .collect()
+    }
+}
+
+/// Prices that differ between the previous and the refreshed table, for models
+/// in both; models that were added or dropped are not price changes
+pub fn diff_pricing(
+    old: &HashMap<String, ModelPricing>,
+    new: &HashMap<String, ModelPricing>,
+) -> Vec<PriceChange> {
+    let mut changes = Vec::new();
+    for (model, new_pricing) in new {
+        let Some(old_pricing) = old.get(model) else {
+            continue;
+        };
+        for (price, old_per_1k, new_per_1k) in [
+            (
+                "input",
+                old_pricing.input_cost_per_1k,
+                new_pricing.input_cost_per_1k,
+            ),
+            (
+                "output",
+                old_pricing.output_cost_per_1k,
+                new_pricing.output_cost_per_1k,
+            ),
+            (
+                "cache_write",
+                old_pricing.cache_creation_cost_per_1k,
+                new_pricing.cache_creation_cost_per_1k,
+            ),
+            (
+                "cache_read",
+                old_pricing.cache_read_cost_per_1k,
+                new_pricing.cache_read_cost_per_1k,
+            ),
+        ] {
+            // Ignore float noise from the per-token to per-1k conversion
+            if (old_per_1k - new_per_1k).abs() > 1e-9 {
+                changes.push(PriceChange {
+                    model: model.clone(),
+                    price: price.to_string(),
+                    old_per_m: old_per_1k * 1000.0,
+                    new_per_m: new_per_1k * 1000.0,
+                });
+            }
+        }
+    }
+    changes.sort_by(|a, b| a.model.cmp(&b.model).then(a.price.cmp(&b.price)));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pricing(model: &str, input: f64, output: f64) -> (String, ModelPricing) {
+        (
+            model.to_string(),
+            ModelPricing {
+                model_name: model.to_string(),
+                input_cost_per_1k: input,
+                output_cost_per_1k: output,
+                cache_creation_cost_per_1k: 0.00375,
+                cache_read_cost_per_1k: 0.0003,
+            },
+        )
+    }
+
+    #[test]
+    fn test_diff_pricing() {
+        let old = HashMap::from([
+            pricing("claude-sonnet-4", 0.003, 0.015),
+            pricing("claude-opus-4", 0.015, 0.075),
+            pricing("claude-2", 0.008, 0.024),
+        ]);
+        let new = HashMap::from([
+            pricing("claude-sonnet-4", 0.003, 0.015),
+            pricing("claude-opus-4", 0.005, 0.075),
+            pricing("claude-haiku-4", 0.001, 0.005),
+        ]);
+
+        let changes = diff_pricing(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].model, "claude-opus-4");
+        assert_eq!(changes[0].price, "input");
+        assert!((changes[0].old_per_m - 15.0).abs() < 1e-9);
+        assert!((changes[0].percent().unwrap() + 66.67).abs() < 0.01);
+
+        let mut log = PriceChangeLog::default();
+        log.record(changes, Utc::now() - Duration::hours(30));
+        log.record(Vec::new(), Utc::now());
+        assert_eq!(log.events.len(), 1);
+        assert!(log.recent(RECENT_HOURS).is_empty());
+        assert_eq!(log.recent(48).len(), 1);
+    }
+}
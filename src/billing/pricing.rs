@@ -6,10 +6,12 @@ use std::fs;
 use std::path::PathBuf;
 use std::sync::RwLock;
 
+use super::price_changes::{diff_pricing, PriceChangeLog};
 use super::ModelPricing;
+use crate::config::PricingConfig;
 
 /// LiteLLM's model pricing and context window data URL
-const LITELLM_PRICING_URL: &str =
+pub const LITELLM_PRICING_URL: &str =
     "https://raw.githubusercontent.com/BerriAI/litellm/main/model_prices_and_context_window.json";
 
 /// Memory cache TTL in seconds (5 minutes)
@@ -18,9 +20,13 @@ const MEMORY_CACHE_TTL_SECONDS: i64 = 300;
 /// File cache TTL in seconds (24 hours)
 const FILE_CACHE_TTL_SECONDS: i64 = 86400;
 
+/// File cache format version; version 1 only held Claude models and version 2
+/// lacked context windows
+const FILE_CACHE_VERSION: u32 = 3;
+
 /// Pricing cache file path
-fn get_cache_file_path() -> PathBuf {
-    dirs::home_dir()
+pub fn get_cache_file_path() -> PathBuf {
+    crate::utils::home_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join(".claude")
         .join("ccline")
@@ -43,9 +49,21 @@ impl CachedPricing {
 /// File cache structure with metadata
 #[derive(Debug, Serialize, Deserialize)]
 struct FileCachePricing {
+    #[serde(default)]
+    version: u32,
     fetched_at: DateTime<Utc>,
     ttl_hours: u32,
+    /// Validators from the response, used for conditional refreshes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
     data: HashMap<String, ModelPricing>,
+    /// LiteLLM `max_input_tokens` per model
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    context_windows: HashMap<String, u32>,
 }
 
 impl FileCachePricing {
@@ -54,7 +72,8 @@ impl FileCachePricing {
         age.num_seconds() > FILE_CACHE_TTL_SECONDS
     }
 
-    /// Load pricing data from file cache
+    /// Load pricing data from file cache, including expired data that can still be
+    /// revalidated with a conditional request
     fn load_from_file() -> Option<Self> {
         let cache_path = get_cache_file_path();
         if !cache_path.exists() {
@@ -64,7 +83,7 @@ impl FileCachePricing {
         let content = fs::read_to_string(&cache_path).ok()?;
         let cache: FileCachePricing = serde_json::from_str(&content).ok()?;
 
-        if cache.is_expired() {
+        if cache.version != FILE_CACHE_VERSION {
             return None;
         }
 
@@ -87,9 +106,91 @@ impl FileCachePricing {
     }
 }
 
+/// Log prices that differ from the previously cached table so cost figures
+/// don't shift silently
+fn record_price_changes(
+    old: &HashMap<String, ModelPricing>,
+    new: &HashMap<String, ModelPricing>,
+    now: DateTime<Utc>,
+) {
+    let changes = diff_pricing(old, new);
+    if changes.is_empty() {
+        return;
+    }
+    crate::debug_println!(
+        "LiteLLM: {} prices changed since the last refresh",
+        changes.len()
+    );
+    let mut log = PriceChangeLog::load();
+    log.record(changes, now);
+    if let Err(e) = log.save() {
+        crate::debug_println!("Failed to save price changes: {}", e);
+    }
+}
+
 /// Pricing data cache with TTL
 static PRICING_CACHE: Lazy<RwLock<Option<CachedPricing>>> = Lazy::new(|| RwLock::new(None));
 
+/// Pricing source settings, set from the loaded config
+static PRICING_CONFIG: Lazy<RwLock<PricingConfig>> =
+    Lazy::new(|| RwLock::new(PricingConfig::default()));
+
+/// Use the pricing source settings from the user's config for subsequent fetches
+pub fn set_pricing_config(config: &PricingConfig) {
+    *PRICING_CONFIG.write().unwrap() = config.clone();
+}
+
+/// Why the last pricing lookup could not use current LiteLLM data
+#[derive(Debug, Clone, PartialEq)]
+pub enum PricingFallback {
+    /// Prices from the expired file cache, fetched at this time
+    StaleCache {
+        fetched_at: DateTime<Utc>,
+        error: String,
+    },
+    /// The built-in table, since there was no cached copy either
+    BuiltIn { error: String },
+}
+
+impl PricingFallback {
+    /// Short name for metadata
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PricingFallback::StaleCache { .. } => "stale_cache",
+            PricingFallback::BuiltIn { .. } => "built_in",
+        }
+    }
+
+    /// Why fetching current prices failed
+    pub fn error(&self) -> &str {
+        match self {
+            PricingFallback::StaleCache { error, .. } | PricingFallback::BuiltIn { error } => error,
+        }
+    }
+}
+
+/// Set when the last `get_pricing_with_fallback` in this process fell back
+static PRICING_FALLBACK: Lazy<RwLock<Option<PricingFallback>>> = Lazy::new(|| RwLock::new(None));
+
+/// How the last pricing lookup in this process fell back, or `None` if it got
+/// current prices
+pub fn pricing_fallback() -> Option<PricingFallback> {
+    PRICING_FALLBACK.read().unwrap().clone()
+}
+
+/// Result of downloading pricing data
+#[derive(Debug)]
+enum Download {
+    Fetched {
+        data: HashMap<String, LiteLLMPricing>,
+        url: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+    /// The server confirmed the cached copy is still current (HTTP 304)
+    NotModified,
+}
+
 /// LiteLLM data format
 #[derive(Debug, Clone, Deserialize)]
 pub struct LiteLLMPricing {
@@ -100,6 +201,104 @@ pub struct LiteLLMPricing {
     pub cache_creation_input_token_cost: Option<f64>,
     #[serde(default)]
     pub cache_read_input_token_cost: Option<f64>,
+    /// Usually a number, but free text in LiteLLM's `sample_spec` entry
+    #[serde(default)]
+    pub max_input_tokens: Option<serde_json::Value>,
+}
+
+/// Context windows from the pricing file cache, read once per process
+static CONTEXT_WINDOWS: Lazy<HashMap<String, u32>> = Lazy::new(|| {
+    FileCachePricing::load_from_file()
+        .map(|cache| cache.context_windows)
+        .unwrap_or_default()
+});
+
+/// Context window LiteLLM reports for a model, from the local pricing cache only
+/// (never triggers a download)
+pub fn cached_context_window(model_id: &str) -> Option<u32> {
+    match_model_key(&CONTEXT_WINDOWS, model_id).copied()
+}
+
+/// Extract each model's `max_input_tokens` from LiteLLM data
+fn context_windows_from_litellm(data: &HashMap<String, LiteLLMPricing>) -> HashMap<String, u32> {
+    data.iter()
+        .filter_map(|(model, entry)| {
+            let tokens = entry.max_input_tokens.as_ref()?.as_u64()?;
+            Some((model.clone(), u32::try_from(tokens).ok()?))
+        })
+        .filter(|(_, tokens)| *tokens > 0)
+        .collect()
+}
+
+/// Model id without provider or region prefixes and with Vertex's `@`
+/// snapshot separator replaced, e.g. `claude-sonnet-4-20250514` for
+/// `us.anthropic.claude-sonnet-4@20250514`
+fn normalize_model_id(name: &str) -> String {
+    let name = name.rsplit('/').next().unwrap_or(name).to_lowercase();
+    let family_end = name.find('-').unwrap_or(name.len());
+    let start = name[..family_end].rfind('.').map_or(0, |dot| dot + 1);
+    name[start..].replace('@', "-")
+}
+
+/// Look up a model in a map keyed by LiteLLM model names:
+/// 1. exact key
+/// 2. key with the same normalized id, shortest key first
+/// 3. the longest known id the requested one extends with a `-`/`:` suffix
+///    (a snapshot date or version), so `gpt-4o-mini-2024-07-18` finds
+///    `gpt-4o-mini` but `gpt-4o` never falls back to `gpt-4`
+/// 4. a dated snapshot of the requested id, e.g. `claude-sonnet-4-20250514`
+///    for `claude-sonnet-4`, shortest key first
+fn match_model_key<'a, V>(map: &'a HashMap<String, V>, model_name: &str) -> Option<&'a V> {
+    // Try exact match first
+    if let Some(value) = map.get(model_name) {
+        return Some(value);
+    }
+
+    let requested = normalize_model_id(model_name);
+    let known = || {
+        map.iter()
+            .map(|(key, value)| (key, normalize_model_id(key), value))
+            .filter(|(_, id, _)| !id.is_empty())
+    };
+
+    // Provider-prefixed keys, e.g. `deepseek/deepseek-chat` for `deepseek-chat`.
+    // Prefer the shortest key so direct providers win over aggregators.
+    if let Some((_, _, value)) = known()
+        .filter(|(_, id, _)| *id == requested)
+        .min_by_key(|(key, _, _)| (key.len(), key.as_str()))
+    {
+        return Some(value);
+    }
+
+    // A known id followed by a snapshot or version suffix, preferring the
+    // longest (most specific) one
+    if let Some((_, _, value)) = known()
+        .filter(|(_, id, _)| {
+            requested
+                .strip_prefix(id.as_str())
+                .is_some_and(|rest| rest.starts_with(['-', ':']))
+        })
+        .max_by_key(|(key, id, _)| {
+            (
+                id.len(),
+                std::cmp::Reverse(key.len()),
+                std::cmp::Reverse(key.as_str()),
+            )
+        })
+    {
+        return Some(value);
+    }
+
+    // Finally a dated (YYYYMMDD) snapshot of the requested alias; a version
+    // suffix such as `-5` would be a different model
+    known()
+        .filter(|(_, id, _)| {
+            id.strip_prefix(requested.as_str())
+                .and_then(|rest| rest.strip_prefix('-'))
+                .is_some_and(|date| date.len() == 8 && date.chars().all(|c| c.is_ascii_digit()))
+        })
+        .min_by_key(|(key, _, _)| (key.len(), key.as_str()))
+        .map(|(_, _, value)| value)
 }
 
 impl ModelPricing {
@@ -109,14 +308,26 @@ impl ModelPricing {
         // Tier 1: Check memory cache first
         if let Some(cached) = PRICING_CACHE.read().unwrap().as_ref() {
             if !cached.is_expired() {
+                crate::trace_step!(
+                    "Pricing: {} models from memory cache (fetched {})",
+                    cached.data.len(),
+                    cached.fetched_at.to_rfc3339()
+                );
                 return Ok(cached.data.clone());
             }
         }
 
         // Tier 2: Check file cache
-        if let Some(file_cache) = FileCachePricing::load_from_file() {
+        let file_cache = FileCachePricing::load_from_file();
+        if let Some(file_cache) = file_cache.as_ref().filter(|c| !c.is_expired()) {
             // Update memory cache from file
             let pricing = file_cache.data.clone();
+            crate::trace_step!(
+                "Pricing: {} models from {} (fetched {})",
+                pricing.len(),
+                get_cache_file_path().display(),
+                file_cache.fetched_at.to_rfc3339()
+            );
             *PRICING_CACHE.write().unwrap() = Some(CachedPricing {
                 data: pricing.clone(),
                 fetched_at: file_cache.fetched_at,
@@ -125,65 +336,67 @@ impl ModelPricing {
         }
 
         // Tier 3: Fetch from network
-        let response = reqwest::get(LITELLM_PRICING_URL).await?;
-        let data: HashMap<String, LiteLLMPricing> = response.json().await?;
-
-        // Convert to internal format, only keep Claude models with valid pricing
-        let mut pricing = HashMap::new();
-        let mut total_models = 0;
-        let mut claude_models = 0;
-        let mut valid_claude_models = 0;
+        let config = PRICING_CONFIG.read().unwrap().clone();
+        let now = Utc::now();
 
-        for (model_name, litellm_pricing) in data {
-            total_models += 1;
-
-            // Check if it's a Claude model
-            if model_name.starts_with("claude-") || model_name.contains("claude") {
-                claude_models += 1;
-
-                // Only process models with valid token pricing (skip image generation models etc.)
-                if let (Some(input_cost), Some(output_cost)) = (
-                    litellm_pricing.input_cost_per_token,
-                    litellm_pricing.output_cost_per_token,
-                ) {
-                    valid_claude_models += 1;
-                    pricing.insert(
-                        model_name.clone(),
-                        ModelPricing {
-                            model_name,
-                            // Convert to cost per 1k tokens
-                            input_cost_per_1k: input_cost * 1000.0,
-                            output_cost_per_1k: output_cost * 1000.0,
-                            cache_creation_cost_per_1k: litellm_pricing
-                                .cache_creation_input_token_cost
-                                .map(|c| c * 1000.0)
-                                .unwrap_or(0.0),
-                            cache_read_cost_per_1k: litellm_pricing
-                                .cache_read_input_token_cost
-                                .map(|c| c * 1000.0)
-                                .unwrap_or(0.0),
-                        },
-                    );
+        let file_cache = match (
+            Self::download(&config, file_cache.as_ref()).await?,
+            file_cache,
+        ) {
+            // Unchanged upstream: keep the cached data and restart its TTL
+            (Download::NotModified, Some(mut stale)) => {
+                if *crate::utils::debug::DEBUG_MODE {
+                    eprintln!("LiteLLM: Pricing data not modified, reusing cache");
                 }
+                stale.fetched_at = now;
+                stale
             }
-        }
-
-        // Only show debug info if DEBUG_MODE is set
-        if *crate::utils::debug::DEBUG_MODE {
-            eprintln!(
-                "LiteLLM: Fetched {} total models, {} Claude models, {} with valid pricing",
-                total_models, claude_models, valid_claude_models
-            );
-        }
+            (Download::NotModified, None) => {
+                return Err("Pricing server returned 304 without a cached copy".into())
+            }
+            (
+                Download::Fetched {
+                    data,
+                    url,
+                    etag,
+                    last_modified,
+                },
+                stale,
+            ) => {
+                let total_models = data.len();
+                let context_windows = context_windows_from_litellm(&data);
+                let pricing = Self::from_litellm(data);
+                if let Some(stale) = &stale {
+                    record_price_changes(&stale.data, &pricing, now);
+                }
 
-        let now = Utc::now();
+                // Only show debug info if DEBUG_MODE is set
+                if *crate::utils::debug::DEBUG_MODE {
+                    eprintln!(
+                        "LiteLLM: Fetched {} total models, {} with valid pricing",
+                        total_models,
+                        pricing.len()
+                    );
+                }
 
-        // Save to file cache
-        let file_cache = FileCachePricing {
-            fetched_at: now,
-            ttl_hours: 24,
-            data: pricing.clone(),
+                FileCachePricing {
+                    version: FILE_CACHE_VERSION,
+                    fetched_at: now,
+                    ttl_hours: 24,
+                    source_url: Some(url),
+                    etag,
+                    last_modified,
+                    data: pricing,
+                    context_windows,
+                }
+            }
         };
+        let pricing = file_cache.data.clone();
+        crate::trace_step!(
+            "Pricing: {} models from {}",
+            pricing.len(),
+            file_cache.source_url.as_deref().unwrap_or("the network")
+        );
 
         if let Err(e) = file_cache.save_to_file() {
             eprintln!("Warning: Failed to save pricing cache to file: {}", e);
@@ -198,16 +411,136 @@ impl ModelPricing {
         Ok(pricing)
     }
 
-    /// Get pricing with fallback
-    pub async fn get_pricing_with_fallback() -> HashMap<String, ModelPricing> {
-        match Self::fetch_pricing().await {
-            Ok(pricing) => pricing,
-            Err(e) => {
-                eprintln!("Failed to fetch pricing from LiteLLM: {}", e);
-                eprintln!("Using fallback pricing data");
-                Self::fallback_pricing()
+    /// Download LiteLLM data, trying the configured URL and then each mirror in order.
+    /// When `stale` came from the same URL its validators are sent so an unchanged
+    /// file is not downloaded again.
+    async fn download(
+        config: &PricingConfig,
+        stale: Option<&FileCachePricing>,
+    ) -> Result<Download, Box<dyn std::error::Error>> {
+        // reqwest honors HTTPS_PROXY/HTTP_PROXY/NO_PROXY unless a proxy is set explicitly
+        let mut builder =
+            reqwest::Client::builder().timeout(std::time::Duration::from_secs(config.timeout_secs));
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        let client = builder.build()?;
+
+        let mut errors = Vec::new();
+        for url in config.urls() {
+            let mut request = client.get(url);
+            if let Some(stale) = stale.filter(|c| c.source_url.as_deref() == Some(url)) {
+                if let Some(etag) = &stale.etag {
+                    request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &stale.last_modified {
+                    request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+
+            let result = async {
+                let response = request.send().await?;
+                if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+                    return Ok(Download::NotModified);
+                }
+
+                let response = response.error_for_status()?;
+                let header = |name| {
+                    response
+                        .headers()
+                        .get(name)
+                        .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+                        .map(String::from)
+                };
+                let etag = header(reqwest::header::ETAG);
+                let last_modified = header(reqwest::header::LAST_MODIFIED);
+
+                Ok::<_, reqwest::Error>(Download::Fetched {
+                    data: response.json().await?,
+                    url: url.to_string(),
+                    etag,
+                    last_modified,
+                })
+            }
+            .await;
+
+            match result {
+                Ok(data) => return Ok(data),
+                Err(e) => errors.push(format!("{}: {}", url, e)),
             }
         }
+
+        Err(format!("all pricing sources failed ({})", errors.join("; ")).into())
+    }
+
+    /// Convert LiteLLM data to the internal format, keeping every provider's models
+    /// that have token pricing (Claude Code may be routed to non-Claude models via proxies)
+    fn from_litellm(data: HashMap<String, LiteLLMPricing>) -> HashMap<String, ModelPricing> {
+        let mut pricing = HashMap::new();
+
+        for (model_name, litellm_pricing) in data {
+            // Only process models with valid token pricing (skip image generation models etc.)
+            if let (Some(input_cost), Some(output_cost)) = (
+                litellm_pricing.input_cost_per_token,
+                litellm_pricing.output_cost_per_token,
+            ) {
+                pricing.insert(
+                    model_name.clone(),
+                    ModelPricing {
+                        model_name,
+                        // Convert to cost per 1k tokens
+                        input_cost_per_1k: input_cost * 1000.0,
+                        output_cost_per_1k: output_cost * 1000.0,
+                        cache_creation_cost_per_1k: litellm_pricing
+                            .cache_creation_input_token_cost
+                            .map(|c| c * 1000.0)
+                            .unwrap_or(0.0),
+                        cache_read_cost_per_1k: litellm_pricing
+                            .cache_read_input_token_cost
+                            .map(|c| c * 1000.0)
+                            .unwrap_or(0.0),
+                    },
+                );
+            }
+        }
+
+        pricing
+    }
+
+    /// Get pricing with fallback
+    ///
+    /// When no current prices can be fetched, an expired cached copy is
+    /// preferred over the built-in table; [`pricing_fallback`] tells which was
+    /// used.
+    pub async fn get_pricing_with_fallback() -> HashMap<String, ModelPricing> {
+        let (pricing, fallback) = match Self::fetch_pricing().await {
+            Ok(pricing) => (pricing, None),
+            Err(e) => match FileCachePricing::load_from_file() {
+                Some(stale) => {
+                    crate::trace_step!(
+                        "Pricing: expired cache from {} ({})",
+                        stale.fetched_at.to_rfc3339(),
+                        e
+                    );
+                    let fallback = PricingFallback::StaleCache {
+                        fetched_at: stale.fetched_at,
+                        error: e.to_string(),
+                    };
+                    (stale.data, Some(fallback))
+                }
+                None => {
+                    crate::trace_step!("Pricing: built-in fallback table ({})", e);
+                    eprintln!("Failed to fetch pricing from LiteLLM: {}", e);
+                    eprintln!("Using fallback pricing data");
+                    let fallback = PricingFallback::BuiltIn {
+                        error: e.to_string(),
+                    };
+                    (Self::fallback_pricing(), Some(fallback))
+                }
+            },
+        };
+        *PRICING_FALLBACK.write().unwrap() = fallback;
+        pricing
     }
 
     /// Fallback pricing data for offline use
@@ -294,6 +627,41 @@ impl ModelPricing {
             },
         );
 
+        // OpenAI models used by Codex CLI (`include_codex`)
+        m.insert(
+            "gpt-5".to_string(),
+            ModelPricing {
+                model_name: "gpt-5".to_string(),
+                input_cost_per_1k: 0.00125, // $0.00125/1k tokens = $1.25/1M tokens
+                output_cost_per_1k: 0.01,   // $0.01/1k tokens = $10/1M tokens
+                cache_creation_cost_per_1k: 0.0, // No cache write charge
+                cache_read_cost_per_1k: 0.000125, // $0.000125/1k tokens = $0.125/1M tokens
+            },
+        );
+
+        // Gemini models used by Gemini CLI (`include_gemini`), prompts up to 200k
+        m.insert(
+            "gemini-2.5-pro".to_string(),
+            ModelPricing {
+                model_name: "gemini-2.5-pro".to_string(),
+                input_cost_per_1k: 0.00125, // $0.00125/1k tokens = $1.25/1M tokens
+                output_cost_per_1k: 0.01,   // $0.01/1k tokens = $10/1M tokens
+                cache_creation_cost_per_1k: 0.0, // No cache write charge
+                cache_read_cost_per_1k: 0.00031, // $0.00031/1k tokens = $0.31/1M tokens
+            },
+        );
+
+        m.insert(
+            "gemini-2.5-flash".to_string(),
+            ModelPricing {
+                model_name: "gemini-2.5-flash".to_string(),
+                input_cost_per_1k: 0.0003, // $0.0003/1k tokens = $0.30/1M tokens
+                output_cost_per_1k: 0.0025, // $0.0025/1k tokens = $2.50/1M tokens
+                cache_creation_cost_per_1k: 0.0, // No cache write charge
+                cache_read_cost_per_1k: 0.000075, // $0.000075/1k tokens = $0.075/1M tokens
+            },
+        );
+
         m
     }
 
@@ -302,23 +670,7 @@ impl ModelPricing {
         pricing_map: &'a HashMap<String, ModelPricing>,
         model_name: &str,
     ) -> Option<&'a ModelPricing> {
-        // Try exact match first
-        if let Some(pricing) = pricing_map.get(model_name) {
-            return Some(pricing);
-        }
-
-        // Try fuzzy matching
-        let model_lower = model_name.to_lowercase();
-
-        // Look for the most specific match
-        pricing_map
-            .iter()
-            .filter(|(key, _)| {
-                let key_lower = key.to_lowercase();
-                model_lower.contains(&key_lower) || key_lower.contains(&model_lower)
-            })
-            .max_by_key(|(key, _)| key.len()) // Prefer longer (more specific) matches
-            .map(|(_, pricing)| pricing)
+        match_model_key(pricing_map, model_name)
     }
 }
 
@@ -326,3 +678,171 @@ impl ModelPricing {
 pub fn clear_pricing_cache() {
     *PRICING_CACHE.write().unwrap() = None;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn litellm(input: Option<f64>) -> LiteLLMPricing {
+        LiteLLMPricing {
+            input_cost_per_token: input,
+            output_cost_per_token: input.map(|c| c * 4.0),
+            cache_creation_input_token_cost: None,
+            cache_read_input_token_cost: None,
+            max_input_tokens: None,
+        }
+    }
+
+    #[test]
+    fn test_from_litellm_keeps_all_providers() {
+        let data = HashMap::from([
+            ("claude-sonnet-4-20250514".to_string(), litellm(Some(3e-6))),
+            ("deepseek/deepseek-chat".to_string(), litellm(Some(2.7e-7))),
+            ("glm-4.5".to_string(), litellm(Some(6e-7))),
+            ("dall-e-3".to_string(), litellm(None)),
+        ]);
+
+        let pricing = ModelPricing::from_litellm(data);
+        assert_eq!(pricing.len(), 3);
+        assert!(pricing.contains_key("deepseek/deepseek-chat"));
+        assert!(pricing.contains_key("glm-4.5"));
+        assert!(!pricing.contains_key("dall-e-3"));
+    }
+
+    #[test]
+    fn test_context_windows_from_litellm() {
+        let data: HashMap<String, LiteLLMPricing> = serde_json::from_value(serde_json::json!({
+            "sample_spec": { "max_input_tokens": "max input tokens, if the provider specifies it" },
+            "claude-sonnet-4-20250514": { "input_cost_per_token": 3e-6, "max_input_tokens": 200000 },
+            "gpt-4.1": { "input_cost_per_token": 2e-6, "max_input_tokens": 1047576 },
+            "dall-e-3": {}
+        }))
+        .unwrap();
+
+        let windows = context_windows_from_litellm(&data);
+        assert_eq!(windows.len(), 2);
+        assert_eq!(
+            match_model_key(&windows, "claude-sonnet-4-20250514"),
+            Some(&200_000)
+        );
+        assert_eq!(match_model_key(&windows, "mystery-model"), None);
+        assert_eq!(
+            match_model_key(&windows, "gpt-4.1-2025-04-14"),
+            Some(&1_047_576)
+        );
+    }
+
+    #[test]
+    fn test_get_model_pricing_across_providers() {
+        let pricing = ModelPricing::from_litellm(HashMap::from([
+            ("deepseek/deepseek-chat".to_string(), litellm(Some(2.7e-7))),
+            (
+                "openrouter/deepseek/deepseek-chat".to_string(),
+                litellm(Some(1e-6)),
+            ),
+            ("gpt-4o".to_string(), litellm(Some(2.5e-6))),
+            ("gpt-4o-mini".to_string(), litellm(Some(1.5e-7))),
+        ]));
+
+        let model =
+            |name| ModelPricing::get_model_pricing(&pricing, name).map(|p| p.model_name.as_str());
+        assert_eq!(model("deepseek-chat"), Some("deepseek/deepseek-chat"));
+        assert_eq!(model("gpt-4o-mini-2024-07-18"), Some("gpt-4o-mini"));
+        assert_eq!(model("gpt-4o"), Some("gpt-4o"));
+        assert_eq!(model("qwen3-coder"), None);
+    }
+
+    #[test]
+    fn test_get_model_pricing_ambiguous_names() {
+        let pricing = ModelPricing::from_litellm(HashMap::from([
+            ("gpt-4".to_string(), litellm(Some(3e-5))),
+            ("gpt-4.1".to_string(), litellm(Some(2e-6))),
+            ("claude-sonnet-4-5".to_string(), litellm(Some(3e-6))),
+            ("claude-sonnet-4-20250514".to_string(), litellm(Some(3e-6))),
+        ]));
+
+        let model =
+            |name| ModelPricing::get_model_pricing(&pricing, name).map(|p| p.model_name.as_str());
+        // A longer id is not priced as a model whose name it merely contains
+        assert_eq!(model("gpt-4o"), None);
+        assert_eq!(model("gpt-4o-mini-2024-07-18"), None);
+        assert_eq!(model("gpt-4.1-mini"), Some("gpt-4.1"));
+        assert_eq!(model("gpt-4-0613"), Some("gpt-4"));
+        // An alias resolves to its dated snapshot, not a newer version
+        assert_eq!(model("claude-sonnet-4"), Some("claude-sonnet-4-20250514"));
+        assert_eq!(
+            model("claude-sonnet-4-5-20250929"),
+            Some("claude-sonnet-4-5")
+        );
+        assert_eq!(
+            model("us.anthropic.claude-sonnet-4-20250514-v1:0"),
+            Some("claude-sonnet-4-20250514")
+        );
+        assert_eq!(
+            model("claude-sonnet-4@20250514"),
+            Some("claude-sonnet-4-20250514")
+        );
+    }
+
+    #[test]
+    fn test_download_tries_mirrors_in_order() {
+        let config = PricingConfig {
+            url: "http://127.0.0.1:1/primary.json".to_string(),
+            mirrors: vec!["http://127.0.0.1:1/mirror.json".to_string()],
+            timeout_secs: 2,
+            ..Default::default()
+        };
+
+        let err = crate::utils::block_on(ModelPricing::download(&config, None))
+            .unwrap_err()
+            .to_string();
+        let primary = err.find("primary.json").unwrap();
+        let mirror = err.find("mirror.json").unwrap();
+        assert!(primary < mirror);
+    }
+
+    #[test]
+    fn test_download_sends_validators_for_cached_source() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/prices.json", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let len = stream.read(&mut buf).unwrap();
+            let request = String::from_utf8_lossy(&buf[..len]).to_lowercase();
+            let status = if request.contains("if-none-match: \"v1\"") {
+                "304 Not Modified"
+            } else {
+                "500 Internal Server Error"
+            };
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            )
+            .unwrap();
+        });
+
+        let stale = FileCachePricing {
+            version: FILE_CACHE_VERSION,
+            fetched_at: Utc::now() - chrono::Duration::days(2),
+            ttl_hours: 24,
+            source_url: Some(url.clone()),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            data: HashMap::new(),
+            context_windows: HashMap::new(),
+        };
+        let config = PricingConfig {
+            url,
+            timeout_secs: 5,
+            ..Default::default()
+        };
+
+        let result = crate::utils::block_on(ModelPricing::download(&config, Some(&stale)));
+        server.join().unwrap();
+        assert!(matches!(result, Ok(Download::NotModified)));
+    }
+}
@@ -1,13 +1,14 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Session usage data aggregated from transcript files
 #[derive(Debug, Clone, Default)]
 pub struct SessionUsage {
-    pub total_input_tokens: u32,
-    pub total_output_tokens: u32,
-    pub cache_creation_tokens: u32,
-    pub cache_read_tokens: u32,
+    pub total_input_tokens: u64,
+    pub total_output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
     pub entries: Vec<UsageEntry>,
     pub session_id: String,
     pub start_time: Option<DateTime<Utc>>,
@@ -15,16 +16,20 @@ pub struct SessionUsage {
 }
 
 /// Single usage record from a transcript entry
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageEntry {
     pub timestamp: DateTime<Utc>,
-    pub input_tokens: u32,
-    pub output_tokens: u32,
-    pub cache_creation_tokens: u32,
-    pub cache_read_tokens: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
     pub model: String,
     pub cost: Option<f64>, // Optional until pricing is calculated
     pub session_id: String,
+    /// Written by a subagent rather than the main conversation
+    pub subagent: bool,
+    /// Project directory the transcript was found in, e.g. `-home-me-my-app`
+    pub project: String,
 }
 
 /// 5-hour billing block with dynamic start time support
@@ -36,7 +41,7 @@ pub struct BillingBlock {
     pub remaining_minutes: i64,
     pub is_active: bool,
     pub session_count: usize,
-    pub total_tokens: u32,
+    pub total_tokens: u64,
     /// Source of the block start time
     pub start_time_source: BlockStartSource,
     /// Whether this is a gap block (no activity)
@@ -50,6 +55,8 @@ pub enum BlockStartSource {
     Auto,
     /// Manually set by user override
     Manual,
+    /// From a recurring `block_schedule` rule
+    Schedule,
     /// Fixed 5-hour system (legacy mode)
     Fixed,
 }
@@ -61,6 +68,37 @@ pub struct BurnRate {
     pub tokens_per_minute_for_indicator: f64, // Excludes cache tokens
     pub cost_per_hour: f64,
     pub trend: BurnRateTrend,
+    /// Where the block ends up if the current rate holds
+    pub projection: BlockProjection,
+}
+
+/// Estimated block totals at block end, assuming the current burn rate continues
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockProjection {
+    pub total_tokens: u64,
+    pub total_cost: f64,
+}
+
+impl BillingBlock {
+    /// Project this block's totals to its end time at the given rates
+    pub fn project(&self, tokens_per_minute: f64, cost_per_hour: f64) -> BlockProjection {
+        let remaining = self.remaining_minutes.max(0) as f64;
+        let extra_tokens = if tokens_per_minute.is_finite() {
+            tokens_per_minute.max(0.0) * remaining
+        } else {
+            0.0
+        };
+        let extra_cost = if cost_per_hour.is_finite() {
+            cost_per_hour.max(0.0) * remaining / 60.0
+        } else {
+            0.0
+        };
+
+        BlockProjection {
+            total_tokens: self.total_tokens + extra_tokens.round() as u64,
+            total_cost: self.cost + extra_cost,
+        }
+    }
 }
 
 /// Burn rate trend indicator
@@ -106,6 +144,21 @@ impl BurnRateThresholds {
 
         thresholds
     }
+
+    /// Thresholds from a segment's `high_threshold` and `medium_threshold`
+    /// options (tokens/minute), falling back to [`Self::from_env`] for any
+    /// that are not set
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let mut thresholds = Self::from_env();
+        let option = |key: &str| options.get(key).and_then(|v| v.as_f64());
+        if let Some(high) = option("high_threshold") {
+            thresholds.high = high;
+        }
+        if let Some(medium) = option("medium_threshold") {
+            thresholds.medium = medium;
+        }
+        thresholds
+    }
 }
 
 /// Model pricing information
@@ -146,7 +199,7 @@ impl SessionUsage {
     }
 
     /// Get total tokens (all types)
-    pub fn total_tokens(&self) -> u32 {
+    pub fn total_tokens(&self) -> u64 {
         self.total_input_tokens
             + self.total_output_tokens
             + self.cache_creation_tokens
@@ -0,0 +1,109 @@
+use crate::billing::UsageEntry;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Weekday};
+
+/// Usage accumulated since the start of the current week
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekUsage {
+    pub start: NaiveDate,
+    pub tokens: u64,
+    pub cost: f64,
+    pub entries: usize,
+}
+
+/// Parse a week start day such as `monday`, `sun` or `Sat`
+pub fn parse_week_start(day: &str) -> Result<Weekday, String> {
+    day.trim()
+        .parse::<Weekday>()
+        .map_err(|_| format!("Invalid week start day '{}'", day))
+}
+
+/// First day of the week containing `date`, for weeks starting on `week_start`
+pub fn week_start_date(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+    let days_back =
+        (date.weekday().num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7;
+    date - Duration::days(days_back as i64)
+}
+
+/// Sum tokens and (already calculated) costs of entries from midnight in
+/// `now`'s timezone at the start of the current week up to `now`
+pub fn week_to_date<Tz: TimeZone>(
+    entries: &[UsageEntry],
+    now: DateTime<Tz>,
+    week_start: Weekday,
+) -> WeekUsage {
+    let start = week_start_date(now.date_naive(), week_start);
+    let start_time = start
+        .and_hms_opt(0, 0, 0)
+        .and_then(|midnight| now.timezone().from_local_datetime(&midnight).earliest())
+        .unwrap_or_else(|| now.clone());
+
+    let mut usage = WeekUsage {
+        start,
+        tokens: 0,
+        cost: 0.0,
+        entries: 0,
+    };
+    for entry in entries {
+        if entry.timestamp < start_time || entry.timestamp > now {
+            continue;
+        }
+        usage.tokens += entry.input_tokens
+            + entry.output_tokens
+            + entry.cache_creation_tokens
+            + entry.cache_read_tokens;
+        usage.cost += entry.cost.unwrap_or(0.0);
+        usage.entries += 1;
+    }
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_week_start_date() {
+        // 2025-06-04 is a Wednesday
+        let wednesday = NaiveDate::from_ymd_opt(2025, 6, 4).unwrap();
+        assert_eq!(
+            week_start_date(wednesday, Weekday::Mon),
+            NaiveDate::from_ymd_opt(2025, 6, 2).unwrap()
+        );
+        assert_eq!(
+            week_start_date(wednesday, Weekday::Sun),
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+        );
+        assert_eq!(week_start_date(wednesday, Weekday::Wed), wednesday);
+        assert_eq!(
+            week_start_date(wednesday, Weekday::Thu),
+            NaiveDate::from_ymd_opt(2025, 5, 29).unwrap()
+        );
+        assert_eq!(parse_week_start("sunday"), Ok(Weekday::Sun));
+        assert!(parse_week_start("someday").is_err());
+    }
+
+    #[test]
+    fn test_week_to_date() {
+        let now = chrono::Local::now();
+        let entry = |days_ago: i64, cost: f64| UsageEntry {
+            timestamp: (now - Duration::days(days_ago)).with_timezone(&chrono::Utc),
+            input_tokens: 100,
+            output_tokens: 50,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 850,
+            model: "claude-sonnet-4".to_string(),
+            cost: Some(cost),
+            session_id: "s".to_string(),
+            subagent: false,
+            project: String::new(),
+        };
+        let entries = vec![entry(0, 1.5), entry(8, 4.0)];
+
+        // Whatever today is, a week starting today only includes today's entry
+        let usage = week_to_date(&entries, now, now.weekday());
+        assert_eq!(usage.start, now.date_naive());
+        assert_eq!(usage.tokens, 1_000);
+        assert_eq!(usage.entries, 1);
+        assert!((usage.cost - 1.5).abs() < 0.001);
+    }
+}
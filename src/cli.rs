@@ -1,17 +1,27 @@
-use clap::Parser;
+use crate::config::MergeStrategy;
+use crate::core::scenarios::Scenario;
+use clap::{ArgGroup, Args, CommandFactory, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[command(name = "ccline")]
 #[command(version, about = "High-performance Claude Code StatusLine")]
+#[command(group(ArgGroup::new("block_action").multiple(true)))]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Enter TUI configuration mode
     #[arg(short = 'c', long = "config")]
     pub config: bool,
 
     /// Set theme
-    #[arg(short = 't', long = "theme")]
+    #[arg(short = 't', long = "theme", global = true)]
     pub theme: Option<String>,
 
+    /// Reset segment options to the theme's values when applying a theme
+    #[arg(long = "reset-options", global = true)]
+    pub reset_options: bool,
+
     /// Print current configuration
     #[arg(long = "print")]
     pub print: bool,
@@ -29,24 +39,339 @@ pub struct Cli {
     pub update: bool,
 
     /// Set block start time for today (formats: 0-23, HH:MM, ISO timestamp)
-    #[arg(long, value_name = "TIME")]
+    #[arg(long, value_name = "TIME", group = "block_action")]
     pub set_block_start: Option<String>,
 
     /// Clear block start override for today
-    #[arg(long)]
+    #[arg(long, group = "block_action")]
     pub clear_block_start: bool,
 
     /// Show current block override status
-    #[arg(long)]
+    #[arg(long, group = "block_action")]
     pub show_block_status: bool,
 
-    /// Set context window limit for usage calculation (in tokens)
-    #[arg(long = "context-limit", value_name = "TOKENS")]
-    pub context_limit: Option<u32>,
+    /// Day the block start options apply to instead of today
+    #[arg(long, value_name = "YYYY-MM-DD", requires = "block_action")]
+    pub date: Option<String>,
+
+    /// Write a JSON timing profile of this run to ~/.claude/ccline/profiles
+    /// (also enabled by CCLINE_PROFILE=1)
+    #[arg(long)]
+    pub profile: bool,
+
+    /// Save the stdin JSON and the rendered line to FILE, e.g. to attach to a
+    /// bug report
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<std::path::PathBuf>,
+
+    /// Render from a file saved by --record (or a bare input JSON) instead of stdin
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<std::path::PathBuf>,
+
+    /// Output format for the rendered statusline
+    #[arg(long = "output", value_enum, default_value_t = OutputFormat::Ansi)]
+    pub output: OutputFormat,
+
+    /// Leave out all color and style escape sequences (also enabled by NO_COLOR)
+    #[arg(long = "no-color", global = true)]
+    pub no_color: bool,
+
+    /// Set context window limit for usage calculation: a token count such as
+    /// `200k`, `1m` or `1000000`, or `auto` to resolve it from the current model
+    #[arg(long = "context-limit", value_name = "TOKENS|auto")]
+    pub context_limit: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Show usage and cost reports
+    Report(ReportArgs),
+    /// Live overview of billing blocks, burn rate, model costs and top sessions
+    Dashboard,
+    /// Rank the most expensive sessions
+    Sessions(SessionsArgs),
+    /// Inspect 5-hour billing blocks and share block start overrides
+    #[command(alias = "block")]
+    Blocks(BlocksArgs),
+    /// List every external command ccline is configured to run
+    Audit {
+        /// Approve the named declared commands so they are allowed to run
+        #[arg(long, value_name = "NAME", num_args = 0..)]
+        approve: Option<Vec<String>>,
+        /// With --approve, approve every valid declared command
+        #[arg(long, requires = "approve")]
+        all: bool,
+    },
+    /// Manage per-model context limits
+    Context {
+        #[command(subcommand)]
+        action: ContextAction,
+    },
+    /// Inspect themes
+    Theme {
+        #[command(subcommand)]
+        action: ThemeAction,
+    },
+    /// Manage the caches under ~/.claude/ccline/cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Render the statusline with synthetic data, e.g. to try a theme
+    Preview(PreviewArgs),
+    /// Print a shell completion script, e.g. `ccline completions zsh > _ccline`
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Keep re-rendering the statusline for terminal multiplexers and widgets
+    Watch(WatchArgs),
+    /// Run the statusline against fixture data and report any failures
+    SelfTest,
+    /// Run a single segment and trace how it arrived at its output
+    Explain {
+        /// Segment id such as `cost`, or an instance key such as `git:sha`
+        segment: String,
+    },
+    /// Write synthetic transcripts for demos, screenshots and benchmarks
+    GenFixtures {
+        /// Spread sessions over this many days before the end time
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+        /// Number of sessions to generate
+        #[arg(long, default_value_t = 50)]
+        sessions: u32,
+        /// Seed for reproducible output
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+        /// End of the generated history as an RFC 3339 time (default: now);
+        /// set it along with the seed to rebuild the same data set
+        #[arg(long)]
+        until: Option<chrono::DateTime<chrono::Utc>>,
+        /// Directory to write into; transcripts go to `<out>/.claude/projects`
+        #[arg(long)]
+        out: std::path::PathBuf,
+    },
+    /// Scan all usage data once so the statusline can skip its cold-start placeholder
+    #[command(hide = true)]
+    Index,
+    /// Run an approved declared command (used internally by hooks)
+    #[command(hide = true)]
+    RunCommand { name: String },
+    /// Finish collecting a segment that missed the render budget, reading
+    /// the statusline input from stdin
+    #[command(hide = true)]
+    CollectSegment { key: String },
+}
+
+#[derive(Args, Debug)]
+pub struct PreviewArgs {
+    /// Situation to render
+    #[arg(long, value_enum, default_value_t = Scenario::Default)]
+    pub scenario: Scenario,
+
+    /// Render every scenario, one per line
+    #[arg(long, conflicts_with = "scenario")]
+    pub all: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// Seconds between renders
+    #[arg(long, default_value_t = 5)]
+    pub interval: u64,
+
+    /// Also re-render as soon as a transcript changes
+    #[arg(long)]
+    pub on_change: bool,
+
+    /// Write each render to this file instead of refreshing one terminal line
+    #[arg(long)]
+    pub out: Option<std::path::PathBuf>,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum ContextAction {
+    /// Set the context limit for models matching a glob, e.g. `claude-sonnet-4*`
+    Set {
+        pattern: String,
+        /// Token count such as `200k`, `1m` or `1000000`
+        limit: String,
+    },
+    /// Show configured per-model limits
+    List,
+    /// Remove a per-model limit
+    Unset { pattern: String },
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum CacheAction {
+    /// Show the size and age of each cache
+    Status,
+    /// Delete one cache, or all of them; each is rebuilt when next needed
+    Clear {
+        #[arg(value_enum)]
+        cache: Option<CacheKind>,
+    },
+    /// Delete entries that can no longer be used: usage of deleted transcripts
+    /// and statuslines cached over an hour ago
+    Prune,
+}
+
+/// A cache kept under ~/.claude/ccline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CacheKind {
+    /// Model prices downloaded from LiteLLM
+    Pricing,
+    /// Usage entries parsed from each transcript
+    Usage,
+    /// Whole statuslines kept for `render_cache_secs`
+    Render,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum ThemeAction {
+    /// Warn about unreadable colors, misplaced icons and missing segments
+    Lint {
+        /// Built-in or custom theme name
+        name: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct BlocksArgs {
+    /// Without a subcommand, list recent blocks
+    #[command(subcommand)]
+    pub action: Option<BlocksAction>,
+
+    /// Only list blocks from the last N days
+    #[arg(long, default_value_t = 7)]
+    pub days: i64,
+
+    /// Include the idle gaps between blocks
+    #[arg(long)]
+    pub gaps: bool,
+
+    /// Print JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SessionsArgs {
+    /// Number of sessions to list
+    #[arg(long, default_value_t = 10)]
+    pub top: usize,
+
+    /// Only include usage from the last N days
+    #[arg(long, default_value_t = 30)]
+    pub days: i64,
+
+    /// Print JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum BlocksAction {
+    /// Month grid of block counts and utilization per day
+    Calendar {
+        /// Month to show as YYYY-MM (defaults to the current month)
+        #[arg(long)]
+        month: Option<String>,
+    },
+    /// Write block start overrides as JSON for another machine
+    Export {
+        /// File to write (defaults to stdout)
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+    },
+    /// Merge block start overrides exported on another machine
+    Import {
+        /// Export file, or a copy of another machine's block_overrides.json
+        file: std::path::PathBuf,
+        /// How to resolve dates overridden on both machines
+        #[arg(long, value_enum, default_value_t = MergeStrategy::Newest)]
+        strategy: MergeStrategy,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct ReportArgs {
+    #[command(subcommand)]
+    pub kind: Option<ReportKind>,
+
+    /// Browse reports in an interactive TUI
+    #[arg(short = 'i', long)]
+    pub interactive: bool,
+
+    /// Only include usage from the last N days
+    #[arg(long, global = true, default_value_t = 30)]
+    pub days: i64,
+
+    /// Print JSON instead of a table
+    #[arg(long, global = true)]
+    pub json: bool,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum ReportKind {
+    /// Usage grouped by day
+    Daily,
+    /// Usage grouped by session
+    Session,
+    /// Usage grouped by 5-hour billing block
+    Block,
+    /// Usage grouped by git branch for the current repository
+    Branches,
+    /// Usage grouped by project, marking the current directory's project
+    Projects,
+    /// Spend over rolling 7- and 30-day windows (plus `--days`)
+    Rolling,
+    /// Model prices that changed when pricing data was refreshed
+    Prices,
+    /// Export daily cost and token bar charts as SVG
+    Chart {
+        /// Output file
+        #[arg(long, default_value = "usage.svg")]
+        out: std::path::PathBuf,
+
+        /// Chart width in pixels
+        #[arg(long, default_value_t = 1000)]
+        width: u32,
+
+        /// Chart height in pixels
+        #[arg(long, default_value_t = 600)]
+        height: u32,
+    },
+}
+
+/// How the rendered statusline is written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Raw ANSI escape sequences (for Claude Code)
+    #[default]
+    Ansi,
+    /// Escape sequences wrapped in %{...%} for zsh PS1
+    Zsh,
+    /// Escape sequences wrapped in \[...\] for bash PS1
+    Bash,
 }
 
 impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
     }
+
+    /// Write the completion script for `shell` to stdout
+    pub fn print_completions(shell: clap_complete::Shell) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut command = Self::command();
+        let name = command.get_name().to_string();
+        // Generate into memory first; writing straight to a closed pipe panics
+        let mut script = Vec::new();
+        clap_complete::generate(shell, &mut command, name, &mut script);
+        std::io::stdout().write_all(&script)
+    }
 }
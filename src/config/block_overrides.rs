@@ -1,8 +1,26 @@
-use chrono::{DateTime, Local, NaiveDate, Timelike, Utc};
+use crate::utils::timezone;
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc, Weekday};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Recurring block starts from the config, consulted on days without an
+/// explicit override
+static SCHEDULE: Lazy<RwLock<Vec<BlockScheduleRule>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Use the `block_schedule` rules from the user's config for subsequent
+/// block detection
+pub fn set_block_schedule(rules: &[BlockScheduleRule]) {
+    if let Ok(mut schedule) = SCHEDULE.write() {
+        *schedule = rules.to_vec();
+    }
+}
+
+/// `source` of the overrides built from `block_schedule` rules
+pub const SCHEDULE_SOURCE: &str = "schedule";
 
 /// Block override configuration for a specific date
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +46,97 @@ impl BlockOverride {
     }
 }
 
+/// Recurring block start such as "weekdays at 09:00"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockScheduleRule {
+    /// `daily`, `weekdays`, `weekends`, or day names such as `mon,wed,fri`
+    pub days: String,
+    /// Start hour as `H` or `HH:MM` in the configured timezone; minutes are
+    /// ignored like in `--set-block-start`
+    pub start: String,
+}
+
+impl BlockScheduleRule {
+    pub fn validate(&self) -> Result<(), String> {
+        self.parse().map(|_| ())
+    }
+
+    fn parse(&self) -> Result<(Vec<Weekday>, u32), String> {
+        let days = match self.days.trim().to_lowercase().as_str() {
+            "daily" | "everyday" => vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+                Weekday::Sat,
+                Weekday::Sun,
+            ],
+            "weekdays" => vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ],
+            "weekends" => vec![Weekday::Sat, Weekday::Sun],
+            list => list
+                .split(',')
+                .map(|day| {
+                    day.trim()
+                        .parse::<Weekday>()
+                        .map_err(|_| format!("Invalid day '{}' in block schedule", day.trim()))
+                })
+                .collect::<Result<_, _>>()?,
+        };
+        let hour = self
+            .start
+            .split(':')
+            .next()
+            .and_then(|hour| hour.trim().parse::<u32>().ok())
+            .filter(|hour| *hour <= 23)
+            .ok_or_else(|| format!("Invalid block schedule start '{}'", self.start))?;
+        Ok((days, hour))
+    }
+
+    /// Block start this rule gives `date`, or `None` when it does not apply
+    pub fn start_on(&self, date: NaiveDate) -> Option<DateTime<Utc>> {
+        let (days, hour) = self.parse().ok()?;
+        if !days.contains(&date.weekday()) {
+            return None;
+        }
+        date.and_hms_opt(hour, 0, 0).and_then(timezone::from_local)
+    }
+}
+
+/// How `ccline block import` resolves a date present on both machines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum MergeStrategy {
+    /// Keep whichever override was created most recently
+    #[default]
+    Newest,
+    /// Keep local overrides and only add dates missing locally
+    KeepLocal,
+    /// Take the imported override for every date it covers
+    Theirs,
+}
+
+/// Portable form of the overrides written by `ccline block export`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockOverrideExport {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub overrides: HashMap<String, BlockOverride>,
+}
+
+/// What an import changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+}
+
 /// Error types for block override operations
 #[derive(Debug)]
 pub enum BlockOverrideError {
@@ -79,12 +188,13 @@ impl From<serde_json::Error> for BlockOverrideError {
 pub struct BlockOverrideManager {
     config_path: PathBuf,
     overrides: HashMap<String, BlockOverride>,
+    schedule: Vec<BlockScheduleRule>,
 }
 
 impl BlockOverrideManager {
     /// Create a new BlockOverrideManager with default config path
     pub fn new() -> Result<Self, BlockOverrideError> {
-        let config_dir = dirs::home_dir()
+        let config_dir = crate::utils::home_dir()
             .ok_or_else(|| {
                 BlockOverrideError::FileAccess(std::io::Error::new(
                     std::io::ErrorKind::NotFound,
@@ -99,6 +209,10 @@ impl BlockOverrideManager {
         Ok(Self {
             config_path,
             overrides: HashMap::new(),
+            schedule: SCHEDULE
+                .read()
+                .map(|rules| rules.clone())
+                .unwrap_or_default(),
         })
     }
 
@@ -107,9 +221,16 @@ impl BlockOverrideManager {
         Self {
             config_path,
             overrides: HashMap::new(),
+            schedule: Vec::new(),
         }
     }
 
+    /// Use these recurring rules instead of the ones from the config
+    pub fn with_schedule(mut self, schedule: Vec<BlockScheduleRule>) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
     /// Ensure the configuration directory exists
     fn ensure_config_dir(&self) -> Result<(), BlockOverrideError> {
         if let Some(parent) = self.config_path.parent() {
@@ -168,6 +289,27 @@ impl BlockOverrideManager {
         self.overrides.get(&date_str)
     }
 
+    /// The explicit override for `date`, or else one built from the first
+    /// matching `block_schedule` rule
+    pub fn effective_override(&self, date: NaiveDate) -> Option<BlockOverride> {
+        if let Some(override_config) = self.get_override(date) {
+            return Some(override_config.clone());
+        }
+        self.schedule.iter().find_map(|rule| {
+            rule.start_on(date).map(|start_time| BlockOverride {
+                start_time,
+                source: SCHEDULE_SOURCE.to_string(),
+                created_at: start_time,
+                notes: Some(format!("{} at {}", rule.days, rule.start)),
+            })
+        })
+    }
+
+    /// Recurring rules in effect
+    pub fn schedule(&self) -> &[BlockScheduleRule] {
+        &self.schedule
+    }
+
     /// Clear an override for a specific date
     pub fn clear_override(&mut self, date: NaiveDate) -> Result<bool, BlockOverrideError> {
         let date_str = date.format("%Y-%m-%d").to_string();
@@ -194,27 +336,30 @@ impl BlockOverrideManager {
         Ok(removed_count)
     }
 
-    /// Parse various time input formats (input interpreted as local time)
+    /// Parse various time input formats; hours are interpreted in the
+    /// configured timezone (local time by default)
     pub fn parse_time_input(input: &str) -> Result<DateTime<Utc>, BlockOverrideError> {
-        let today = Local::now().date_naive();
+        Self::parse_time_input_on(input, timezone::today())
+    }
 
+    /// Like `parse_time_input`, with hours taken on `date` instead of today
+    pub fn parse_time_input_on(
+        input: &str,
+        date: NaiveDate,
+    ) -> Result<DateTime<Utc>, BlockOverrideError> {
         // Try parsing as a single number (0-23 hour)
         if let Ok(hour) = input.parse::<u32>() {
             if hour <= 23 {
-                let local_time = today
+                let start_time = date
                     .and_hms_opt(hour, 0, 0)
-                    .ok_or(BlockOverrideError::TimeOutOfRange)?
-                    .and_local_timezone(Local)
-                    .single()
+                    .and_then(timezone::from_local)
                     .ok_or(BlockOverrideError::TimeOutOfRange)?;
 
-                // Check if this would be a future time (compare in local timezone)
-                if local_time > Local::now() {
+                if start_time > Utc::now() {
                     return Err(BlockOverrideError::FutureTime);
                 }
 
-                // Convert to UTC for storage
-                return Ok(local_time.with_timezone(&Utc));
+                return Ok(start_time);
             } else {
                 return Err(BlockOverrideError::HourOutOfRange);
             }
@@ -230,20 +375,16 @@ impl BlockOverrideManager {
                 .map_err(|_| BlockOverrideError::InvalidFormat)?;
 
             if hour <= 23 && minute <= 59 {
-                let local_time = today
+                let start_time = date
                     .and_hms_opt(hour, 0, 0) // Floor to hour (ignore minutes)
-                    .ok_or(BlockOverrideError::TimeOutOfRange)?
-                    .and_local_timezone(Local)
-                    .single()
+                    .and_then(timezone::from_local)
                     .ok_or(BlockOverrideError::TimeOutOfRange)?;
 
-                // Check if this would be a future time (compare in local timezone)
-                if local_time > Local::now() {
+                if start_time > Utc::now() {
                     return Err(BlockOverrideError::FutureTime);
                 }
 
-                // Convert to UTC for storage
-                return Ok(local_time.with_timezone(&Utc));
+                return Ok(start_time);
             } else {
                 return Err(BlockOverrideError::TimeOutOfRange);
             }
@@ -252,10 +393,7 @@ impl BlockOverrideManager {
         // Try parsing as ISO timestamp (interpreted as given timezone)
         match DateTime::parse_from_rfc3339(input) {
             Ok(dt) => {
-                let local_time = dt.with_timezone(&Local);
-
-                // Check if this would be a future time (compare in local timezone)
-                if local_time > Local::now() {
+                if dt > Utc::now() {
                     return Err(BlockOverrideError::FutureTime);
                 }
 
@@ -266,6 +404,75 @@ impl BlockOverrideManager {
         }
     }
 
+    /// All stored overrides in the format `import_json` reads
+    pub fn export(&self) -> BlockOverrideExport {
+        BlockOverrideExport {
+            version: 1,
+            exported_at: Utc::now(),
+            overrides: self.overrides.clone(),
+        }
+    }
+
+    /// Merge overrides from an export, or from a copy of another machine's
+    /// `block_overrides.json`, and save the result
+    pub fn import_json(
+        &mut self,
+        content: &str,
+        strategy: MergeStrategy,
+    ) -> Result<MergeSummary, BlockOverrideError> {
+        let incoming = match serde_json::from_str::<BlockOverrideExport>(content) {
+            Ok(export) => export.overrides,
+            Err(_) => serde_json::from_str(content).map_err(|e| {
+                BlockOverrideError::CorruptedConfig(format!("Not a block override export: {}", e))
+            })?,
+        };
+        for date in incoming.keys() {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+                BlockOverrideError::CorruptedConfig(format!("Invalid override date '{}'", date))
+            })?;
+        }
+
+        let summary = self.merge(incoming, strategy);
+        if summary.added + summary.updated > 0 {
+            self.save()?;
+        }
+        Ok(summary)
+    }
+
+    fn merge(
+        &mut self,
+        incoming: HashMap<String, BlockOverride>,
+        strategy: MergeStrategy,
+    ) -> MergeSummary {
+        let mut summary = MergeSummary::default();
+        for (date, theirs) in incoming {
+            let theirs = BlockOverride {
+                start_time: floor_to_hour(theirs.start_time),
+                ..theirs
+            };
+            match self.overrides.get(&date) {
+                None => {
+                    self.overrides.insert(date, theirs);
+                    summary.added += 1;
+                }
+                Some(ours) => {
+                    let take = match strategy {
+                        MergeStrategy::Newest => theirs.created_at > ours.created_at,
+                        MergeStrategy::KeepLocal => false,
+                        MergeStrategy::Theirs => true,
+                    };
+                    if take && ours.start_time != theirs.start_time {
+                        self.overrides.insert(date, theirs);
+                        summary.updated += 1;
+                    } else {
+                        summary.unchanged += 1;
+                    }
+                }
+            }
+        }
+        summary
+    }
+
     /// Get the number of currently stored overrides
     pub fn override_count(&self) -> usize {
         self.overrides.len()
@@ -339,7 +546,7 @@ mod tests {
                 assert_eq!(time.minute(), 0);
                 assert_eq!(time.second(), 0);
                 // Verify it's today's date
-                let today = Local::now().date_naive();
+                let today = chrono::Local::now().date_naive();
                 assert_eq!(time.date_naive(), today);
             }
             Err(BlockOverrideError::FutureTime) => {
@@ -379,4 +586,105 @@ mod tests {
         assert_eq!(override_config.notes, Some("Test override".to_string()));
         assert!(override_config.created_at <= Utc::now());
     }
+
+    #[test]
+    fn test_schedule_fills_days_without_override() {
+        let dir = std::env::temp_dir().join(format!("ccline-schedule-{}", std::process::id()));
+        let mut manager = BlockOverrideManager::with_path(dir.join("block_overrides.json"))
+            .with_schedule(vec![BlockScheduleRule {
+                days: "weekdays".to_string(),
+                start: "09:00".to_string(),
+            }]);
+        let monday = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let saturday = NaiveDate::from_ymd_opt(2025, 6, 7).unwrap();
+
+        let scheduled = manager.effective_override(monday).unwrap();
+        assert_eq!(scheduled.source, "schedule");
+        assert_eq!(timezone::to_local(scheduled.start_time).hour(), 9);
+        assert!(manager.effective_override(saturday).is_none());
+
+        // An explicit override for a past date wins over the schedule
+        let start = BlockOverrideManager::parse_time_input_on("7:45", monday).unwrap();
+        manager
+            .set_override(monday, start, "manual".to_string(), None)
+            .unwrap();
+        let explicit = manager.effective_override(monday).unwrap();
+        assert_eq!(explicit.source, "manual");
+        assert_eq!(timezone::to_local(explicit.start_time).hour(), 7);
+
+        assert!(BlockScheduleRule {
+            days: "mon,someday".to_string(),
+            start: "9".to_string(),
+        }
+        .validate()
+        .is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_import_merge_strategies() {
+        let dir = std::env::temp_dir().join(format!("ccline-import-{}", std::process::id()));
+        let older = Utc.with_ymd_and_hms(2025, 6, 1, 8, 0, 0).unwrap();
+        let newer = Utc.with_ymd_and_hms(2025, 6, 2, 8, 0, 0).unwrap();
+        let entry = |hour, created_at| BlockOverride {
+            start_time: Utc.with_ymd_and_hms(2025, 6, 2, hour, 0, 0).unwrap(),
+            source: "manual".to_string(),
+            created_at,
+            notes: None,
+        };
+        let local = || {
+            let mut manager = BlockOverrideManager::with_path(dir.join("block_overrides.json"));
+            manager
+                .overrides
+                .insert("2025-06-02".to_string(), entry(9, older));
+            manager
+                .overrides
+                .insert("2025-06-03".to_string(), entry(10, newer));
+            manager
+        };
+        let mut remote = BlockOverrideManager::with_path(dir.join("remote.json"));
+        remote
+            .overrides
+            .insert("2025-06-02".to_string(), entry(7, newer));
+        remote
+            .overrides
+            .insert("2025-06-03".to_string(), entry(6, older));
+        remote
+            .overrides
+            .insert("2025-06-04".to_string(), entry(8, older));
+        let export = serde_json::to_string(&remote.export()).unwrap();
+
+        let mut newest = local();
+        let summary = newest.import_json(&export, MergeStrategy::Newest).unwrap();
+        assert_eq!(
+            summary,
+            MergeSummary {
+                added: 1,
+                updated: 1,
+                unchanged: 1
+            }
+        );
+        let start_hour =
+            |manager: &BlockOverrideManager, date: &str| manager.overrides[date].start_time.hour();
+        assert_eq!(start_hour(&newest, "2025-06-02"), 7);
+        assert_eq!(start_hour(&newest, "2025-06-03"), 10);
+
+        let mut keep = local();
+        keep.import_json(&export, MergeStrategy::KeepLocal).unwrap();
+        assert_eq!(start_hour(&keep, "2025-06-02"), 9);
+        assert_eq!(keep.override_count(), 3);
+
+        // A plain copy of block_overrides.json is accepted too
+        let mut theirs = local();
+        let raw = serde_json::to_string(&remote.overrides).unwrap();
+        theirs.import_json(&raw, MergeStrategy::Theirs).unwrap();
+        assert_eq!(start_hour(&theirs, "2025-06-03"), 6);
+
+        let mut bad = HashMap::new();
+        bad.insert("June 2".to_string(), entry(9, older));
+        assert!(local()
+            .import_json(&serde_json::to_string(&bad).unwrap(), MergeStrategy::Newest)
+            .is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
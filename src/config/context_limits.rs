@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+/// Known context window sizes, matched against model ids by substring
+const BUILTIN_CONTEXT_LIMITS: &[(&str, u32)] = &[
+    ("claude", 200_000),
+    ("gpt-4o", 128_000),
+    ("gpt-4.1", 1_047_576),
+    ("gpt-5", 400_000),
+    ("o3", 200_000),
+    ("o4-mini", 200_000),
+    ("gemini-2.5", 1_048_576),
+    ("deepseek", 128_000),
+    ("glm-4.5", 128_000),
+    ("glm-4.6", 200_000),
+    ("qwen3-coder", 262_144),
+    ("kimi-k2", 131_072),
+];
+
+/// Context window of models running in Claude Code's 1M-token mode
+const EXTENDED_CONTEXT_LIMIT: u32 = 1_000_000;
+
+/// Context window for a model: Claude Code's 1M mode, then LiteLLM's
+/// `max_input_tokens` from the cached pricing data, then the built-in table
+pub fn detect_context_limit(model_id: &str) -> Option<u32> {
+    if model_id.to_lowercase().ends_with("[1m]") {
+        return Some(EXTENDED_CONTEXT_LIMIT);
+    }
+    crate::billing::pricing::cached_context_window(model_id)
+        .or_else(|| builtin_context_limit(model_id))
+}
+
+/// Look up the context window for a model id such as `claude-sonnet-4-20250514`
+/// or `openrouter/deepseek/deepseek-chat`
+pub fn builtin_context_limit(model_id: &str) -> Option<u32> {
+    let id = model_id
+        .rsplit('/')
+        .next()
+        .unwrap_or(model_id)
+        .to_lowercase();
+    if id.is_empty() {
+        return None;
+    }
+    // Claude Code marks the 1M-context variants with a `[1m]` suffix
+    if id.ends_with("[1m]") {
+        return Some(EXTENDED_CONTEXT_LIMIT);
+    }
+
+    BUILTIN_CONTEXT_LIMITS
+        .iter()
+        .filter(|(pattern, _)| id.contains(pattern))
+        .max_by_key(|(pattern, _)| pattern.len())
+        .map(|(_, limit)| *limit)
+}
+
+/// Find the limit for a model id in a user-defined table of id globs
+///
+/// Patterns are matched against the full id and against the id without any
+/// provider prefix; when several match, the one with the most literal characters wins.
+pub fn match_context_limit(table: &BTreeMap<String, u32>, model_id: &str) -> Option<u32> {
+    let bare = model_id.rsplit('/').next().unwrap_or(model_id);
+    let options = glob::MatchOptions {
+        case_sensitive: false,
+        ..Default::default()
+    };
+
+    table
+        .iter()
+        .filter_map(|(pattern, limit)| {
+            let glob = glob::Pattern::new(pattern).ok()?;
+            (glob.matches_with(model_id, options) || glob.matches_with(bare, options))
+                .then_some((pattern, *limit))
+        })
+        .max_by_key(|(pattern, _)| {
+            let literal = pattern.chars().filter(|c| !"*?[]".contains(*c)).count();
+            (literal, pattern.len())
+        })
+        .map(|(_, limit)| limit)
+}
+
+/// Parse a token count with an optional `k`/`m` suffix, e.g. `200k`, `1m`, `1.5M`
+/// or `1000000`
+pub fn parse_token_count(input: &str) -> Result<u32, String> {
+    let cleaned = input.trim().replace('_', "").to_lowercase();
+    let (number, multiplier) = match cleaned.strip_suffix('k') {
+        Some(number) => (number, 1_000.0),
+        None => match cleaned.strip_suffix('m') {
+            Some(number) => (number, 1_000_000.0),
+            None => (cleaned.as_str(), 1.0),
+        },
+    };
+
+    let invalid = || {
+        format!(
+            "Invalid token count '{}' (expected e.g. 200k, 1m or 1000000)",
+            input
+        )
+    };
+    let value = number.trim().parse::<f64>().map_err(|_| invalid())? * multiplier;
+    if !value.is_finite() || value < 1.0 || value > u32::MAX as f64 || value.fract() != 0.0 {
+        return Err(invalid());
+    }
+    Ok(value as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_context_limit() {
+        let table = BTreeMap::from([
+            ("claude-*".to_string(), 200_000),
+            ("claude-sonnet-4*".to_string(), 1_000_000),
+            ("deepseek-chat".to_string(), 64_000),
+        ]);
+
+        assert_eq!(
+            match_context_limit(&table, "claude-sonnet-4-20250514"),
+            Some(1_000_000)
+        );
+        assert_eq!(
+            match_context_limit(&table, "claude-opus-4-1-20250805"),
+            Some(200_000)
+        );
+        assert_eq!(
+            match_context_limit(&table, "openrouter/deepseek/deepseek-chat"),
+            Some(64_000)
+        );
+        assert_eq!(match_context_limit(&table, "gpt-4o"), None);
+    }
+
+    #[test]
+    fn test_parse_token_count() {
+        assert_eq!(parse_token_count("200k"), Ok(200_000));
+        assert_eq!(parse_token_count("1M"), Ok(1_000_000));
+        assert_eq!(parse_token_count("1.5m"), Ok(1_500_000));
+        assert_eq!(parse_token_count("1000000"), Ok(1_000_000));
+        assert_eq!(parse_token_count("200_000"), Ok(200_000));
+        assert!(parse_token_count("0").is_err());
+        assert!(parse_token_count("lots").is_err());
+        assert!(parse_token_count("1.0005k").is_err());
+    }
+
+    #[test]
+    fn test_builtin_context_limit() {
+        assert_eq!(
+            builtin_context_limit("claude-sonnet-4-20250514"),
+            Some(200_000)
+        );
+        assert_eq!(
+            builtin_context_limit("claude-sonnet-4-20250514[1m]"),
+            Some(1_000_000)
+        );
+        assert_eq!(builtin_context_limit("gpt-4.1-mini"), Some(1_047_576));
+        assert_eq!(
+            builtin_context_limit("openrouter/qwen/qwen3-coder"),
+            Some(262_144)
+        );
+        assert_eq!(builtin_context_limit("mystery-model"), None);
+        assert_eq!(builtin_context_limit(""), None);
+    }
+}
@@ -6,6 +6,8 @@ use super::types::Config;
 impl Default for Config {
     fn default() -> Self {
         // Use the theme presets as the source of truth
-        crate::ui::themes::ThemePresets::get_default()
+        let mut config = crate::ui::themes::ThemePresets::get_default();
+        crate::ui::themes::PlainIconSet::detect().apply(&mut config);
+        config
     }
 }
@@ -1,3 +1,4 @@
+use super::palette::{has_palette, parse_config, to_toml_keeping_palette};
 use super::types::Config;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -11,8 +12,7 @@ impl ConfigLoader {
 
     pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Config, Box<dyn std::error::Error>> {
         let content = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
-        Ok(config)
+        parse_config(&content)
     }
 
     /// Initialize themes directory and create built-in theme files
@@ -55,7 +55,7 @@ impl ConfigLoader {
 
     /// Get the themes directory path (~/.claude/ccline/themes/)
     pub fn get_themes_path() -> PathBuf {
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::utils::home_dir() {
             home.join(".claude").join("ccline").join("themes")
         } else {
             PathBuf::from(".claude/ccline/themes")
@@ -108,7 +108,11 @@ impl ConfigLoader {
         }
 
         let content = fs::read_to_string(theme_path)?;
-        let mut config: Config = toml::from_str(&content)?;
+        // Rewriting would replace the palette references with plain colors
+        if has_palette(&content) {
+            return Ok(false);
+        }
+        let mut config: Config = parse_config(&content)?;
         let mut needs_migration = false;
 
         // First, add missing options to existing segments
@@ -135,57 +139,30 @@ impl ConfigLoader {
                         needs_migration = true;
                     }
                 }
-                crate::config::SegmentId::BurnRate => {
-                    // Add missing options for BurnRate segment
-                    if !segment.options.contains_key("fast_loader") {
-                        segment
-                            .options
-                            .insert("fast_loader".to_string(), serde_json::json!(true));
-                        needs_migration = true;
-                    }
+                // Add missing options for BurnRate segment
+                crate::config::SegmentId::BurnRate
+                    if !segment.options.contains_key("fast_loader") =>
+                {
+                    segment
+                        .options
+                        .insert("fast_loader".to_string(), serde_json::json!(true));
+                    needs_migration = true;
                 }
                 _ => {}
             }
         }
 
-        // Then check if segments exist at all
-        let has_cost = config
-            .segments
-            .iter()
-            .any(|s| s.id == crate::config::SegmentId::Cost);
-        let has_burn_rate = config
-            .segments
-            .iter()
-            .any(|s| s.id == crate::config::SegmentId::BurnRate);
-
-        // Get the theme name from the file name
+        // Then add the segments it predates, disabled, with the built-in
+        // theme's options and colors
         let theme_name = theme_path
             .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("default");
-
-        // Get the complete theme configuration from presets
-        let complete_theme = crate::ui::themes::ThemePresets::get_theme(theme_name);
-
-        // Add missing segments (they will already have the correct options from the preset)
-        if !has_cost {
-            if let Some(cost_segment) = complete_theme
-                .segments
-                .iter()
-                .find(|s| s.id == crate::config::SegmentId::Cost)
-            {
-                config.segments.push(cost_segment.clone());
-                needs_migration = true;
-            }
-        }
-
-        if !has_burn_rate {
-            if let Some(burn_rate_segment) = complete_theme
-                .segments
-                .iter()
-                .find(|s| s.id == crate::config::SegmentId::BurnRate)
-            {
-                config.segments.push(burn_rate_segment.clone());
+        let mut complete_theme = crate::ui::themes::ThemePresets::get_builtin_theme(theme_name);
+        crate::ui::themes::PlainIconSet::detect().apply(&mut complete_theme);
+        for segment in complete_theme.segments {
+            if !config.segments.iter().any(|s| s.id == segment.id) {
+                config.segments.push(segment);
                 needs_migration = true;
             }
         }
@@ -234,10 +211,10 @@ impl Config {
         }
 
         let content = fs::read_to_string(config_path)?;
-        let mut config: Config = toml::from_str(&content)?;
+        let mut config: Config = parse_config(&content)?;
 
-        // Migrate config if needed
-        if Self::migrate_config_if_needed(&mut config)? {
+        // Migrate config if needed, keeping a palette-based file as written
+        if Self::migrate_config_if_needed(&mut config)? && !has_palette(&content) {
             // Save the migrated config
             config.save()?;
         }
@@ -273,51 +250,25 @@ impl Config {
                         needs_migration = true;
                     }
                 }
-                crate::config::SegmentId::BurnRate => {
-                    // Add missing options for BurnRate segment
-                    if !segment.options.contains_key("fast_loader") {
-                        segment
-                            .options
-                            .insert("fast_loader".to_string(), serde_json::json!(true));
-                        needs_migration = true;
-                    }
+                // Add missing options for BurnRate segment
+                crate::config::SegmentId::BurnRate
+                    if !segment.options.contains_key("fast_loader") =>
+                {
+                    segment
+                        .options
+                        .insert("fast_loader".to_string(), serde_json::json!(true));
+                    needs_migration = true;
                 }
                 _ => {}
             }
         }
 
-        // Then check if segments exist at all
-        let has_cost = config
-            .segments
-            .iter()
-            .any(|s| s.id == crate::config::SegmentId::Cost);
-        let has_burn_rate = config
-            .segments
-            .iter()
-            .any(|s| s.id == crate::config::SegmentId::BurnRate);
-
-        // Get the default theme configuration to get the missing segments
-        let default_config = crate::ui::themes::ThemePresets::get_default();
-
-        // Add missing segments (they will already have the correct options from the default)
-        if !has_cost {
-            if let Some(cost_segment) = default_config
-                .segments
-                .iter()
-                .find(|s| s.id == crate::config::SegmentId::Cost)
-            {
-                config.segments.push(cost_segment.clone());
-                needs_migration = true;
-            }
-        }
-
-        if !has_burn_rate {
-            if let Some(burn_rate_segment) = default_config
-                .segments
-                .iter()
-                .find(|s| s.id == crate::config::SegmentId::BurnRate)
-            {
-                config.segments.push(burn_rate_segment.clone());
+        // Then add the segments it predates from its theme, disabled, with
+        // the theme's options and colors
+        let theme = crate::ui::themes::ThemePresets::get_theme(&config.theme);
+        for segment in theme.segments {
+            if !config.segments.iter().any(|s| s.id == segment.id) {
+                config.segments.push(segment);
                 needs_migration = true;
             }
         }
@@ -334,14 +285,18 @@ impl Config {
             fs::create_dir_all(parent)?;
         }
 
-        let content = toml::to_string_pretty(self)?;
+        // Keep a hand-written `[palette]` and its `$name` references
+        let content = match fs::read_to_string(&config_path) {
+            Ok(previous) if has_palette(&previous) => to_toml_keeping_palette(self, &previous)?,
+            _ => toml::to_string_pretty(self)?,
+        };
         fs::write(config_path, content)?;
         Ok(())
     }
 
     /// Get the default config file path (~/.claude/ccline/config.toml)
     fn get_config_path() -> PathBuf {
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::utils::home_dir() {
             home.join(".claude").join("ccline").join("config.toml")
         } else {
             PathBuf::from(".claude/ccline/config.toml")
@@ -379,11 +334,87 @@ impl Config {
             return Err("No segments configured".into());
         }
 
-        // Validate segment IDs are unique
-        let mut seen_ids = std::collections::HashSet::new();
+        self.global.validate()?;
+
+        // Validate segment instances are unique; the same segment may appear
+        // more than once as long as each instance has its own name
+        let mut seen_keys = std::collections::HashSet::new();
+        for segment in &self.segments {
+            if !seen_keys.insert(segment.instance_key()) {
+                return Err(format!(
+                    "Duplicate segment instance: {} (give repeated segments a unique `name`)",
+                    segment.instance_key()
+                )
+                .into());
+            }
+        }
+
+        if self
+            .segments
+            .iter()
+            .any(|s| s.id == crate::config::SegmentId::Custom && s.name.is_none())
+        {
+            return Err(
+                "Custom segments need a `name` matching the key they were registered under".into(),
+            );
+        }
+
+        for segment in &self.segments {
+            if segment.id != crate::config::SegmentId::Plugin {
+                continue;
+            }
+            match crate::core::commands::plugin_command(segment) {
+                Some(name) if self.commands.contains_key(name) => {}
+                Some(name) => {
+                    return Err(format!(
+                        "Segment '{}' refers to undeclared command '{}' (add it under [commands])",
+                        segment.instance_key(),
+                        name
+                    )
+                    .into())
+                }
+                None => {
+                    return Err(format!(
+                        "Segment '{}' needs a `command` option naming a declared command",
+                        segment.instance_key()
+                    )
+                    .into())
+                }
+            }
+        }
+
         for segment in &self.segments {
-            if !seen_ids.insert(segment.id) {
-                return Err(format!("Duplicate segment ID: {:?}", segment.id).into());
+            crate::core::color_rules::parse_rules(segment)
+                .map_err(|e| format!("Segment '{}' {}", segment.instance_key(), e))?;
+
+            if segment.id == crate::config::SegmentId::BurnRate {
+                let thresholds = crate::billing::BurnRateThresholds::from_options(&segment.options);
+                let configured = ["high_threshold", "medium_threshold"]
+                    .iter()
+                    .any(|key| segment.options.contains_key(*key));
+                if configured && thresholds.medium >= thresholds.high {
+                    return Err(format!(
+                        "Segment '{}': medium_threshold must be below high_threshold",
+                        segment.instance_key()
+                    )
+                    .into());
+                }
+            }
+        }
+
+        for (name, command) in &self.commands {
+            command
+                .validate()
+                .map_err(|e| format!("Command '{}': {}", name, e))?;
+        }
+
+        for (event, hook) in self.hooks.iter() {
+            if !self.commands.contains_key(&hook.command) {
+                return Err(format!(
+                    "Hook '{}' refers to undeclared command '{}' (add it under [commands])",
+                    event, hook.command
+                )
+                .into());
             }
         }
 
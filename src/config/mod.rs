@@ -1,6 +1,8 @@
 pub mod block_overrides;
+pub mod context_limits;
 pub mod defaults;
 pub mod loader;
+pub mod palette;
 pub mod types;
 
 pub use block_overrides::*;
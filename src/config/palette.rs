@@ -0,0 +1,253 @@
+use super::types::Config;
+
+/// Parse a config or theme file, first replacing `"$name"` segment colors
+/// (including those of `color_rules`) with the colors of the same name in its
+/// `[palette]` table
+///
+/// Palette entries are `"#rrggbb"` hex strings, 256-color indexes or color
+/// tables such as `{ c16 = 4 }`. Segment colors may also be hex strings.
+pub fn parse_config(content: &str) -> Result<Config, Box<dyn std::error::Error>> {
+    let mut value: toml::Value = toml::from_str(content)?;
+    let palette = match value.as_table_mut().and_then(|t| t.remove("palette")) {
+        Some(toml::Value::Table(palette)) => palette,
+        Some(_) => return Err("[palette] must be a table of colors".into()),
+        None => toml::Table::new(),
+    };
+
+    let segments = value
+        .get_mut("segments")
+        .and_then(|s| s.as_array_mut())
+        .into_iter()
+        .flatten();
+    for segment in segments {
+        let id = segment
+            .get("id")
+            .and_then(|id| id.as_str())
+            .unwrap_or("?")
+            .to_string();
+        if let Some(colors) = segment.get_mut("colors").and_then(|c| c.as_table_mut()) {
+            for (slot, color) in colors.iter_mut() {
+                if let toml::Value::String(name) = color {
+                    *color = resolve(name, &palette)
+                        .map_err(|e| format!("Segment '{}' {} color: {}", id, slot, e))?;
+                }
+            }
+        }
+
+        // Colors of `color_rules` entries may use the palette as well
+        let rules = segment
+            .get_mut("options")
+            .and_then(|o| o.get_mut("color_rules"))
+            .and_then(|r| r.as_array_mut())
+            .into_iter()
+            .flatten();
+        for (i, rule) in rules.enumerate() {
+            let Some(rule) = rule.as_table_mut() else {
+                continue;
+            };
+            for slot in ["icon", "text", "background"] {
+                if let Some(toml::Value::String(name)) = rule.get(slot) {
+                    let color = resolve(name, &palette).map_err(|e| {
+                        format!("Segment '{}' color_rules[{}] {} color: {}", id, i, slot, e)
+                    })?;
+                    rule.insert(slot.to_string(), color);
+                }
+            }
+        }
+    }
+
+    Ok(value.try_into()?)
+}
+
+/// Whether a config or theme file defines a `[palette]`, which saving the
+/// parsed config would replace with plain colors
+pub fn has_palette(content: &str) -> bool {
+    toml::from_str::<toml::Table>(content).is_ok_and(|table| table.contains_key("palette"))
+}
+
+/// Serialize `config` to replace a file that currently reads `previous`,
+/// keeping its `[palette]` and writing each color that still resolves to the
+/// same value as the `$name` reference it was read from
+///
+/// Without this, saving from the TUI or `ccline context set` would flatten
+/// every reference into a plain color and drop the palette.
+pub fn to_toml_keeping_palette(
+    config: &Config,
+    previous: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut value = toml::Value::try_from(config)?;
+    let previous: toml::Value = toml::from_str(previous)?;
+    let Some(palette) = previous.get("palette").and_then(|p| p.as_table()) else {
+        return Ok(toml::to_string_pretty(config)?);
+    };
+    let previous_segments = previous
+        .get("segments")
+        .and_then(|s| s.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let segments = value
+        .get_mut("segments")
+        .and_then(|s| s.as_array_mut())
+        .into_iter()
+        .flatten();
+    for segment in segments {
+        // The same instance in the file: same `id` and `name`
+        let Some(before) = previous_segments.iter().find(|before| {
+            before.get("id") == segment.get("id") && before.get("name") == segment.get("name")
+        }) else {
+            continue;
+        };
+
+        if let (Some(colors), Some(before)) = (
+            segment.get_mut("colors").and_then(|c| c.as_table_mut()),
+            before.get("colors").and_then(|c| c.as_table()),
+        ) {
+            keep_references(colors, before, palette);
+        }
+
+        let rules = segment
+            .get_mut("options")
+            .and_then(|o| o.get_mut("color_rules"))
+            .and_then(|r| r.as_array_mut())
+            .into_iter()
+            .flatten();
+        let rules_before = before
+            .get("options")
+            .and_then(|o| o.get("color_rules"))
+            .and_then(|r| r.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for (rule, rule_before) in rules.zip(rules_before) {
+            if let (Some(rule), Some(rule_before)) = (rule.as_table_mut(), rule_before.as_table()) {
+                keep_references(rule, rule_before, palette);
+            }
+        }
+    }
+
+    if let Some(table) = value.as_table_mut() {
+        table.insert("palette".to_string(), toml::Value::Table(palette.clone()));
+    }
+    Ok(toml::to_string_pretty(&value)?)
+}
+
+/// Put back the `$name` strings of `before` whose color is unchanged in `colors`
+fn keep_references(colors: &mut toml::Table, before: &toml::Table, palette: &toml::Table) {
+    for (slot, color) in colors.iter_mut() {
+        let Some(reference) = before
+            .get(slot)
+            .and_then(|c| c.as_str())
+            .filter(|c| c.starts_with('$'))
+        else {
+            continue;
+        };
+        if resolve(reference, palette).is_ok_and(|resolved| resolved == *color) {
+            *color = toml::Value::String(reference.to_string());
+        }
+    }
+}
+
+/// A color table for a `$name` reference or a hex string
+fn resolve(color: &str, palette: &toml::Table) -> Result<toml::Value, String> {
+    let Some(name) = color.strip_prefix('$') else {
+        return hex_color(color);
+    };
+    match palette.get(name) {
+        Some(toml::Value::String(hex)) => hex_color(hex),
+        Some(toml::Value::Integer(index)) => {
+            let c256 = u8::try_from(*index)
+                .map_err(|_| format!("palette color '{}' must be 0-255", name))?;
+            Ok(toml::Value::Table(toml::Table::from_iter([(
+                "c256".to_string(),
+                toml::Value::Integer(c256.into()),
+            )])))
+        }
+        Some(table @ toml::Value::Table(_)) => Ok(table.clone()),
+        Some(_) => Err(format!("palette color '{}' has an unsupported value", name)),
+        None => Err(format!("no palette color named '{}'", name)),
+    }
+}
+
+/// `{ r, g, b }` for `#rrggbb`
+fn hex_color(hex: &str) -> Result<toml::Value, String> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    let channel = |i: usize| {
+        digits
+            .get(i..i + 2)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+    };
+    match (digits.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(r), Some(g), Some(b)) => Ok(toml::Value::Table(toml::Table::from_iter(
+            [("r", r), ("g", g), ("b", b)]
+                .map(|(k, v)| (k.to_string(), toml::Value::Integer(v.into()))),
+        ))),
+        _ => Err(format!("'{}' is not a #rrggbb color or $palette name", hex)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AnsiColor;
+
+    #[test]
+    fn test_palette_colors() {
+        let mut value = toml::Value::try_from(Config::default()).unwrap();
+        value["segments"][0]["colors"]
+            .as_table_mut()
+            .unwrap()
+            .insert("background".to_string(), "$rose".into());
+        value.as_table_mut().unwrap().insert(
+            "palette".to_string(),
+            toml::from_str("rose = \"#ebbcba\"").unwrap(),
+        );
+        let content = toml::to_string(&value).unwrap();
+        assert!(has_palette(&content));
+
+        let config = parse_config(&content).unwrap();
+        assert_eq!(
+            config.segments[0].colors.background,
+            Some(AnsiColor::Rgb {
+                r: 0xeb,
+                g: 0xbc,
+                b: 0xba
+            })
+        );
+
+        let mut with_rules = value.clone();
+        with_rules["segments"][0].as_table_mut().unwrap().insert(
+            "options".to_string(),
+            toml::from_str("color_rules = [{ min = 80, text = \"$rose\" }]").unwrap(),
+        );
+        let config = parse_config(&toml::to_string(&with_rules).unwrap()).unwrap();
+        assert_eq!(
+            config.segments[0].options["color_rules"][0]["text"],
+            serde_json::json!({"r": 0xeb, "g": 0xbc, "b": 0xba})
+        );
+
+        // Saving keeps the palette and the unchanged reference
+        let saved =
+            to_toml_keeping_palette(&config, &toml::to_string(&with_rules).unwrap()).unwrap();
+        assert!(has_palette(&saved));
+        assert!(saved.contains("\"$rose\""), "{}", saved);
+        assert_eq!(
+            parse_config(&saved).unwrap().segments[0].options["color_rules"],
+            config.segments[0].options["color_rules"]
+        );
+        let mut recolored = config.clone();
+        recolored.segments[0].colors.background = Some(AnsiColor::Color16 { c16: 4 });
+        let saved =
+            to_toml_keeping_palette(&recolored, &toml::to_string(&with_rules).unwrap()).unwrap();
+        assert_eq!(saved.matches("\"$rose\"").count(), 1, "{}", saved);
+
+        let unknown = content.replace("$rose", "$pine");
+        let error = parse_config(&unknown).unwrap_err().to_string();
+        assert!(error.contains("no palette color named 'pine'"), "{}", error);
+
+        assert!(resolve("#12345", &toml::Table::new()).is_err());
+        assert_eq!(
+            resolve("$blue", &toml::from_str("blue = 33").unwrap()).unwrap(),
+            toml::from_str::<toml::Value>("c256 = 33").unwrap()
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 // Main config structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,6 +10,13 @@ pub struct Config {
     pub theme: String,
     #[serde(default)]
     pub global: GlobalConfig,
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
+    #[serde(default, skip_serializing_if = "PricingConfig::is_default")]
+    pub pricing: PricingConfig,
+    /// External programs ccline may run, keyed by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub commands: BTreeMap<String, CommandConfig>,
 }
 
 // Default implementation moved to ui/themes/presets.rs
@@ -17,12 +25,73 @@ pub struct Config {
 pub struct GlobalConfig {
     #[serde(default = "default_context_limit")]
     pub context_limit: u32,
+    /// Resolve the context limit from the current model, falling back to
+    /// `context_limit`; turned off by `ccline --context-limit <TOKENS>`.
+    /// When unset it is on unless `context_limit` was changed by hand
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_context_limit: Option<bool>,
+    /// Per-model limits keyed by model id glob, e.g. `"claude-sonnet-4*" = 1000000`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub context_limits: BTreeMap<String, u32>,
+    /// Opt-in JSON-lines log of per-render usage snapshots
+    #[serde(default, skip_serializing_if = "MetricsLogConfig::is_default")]
+    pub metrics_log: MetricsLogConfig,
+    /// How subagent usage counts toward session and daily costs
+    #[serde(default, skip_serializing_if = "SubagentMode::is_default")]
+    pub subagents: SubagentMode,
+    /// Move segments reporting a `critical` severity to the front of the line
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub urgency_sort: bool,
+    /// Timezone for daily and monthly totals, reports and block start
+    /// overrides: `local` (default), `utc` or an offset such as `+09:00`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Recurring block start times for days without a `--set-block-start`
+    /// override
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block_schedule: Vec<super::block_overrides::BlockScheduleRule>,
+    /// Transcript roots read in addition to the default Claude directories
+    /// and `CLAUDE_CONFIG_DIR`, e.g. synced or mounted archives
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transcript_dirs: Vec<TranscriptDir>,
+    /// Add usage from OpenAI Codex CLI session logs (`~/.codex/sessions`) to
+    /// daily, monthly and rolling totals and reports; billing blocks, limits and
+    /// burn rate stay Claude-only
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_codex: bool,
+    /// Add usage from Gemini CLI chats (`~/.gemini/tmp`) to daily, monthly
+    /// and rolling totals and reports; billing blocks, limits and burn rate
+    /// stay Claude-only
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_gemini: bool,
+    /// Print the previous statusline without collecting segments while the
+    /// config, input and transcript are unchanged for this many seconds
+    /// (0, the default, renders every time)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub render_cache_secs: u64,
+    /// Time budget for collecting segments; expensive segments still running
+    /// after this many milliseconds show their last known value or
+    /// placeholder instead (0, the default, waits for every segment)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub max_render_ms: u64,
 }
 
 impl Default for GlobalConfig {
     fn default() -> Self {
         Self {
             context_limit: default_context_limit(),
+            auto_context_limit: None,
+            context_limits: BTreeMap::new(),
+            metrics_log: MetricsLogConfig::default(),
+            subagents: SubagentMode::default(),
+            urgency_sort: false,
+            timezone: None,
+            block_schedule: Vec::new(),
+            transcript_dirs: Vec::new(),
+            include_codex: false,
+            include_gemini: false,
+            render_cache_secs: 0,
+            max_render_ms: 0,
         }
     }
 }
@@ -33,18 +102,275 @@ impl GlobalConfig {
         if self.context_limit == 0 {
             return Err("Context limit must be greater than 0".to_string());
         }
+        for (pattern, limit) in &self.context_limits {
+            glob::Pattern::new(pattern)
+                .map_err(|e| format!("Invalid context limit pattern '{}': {}", pattern, e))?;
+            if *limit == 0 {
+                return Err(format!(
+                    "Context limit for '{}' must be greater than 0",
+                    pattern
+                ));
+            }
+        }
+        if let Some(timezone) = &self.timezone {
+            crate::utils::timezone::parse_timezone(timezone)?;
+        }
+        for rule in &self.block_schedule {
+            rule.validate()?;
+        }
+        if self
+            .transcript_dirs
+            .iter()
+            .any(|dir| dir.path.trim().is_empty())
+        {
+            return Err("Transcript directory paths must not be empty".to_string());
+        }
         Ok(())
     }
+
+    /// Whether the context limit follows the current model
+    pub fn auto_context_limit(&self) -> bool {
+        self.auto_context_limit
+            .unwrap_or(self.context_limit == default_context_limit())
+    }
+
+    /// Context limit to use for the given model id: a matching `context_limits`
+    /// entry, then the model's detected window when `auto_context_limit` is set,
+    /// then `context_limit`
+    pub fn context_limit_for(&self, model_id: &str) -> u32 {
+        if let Some(limit) =
+            super::context_limits::match_context_limit(&self.context_limits, model_id)
+        {
+            return limit;
+        }
+        if self.auto_context_limit() {
+            if let Some(limit) = super::context_limits::detect_context_limit(model_id) {
+                return limit;
+            }
+        }
+        self.context_limit
+    }
 }
 
 fn default_context_limit() -> u32 {
     200000
 }
 
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// Extra transcript root (`[[global.transcript_dirs]]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptDir {
+    /// A Claude data directory holding `projects`, or a `projects` directory
+    /// itself; a leading `~` is the home directory
+    pub path: String,
+    /// Name shown for the directory in traces and `ccline --check`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl TranscriptDir {
+    /// The `projects` directory to scan
+    pub fn projects_dir(&self) -> PathBuf {
+        let path = self.path.trim();
+        let path = match path.strip_prefix("~") {
+            Some(rest) => match crate::utils::home_dir() {
+                Some(home) => home.join(rest.trim_start_matches(['/', '\\'])),
+                None => PathBuf::from(path),
+            },
+            None => PathBuf::from(path),
+        };
+        let projects = path.join("projects");
+        if projects.is_dir() {
+            projects
+        } else {
+            path
+        }
+    }
+}
+
+/// Per-render usage snapshot log (`[global.metrics_log]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsLogConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Log file, defaults to ~/.claude/ccline/metrics.jsonl
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Size at which the log is rotated to `<path>.1`
+    #[serde(default = "default_metrics_max_bytes")]
+    pub max_bytes: u64,
+    /// Number of rotated files to keep
+    #[serde(default = "default_metrics_keep")]
+    pub keep: u32,
+}
+
+impl Default for MetricsLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            max_bytes: default_metrics_max_bytes(),
+            keep: default_metrics_keep(),
+        }
+    }
+}
+
+impl MetricsLogConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn resolved_path(&self) -> Option<PathBuf> {
+        self.path.clone().or_else(|| {
+            crate::utils::home_dir()
+                .map(|home| home.join(".claude").join("ccline").join("metrics.jsonl"))
+        })
+    }
+}
+
+fn default_metrics_max_bytes() -> u64 {
+    5 * 1024 * 1024
+}
+
+fn default_metrics_keep() -> u32 {
+    3
+}
+
+/// Where model pricing data is downloaded from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PricingConfig {
+    /// Primary LiteLLM pricing JSON URL
+    #[serde(default = "default_pricing_url")]
+    pub url: String,
+    /// Fallback URLs tried in order when the primary URL fails
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// Explicit proxy URL; `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` are honored when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Per-request timeout in seconds
+    #[serde(default = "default_pricing_timeout")]
+    pub timeout_secs: u64,
+}
+
+impl Default for PricingConfig {
+    fn default() -> Self {
+        Self {
+            url: default_pricing_url(),
+            mirrors: Vec::new(),
+            proxy: None,
+            timeout_secs: default_pricing_timeout(),
+        }
+    }
+}
+
+impl PricingConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Primary URL followed by mirrors
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.url.as_str()).chain(self.mirrors.iter().map(String::as_str))
+    }
+}
+
+fn default_pricing_url() -> String {
+    crate::billing::pricing::LITELLM_PRICING_URL.to_string()
+}
+
+fn default_pricing_timeout() -> u64 {
+    10
+}
+
+/// An external program declared up front so it can be audited and approved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandConfig {
+    /// Absolute path to the executable (no shell or PATH lookup is involved)
+    pub path: PathBuf,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// The process is killed once this many seconds have passed
+    #[serde(default = "default_command_timeout")]
+    pub timeout_secs: u64,
+    /// Environment variables passed through; everything else is scrubbed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+}
+
+impl CommandConfig {
+    /// Check the declaration is safe to run
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.path.is_absolute() {
+            return Err(format!(
+                "path must be absolute, got '{}'",
+                self.path.display()
+            ));
+        }
+        if self.timeout_secs == 0 {
+            return Err("timeout_secs must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn default_command_timeout() -> u64 {
+    5
+}
+
+/// Declared commands run when usage thresholds are crossed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Fires when the active 5-hour block has fewer than `threshold` minutes left (default 15)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_expiring: Option<HookConfig>,
+    /// Fires when today's total cost exceeds `threshold` dollars
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_cost_over: Option<HookConfig>,
+    /// Fires when the burn rate exceeds `threshold` tokens/minute (default: the
+    /// BurnRate segment's `high_threshold`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burn_rate_high: Option<HookConfig>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.block_expiring.is_none()
+            && self.daily_cost_over.is_none()
+            && self.burn_rate_high.is_none()
+    }
+
+    /// Configured hooks with their event names
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &HookConfig)> {
+        [
+            ("block_expiring", self.block_expiring.as_ref()),
+            ("daily_cost_over", self.daily_cost_over.as_ref()),
+            ("burn_rate_high", self.burn_rate_high.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(event, hook)| hook.map(|h| (event, h)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookConfig {
+    /// Name of an entry in `[commands]`
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StyleConfig {
     pub mode: StyleMode,
     pub separator: String,
+    /// Lighten or darken text and icon colors that are hard to read on their
+    /// segment's background
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_contrast: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -53,11 +379,15 @@ pub enum StyleMode {
     Plain,     // emoji + 颜色
     NerdFont,  // Nerd Font 图标 + 颜色
     Powerline, // 未来支持
+    Ascii,     // ASCII 标签，无图标字形
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SegmentConfig {
     pub id: SegmentId,
+    /// Optional instance name, allowing the same segment to be configured more than once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     pub enabled: bool,
     pub icon: IconConfig,
     pub colors: ColorConfig,
@@ -78,9 +408,136 @@ pub struct ColorConfig {
     pub background: Option<AnsiColor>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct TextStyleConfig {
     pub text_bold: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub text_dim: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub text_italic: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub text_underline: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub text_strikethrough: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub text_blink: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub icon_bold: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub icon_dim: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub icon_italic: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub icon_underline: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub icon_strikethrough: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub icon_blink: bool,
+}
+
+/// A text attribute a segment can turn on for its icon or its text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextStyle {
+    Bold,
+    Dim,
+    Italic,
+    Underline,
+    Strikethrough,
+    Blink,
+}
+
+impl TextStyle {
+    pub const ALL: [TextStyle; 6] = [
+        TextStyle::Bold,
+        TextStyle::Dim,
+        TextStyle::Italic,
+        TextStyle::Underline,
+        TextStyle::Strikethrough,
+        TextStyle::Blink,
+    ];
+
+    /// SGR parameter turning the attribute on
+    pub fn sgr(&self) -> &'static str {
+        match self {
+            TextStyle::Bold => "1",
+            TextStyle::Dim => "2",
+            TextStyle::Italic => "3",
+            TextStyle::Underline => "4",
+            TextStyle::Blink => "5",
+            TextStyle::Strikethrough => "9",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TextStyle::Bold => "Bold",
+            TextStyle::Dim => "Dim",
+            TextStyle::Italic => "Italic",
+            TextStyle::Underline => "Underline",
+            TextStyle::Strikethrough => "Strikethrough",
+            TextStyle::Blink => "Blink",
+        }
+    }
+}
+
+impl TextStyleConfig {
+    /// The flag for `style` on the icon (`icon = true`) or the text
+    pub fn flag_mut(&mut self, icon: bool, style: TextStyle) -> &mut bool {
+        match (icon, style) {
+            (false, TextStyle::Bold) => &mut self.text_bold,
+            (false, TextStyle::Dim) => &mut self.text_dim,
+            (false, TextStyle::Italic) => &mut self.text_italic,
+            (false, TextStyle::Underline) => &mut self.text_underline,
+            (false, TextStyle::Strikethrough) => &mut self.text_strikethrough,
+            (false, TextStyle::Blink) => &mut self.text_blink,
+            (true, TextStyle::Bold) => &mut self.icon_bold,
+            (true, TextStyle::Dim) => &mut self.icon_dim,
+            (true, TextStyle::Italic) => &mut self.icon_italic,
+            (true, TextStyle::Underline) => &mut self.icon_underline,
+            (true, TextStyle::Strikethrough) => &mut self.icon_strikethrough,
+            (true, TextStyle::Blink) => &mut self.icon_blink,
+        }
+    }
+
+    pub fn is_set(&self, icon: bool, style: TextStyle) -> bool {
+        match (icon, style) {
+            (false, TextStyle::Bold) => self.text_bold,
+            (false, TextStyle::Dim) => self.text_dim,
+            (false, TextStyle::Italic) => self.text_italic,
+            (false, TextStyle::Underline) => self.text_underline,
+            (false, TextStyle::Strikethrough) => self.text_strikethrough,
+            (false, TextStyle::Blink) => self.text_blink,
+            (true, TextStyle::Bold) => self.icon_bold,
+            (true, TextStyle::Dim) => self.icon_dim,
+            (true, TextStyle::Italic) => self.icon_italic,
+            (true, TextStyle::Underline) => self.icon_underline,
+            (true, TextStyle::Strikethrough) => self.icon_strikethrough,
+            (true, TextStyle::Blink) => self.icon_blink,
+        }
+    }
+
+    /// SGR parameters of the attributes turned on for the icon or the text
+    pub fn sgr_codes(&self, icon: bool) -> Vec<&'static str> {
+        TextStyle::ALL
+            .iter()
+            .filter(|style| self.is_set(icon, **style))
+            .map(TextStyle::sgr)
+            .collect()
+    }
+
+    /// Short summary such as `Bold, Italic`, or `-` when nothing is set
+    pub fn describe(&self, icon: bool) -> String {
+        let names: Vec<_> = TextStyle::ALL
+            .iter()
+            .filter(|style| self.is_set(icon, **style))
+            .map(TextStyle::display_name)
+            .collect();
+        if names.is_empty() {
+            "-".to_string()
+        } else {
+            names.join(", ")
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -101,6 +558,148 @@ pub enum SegmentId {
     Update,
     Cost,
     BurnRate,
+    Block,
+    BlockLimit,
+    Weekly,
+    ProjectCost,
+    LastTurn,
+    SessionStats,
+    ApiErrors,
+    Condensed,
+    /// Output of an external program declared under `[commands]`
+    Plugin,
+    /// A segment registered by a library user, looked up by instance name
+    Custom,
+}
+
+impl SegmentId {
+    /// Every segment that works without user-supplied code (everything but
+    /// `Plugin` and `Custom`)
+    pub const BUILTIN: [SegmentId; 15] = [
+        SegmentId::Model,
+        SegmentId::Directory,
+        SegmentId::Git,
+        SegmentId::Usage,
+        SegmentId::Update,
+        SegmentId::Cost,
+        SegmentId::BurnRate,
+        SegmentId::Block,
+        SegmentId::BlockLimit,
+        SegmentId::Weekly,
+        SegmentId::ProjectCost,
+        SegmentId::LastTurn,
+        SegmentId::SessionStats,
+        SegmentId::ApiErrors,
+        SegmentId::Condensed,
+    ];
+
+    /// Whether collecting can take long enough to hold up the statusline
+    /// (scanning usage history or a whole transcript, fetching prices,
+    /// running git or a command);
+    /// under `max_render_ms` these are collected in the background
+    pub fn is_expensive(&self) -> bool {
+        matches!(
+            self,
+            SegmentId::Git
+                | SegmentId::Cost
+                | SegmentId::BurnRate
+                | SegmentId::Block
+                | SegmentId::BlockLimit
+                | SegmentId::Weekly
+                | SegmentId::ProjectCost
+                | SegmentId::LastTurn
+                | SegmentId::Condensed
+                | SegmentId::SessionStats
+                | SegmentId::Plugin
+        )
+    }
+
+    /// Identifier as written in config files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SegmentId::Model => "model",
+            SegmentId::Directory => "directory",
+            SegmentId::Git => "git",
+            SegmentId::Usage => "usage",
+            SegmentId::Update => "update",
+            SegmentId::Cost => "cost",
+            SegmentId::BurnRate => "burn_rate",
+            SegmentId::Block => "block",
+            SegmentId::BlockLimit => "block_limit",
+            SegmentId::Weekly => "weekly",
+            SegmentId::ProjectCost => "project_cost",
+            SegmentId::LastTurn => "last_turn",
+            SegmentId::SessionStats => "session_stats",
+            SegmentId::ApiErrors => "api_errors",
+            SegmentId::Condensed => "condensed",
+            SegmentId::Plugin => "plugin",
+            SegmentId::Custom => "custom",
+        }
+    }
+
+    /// Human-readable name shown in the TUI
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SegmentId::Model => "Model",
+            SegmentId::Directory => "Directory",
+            SegmentId::Git => "Git",
+            SegmentId::Usage => "Usage",
+            SegmentId::Update => "Update",
+            SegmentId::Cost => "Cost",
+            SegmentId::BurnRate => "BurnRate",
+            SegmentId::Block => "Block",
+            SegmentId::BlockLimit => "Block Limit",
+            SegmentId::Weekly => "Weekly",
+            SegmentId::ProjectCost => "Project Cost",
+            SegmentId::LastTurn => "Last Turn",
+            SegmentId::SessionStats => "Session Stats",
+            SegmentId::ApiErrors => "API Errors",
+            SegmentId::Condensed => "Condensed",
+            SegmentId::Plugin => "Plugin",
+            SegmentId::Custom => "Custom",
+        }
+    }
+
+    /// Short ASCII label standing in for the icon in `ascii` style mode
+    pub fn ascii_label(&self) -> &'static str {
+        match self {
+            SegmentId::Model => "model",
+            SegmentId::Directory => "dir",
+            SegmentId::Git => "git",
+            SegmentId::Usage => "ctx",
+            SegmentId::Update => "update",
+            SegmentId::Cost => "cost",
+            SegmentId::BurnRate => "burn",
+            SegmentId::Block => "block",
+            SegmentId::BlockLimit => "limit",
+            SegmentId::Weekly => "week",
+            SegmentId::ProjectCost => "project",
+            SegmentId::LastTurn => "turn",
+            SegmentId::SessionStats => "stats",
+            SegmentId::ApiErrors => "err",
+            SegmentId::Condensed => "",
+            SegmentId::Plugin => "plugin",
+            SegmentId::Custom => "",
+        }
+    }
+}
+
+impl SegmentConfig {
+    /// Key identifying this segment instance, e.g. `usage` or `usage:detail`
+    pub fn instance_key(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{}:{}", self.id.as_str(), name),
+            None => self.id.as_str().to_string(),
+        }
+    }
+
+    /// Display name including the instance name, e.g. `Usage (detail)`
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} ({})", self.id.display_name(), name),
+            None => self.id.display_name().to_string(),
+        }
+    }
 }
 
 // Cost source strategy for CostSegment
@@ -114,6 +713,25 @@ pub enum CostSource {
     Both,       // Show both native and calculated costs
 }
 
+/// How usage from subagent (sidechain) transcripts is attributed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SubagentMode {
+    /// Counted as part of the session that launched the subagent
+    #[default]
+    Include,
+    /// Left out of session and daily costs entirely
+    Exclude,
+    /// Counted in daily totals but kept apart from the parent session
+    Separate,
+}
+
+impl SubagentMode {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 // Legacy compatibility structure
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SegmentsConfig {
@@ -131,18 +749,71 @@ fn default_true() -> bool {
     true
 }
 
-// Data structures compatible with existing main.rs
-#[derive(Deserialize)]
+// Statusline input sent by Claude Code on stdin
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Model {
+    #[serde(default)]
+    pub id: String,
     pub display_name: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Workspace {
     pub current_dir: String,
+    /// Directory Claude Code was started in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_dir: Option<String>,
+    /// Extra directories Claude Code was given with `--add-dir`
+    #[serde(
+        default,
+        alias = "additional_directories",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub added_dirs: Vec<String>,
 }
 
-#[derive(Deserialize)]
+impl Workspace {
+    /// Every root of the workspace: the project directory (or the current
+    /// one when unknown) followed by the added directories, without repeats
+    pub fn roots(&self) -> Vec<&str> {
+        let primary = self
+            .project_dir
+            .as_deref()
+            .filter(|dir| !dir.is_empty())
+            .unwrap_or(&self.current_dir);
+        let mut roots = vec![primary];
+        for dir in &self.added_dirs {
+            let dir = dir.trim_end_matches(['/', '\\']);
+            if !dir.is_empty()
+                && !roots
+                    .iter()
+                    .any(|root| root.trim_end_matches(['/', '\\']) == dir)
+            {
+                roots.push(dir);
+            }
+        }
+        roots
+    }
+
+    /// Index into [`roots`](Self::roots) of the root containing the current
+    /// directory, preferring the deepest when roots are nested
+    pub fn active_root(&self) -> Option<usize> {
+        let current = Path::new(&self.current_dir);
+        self.roots()
+            .iter()
+            .enumerate()
+            .filter(|(_, root)| current.starts_with(root))
+            .max_by_key(|(_, root)| root.len())
+            .map(|(index, _)| index)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OutputStyle {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct InputData {
     pub model: Model,
     pub workspace: Workspace,
@@ -151,6 +822,81 @@ pub struct InputData {
     pub session_id: Option<String>,
     #[serde(default)]
     pub cost: Option<SessionCost>,
+    /// Claude Code version, e.g. `1.0.80`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_style: Option<OutputStyle>,
+    /// Whether the last response used more than 200k tokens of context
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exceeds_200k_tokens: Option<bool>,
+    /// `default`, `acceptEdits`, `plan` or `bypassPermissions`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook_event_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Fields not known to this version of ccline, kept for generic lookups
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl InputData {
+    /// Start building input by hand, for embedding without Claude Code's JSON
+    pub fn builder() -> InputDataBuilder {
+        InputDataBuilder::default()
+    }
+}
+
+/// Builder for [`InputData`]; unset fields keep their defaults
+#[derive(Debug, Clone, Default)]
+pub struct InputDataBuilder {
+    input: InputData,
+}
+
+impl InputDataBuilder {
+    pub fn model(mut self, id: impl Into<String>, display_name: impl Into<String>) -> Self {
+        self.input.model = Model {
+            id: id.into(),
+            display_name: display_name.into(),
+        };
+        self
+    }
+
+    pub fn current_dir(mut self, dir: impl Into<String>) -> Self {
+        self.input.workspace.current_dir = dir.into();
+        self
+    }
+
+    pub fn project_dir(mut self, dir: impl Into<String>) -> Self {
+        self.input.workspace.project_dir = Some(dir.into());
+        self
+    }
+
+    pub fn transcript_path(mut self, path: impl Into<String>) -> Self {
+        self.input.transcript_path = path.into();
+        self
+    }
+
+    pub fn session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.input.session_id = Some(session_id.into());
+        self
+    }
+
+    pub fn cost(mut self, cost: SessionCost) -> Self {
+        self.input.cost = Some(cost);
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.input.version = Some(version.into());
+        self
+    }
+
+    pub fn build(self) -> InputData {
+        self.input
+    }
 }
 
 // Session cost information from Claude Code
@@ -171,9 +917,9 @@ pub struct SessionCost {
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct PromptTokensDetails {
     #[serde(default)]
-    pub cached_tokens: Option<u32>,
+    pub cached_tokens: Option<u64>,
     #[serde(default)]
-    pub audio_tokens: Option<u32>,
+    pub audio_tokens: Option<u64>,
 }
 
 // Raw usage data from different LLM providers (flexible parsing)
@@ -181,22 +927,22 @@ pub struct PromptTokensDetails {
 pub struct RawUsage {
     // Common input token naming variants
     #[serde(default, alias = "prompt_tokens")]
-    pub input_tokens: Option<u32>,
+    pub input_tokens: Option<u64>,
 
     // Common output token naming variants
     #[serde(default, alias = "completion_tokens")]
-    pub output_tokens: Option<u32>,
+    pub output_tokens: Option<u64>,
 
     // Total tokens (some providers only provide this)
     #[serde(default)]
-    pub total_tokens: Option<u32>,
+    pub total_tokens: Option<u64>,
 
     // Anthropic-style cache fields
     #[serde(default, alias = "cache_creation_prompt_tokens")]
-    pub cache_creation_input_tokens: Option<u32>,
+    pub cache_creation_input_tokens: Option<u64>,
 
     #[serde(default, alias = "cache_read_prompt_tokens")]
-    pub cache_read_input_tokens: Option<u32>,
+    pub cache_read_input_tokens: Option<u64>,
 
     // OpenAI-style nested details
     #[serde(default)]
@@ -204,14 +950,15 @@ pub struct RawUsage {
 
     // Completion token details (OpenAI)
     #[serde(default)]
-    pub completion_tokens_details: Option<HashMap<String, u32>>,
+    pub completion_tokens_details: Option<HashMap<String, u64>>,
 
     // Catch unknown fields for future compatibility and debugging
     #[serde(flatten, skip_serializing)]
     pub extra: HashMap<String, serde_json::Value>,
 }
 
-// Normalized internal representation after processing
+// Normalized internal representation after processing; counts are per message
+// and clamped to u32, totals across messages are u64 in `billing`
 #[derive(Debug, Clone, Serialize, Default, PartialEq)]
 pub struct NormalizedUsage {
     pub input_tokens: u32,
@@ -231,9 +978,9 @@ impl NormalizedUsage {
     /// Output tokens from this turn will become input tokens in the next turn
     pub fn context_tokens(&self) -> u32 {
         self.input_tokens
-            + self.cache_creation_input_tokens
-            + self.cache_read_input_tokens
-            + self.output_tokens
+            .saturating_add(self.cache_creation_input_tokens)
+            .saturating_add(self.cache_read_input_tokens)
+            .saturating_add(self.output_tokens)
     }
 
     /// Get total tokens for cost calculation
@@ -243,9 +990,9 @@ impl NormalizedUsage {
             self.total_tokens
         } else {
             self.input_tokens
-                + self.output_tokens
-                + self.cache_creation_input_tokens
-                + self.cache_read_input_tokens
+                .saturating_add(self.output_tokens)
+                .saturating_add(self.cache_creation_input_tokens)
+                .saturating_add(self.cache_read_input_tokens)
         }
     }
 
@@ -270,6 +1017,19 @@ impl NormalizedUsage {
 }
 
 impl Config {
+    /// Hand the config's pricing, timezone, block schedule, transcript
+    /// directories and other-tool switches to the modules that read them.
+    /// [`Pipeline::new`](crate::core::Pipeline::new) does this for every
+    /// render; commands that skip the pipeline call it themselves
+    pub fn apply_global_settings(&self) {
+        crate::billing::pricing::set_pricing_config(&self.pricing);
+        crate::utils::timezone::set_timezone(self.global.timezone.as_deref());
+        super::block_overrides::set_block_schedule(&self.global.block_schedule);
+        crate::utils::data_dirs::set_transcript_dirs(&self.global.transcript_dirs);
+        crate::utils::codex::set_enabled(self.global.include_codex);
+        crate::utils::gemini::set_enabled(self.global.include_gemini);
+    }
+
     /// Check if current config matches the specified theme preset
     pub fn matches_theme(&self, theme_name: &str) -> bool {
         let theme_preset = crate::ui::themes::ThemePresets::get_theme(theme_name);
@@ -296,6 +1056,44 @@ impl Config {
         true
     }
 
+    /// Apply a theme's visuals (style, icons, colors, text styles) over this config.
+    /// Global settings, segment order and enabled flags are preserved. User option
+    /// values are kept unless `reset_options` is set, in which case the theme's
+    /// options replace them.
+    pub fn apply_theme(&mut self, theme: &Config, reset_options: bool) {
+        self.style = theme.style.clone();
+        self.theme = theme.theme.clone();
+
+        for segment in self.segments.iter_mut() {
+            if let Some(themed) = theme.segments.iter().find(|s| s.id == segment.id) {
+                segment.icon = themed.icon.clone();
+                segment.colors = themed.colors.clone();
+                segment.styles = themed.styles.clone();
+                if reset_options {
+                    segment.options = themed.options.clone();
+                } else {
+                    segment.options = merge_options(&themed.options, &segment.options);
+                }
+            }
+        }
+    }
+
+    /// Carry global settings and user-set segment options over from a previous
+    /// config, used when a whole theme config replaces the current one
+    pub fn preserve_options_from(&mut self, previous: &Config) {
+        self.global = previous.global.clone();
+        self.hooks = previous.hooks.clone();
+        self.pricing = previous.pricing.clone();
+        self.commands = previous.commands.clone();
+
+        for segment in self.segments.iter_mut() {
+            let key = segment.instance_key();
+            if let Some(old) = previous.segments.iter().find(|s| s.instance_key() == key) {
+                segment.options = merge_options(&segment.options, &old.options);
+            }
+        }
+    }
+
     /// Check if current config has been modified from the selected theme
     pub fn is_modified_from_theme(&self) -> bool {
         !self.matches_theme(&self.theme)
@@ -310,7 +1108,7 @@ impl Config {
             && self.color_matches(&current.colors.icon, &preset.colors.icon)
             && self.color_matches(&current.colors.text, &preset.colors.text)
             && self.color_matches(&current.colors.background, &preset.colors.background)
-            && current.styles.text_bold == preset.styles.text_bold
+            && current.styles == preset.styles
             && current.options == preset.options
     }
 
@@ -324,6 +1122,74 @@ impl Config {
     }
 }
 
+/// Overlay user option values on top of a theme's options, so options the user
+/// never set still pick up the theme's defaults
+fn merge_options(
+    theme_options: &HashMap<String, serde_json::Value>,
+    user_options: &HashMap<String, serde_json::Value>,
+) -> HashMap<String, serde_json::Value> {
+    let mut merged = theme_options.clone();
+    merged.extend(
+        user_options
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone())),
+    );
+    merged
+}
+
+impl AnsiColor {
+    /// ANSI escape sequence setting this color as the foreground
+    pub fn foreground_code(&self) -> String {
+        match self {
+            AnsiColor::Color16 { c16 } => {
+                let code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
+                format!("\x1b[{}m", code)
+            }
+            AnsiColor::Color256 { c256 } => format!("\x1b[38;5;{}m", c256),
+            AnsiColor::Rgb { r, g, b } => format!("\x1b[38;2;{};{};{}m", r, g, b),
+        }
+    }
+
+    /// Approximate RGB value using the standard xterm palette
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        const BASIC: [(u8, u8, u8); 16] = [
+            (0, 0, 0),
+            (205, 0, 0),
+            (0, 205, 0),
+            (205, 205, 0),
+            (0, 0, 238),
+            (205, 0, 205),
+            (0, 205, 205),
+            (229, 229, 229),
+            (127, 127, 127),
+            (255, 0, 0),
+            (0, 255, 0),
+            (255, 255, 0),
+            (92, 92, 255),
+            (255, 0, 255),
+            (0, 255, 255),
+            (255, 255, 255),
+        ];
+
+        match self {
+            AnsiColor::Color16 { c16 } => BASIC[(*c16 as usize) % 16],
+            AnsiColor::Color256 { c256 } => match *c256 {
+                c @ 0..=15 => BASIC[c as usize],
+                c @ 16..=231 => {
+                    let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+                    let c = c - 16;
+                    (level(c / 36), level((c / 6) % 6), level(c % 6))
+                }
+                c => {
+                    let gray = 8 + (c - 232) * 10;
+                    (gray, gray, gray)
+                }
+            },
+            AnsiColor::Rgb { r, g, b } => (*r, *g, *b),
+        }
+    }
+}
+
 impl PartialEq for AnsiColor {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -372,29 +1238,31 @@ impl RawUsage {
 
         result.raw_data_available = available_fields;
 
-        // Extract directly available values
-        let input = self.input_tokens.unwrap_or(0);
-        let output = self.output_tokens.unwrap_or(0);
-        let total = self.total_tokens.unwrap_or(0);
+        // Extract directly available values; a single message never gets near
+        // u32::MAX, so larger values are clamped rather than rejected
+        let saturate = |tokens: Option<u64>| tokens.unwrap_or(0).min(u32::MAX as u64) as u32;
+        let input = saturate(self.input_tokens);
+        let output = saturate(self.output_tokens);
+        let total = saturate(self.total_tokens);
 
         // Handle cache tokens with fallback to OpenAI nested format
-        let cache_read = self
-            .cache_read_input_tokens
-            .or_else(|| {
-                self.prompt_tokens_details
-                    .as_ref()
-                    .and_then(|d| d.cached_tokens)
-            })
-            .unwrap_or(0);
+        let cache_read = saturate(self.cache_read_input_tokens.or_else(|| {
+            self.prompt_tokens_details
+                .as_ref()
+                .and_then(|d| d.cached_tokens)
+        }));
 
-        let cache_creation = self.cache_creation_input_tokens.unwrap_or(0);
+        let cache_creation = saturate(self.cache_creation_input_tokens);
 
         // Token calculation logic - prioritize total_tokens for OpenAI format
         let total_value = if total > 0 {
             sources.push("total_tokens_direct".to_string());
             total
         } else if input > 0 || output > 0 || cache_read > 0 || cache_creation > 0 {
-            let calculated = input + output + cache_read + cache_creation;
+            let calculated = input
+                .saturating_add(output)
+                .saturating_add(cache_read)
+                .saturating_add(cache_creation);
             sources.push("total_from_components".to_string());
             calculated
         } else {
@@ -434,6 +1302,10 @@ pub struct TranscriptEntry {
     pub timestamp: Option<String>,
     #[serde(default, alias = "costUSD")]
     pub cost_usd: Option<f64>,
+    #[serde(default, alias = "sessionId")]
+    pub session_id: Option<String>,
+    #[serde(default, alias = "isSidechain")]
+    pub is_sidechain: Option<bool>,
 }
 
 #[cfg(test)]
@@ -446,17 +1318,53 @@ mod tests {
         assert_eq!(config.context_limit, 200000);
     }
 
+    #[test]
+    fn test_input_data_full_schema() {
+        let input: InputData = serde_json::from_str(
+            r#"{
+                "hook_event_name": "Status",
+                "session_id": "abc123",
+                "transcript_path": "/tmp/abc123.jsonl",
+                "cwd": "/work/app/src",
+                "model": { "id": "claude-opus-4-1", "display_name": "Opus" },
+                "workspace": { "current_dir": "/work/app/src", "project_dir": "/work/app" },
+                "version": "1.0.80",
+                "output_style": { "name": "Explanatory" },
+                "cost": { "total_cost_usd": 0.01234, "total_lines_added": 156 },
+                "exceeds_200k_tokens": false,
+                "permission_mode": "plan",
+                "future_field": { "enabled": true }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(input.version.as_deref(), Some("1.0.80"));
+        assert_eq!(input.workspace.project_dir.as_deref(), Some("/work/app"));
+        assert_eq!(input.exceeds_200k_tokens, Some(false));
+        assert_eq!(input.output_style.unwrap().name, "Explanatory");
+        assert_eq!(input.cost.unwrap().total_lines_added, Some(156));
+        assert_eq!(input.permission_mode.as_deref(), Some("plan"));
+        assert_eq!(
+            input.extra["future_field"],
+            serde_json::json!({ "enabled": true })
+        );
+    }
+
     #[test]
     fn test_global_config_validate_valid() {
         let config = GlobalConfig {
             context_limit: 100000,
+            ..Default::default()
         };
         assert!(config.validate().is_ok());
     }
 
     #[test]
     fn test_global_config_validate_zero() {
-        let config = GlobalConfig { context_limit: 0 };
+        let config = GlobalConfig {
+            context_limit: 0,
+            ..Default::default()
+        };
         assert!(config.validate().is_err());
         assert_eq!(
             config.validate().unwrap_err(),
@@ -467,7 +1375,10 @@ mod tests {
     #[test]
     fn test_global_config_validate_small_value() {
         // Even 1 is valid, we only check for 0
-        let config = GlobalConfig { context_limit: 1 };
+        let config = GlobalConfig {
+            context_limit: 1,
+            ..Default::default()
+        };
         assert!(config.validate().is_ok());
     }
 
@@ -475,7 +1386,182 @@ mod tests {
     fn test_global_config_validate_large_value() {
         let config = GlobalConfig {
             context_limit: u32::MAX,
+            ..Default::default()
         };
         assert!(config.validate().is_ok());
     }
+
+    #[test]
+    fn test_global_config_validate_context_limits() {
+        let mut config = GlobalConfig::default();
+        config
+            .context_limits
+            .insert("claude-sonnet-4*".to_string(), 1_000_000);
+        assert!(config.validate().is_ok());
+
+        config
+            .context_limits
+            .insert("claude-[".to_string(), 500_000);
+        assert!(config.validate().is_err());
+        config.context_limits.remove("claude-[");
+
+        config
+            .context_limits
+            .insert("claude-opus-4*".to_string(), 0);
+        assert_eq!(
+            config.validate().unwrap_err(),
+            "Context limit for 'claude-opus-4*' must be greater than 0"
+        );
+    }
+
+    #[test]
+    fn test_context_limit_for_model() {
+        let mut config = GlobalConfig {
+            context_limit: 150_000,
+            auto_context_limit: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(config.context_limit_for("claude-sonnet-4[1m]"), 150_000);
+
+        // A hand-set limit wins unless auto is turned on explicitly
+        config.auto_context_limit = None;
+        assert_eq!(config.context_limit_for("claude-sonnet-4[1m]"), 150_000);
+        config.context_limit = 200_000;
+        assert_eq!(config.context_limit_for("claude-sonnet-4[1m]"), 1_000_000);
+        config.context_limit = 150_000;
+
+        config.auto_context_limit = Some(true);
+        assert_eq!(config.context_limit_for("claude-sonnet-4[1m]"), 1_000_000);
+        assert_eq!(config.context_limit_for("unknown-model"), 150_000);
+
+        config
+            .context_limits
+            .insert("unknown-*".to_string(), 64_000);
+        assert_eq!(config.context_limit_for("unknown-model"), 64_000);
+    }
+
+    #[test]
+    fn test_apply_theme_preserves_global_and_options() {
+        use crate::ui::themes::ThemePresets;
+
+        let mut config = ThemePresets::get_default();
+        config.global.context_limit = 1_000_000;
+        let cost = config
+            .segments
+            .iter_mut()
+            .find(|s| s.id == SegmentId::Cost)
+            .unwrap();
+        cost.enabled = true;
+        cost.options
+            .insert("cost_source".to_string(), serde_json::json!("native"));
+
+        let theme = ThemePresets::get_minimal();
+        config.apply_theme(&theme, false);
+
+        assert_eq!(config.theme, "minimal");
+        assert_eq!(config.style.mode, theme.style.mode);
+        assert_eq!(config.global.context_limit, 1_000_000);
+
+        let cost = config
+            .segments
+            .iter()
+            .find(|s| s.id == SegmentId::Cost)
+            .unwrap();
+        let themed_cost = theme
+            .segments
+            .iter()
+            .find(|s| s.id == SegmentId::Cost)
+            .unwrap();
+        assert!(cost.enabled);
+        assert_eq!(cost.options["cost_source"], serde_json::json!("native"));
+        assert_eq!(cost.icon.plain, themed_cost.icon.plain);
+    }
+
+    #[test]
+    fn test_segment_instance_key() {
+        use crate::ui::themes::ThemePresets;
+
+        let mut usage = ThemePresets::get_default()
+            .segments
+            .into_iter()
+            .find(|s| s.id == SegmentId::Usage)
+            .unwrap();
+        assert_eq!(usage.instance_key(), "usage");
+        assert_eq!(usage.display_name(), "Usage");
+
+        usage.name = Some("detail".to_string());
+        assert_eq!(usage.instance_key(), "usage:detail");
+        assert_eq!(usage.display_name(), "Usage (detail)");
+    }
+
+    #[test]
+    fn test_apply_theme_reset_options() {
+        use crate::ui::themes::ThemePresets;
+
+        let mut config = ThemePresets::get_default();
+        for segment in config.segments.iter_mut() {
+            segment
+                .options
+                .insert("fast_loader".to_string(), serde_json::json!(false));
+        }
+
+        let theme = ThemePresets::get_nord();
+        config.apply_theme(&theme, true);
+
+        for segment in &config.segments {
+            let themed = theme.segments.iter().find(|s| s.id == segment.id).unwrap();
+            assert_eq!(segment.options, themed.options);
+        }
+    }
+
+    #[test]
+    fn test_preserve_options_from_previous_config() {
+        use crate::ui::themes::ThemePresets;
+
+        let mut previous = ThemePresets::get_default();
+        previous.global.context_limit = 500_000;
+        let git = previous
+            .segments
+            .iter_mut()
+            .find(|s| s.id == SegmentId::Git)
+            .unwrap();
+        git.options
+            .insert("show_sha".to_string(), serde_json::json!(true));
+
+        let mut next = ThemePresets::get_gruvbox();
+        next.preserve_options_from(&previous);
+
+        assert_eq!(next.theme, "gruvbox");
+        assert_eq!(next.global.context_limit, 500_000);
+        let git = next
+            .segments
+            .iter()
+            .find(|s| s.id == SegmentId::Git)
+            .unwrap();
+        assert_eq!(git.options["show_sha"], serde_json::json!(true));
+    }
+
+    #[test]
+    fn test_validate_thresholds_only_on_burn_rate() {
+        let mut config = Config::default();
+        let model = config
+            .segments
+            .iter_mut()
+            .find(|s| s.id == SegmentId::Model)
+            .unwrap();
+        model
+            .options
+            .insert("high_threshold".to_string(), serde_json::json!(10));
+        assert!(config.check().is_ok());
+
+        let burn_rate = config
+            .segments
+            .iter_mut()
+            .find(|s| s.id == SegmentId::BurnRate)
+            .unwrap();
+        burn_rate
+            .options
+            .insert("high_threshold".to_string(), serde_json::json!(10));
+        assert!(config.check().is_err());
+    }
 }
@@ -0,0 +1,125 @@
+use crate::config::{InputData, SegmentConfig, SegmentId};
+use crate::core::segments::SegmentData;
+use crate::utils::{extract_session_id, state_file};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const LEDGER_FILE: &str = "branch_ledger.json";
+
+/// A git branch seen by a session from `since` until the session's next record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BranchRecord {
+    pub since: DateTime<Utc>,
+    pub repo: PathBuf,
+    pub branch: String,
+}
+
+/// Which git branch each session was on over time (~/.claude/ccline/branch_ledger.json)
+///
+/// A record is only added when a session's repository or branch changes, so the
+/// file stays small even though the statusline runs constantly.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BranchLedger {
+    #[serde(default)]
+    sessions: BTreeMap<String, Vec<BranchRecord>>,
+}
+
+impl BranchLedger {
+    pub fn load() -> Self {
+        state_file::load(LEDGER_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        state_file::save(LEDGER_FILE, &serde_json::to_string(self)?)
+    }
+
+    /// Note the session's current branch, returning whether anything changed
+    pub fn record(
+        &mut self,
+        session_id: &str,
+        repo: &Path,
+        branch: &str,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let records = self.sessions.entry(session_id.to_string()).or_default();
+        if records
+            .last()
+            .is_some_and(|last| last.repo == repo && last.branch == branch)
+        {
+            return false;
+        }
+        records.push(BranchRecord {
+            since: now,
+            repo: repo.to_path_buf(),
+            branch: branch.to_string(),
+        });
+        true
+    }
+
+    /// The branch a session was on at `timestamp`; activity before the first
+    /// record is attributed to the first branch seen
+    pub fn branch_at(&self, session_id: &str, timestamp: DateTime<Utc>) -> Option<&BranchRecord> {
+        let records = self.sessions.get(session_id)?;
+        records
+            .iter()
+            .rev()
+            .find(|record| record.since <= timestamp)
+            .or_else(|| records.first())
+    }
+}
+
+/// The enclosing git work tree of `dir`, found by looking for `.git` upwards
+pub fn find_repo_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|candidate| candidate.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Record the branch reported by the Git segment for this invocation's session
+pub fn record_branch(input: &InputData, segments: &[(SegmentConfig, SegmentData)]) {
+    let Some(branch) = segments
+        .iter()
+        .find(|(config, _)| config.id == SegmentId::Git)
+        .and_then(|(_, data)| data.metadata.get("branch"))
+    else {
+        return;
+    };
+    let Some(repo) = find_repo_root(Path::new(&input.workspace.current_dir)) else {
+        return;
+    };
+
+    let session_id = extract_session_id(Path::new(&input.transcript_path));
+    let mut ledger = BranchLedger::load();
+    if ledger.record(&session_id, &repo, branch, Utc::now()) {
+        let _ = ledger.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_ledger_records_changes_and_attributes_time() {
+        let mut ledger = BranchLedger::default();
+        let repo = Path::new("/work/app");
+        let start = Utc::now() - Duration::hours(2);
+
+        assert!(ledger.record("s1", repo, "main", start));
+        assert!(!ledger.record("s1", repo, "main", start + Duration::minutes(5)));
+        assert!(ledger.record("s1", repo, "refactor", start + Duration::hours(1)));
+
+        let branch = |offset: Duration| {
+            ledger
+                .branch_at("s1", start + offset)
+                .map(|r| r.branch.as_str())
+        };
+        assert_eq!(branch(Duration::minutes(-10)), Some("main"));
+        assert_eq!(branch(Duration::minutes(30)), Some("main"));
+        assert_eq!(branch(Duration::minutes(90)), Some("refactor"));
+        assert!(ledger.branch_at("other", start).is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::config::{AnsiColor, SegmentConfig, SegmentId};
+use crate::core::segments::SegmentData;
+use serde::Deserialize;
+
+/// One entry of a segment's `color_rules` option: colors used while a numeric
+/// metadata value lies in `[min, max)`
+///
+/// ```toml
+/// [segments.options]
+/// color_rules = [
+///   { max = 50, text = { c16 = 2 } },
+///   { min = 50, max = 80, text = { c16 = 3 } },
+///   { min = 80, text = { c16 = 1 }, icon = { c16 = 1 } },
+/// ]
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColorRule {
+    /// Metadata key to compare, defaulting to the segment's main number
+    pub metric: Option<String>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub icon: Option<AnsiColor>,
+    pub text: Option<AnsiColor>,
+    pub background: Option<AnsiColor>,
+}
+
+impl ColorRule {
+    fn matches(&self, value: f64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value < max)
+    }
+}
+
+/// Metadata value `color_rules` compare when a rule names no `metric`
+pub fn default_metric(id: SegmentId) -> Option<&'static str> {
+    match id {
+        SegmentId::Usage => Some("percentage"),
+        SegmentId::BurnRate => Some("cost_per_hour"),
+        SegmentId::BlockLimit => Some("block_limit_percent"),
+        SegmentId::Weekly => Some("week_limit_percent"),
+        SegmentId::ProjectCost => Some("project_percent"),
+        SegmentId::LastTurn => Some("last_turn_cost"),
+        _ => None,
+    }
+}
+
+/// The segment's `color_rules`, or an error naming what is wrong with them
+pub fn parse_rules(config: &SegmentConfig) -> Result<Vec<ColorRule>, String> {
+    let Some(value) = config.options.get("color_rules") else {
+        return Ok(Vec::new());
+    };
+    let rules: Vec<ColorRule> =
+        serde_json::from_value(value.clone()).map_err(|e| format!("color_rules: {}", e))?;
+    for (i, rule) in rules.iter().enumerate() {
+        if rule.metric.is_none() && default_metric(config.id).is_none() {
+            return Err(format!(
+                "color_rules[{}] needs a `metric` naming the value to compare",
+                i
+            ));
+        }
+        if let (Some(min), Some(max)) = (rule.min, rule.max) {
+            if min >= max {
+                return Err(format!("color_rules[{}]: min must be below max", i));
+            }
+        }
+    }
+    Ok(rules)
+}
+
+/// Replace the segment's colors with those of its first `color_rules` entry
+/// matching the collected data, recording the rule's index as `color_rule`
+/// metadata
+///
+/// Rules with a metric the segment did not report are skipped; invalid rules
+/// are reported by `ccline --check` and ignored here.
+pub fn apply((mut config, mut data): (SegmentConfig, SegmentData)) -> (SegmentConfig, SegmentData) {
+    let Ok(rules) = parse_rules(&config) else {
+        return (config, data);
+    };
+    let value = |rule: &ColorRule| {
+        let metric = rule.metric.as_deref().or(default_metric(config.id))?;
+        data.metadata.get(metric)?.trim().parse::<f64>().ok()
+    };
+    let Some((index, rule)) = rules
+        .iter()
+        .enumerate()
+        .find(|(_, rule)| value(rule).is_some_and(|v| rule.matches(v)))
+    else {
+        return (config, data);
+    };
+
+    if let Some(icon) = &rule.icon {
+        config.colors.icon = Some(icon.clone());
+    }
+    if let Some(text) = &rule.text {
+        config.colors.text = Some(text.clone());
+    }
+    if let Some(background) = &rule.background {
+        config.colors.background = Some(background.clone());
+    }
+    data.metadata
+        .insert("color_rule".to_string(), index.to_string());
+    (config, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use serde_json::json;
+
+    #[test]
+    fn test_color_rules() {
+        let mut usage = Config::default()
+            .segments
+            .into_iter()
+            .find(|s| s.id == SegmentId::Usage)
+            .unwrap();
+        usage.options.insert(
+            "color_rules".to_string(),
+            json!([
+                {"max": 50, "text": {"c16": 2}},
+                {"min": 50, "max": 80, "text": {"c16": 3}},
+                {"min": 80, "text": {"c16": 1}, "background": {"c256": 52}}
+            ]),
+        );
+        let at = |percentage: &str| {
+            let mut data = SegmentData {
+                primary: String::new(),
+                secondary: String::new(),
+                metadata: Default::default(),
+            };
+            data.metadata
+                .insert("percentage".to_string(), percentage.to_string());
+            apply((usage.clone(), data))
+        };
+
+        let (config, data) = at("42.5");
+        assert_eq!(config.colors.text, Some(AnsiColor::Color16 { c16: 2 }));
+        assert_eq!(
+            data.metadata.get("color_rule").map(String::as_str),
+            Some("0")
+        );
+        assert_eq!(at("50").0.colors.text, Some(AnsiColor::Color16 { c16: 3 }));
+        let (config, _) = at("91");
+        assert_eq!(config.colors.text, Some(AnsiColor::Color16 { c16: 1 }));
+        assert_eq!(
+            config.colors.background,
+            Some(AnsiColor::Color256 { c256: 52 })
+        );
+
+        // No metric value, no change
+        let (config, data) = at("n/a");
+        assert_eq!(config.colors.text, usage.colors.text);
+        assert!(!data.metadata.contains_key("color_rule"));
+
+        usage
+            .options
+            .insert("color_rules".to_string(), json!([{"min": 9, "max": 1}]));
+        assert!(parse_rules(&usage).is_err());
+        usage.id = SegmentId::Git;
+        usage
+            .options
+            .insert("color_rules".to_string(), json!([{"min": 1}]));
+        assert!(parse_rules(&usage).unwrap_err().contains("metric"));
+    }
+}
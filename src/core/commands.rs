@@ -0,0 +1,444 @@
+use crate::config::{CommandConfig, Config, SegmentConfig, SegmentId};
+use crate::report::table::{ColumnKind, Table, TablePalette};
+use crate::utils::state_file;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+const APPROVALS_FILE: &str = "approved_commands.json";
+
+/// Approval state of a declared command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandStatus {
+    Approved,
+    /// Never approved with `ccline audit --approve <name>`
+    Unapproved,
+    /// Approved once, but the declaration has changed since
+    Changed,
+    Invalid(String),
+}
+
+impl CommandStatus {
+    pub fn label(&self) -> String {
+        match self {
+            CommandStatus::Approved => "approved".to_string(),
+            CommandStatus::Unapproved => "not approved".to_string(),
+            CommandStatus::Changed => "changed since approval".to_string(),
+            CommandStatus::Invalid(reason) => format!("invalid: {}", reason),
+        }
+    }
+}
+
+/// Command declarations the user has approved (~/.claude/ccline/approved_commands.json)
+///
+/// The full declaration is stored, so editing a command's path, arguments or
+/// environment in the config requires approving it again.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandApprovals {
+    #[serde(default)]
+    approved: BTreeMap<String, CommandConfig>,
+}
+
+impl CommandApprovals {
+    pub fn load() -> Self {
+        state_file::load(APPROVALS_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        state_file::save(APPROVALS_FILE, &serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn status(&self, name: &str, command: &CommandConfig) -> CommandStatus {
+        if let Err(reason) = command.validate() {
+            return CommandStatus::Invalid(reason);
+        }
+        match self.approved.get(name) {
+            Some(approved) if approved == command => CommandStatus::Approved,
+            Some(_) => CommandStatus::Changed,
+            None => CommandStatus::Unapproved,
+        }
+    }
+
+    pub fn approve(&mut self, name: &str, command: &CommandConfig) {
+        self.approved.insert(name.to_string(), command.clone());
+    }
+}
+
+/// Run a declared command with a scrubbed environment, killing it on timeout
+///
+/// Only the variables listed in the declaration plus `extra_env` are visible to
+/// the process; output is discarded.
+pub fn run_command(
+    command: &CommandConfig,
+    extra_env: &[(String, String)],
+) -> Result<ExitStatus, Box<dyn std::error::Error>> {
+    let mut child = spawn(command, extra_env, Stdio::null(), Stdio::null())?;
+    wait_with_timeout(&mut child, command)
+}
+
+/// Run a declared command like [`run_command`], writing `input` to its stdin
+/// and returning its stdout; a non-zero exit is an error
+pub fn run_command_with_io(
+    command: &CommandConfig,
+    extra_env: &[(String, String)],
+    input: &[u8],
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut child = spawn(command, extra_env, Stdio::piped(), Stdio::piped())?;
+
+    // Feed stdin and drain stdout on threads so a chatty or stubborn process
+    // cannot block us past the timeout
+    let mut stdin = child.stdin.take().ok_or("stdin not captured")?;
+    let input = input.to_vec();
+    std::thread::spawn(move || {
+        let _ = stdin.write_all(&input);
+    });
+    let mut stdout = child.stdout.take().ok_or("stdout not captured")?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+    });
+
+    let status = wait_with_timeout(&mut child, command)?;
+    let output = reader.join().map_err(|_| "failed to read command output")?;
+    if !status.success() {
+        return Err(format!("'{}' exited with {}", command.path.display(), status).into());
+    }
+    Ok(output)
+}
+
+fn spawn(
+    command: &CommandConfig,
+    extra_env: &[(String, String)],
+    stdin: Stdio,
+    stdout: Stdio,
+) -> Result<Child, Box<dyn std::error::Error>> {
+    command.validate()?;
+
+    let passthrough = command
+        .env
+        .iter()
+        .filter_map(|name| std::env::var(name).ok().map(|value| (name.clone(), value)));
+
+    Ok(Command::new(&command.path)
+        .args(&command.args)
+        .env_clear()
+        .envs(passthrough)
+        .envs(extra_env.iter().cloned())
+        .stdin(stdin)
+        .stdout(stdout)
+        .stderr(Stdio::null())
+        .spawn()?)
+}
+
+fn wait_with_timeout(
+    child: &mut Child,
+    command: &CommandConfig,
+) -> Result<ExitStatus, Box<dyn std::error::Error>> {
+    let deadline = Instant::now() + Duration::from_secs(command.timeout_secs);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!(
+                "'{}' timed out after {}s",
+                command.path.display(),
+                command.timeout_secs
+            )
+            .into());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Run a declared command in a detached `ccline run-command` process so the caller
+/// never waits on it; the helper re-checks approval and enforces the timeout
+///
+/// When the library is embedded in another program the command runs on a
+/// background thread of that program instead.
+pub fn spawn_detached(
+    name: &str,
+    command: &CommandConfig,
+    extra_env: &[(String, String)],
+) -> std::io::Result<()> {
+    let Some(exe) = crate::utils::ccline_exe() else {
+        let command = command.clone();
+        let extra_env = extra_env.to_vec();
+        std::thread::spawn(move || {
+            if let Err(e) = run_command(&command, &extra_env) {
+                crate::debug_println!("Command failed: {}", e);
+            }
+        });
+        return Ok(());
+    };
+    Command::new(exe)
+        .arg("run-command")
+        .arg(name)
+        .envs(extra_env.iter().cloned())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// Name of the declared command a `plugin` segment runs (its `command` option)
+pub fn plugin_command(segment: &SegmentConfig) -> Option<&str> {
+    segment.options.get("command").and_then(|v| v.as_str())
+}
+
+/// List every external command ccline is configured to run (`ccline audit`),
+/// optionally approving all valid declared commands
+pub fn audit(
+    config: &Config,
+    approve: Option<&[String]>,
+    all: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let to_approve = approval_targets(config, approve, all)?;
+    let mut approvals = CommandApprovals::load();
+    let mut table = Table::new(
+        &[
+            ("Name", ColumnKind::Text),
+            ("Command", ColumnKind::Text),
+            ("Timeout", ColumnKind::Text),
+            ("Env", ColumnKind::Text),
+            ("Used By", ColumnKind::Text),
+            ("Status", ColumnKind::Text),
+        ],
+        TablePalette::from_config(config),
+    );
+
+    for (name, command) in &config.commands {
+        if to_approve.contains(&name.as_str()) && command.validate().is_ok() {
+            approvals.approve(name, command);
+        }
+
+        let used_by: Vec<String> = config
+            .hooks
+            .iter()
+            .filter(|(_, hook)| &hook.command == name)
+            .map(|(event, _)| format!("hook:{}", event))
+            .chain(
+                config
+                    .segments
+                    .iter()
+                    .filter(|s| s.id == SegmentId::Plugin && plugin_command(s) == Some(name))
+                    .map(|s| format!("segment:{}", s.instance_key())),
+            )
+            .collect();
+
+        table.add_row(vec![
+            name.clone(),
+            std::iter::once(command.path.display().to_string())
+                .chain(command.args.iter().cloned())
+                .collect::<Vec<_>>()
+                .join(" "),
+            format!("{}s", command.timeout_secs),
+            if command.env.is_empty() {
+                "-".to_string()
+            } else {
+                command.env.join(",")
+            },
+            if used_by.is_empty() {
+                "-".to_string()
+            } else {
+                used_by.join(", ")
+            },
+            approvals.status(name, command).label(),
+        ]);
+    }
+
+    for (event, hook) in config.hooks.iter() {
+        if !config.commands.contains_key(&hook.command) {
+            table.add_row(vec![
+                hook.command.clone(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                format!("hook:{}", event),
+                "undeclared (will not run)".to_string(),
+            ]);
+        }
+    }
+
+    for segment in config.segments.iter().filter(|s| s.id == SegmentId::Plugin) {
+        if let Some(name) = plugin_command(segment).filter(|n| !config.commands.contains_key(*n)) {
+            table.add_row(vec![
+                name.to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                format!("segment:{}", segment.instance_key()),
+                "undeclared (will not run)".to_string(),
+            ]);
+        }
+    }
+
+    // Built-in integrations that shell out to tools found on PATH
+    if config
+        .segments
+        .iter()
+        .any(|s| s.id == SegmentId::Git && s.enabled)
+    {
+        table.add_row(vec![
+            "git".to_string(),
+            "git (from PATH)".to_string(),
+            "-".to_string(),
+            "inherited".to_string(),
+            "segment:git".to_string(),
+            "built-in".to_string(),
+        ]);
+    }
+
+    if table.is_empty() {
+        println!("ccline is not configured to run any external commands");
+    } else {
+        println!("{}", table.render());
+    }
+
+    if !to_approve.is_empty() {
+        approvals.save()?;
+        let approved: Vec<&str> = to_approve
+            .iter()
+            .copied()
+            .filter(|name| {
+                approvals.status(name, &config.commands[*name]) == CommandStatus::Approved
+            })
+            .collect();
+        println!("\n✓ Approved {}", approved.join(", "));
+    } else if config
+        .commands
+        .iter()
+        .any(|(name, command)| approvals.status(name, command) != CommandStatus::Approved)
+    {
+        println!(
+            "\nRun `ccline audit --approve <name>` (or `--approve --all`) to allow declared commands to run"
+        );
+    }
+
+    Ok(())
+}
+
+/// Names of the declared commands `--approve` should approve; approving
+/// everything takes an explicit `--all` so it is never done by accident
+fn approval_targets<'a>(
+    config: &'a Config,
+    approve: Option<&'a [String]>,
+    all: bool,
+) -> Result<Vec<&'a str>, Box<dyn std::error::Error>> {
+    let Some(names) = approve else {
+        return Ok(Vec::new());
+    };
+    if all {
+        return Ok(config.commands.keys().map(String::as_str).collect());
+    }
+    if names.is_empty() {
+        return Err(
+            "Name the commands to approve, or pass --all to approve every declared command".into(),
+        );
+    }
+    for name in names {
+        if !config.commands.contains_key(name) {
+            return Err(format!("Command '{}' is not declared under [commands]", name).into());
+        }
+    }
+    Ok(names.iter().map(String::as_str).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn command(path: &str) -> CommandConfig {
+        CommandConfig {
+            path: PathBuf::from(path),
+            args: vec![],
+            timeout_secs: 5,
+            env: vec![],
+        }
+    }
+
+    #[test]
+    fn test_command_status() {
+        let mut approvals = CommandApprovals::default();
+        let notify = command("/usr/bin/notify-send");
+
+        assert_eq!(
+            approvals.status("notify", &notify),
+            CommandStatus::Unapproved
+        );
+        approvals.approve("notify", &notify);
+        assert_eq!(approvals.status("notify", &notify), CommandStatus::Approved);
+
+        let mut edited = notify.clone();
+        edited.args.push("--urgency=critical".to_string());
+        assert_eq!(approvals.status("notify", &edited), CommandStatus::Changed);
+
+        assert!(matches!(
+            approvals.status("notify", &command("notify-send")),
+            CommandStatus::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn test_approval_targets() {
+        let mut config = Config::default();
+        config
+            .commands
+            .insert("notify".to_string(), command("/usr/bin/notify-send"));
+        config
+            .commands
+            .insert("log".to_string(), command("/usr/bin/logger"));
+
+        assert!(approval_targets(&config, None, false).unwrap().is_empty());
+        // A bare --approve no longer approves everything
+        assert!(approval_targets(&config, Some(&[]), false).is_err());
+        assert_eq!(
+            approval_targets(&config, Some(&["notify".to_string()]), false).unwrap(),
+            vec!["notify"]
+        );
+        assert!(approval_targets(&config, Some(&["missing".to_string()]), false).is_err());
+        assert_eq!(approval_targets(&config, Some(&[]), true).unwrap().len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_timeout_and_env() {
+        let mut sleep = command("/bin/sh");
+        sleep.args = vec!["-c".to_string(), "sleep 5".to_string()];
+        sleep.timeout_secs = 1;
+        let started = Instant::now();
+        assert!(run_command(&sleep, &[]).is_err());
+        assert!(started.elapsed() < Duration::from_secs(4));
+
+        // HOME is scrubbed unless declared, CCLINE_* values are passed explicitly
+        let mut check = command("/bin/sh");
+        check.args = vec![
+            "-c".to_string(),
+            "[ -z \"$HOME\" ] && [ \"$CCLINE_EVENT\" = test ]".to_string(),
+        ];
+        let env = [("CCLINE_EVENT".to_string(), "test".to_string())];
+        assert!(run_command(&check, &env).unwrap().success());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command_with_io() {
+        let mut upper = command("/bin/sh");
+        upper.args = vec!["-c".to_string(), "tr a-z A-Z".to_string()];
+        assert_eq!(
+            run_command_with_io(&upper, &[], b"hello").unwrap(),
+            b"HELLO"
+        );
+
+        let mut failing = command("/bin/sh");
+        failing.args = vec!["-c".to_string(), "exit 3".to_string()];
+        assert!(run_command_with_io(&failing, &[], b"").is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::config::{Config, InputData, SegmentConfig};
+use crate::core::segments::SegmentData;
+use crate::core::{Pipeline, StatusLineGenerator};
+use crate::utils::width::{display_width, strip_ansi};
+
+/// A generated statusline together with the segment data behind it
+#[derive(Debug, Clone)]
+pub struct RenderedLine {
+    /// The line as ccline prints it, with ANSI colors
+    pub text: String,
+    /// Collected segments in render order
+    pub segments: Vec<(SegmentConfig, SegmentData)>,
+}
+
+impl RenderedLine {
+    /// The line without ANSI escape sequences
+    pub fn plain(&self) -> String {
+        strip_ansi(&self.text)
+    }
+
+    /// Terminal columns the line takes, counting wide characters (CJK,
+    /// emoji) as two, for aligning it with other output
+    pub fn display_width(&self) -> usize {
+        display_width(&self.text)
+    }
+
+    /// Data of the first segment with the given config identifier (`usage`,
+    /// `git`, ...)
+    pub fn segment(&self, id: &str) -> Option<&SegmentData> {
+        self.segments
+            .iter()
+            .find(|(config, _)| config.id.as_str() == id)
+            .map(|(_, data)| data)
+    }
+}
+
+/// Collect and render a statusline in-process, as `ccline` does for the JSON
+/// Claude Code pipes to it
+///
+/// Side effects of the binary (threshold hooks, branch ledger, metrics log)
+/// are left to the caller. Use [`Pipeline`] directly to add hooks.
+pub fn generate(config: &Config, input: &InputData) -> RenderedLine {
+    let segments = Pipeline::new(config).collect(input);
+    let text = StatusLineGenerator::new(config.clone()).generate(segments.clone());
+    RenderedLine { text, segments }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AnsiColor, SegmentId};
+
+    #[test]
+    fn test_generate_in_process() {
+        let mut config = Config::default();
+        config
+            .segments
+            .retain(|s| matches!(s.id, SegmentId::Model | SegmentId::Directory));
+        let model = &mut config.segments[0];
+        model.colors.text = Some(AnsiColor::Color16 { c16: 14 });
+        model.colors.background = None;
+        model.styles = Default::default();
+        let input = InputData::builder()
+            .model("claude-sonnet-4", "Sonnet 4")
+            .current_dir("/home/user/my-project")
+            .build();
+
+        let line = generate(&config, &input);
+        assert!(line.plain().contains("Sonnet 4"));
+        assert!(line.plain().contains("my-project"));
+        assert_eq!(line.display_width(), line.plain().chars().count());
+        // The text keeps the segment's colors
+        assert!(line.text.contains("\x1b[96mSonnet 4\x1b[0m"));
+        assert_eq!(line.segment("model").unwrap().primary, "Sonnet 4");
+        assert!(line.segment("usage").is_none());
+    }
+}
@@ -0,0 +1,268 @@
+use crate::billing::BurnRateThresholds;
+use crate::config::{Config, HookConfig, HooksConfig, SegmentConfig, SegmentId};
+use crate::core::commands::{spawn_detached, CommandApprovals, CommandStatus};
+use crate::core::segments::SegmentData;
+use crate::utils::state_file;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const DEFAULT_EXPIRING_MINUTES: f64 = 15.0;
+const STATE_FILE: &str = "hooks_state.json";
+
+/// Usage events that can trigger a hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    BlockExpiring,
+    DailyCostOver,
+    BurnRateHigh,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::BlockExpiring => "block_expiring",
+            HookEvent::DailyCostOver => "daily_cost_over",
+            HookEvent::BurnRateHigh => "burn_rate_high",
+        }
+    }
+}
+
+/// Usage figures reported by the collected segments
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    /// Identifies the active block; falls back to the local date when no block is active
+    pub block_key: String,
+    pub block_remaining: Option<f64>,
+    pub daily_cost: Option<f64>,
+    pub tokens_per_minute: Option<f64>,
+    /// High burn threshold of the BurnRate segment, the default for `burn_rate_high`
+    pub burn_high: f64,
+}
+
+impl HookContext {
+    /// Gather hook inputs from segment metadata
+    pub fn from_segments(segments: &[(SegmentConfig, SegmentData)]) -> Self {
+        let metadata = |key: &str| {
+            segments
+                .iter()
+                .find_map(|(_, data)| data.metadata.get(key).cloned())
+        };
+        let number = |key: &str| metadata(key).and_then(|v| v.parse::<f64>().ok());
+
+        Self {
+            block_key: metadata("block_start").unwrap_or_else(|| {
+                crate::utils::timezone::today()
+                    .format("%Y-%m-%d")
+                    .to_string()
+            }),
+            block_remaining: number("block_remaining"),
+            daily_cost: number("daily_total"),
+            tokens_per_minute: number("tokens_per_minute"),
+            burn_high: segments
+                .iter()
+                .find(|(config, _)| config.id == SegmentId::BurnRate)
+                .map(|(config, _)| BurnRateThresholds::from_options(&config.options))
+                .unwrap_or_else(BurnRateThresholds::from_env)
+                .high,
+        }
+    }
+
+    fn env(&self, event: HookEvent) -> Vec<(String, String)> {
+        let mut env = vec![
+            ("CCLINE_EVENT".to_string(), event.as_str().to_string()),
+            ("CCLINE_BLOCK".to_string(), self.block_key.clone()),
+        ];
+        let values = [
+            ("CCLINE_BLOCK_REMAINING", self.block_remaining),
+            ("CCLINE_DAILY_COST", self.daily_cost),
+            ("CCLINE_TOKENS_PER_MINUTE", self.tokens_per_minute),
+        ];
+        for (name, value) in values {
+            if let Some(value) = value {
+                env.push((name.to_string(), value.to_string()));
+            }
+        }
+        env
+    }
+}
+
+/// Remembers which block each event last fired for (~/.claude/ccline/hooks_state.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HookState {
+    #[serde(default)]
+    fired: HashMap<String, String>,
+}
+
+impl HookState {
+    pub fn load() -> Self {
+        state_file::load(STATE_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        state_file::save(STATE_FILE, &serde_json::to_string_pretty(self)?)
+    }
+
+    fn has_fired(&self, event: HookEvent, block_key: &str) -> bool {
+        self.fired.get(event.as_str()).map(String::as_str) == Some(block_key)
+    }
+
+    fn mark_fired(&mut self, event: HookEvent, block_key: &str) {
+        self.fired
+            .insert(event.as_str().to_string(), block_key.to_string());
+    }
+}
+
+/// Determine which configured hooks are due: triggered and not yet fired for
+/// the current block. Callers mark a hook fired once it has actually run.
+pub fn due_hooks<'a>(
+    hooks: &'a HooksConfig,
+    context: &HookContext,
+    state: &HookState,
+) -> Vec<(HookEvent, &'a HookConfig)> {
+    let candidates = [
+        (HookEvent::BlockExpiring, hooks.block_expiring.as_ref()),
+        (HookEvent::DailyCostOver, hooks.daily_cost_over.as_ref()),
+        (HookEvent::BurnRateHigh, hooks.burn_rate_high.as_ref()),
+    ];
+
+    let mut due = Vec::new();
+    for (event, hook) in candidates {
+        let Some(hook) = hook else {
+            continue;
+        };
+        let triggered = match event {
+            HookEvent::BlockExpiring => context.block_remaining.is_some_and(|remaining| {
+                remaining > 0.0 && remaining < hook.threshold.unwrap_or(DEFAULT_EXPIRING_MINUTES)
+            }),
+            // Without an explicit amount there is nothing to compare against
+            HookEvent::DailyCostOver => match (context.daily_cost, hook.threshold) {
+                (Some(cost), Some(limit)) => cost > limit,
+                _ => false,
+            },
+            HookEvent::BurnRateHigh => context
+                .tokens_per_minute
+                .is_some_and(|rate| rate > hook.threshold.unwrap_or(context.burn_high)),
+        };
+
+        if triggered && !state.has_fired(event, &context.block_key) {
+            due.push((event, hook));
+        }
+    }
+    due
+}
+
+/// Fire any due hooks for the collected segments without waiting for them to finish
+///
+/// Hooks run declared commands only, and only once approved with `ccline audit --approve <name>`.
+pub fn run_hooks(config: &Config, segments: &[(SegmentConfig, SegmentData)]) {
+    if config.hooks.is_empty() {
+        return;
+    }
+
+    let context = HookContext::from_segments(segments);
+    let mut state = HookState::load();
+    let due = due_hooks(&config.hooks, &context, &state);
+    if due.is_empty() {
+        return;
+    }
+
+    let approvals = CommandApprovals::load();
+    let mut fired = false;
+    for (event, hook) in due {
+        let command = config.commands.get(&hook.command);
+        let status = match command {
+            Some(command) => approvals.status(&hook.command, command),
+            None => CommandStatus::Invalid("not declared under [commands]".to_string()),
+        };
+        let (Some(command), CommandStatus::Approved) = (command, &status) else {
+            crate::debug_println!(
+                "Skipping {} hook: command '{}' is {} (see `ccline audit`)",
+                event.as_str(),
+                hook.command,
+                status.label()
+            );
+            continue;
+        };
+
+        if let Err(e) = spawn_detached(&hook.command, command, &context.env(event)) {
+            crate::debug_println!("Failed to run {} hook: {}", event.as_str(), e);
+            continue;
+        }
+        // Skipped and failed hooks stay due, so one approved later still runs
+        state.mark_fired(event, &context.block_key);
+        fired = true;
+    }
+    if fired {
+        let _ = state.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(threshold: Option<f64>) -> Option<HookConfig> {
+        Some(HookConfig {
+            command: "notify".to_string(),
+            threshold,
+        })
+    }
+
+    #[test]
+    fn test_hooks_fire_once_per_block() {
+        let hooks = HooksConfig {
+            block_expiring: hook(None),
+            daily_cost_over: hook(Some(20.0)),
+            burn_rate_high: hook(Some(1000.0)),
+        };
+        let mut context = HookContext {
+            block_key: "2025-06-01T10:00:00+00:00".to_string(),
+            block_remaining: Some(10.0),
+            daily_cost: Some(25.0),
+            tokens_per_minute: Some(500.0),
+            burn_high: 5000.0,
+        };
+        let mut state = HookState::default();
+
+        let events: Vec<HookEvent> = due_hooks(&hooks, &context, &state)
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect();
+        assert_eq!(
+            events,
+            vec![HookEvent::BlockExpiring, HookEvent::DailyCostOver]
+        );
+        // Until a hook has run it stays due
+        assert_eq!(due_hooks(&hooks, &context, &state).len(), 2);
+        for event in events {
+            state.mark_fired(event, &context.block_key);
+        }
+
+        // Same block: nothing fires again, but a newly crossed threshold does
+        context.tokens_per_minute = Some(1500.0);
+        let events: Vec<HookEvent> = due_hooks(&hooks, &context, &state)
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect();
+        assert_eq!(events, vec![HookEvent::BurnRateHigh]);
+        state.mark_fired(HookEvent::BurnRateHigh, &context.block_key);
+
+        // A new block re-arms every hook
+        context.block_key = "2025-06-01T15:00:00+00:00".to_string();
+        assert_eq!(due_hooks(&hooks, &context, &state).len(), 3);
+    }
+
+    #[test]
+    fn test_block_expiring_respects_threshold() {
+        let hooks = HooksConfig {
+            block_expiring: hook(Some(5.0)),
+            ..Default::default()
+        };
+        let context = HookContext {
+            block_key: "block".to_string(),
+            block_remaining: Some(10.0),
+            ..Default::default()
+        };
+        assert!(due_hooks(&hooks, &context, &HookState::default()).is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::config::{Config, InputData, SegmentConfig};
+use crate::core::segments::SegmentData;
+use crate::utils::extract_session_id;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// One line of the metrics log, built from what the segments already computed
+/// for this render; values from disabled segments are left out
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricsSnapshot {
+    pub ts: DateTime<Utc>,
+    pub session: String,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_pct: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_cost: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_cost: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tpm: Option<f64>,
+}
+
+impl MetricsSnapshot {
+    pub fn from_segments(input: &InputData, segments: &[(SegmentConfig, SegmentData)]) -> Self {
+        let metadata = |key: &str| {
+            segments
+                .iter()
+                .find_map(|(_, data)| data.metadata.get(key))
+                .and_then(|value| value.parse::<f64>().ok())
+        };
+
+        Self {
+            ts: Utc::now(),
+            session: extract_session_id(Path::new(&input.transcript_path)),
+            model: input.model.id.clone(),
+            context_pct: metadata("percentage").map(|pct| (pct * 10.0).round() / 10.0),
+            context_tokens: metadata("tokens").map(|tokens| tokens as u64),
+            session_cost: metadata("session_cost"),
+            block_cost: metadata("block_cost"),
+            tpm: metadata("tokens_per_minute"),
+        }
+    }
+}
+
+/// Append a line to the log, rotating it first if it has grown past `max_bytes`
+pub fn append_line(
+    path: &Path,
+    line: &str,
+    max_bytes: u64,
+    keep: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::metadata(path).is_ok_and(|meta| meta.len() >= max_bytes) {
+        rotate(path, keep)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Shift `path` to `path.1`, `path.1` to `path.2`, ..., dropping the oldest
+fn rotate(path: &Path, keep: u32) -> std::io::Result<()> {
+    let numbered = |n: u32| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    };
+
+    if keep == 0 {
+        return std::fs::remove_file(path);
+    }
+    let _ = std::fs::remove_file(numbered(keep));
+    for n in (1..keep).rev() {
+        let from = numbered(n);
+        if from.exists() {
+            std::fs::rename(from, numbered(n + 1))?;
+        }
+    }
+    std::fs::rename(path, numbered(1))
+}
+
+/// Log this render's snapshot when `global.metrics_log` is enabled; failures are
+/// ignored so the statusline always renders
+pub fn record_metrics(
+    config: &Config,
+    input: &InputData,
+    segments: &[(SegmentConfig, SegmentData)],
+) {
+    let log = &config.global.metrics_log;
+    if !log.enabled {
+        return;
+    }
+    let Some(path) = log.resolved_path() else {
+        return;
+    };
+    if let Ok(line) = serde_json::to_string(&MetricsSnapshot::from_segments(input, segments)) {
+        let _ = append_line(&path, &line, log.max_bytes, log.keep);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_line_rotates() {
+        let dir = std::env::temp_dir().join(format!("ccline-metrics-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("metrics.jsonl");
+
+        for i in 0..5 {
+            append_line(&path, &format!("{{\"n\":{}}}", i), 16, 2).unwrap();
+        }
+
+        // Each line is 8 bytes, so every file holds two lines before rotating
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("metrics.jsonl"), "{\"n\":4}\n");
+        assert_eq!(read("metrics.jsonl.1"), "{\"n\":2}\n{\"n\":3}\n");
+        assert_eq!(read("metrics.jsonl.2"), "{\"n\":0}\n{\"n\":1}\n");
+        assert!(!dir.join("metrics.jsonl.3").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
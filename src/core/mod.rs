@@ -1,4 +1,20 @@
+pub mod branch_ledger;
+pub mod color_rules;
+pub mod commands;
+pub mod embed;
+pub mod hooks;
+pub mod metrics_log;
+pub mod pipeline;
+pub mod registry;
+pub mod render_cache;
+pub mod scenarios;
+pub mod segment_cache;
 pub mod segments;
 pub mod statusline;
 
-pub use statusline::{collect_all_segments, StatusLineGenerator};
+pub use embed::{generate, RenderedLine};
+pub use pipeline::Pipeline;
+pub use registry::SegmentRegistry;
+pub use statusline::{
+    collect_all_segments, escape_for_prompt, strip_ansi, PromptShell, StatusLineGenerator,
+};
@@ -0,0 +1,453 @@
+use crate::config::{Config, InputData, SegmentConfig, SegmentId};
+use crate::core::registry::SegmentRegistry;
+use crate::core::segment_cache::SegmentCache;
+use crate::core::segments::SegmentData;
+use crate::core::StatusLineGenerator;
+use crate::utils::{extract_session_id, profile};
+use chrono::Utc;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+type PreCollectHook<'a> = Box<dyn Fn(&mut Vec<SegmentConfig>, &InputData) + 'a>;
+type PostCollectHook<'a> = Box<dyn Fn(&SegmentConfig, &mut SegmentData, Duration) + 'a>;
+type PreRenderHook<'a> = Box<dyn Fn(&mut Vec<(SegmentConfig, SegmentData)>) + 'a>;
+
+/// A segment's data from a background collection and how long it took
+type Collected = (Option<SegmentData>, Duration);
+
+/// The collect → render loop behind the statusline, with attachment points for
+/// library users building their own frontends
+///
+/// - pre-collect hooks may reorder, add or drop segment configs before any data is gathered
+/// - post-collect hooks see each collected segment with its collection time and may edit it
+/// - pre-render hooks get the final list and may filter or reorder it
+///
+/// Segments are collected through a [`SegmentRegistry`], which library users
+/// can extend with their own segments.
+///
+/// Hooks run in the order they were added.
+pub struct Pipeline<'a> {
+    config: &'a Config,
+    registry: Arc<SegmentRegistry>,
+    pre_collect: Vec<PreCollectHook<'a>>,
+    post_collect: Vec<PostCollectHook<'a>>,
+    pre_render: Vec<PreRenderHook<'a>>,
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        config.apply_global_settings();
+        Self {
+            config,
+            registry: Arc::new(SegmentRegistry::new()),
+            pre_collect: Vec::new(),
+            post_collect: Vec::new(),
+            pre_render: Vec::new(),
+        }
+    }
+
+    /// Collect `custom` segments from this registry
+    pub fn with_registry(mut self, registry: SegmentRegistry) -> Self {
+        self.registry = Arc::new(registry);
+        self
+    }
+
+    pub fn with_pre_collect(
+        mut self,
+        hook: impl Fn(&mut Vec<SegmentConfig>, &InputData) + 'a,
+    ) -> Self {
+        self.pre_collect.push(Box::new(hook));
+        self
+    }
+
+    pub fn with_post_collect(
+        mut self,
+        hook: impl Fn(&SegmentConfig, &mut SegmentData, Duration) + 'a,
+    ) -> Self {
+        self.post_collect.push(Box::new(hook));
+        self
+    }
+
+    pub fn with_pre_render(
+        mut self,
+        hook: impl Fn(&mut Vec<(SegmentConfig, SegmentData)>) + 'a,
+    ) -> Self {
+        self.pre_render.push(Box::new(hook));
+        self
+    }
+
+    /// Collect data for each segment, running pre- and post-collect hooks
+    ///
+    /// With `max_render_ms` set, expensive segments (see
+    /// [`SegmentId::is_expensive`](crate::config::SegmentId::is_expensive)) are
+    /// started on their own threads before the others are collected. One
+    /// still running at the deadline is shown with its last known value,
+    /// marked `over_budget` in its metadata, or else by its `on_error` policy.
+    /// It keeps collecting after the render so the next one has a value: in a
+    /// detached `ccline collect-segment` worker when running as ccline, which
+    /// outlives this process, or else on its thread, which saves the result
+    /// when it finishes.
+    pub fn collect(&self, input: &InputData) -> Vec<(SegmentConfig, SegmentData)> {
+        let mut segment_configs = self.config.segments.clone();
+        for hook in &self.pre_collect {
+            hook(&mut segment_configs, input);
+        }
+
+        // Only touch the cache file when some segment asks for throttling
+        let mut cache = segment_configs
+            .iter()
+            .any(|config| refresh_interval(config).is_some())
+            .then(SegmentCache::load);
+        let session = extract_session_id(Path::new(&input.transcript_path));
+        let now = Utc::now();
+        let mut cache_changed = false;
+        let keys: Vec<String> = segment_configs
+            .iter()
+            .map(|config| format!("{}/{}", session, config.instance_key()))
+            .collect();
+        let fresh = |cache: Option<&SegmentCache>, config: &SegmentConfig, key: &str| {
+            let interval = refresh_interval(config)?;
+            cache?.get_fresh(key, interval, now).cloned()
+        };
+
+        // Start expensive segments first so they run alongside the cheap ones
+        let budget_ms = self.config.global.max_render_ms;
+        let deadline = (budget_ms > 0).then(|| Instant::now() + Duration::from_millis(budget_ms));
+        let mut background: Vec<Option<Receiver<Collected>>> = segment_configs
+            .iter()
+            .zip(&keys)
+            .map(|(config, key)| {
+                let run = deadline.is_some()
+                    && config.enabled
+                    && config.id.is_expensive()
+                    && fresh(cache.as_ref(), config, key).is_none();
+                run.then(|| self.spawn_collect(config, input, key))
+            })
+            .collect();
+        let last_known = background
+            .iter()
+            .any(Option::is_some)
+            .then(SegmentCache::load_last_known);
+        let mut remembered = Vec::new();
+
+        let mut results = Vec::new();
+        for (i, segment_config) in segment_configs.into_iter().enumerate() {
+            let key = &keys[i];
+            let started = Instant::now();
+            let mut elapsed = None;
+            let cached_data = fresh(cache.as_ref(), &segment_config, key);
+            let cached = cached_data.is_some();
+            let collected = match (cached_data, background[i].take(), deadline) {
+                (Some(data), _, _) => Some(data),
+                (None, Some(receiver), Some(deadline)) => {
+                    let wait = deadline.saturating_duration_since(Instant::now());
+                    match receiver.recv_timeout(wait) {
+                        Ok((data, took)) => {
+                            elapsed = Some(took);
+                            // Cost keeps its own last known value
+                            if let Some(data) = data
+                                .as_ref()
+                                .filter(|_| segment_config.id != SegmentId::Cost)
+                            {
+                                remembered.push((key.clone(), data.clone()));
+                            }
+                            data
+                        }
+                        Err(_) => {
+                            crate::debug_println!(
+                                "Segment {} exceeded the {}ms render budget",
+                                segment_config.instance_key(),
+                                budget_ms
+                            );
+                            finish_in_worker(&segment_config, input, key);
+                            let data = match last_known.as_ref().and_then(|c| c.get_last(key)) {
+                                Some((data, collected_at)) => {
+                                    let mut data = data.clone();
+                                    data.metadata.insert(
+                                        "stale_since".to_string(),
+                                        collected_at.to_rfc3339(),
+                                    );
+                                    Some(data)
+                                }
+                                None => {
+                                    self.registry
+                                        .timed_out(self.config, &segment_config, budget_ms)
+                                }
+                            };
+                            data.map(|mut data| {
+                                data.metadata
+                                    .insert("over_budget".to_string(), "true".to_string());
+                                data
+                            })
+                        }
+                    }
+                }
+                _ => self.collect_segment(&segment_config, input),
+            };
+            if let (Some(cache), Some(data)) = (cache.as_mut(), &collected) {
+                let over_budget = data.metadata.contains_key("over_budget");
+                if !cached && !over_budget && refresh_interval(&segment_config).is_some() {
+                    cache.insert(key.clone(), data.clone(), now);
+                    cache_changed = true;
+                }
+            }
+
+            let elapsed = elapsed.unwrap_or_else(|| started.elapsed());
+            if profile::is_profiling() {
+                profile::record_collect(&segment_config.instance_key(), elapsed, cached);
+            }
+            if let Some(mut data) = collected {
+                for hook in &self.post_collect {
+                    hook(&segment_config, &mut data, elapsed);
+                }
+                results.push((segment_config, data));
+            }
+        }
+
+        if let Some(cache) = cache.as_mut().filter(|_| cache_changed) {
+            let _ = cache.save(now);
+        }
+        if !remembered.is_empty() {
+            let _ = SegmentCache::update_last_known(now, |last_known| {
+                for (key, data) in remembered {
+                    last_known.insert(key, data, now);
+                }
+                true
+            });
+        }
+        results
+    }
+
+    /// Collect a segment on its own thread, receiving its data and how long
+    /// collecting took
+    fn spawn_collect(
+        &self,
+        segment_config: &SegmentConfig,
+        input: &InputData,
+        key: &str,
+    ) -> Receiver<Collected> {
+        let (sender, receiver) = mpsc::channel();
+        let config = self.config.clone();
+        let registry = Arc::clone(&self.registry);
+        let segment_config = segment_config.clone();
+        let input = input.clone();
+        let key = key.to_string();
+        std::thread::spawn(move || {
+            let started = Instant::now();
+            let data = registry.collect(&config, &segment_config, &input);
+            // The render stopped waiting; keep the result for the next one
+            if let Err(mpsc::SendError((data, _))) = sender.send((data, started.elapsed())) {
+                remember(&key, &segment_config, data);
+            }
+        });
+        receiver
+    }
+
+    /// Collect, run pre-render hooks and render the statusline
+    pub fn render(&self, input: &InputData) -> String {
+        let segments = self.collect(input);
+        self.render_collected(segments)
+    }
+
+    /// Run pre-render hooks on already collected segments and render them
+    pub fn render_collected(&self, mut segments: Vec<(SegmentConfig, SegmentData)>) -> String {
+        for hook in &self.pre_render {
+            hook(&mut segments);
+        }
+        StatusLineGenerator::new(self.config.clone()).generate(segments)
+    }
+
+    /// Collect data for a single segment
+    pub fn collect_segment(
+        &self,
+        segment_config: &SegmentConfig,
+        input: &InputData,
+    ) -> Option<SegmentData> {
+        self.registry.collect(self.config, segment_config, input)
+    }
+}
+
+/// Collect one segment of `config` and save it as its last known value; run
+/// by the detached `ccline collect-segment` worker a render starts for a
+/// segment over its budget
+pub fn collect_in_background(
+    config: &Config,
+    instance_key: &str,
+    input: &InputData,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let segment_config = config
+        .segments
+        .iter()
+        .find(|segment| segment.instance_key() == instance_key)
+        .ok_or_else(|| format!("No segment '{}' in the config", instance_key))?;
+    let session = extract_session_id(Path::new(&input.transcript_path));
+    let key = format!("{}/{}", session, instance_key);
+    let data = SegmentRegistry::new().collect(config, segment_config, input);
+    remember(&key, segment_config, data);
+    Ok(())
+}
+
+/// Start a detached worker finishing a segment that missed the render
+/// budget, unless one is already running for it
+///
+/// Only the ccline binary can do this; a program embedding the library keeps
+/// the segment's thread, which outlives the render in its process.
+fn finish_in_worker(segment_config: &SegmentConfig, input: &InputData, key: &str) {
+    let Some(exe) = crate::utils::ccline_exe() else {
+        return;
+    };
+    let now = Utc::now();
+    let mut started = false;
+    let marked = SegmentCache::update_last_known(now, |last_known| {
+        started = last_known.start_collecting(key, now);
+        started
+    });
+    if !started || marked.is_err() {
+        return;
+    }
+
+    let child = Command::new(exe)
+        .arg("collect-segment")
+        .arg(segment_config.instance_key())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let written = child.and_then(|mut child| {
+        let stdin = child.stdin.take().ok_or(std::io::ErrorKind::BrokenPipe)?;
+        serde_json::to_writer(stdin, input).map_err(std::io::Error::from)
+    });
+    if let Err(e) = written {
+        crate::debug_println!(
+            "Could not finish {} in the background: {}",
+            segment_config.instance_key(),
+            e
+        );
+        let _ = SegmentCache::update_last_known(now, |last_known| {
+            last_known.finish_collecting(key);
+            true
+        });
+    }
+}
+
+/// Save a segment's data collected after its render as its last known value;
+/// the Cost segment keeps its own
+fn remember(key: &str, segment_config: &SegmentConfig, data: Option<SegmentData>) {
+    let now = Utc::now();
+    let _ = SegmentCache::update_last_known(now, |last_known| {
+        last_known.finish_collecting(key);
+        if let Some(data) = data.filter(|_| segment_config.id != SegmentId::Cost) {
+            last_known.insert(key.to_string(), data, now);
+        }
+        true
+    });
+}
+
+/// A segment's `refresh_interval_secs` option; its output is reused from the
+/// segment cache until this much time has passed
+fn refresh_interval(config: &SegmentConfig) -> Option<chrono::Duration> {
+    config
+        .options
+        .get("refresh_interval_secs")
+        .and_then(|v| v.as_u64())
+        .filter(|secs| *secs > 0)
+        .map(|secs| chrono::Duration::seconds(secs as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Model, SegmentId, Workspace};
+    use std::cell::RefCell;
+
+    fn input() -> InputData {
+        InputData {
+            model: Model {
+                id: "claude-sonnet-4".to_string(),
+                display_name: "Sonnet 4".to_string(),
+            },
+            workspace: Workspace {
+                current_dir: "/tmp/project".to_string(),
+                ..Default::default()
+            },
+            transcript_path: "/nonexistent/transcript.jsonl".to_string(),
+            session_id: None,
+            cost: None,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_pipeline_hooks() {
+        let mut config = Config::default();
+        for segment in config.segments.iter_mut() {
+            segment.enabled = matches!(segment.id, SegmentId::Model | SegmentId::Directory);
+        }
+
+        let seen = RefCell::new(Vec::new());
+        let pipeline = Pipeline::new(&config)
+            .with_pre_collect(|segments, _| {
+                segments.retain(|s| s.enabled);
+                segments.reverse();
+            })
+            .with_post_collect(|segment, data, _| {
+                seen.borrow_mut().push(segment.id);
+                data.primary = data.primary.to_uppercase();
+            })
+            .with_pre_render(|segments| segments.retain(|(s, _)| s.id != SegmentId::Model));
+
+        let collected = pipeline.collect(&input());
+        assert_eq!(
+            collected.iter().map(|(s, _)| s.id).collect::<Vec<_>>(),
+            vec![SegmentId::Directory, SegmentId::Model]
+        );
+        assert_eq!(*seen.borrow(), vec![SegmentId::Directory, SegmentId::Model]);
+        assert_eq!(collected[1].1.primary, "SONNET 4");
+
+        let rendered = pipeline.render_collected(collected);
+        assert!(rendered.contains("PROJECT"));
+        assert!(!rendered.contains("SONNET"));
+    }
+
+    #[test]
+    fn test_render_budget() {
+        let mut config = Config::default();
+        for segment in config.segments.iter_mut() {
+            segment.enabled = matches!(
+                segment.id,
+                SegmentId::Model | SegmentId::Directory | SegmentId::Git
+            );
+        }
+        let texts = |collected: Vec<(SegmentConfig, SegmentData)>| {
+            collected
+                .into_iter()
+                .map(|(segment, data)| (segment.id, data.primary))
+                .collect::<Vec<_>>()
+        };
+        let unbudgeted = texts(Pipeline::new(&config).collect(&input()));
+
+        // Git runs in the background and finishes well within the budget
+        config.global.max_render_ms = 10_000;
+        assert_eq!(texts(Pipeline::new(&config).collect(&input())), unbudgeted);
+
+        let mut cost = config
+            .segments
+            .iter()
+            .find(|segment| segment.id == SegmentId::Cost)
+            .unwrap()
+            .clone();
+        let registry = SegmentRegistry::new();
+        let data = registry.timed_out(&config, &cost, 200).unwrap();
+        assert_eq!(data.primary, "$0.00 session");
+        assert_eq!(
+            data.metadata["error"],
+            "still collecting after the 200ms render budget"
+        );
+        cost.options
+            .insert("on_error".to_string(), serde_json::json!("hide"));
+        assert!(registry.timed_out(&config, &cost, 200).is_none());
+    }
+}
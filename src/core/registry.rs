@@ -0,0 +1,238 @@
+use crate::config::{Config, InputData, SegmentConfig, SegmentId};
+use crate::core::segments::*;
+use std::any::Any;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+
+type SegmentFactory = Box<dyn Fn(&SegmentConfig) -> Box<dyn Segment> + Send + Sync>;
+
+/// Maps segment configs to the `Segment` implementations that collect them
+///
+/// Built-in segments are always available. Library users can register their
+/// own under a string key, which config references as `id = "custom"` with
+/// the key as the instance `name`:
+///
+/// ```toml
+/// [[segments]]
+/// id = "custom"
+/// name = "weather"
+/// ```
+#[derive(Default)]
+pub struct SegmentRegistry {
+    custom: HashMap<String, SegmentFactory>,
+}
+
+impl SegmentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a segment built from its config each time it is collected,
+    /// so it can read its own `options`
+    pub fn register_factory(
+        &mut self,
+        key: impl Into<String>,
+        factory: impl Fn(&SegmentConfig) -> Box<dyn Segment> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.custom.insert(key.into(), Box::new(factory));
+        self
+    }
+
+    /// Register a segment that needs no configuration
+    pub fn register<S>(&mut self, key: impl Into<String>, segment: S) -> &mut Self
+    where
+        S: Segment + Clone + Send + Sync + 'static,
+    {
+        self.register_factory(key, move |_| Box::new(segment.clone()))
+    }
+
+    /// Whether a custom segment is registered under `key`
+    pub fn contains(&self, key: &str) -> bool {
+        self.custom.contains_key(key)
+    }
+
+    /// Collect data for a single segment, applying its `on_error` policy when
+    /// collecting fails or panics
+    pub fn collect(
+        &self,
+        config: &Config,
+        segment_config: &SegmentConfig,
+        input: &InputData,
+    ) -> Option<SegmentData> {
+        let segment = self.build(config, segment_config)?;
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| segment.collect(input)))
+            .unwrap_or_else(|payload| Err(SegmentError::Panicked(panic_message(&payload))));
+        match result {
+            Ok(data) => data,
+            Err(error) => {
+                crate::debug_println!(
+                    "Segment {} failed: {}",
+                    segment_config.instance_key(),
+                    error
+                );
+                crate::trace_step!("Collecting failed: {}", error);
+                ErrorPolicy::from_config(segment_config).apply(&error, segment.placeholder())
+            }
+        }
+    }
+
+    /// What to show for a segment still collecting when the render budget of
+    /// `budget_ms` ran out, by its `on_error` policy
+    pub fn timed_out(
+        &self,
+        config: &Config,
+        segment_config: &SegmentConfig,
+        budget_ms: u64,
+    ) -> Option<SegmentData> {
+        let placeholder = self
+            .build(config, segment_config)
+            .and_then(|segment| segment.placeholder());
+        ErrorPolicy::from_config(segment_config)
+            .apply(&SegmentError::TimedOut(budget_ms), placeholder)
+    }
+
+    /// The `Segment` implementation for a config entry
+    fn build<'a>(
+        &self,
+        config: &'a Config,
+        segment_config: &'a SegmentConfig,
+    ) -> Option<Box<dyn Segment + 'a>> {
+        Some(match segment_config.id {
+            SegmentId::Model => Box::new(ModelSegment::new()),
+            SegmentId::Directory => Box::new(DirectorySegment::new().with_options(segment_config)),
+            SegmentId::Git => {
+                let show_sha = segment_config
+                    .options
+                    .get("show_sha")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                Box::new(GitSegment::new().with_sha(show_sha))
+            }
+            SegmentId::Usage => Box::new(
+                UsageSegment::new(&config.global)
+                    .with_options(segment_config)
+                    .with_style(config.style.mode, segment_config),
+            ),
+            SegmentId::Update => Box::new(UpdateSegment::new()),
+            SegmentId::Cost => Box::new(
+                CostSegment::new(segment_config)
+                    .with_subagents(config.global.subagents)
+                    .with_style(config.style.mode, segment_config),
+            ),
+            SegmentId::BurnRate => Box::new(BurnRateSegment::new(segment_config)),
+            SegmentId::Block => Box::new(BlockSegment::new(segment_config)),
+            SegmentId::BlockLimit => Box::new(BlockLimitSegment::new(segment_config)),
+            SegmentId::Weekly => Box::new(WeeklySegment::new(segment_config)),
+            SegmentId::ProjectCost => Box::new(
+                ProjectCostSegment::new(segment_config).with_subagents(config.global.subagents),
+            ),
+            SegmentId::LastTurn => Box::new(LastTurnSegment::new(segment_config)),
+            SegmentId::SessionStats => Box::new(SessionStatsSegment::new(segment_config)),
+            SegmentId::ApiErrors => Box::new(ApiErrorsSegment::new(segment_config)),
+            SegmentId::Condensed => Box::new(CondensedSegment::new(&config.global, segment_config)),
+            SegmentId::Plugin => Box::new(PluginSegment::new(config, segment_config)),
+            SegmentId::Custom => {
+                let key = segment_config.name.as_deref().unwrap_or_default();
+                match self.custom.get(key) {
+                    Some(factory) => factory(segment_config),
+                    None => {
+                        crate::debug_println!("No custom segment registered as '{}'", key);
+                        return None;
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Text of a caught panic
+fn panic_message(payload: &Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Pipeline;
+
+    #[derive(Clone)]
+    struct Greeting;
+
+    impl Segment for Greeting {
+        fn collect(&self, input: &InputData) -> SegmentResult {
+            Ok(Some(SegmentData {
+                primary: format!("hello {}", input.model.display_name),
+                secondary: String::new(),
+                metadata: HashMap::new(),
+            }))
+        }
+
+        fn id(&self) -> SegmentId {
+            SegmentId::Custom
+        }
+    }
+
+    #[test]
+    fn test_custom_segments() {
+        let mut config = Config::default();
+        let mut custom = config.segments[0].clone();
+        custom.id = SegmentId::Custom;
+        custom.name = Some("greeting".to_string());
+        config.segments = vec![custom.clone()];
+        custom.name = Some("missing".to_string());
+        config.segments.push(custom);
+
+        let mut registry = SegmentRegistry::new();
+        registry.register("greeting", Greeting);
+        assert!(registry.contains("greeting"));
+
+        let input = InputData::builder()
+            .model("claude-sonnet-4", "Sonnet")
+            .build();
+        let collected = Pipeline::new(&config)
+            .with_registry(registry)
+            .collect(&input);
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].1.primary, "hello Sonnet");
+
+        // Without a registration the segment is skipped
+        assert!(Pipeline::new(&config).collect(&input).is_empty());
+    }
+
+    #[derive(Clone)]
+    struct Broken;
+
+    impl Segment for Broken {
+        fn collect(&self, _input: &InputData) -> SegmentResult {
+            panic!("boom")
+        }
+
+        fn id(&self) -> SegmentId {
+            SegmentId::Custom
+        }
+    }
+
+    #[test]
+    fn test_panicking_segment_follows_error_policy() {
+        let config = Config::default();
+        let mut segment = config.segments[0].clone();
+        segment.id = SegmentId::Custom;
+        segment.name = Some("broken".to_string());
+        let input = InputData::builder().build();
+
+        let mut registry = SegmentRegistry::new();
+        registry.register("broken", Broken);
+        assert!(registry.collect(&config, &segment, &input).is_none());
+
+        segment
+            .options
+            .insert("on_error".to_string(), serde_json::json!("badge"));
+        let data = registry.collect(&config, &segment, &input).unwrap();
+        assert_eq!(data.primary, "⚠ crashed");
+        assert_eq!(data.metadata["error"], "panicked: boom");
+    }
+}
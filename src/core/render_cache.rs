@@ -0,0 +1,164 @@
+use crate::config::Config;
+use crate::utils::state_file;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Cached lines written longer ago than this are deleted when a new one is
+/// stored
+const MAX_FILE_AGE_SECS: u64 = 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedLine {
+    rendered_at: DateTime<Utc>,
+    line: String,
+}
+
+/// Whole statuslines rendered within the last `render_cache_secs`
+/// (~/.claude/ccline/cache/render/<key>.json)
+///
+/// Claude Code runs the statusline several times a second with mostly the same
+/// input; while the config, the input and the transcript are unchanged the
+/// previous line is printed without collecting any segment.
+pub struct RenderCache {
+    key: String,
+    ttl: Duration,
+}
+
+impl RenderCache {
+    pub fn dir() -> Option<PathBuf> {
+        crate::utils::home_dir().map(|home| {
+            home.join(".claude")
+                .join("ccline")
+                .join("cache")
+                .join("render")
+        })
+    }
+
+    /// Cache entry for rendering `config` with `input` (the JSON Claude Code
+    /// sent), or `None` when `render_cache_secs` is off
+    pub fn new(config: &Config, input: &serde_json::Value) -> Option<Self> {
+        if config.global.render_cache_secs == 0 {
+            return None;
+        }
+        let transcript = input.get("transcript_path").and_then(|v| v.as_str());
+        Some(Self {
+            key: cache_key(config, input, transcript),
+            ttl: Duration::seconds(config.global.render_cache_secs as i64),
+        })
+    }
+
+    /// Delete cached lines written over an hour ago, returning how many were
+    /// removed
+    pub fn prune_expired() -> usize {
+        Self::dir().map_or(0, |dir| {
+            prune(&dir, std::time::Duration::from_secs(MAX_FILE_AGE_SECS))
+        })
+    }
+
+    fn path(&self) -> Option<PathBuf> {
+        Self::dir().map(|dir| dir.join(format!("{}.json", self.key)))
+    }
+
+    /// The line stored under this key if it is younger than the TTL
+    pub fn get(&self, now: DateTime<Utc>) -> Option<String> {
+        let content = std::fs::read_to_string(self.path()?).ok()?;
+        let cached: CachedLine = serde_json::from_str(&content).ok()?;
+        (cached.rendered_at <= now && now - cached.rendered_at < self.ttl).then_some(cached.line)
+    }
+
+    pub fn store(&self, line: &str, now: DateTime<Utc>) -> Result<(), Box<dyn std::error::Error>> {
+        let path = self.path().ok_or("Could not find home directory")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+            prune(dir, std::time::Duration::from_secs(MAX_FILE_AGE_SECS));
+        }
+        let cached = CachedLine {
+            rendered_at: now,
+            line: line.to_string(),
+        };
+        state_file::write_atomic(&path, serde_json::to_string(&cached)?.as_bytes())
+    }
+}
+
+/// Hash of everything the rendered line depends on besides the clock: the
+/// effective config, the input, the transcript's modification time, the
+/// terminal's color support and the `CCLINE_DISABLE_SEGMENTS` /
+/// `CCLINE_ONLY_SEGMENTS` filters
+fn cache_key(config: &Config, input: &serde_json::Value, transcript: Option<&str>) -> String {
+    let mut hasher = DefaultHasher::new();
+    // Through `Value`, whose maps are sorted, so option order is stable
+    serde_json::to_value(config)
+        .map(|value| value.to_string())
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    input.to_string().hash(&mut hasher);
+    transcript
+        .and_then(|path| std::fs::metadata(path).ok())
+        .and_then(|meta| meta.modified().ok())
+        .hash(&mut hasher);
+    crate::utils::terminal::color_support()
+        .as_str()
+        .hash(&mut hasher);
+    for filter in ["CCLINE_DISABLE_SEGMENTS", "CCLINE_ONLY_SEGMENTS"] {
+        std::env::var(filter).ok().hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// Delete files in `dir` last modified more than `max_age` ago, returning how
+/// many were removed
+pub fn prune(dir: &Path, max_age: std::time::Duration) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > max_age)
+        })
+        .filter(|entry| std::fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cache_key() {
+        let mut config = Config::default();
+        let input = json!({"model": {"id": "claude-sonnet-4"}, "session_id": "s1"});
+        let key = cache_key(&config, &input, None);
+        assert_eq!(key, cache_key(&config, &input, None));
+        assert_ne!(
+            key,
+            cache_key(&config, &json!({"model": {"id": "claude-opus-4"}}), None)
+        );
+
+        let mut reordered = config.clone();
+        config.segments[0].options.insert("a".to_string(), json!(1));
+        config.segments[0].options.insert("b".to_string(), json!(2));
+        reordered.segments[0]
+            .options
+            .insert("b".to_string(), json!(2));
+        reordered.segments[0]
+            .options
+            .insert("a".to_string(), json!(1));
+        let with_options = cache_key(&config, &input, None);
+        assert_ne!(key, with_options);
+        // Options live in a HashMap; the key must not depend on its order
+        assert_eq!(with_options, cache_key(&reordered, &input, None));
+
+        assert!(RenderCache::new(&config, &input).is_none());
+        config.global.render_cache_secs = 3;
+        assert!(RenderCache::new(&config, &input).is_some());
+    }
+}
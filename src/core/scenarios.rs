@@ -0,0 +1,336 @@
+use crate::config::{Config, SegmentConfig, SegmentId};
+use crate::core::segments::SegmentData;
+use std::collections::HashMap;
+
+/// Synthetic situations to render the statusline in without Claude Code,
+/// for `ccline preview` and the configurator's preview
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Scenario {
+    /// A typical session: clean branch, moderate context and spend
+    #[default]
+    Default,
+    /// Fast spending: high burn rate, nearly exhausted block and weekly limits
+    HeavyUsage,
+    /// Context close to the auto-compaction threshold
+    NearCompaction,
+    /// A commit checked out instead of a branch
+    DetachedHead,
+    /// A directory outside any Git repository
+    NoGit,
+}
+
+impl Scenario {
+    /// Change a segment's default mock data to fit the scenario; `None` hides
+    /// the segment
+    fn adjust(&self, id: SegmentId, data: SegmentData) -> Option<SegmentData> {
+        let mock = |primary: &str, secondary: &str, metadata: &[(&str, &str)]| SegmentData {
+            primary: primary.to_string(),
+            secondary: secondary.to_string(),
+            metadata: metadata
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        };
+
+        Some(match (self, id) {
+            (Scenario::HeavyUsage, SegmentId::Cost) => mock(
+                "$9.84 session",
+                "$64.12 today · $38.40 block (0h 45m)",
+                &[
+                    ("session_cost", "9.84"),
+                    ("daily_total", "64.12"),
+                    ("block_cost", "38.40"),
+                    ("block_remaining", "45"),
+                ],
+            ),
+            (Scenario::HeavyUsage, SegmentId::BurnRate) => mock(
+                "$18.60/hr",
+                "\u{f0e7}",
+                &[
+                    ("cost_per_hour", "18.60"),
+                    ("tokens_per_minute", "15200.0"),
+                    ("trend", "Increasing"),
+                    ("severity", "warning"),
+                ],
+            ),
+            (Scenario::HeavyUsage, SegmentId::Block) => mock(
+                "█████████░",
+                "0h 45m",
+                &[("block_remaining", "45"), ("block_progress", "85")],
+            ),
+            (Scenario::HeavyUsage, SegmentId::BlockLimit) => mock(
+                "94% of block limit",
+                "827.2k/880.0k",
+                &[("block_limit_percent", "94.0"), ("severity", "critical")],
+            ),
+            (Scenario::HeavyUsage, SegmentId::Weekly) => mock(
+                "9.6M tokens · $212.30 this week",
+                "96% of weekly limit",
+                &[("week_limit_percent", "96.0"), ("severity", "critical")],
+            ),
+            (Scenario::HeavyUsage, SegmentId::Condensed) => mock(
+                "▅▇█",
+                "",
+                &[
+                    ("context_percent", "61.0"),
+                    ("quota_percent", "94.0"),
+                    ("burn_percent", "100.0"),
+                    ("cell_colors", "214,196,196"),
+                ],
+            ),
+            (Scenario::NearCompaction, SegmentId::Usage) => mock(
+                "88.4%",
+                "· 176.8k",
+                &[
+                    ("total_tokens", "176800"),
+                    ("percentage", "88.4"),
+                    ("until_compact", "7200"),
+                    ("severity", "warning"),
+                ],
+            ),
+            (Scenario::NearCompaction, SegmentId::Condensed) => mock(
+                "█▄▆",
+                "",
+                &[
+                    ("context_percent", "88.4"),
+                    ("quota_percent", "42.0"),
+                    ("burn_percent", "70.0"),
+                    ("cell_colors", "202,141,208"),
+                ],
+            ),
+            (Scenario::DetachedHead, SegmentId::Git) => mock(
+                "detached",
+                "●",
+                &[
+                    ("branch", "detached"),
+                    ("status", "Dirty"),
+                    ("ahead", "0"),
+                    ("behind", "0"),
+                    ("sha", "3f9c2e1"),
+                ],
+            ),
+            (Scenario::NoGit, SegmentId::Git) => return None,
+            _ => data,
+        })
+    }
+}
+
+/// Mock data for every enabled segment of `config` in `scenario`, without
+/// depending on the real environment
+pub fn mock_segments_data(
+    config: &Config,
+    scenario: Scenario,
+) -> Vec<(SegmentConfig, SegmentData)> {
+    config
+        .segments
+        .iter()
+        .filter(|segment| segment.enabled)
+        .filter_map(|segment| {
+            let data = scenario.adjust(segment.id, default_data(segment))?;
+            Some((segment.clone(), data))
+        })
+        .collect()
+}
+
+fn default_data(segment_config: &SegmentConfig) -> SegmentData {
+    match segment_config.id {
+        SegmentId::Model => SegmentData {
+            primary: "Sonnet 4".to_string(),
+            secondary: "".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("model".to_string(), "claude-4-sonnet-20250512".to_string());
+                map
+            },
+        },
+        SegmentId::Directory => SegmentData {
+            primary: "CCometixLine".to_string(),
+            secondary: "".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("current_dir".to_string(), "~/CCometixLine".to_string());
+                map
+            },
+        },
+        SegmentId::Git => SegmentData {
+            primary: "master".to_string(),
+            secondary: "✓".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("branch".to_string(), "master".to_string());
+                map.insert("status".to_string(), "Clean".to_string());
+                map.insert("ahead".to_string(), "0".to_string());
+                map.insert("behind".to_string(), "0".to_string());
+                map
+            },
+        },
+        SegmentId::Usage => SegmentData {
+            primary: "78.2%".to_string(),
+            secondary: "· 156.4k".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("total_tokens".to_string(), "156400".to_string());
+                map.insert("percentage".to_string(), "78.2".to_string());
+                map.insert("session_tokens".to_string(), "48200".to_string());
+                map
+            },
+        },
+        SegmentId::Update => SegmentData {
+            primary: format!("v{}", env!("CARGO_PKG_VERSION")),
+            secondary: "".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert(
+                    "current_version".to_string(),
+                    env!("CARGO_PKG_VERSION").to_string(),
+                );
+                map.insert("update_available".to_string(), "false".to_string());
+                map
+            },
+        },
+        SegmentId::Cost => SegmentData {
+            primary: "$2.45 session".to_string(),
+            secondary: "$12.87 today · $5.00 block (2h 30m)".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("session_cost".to_string(), "2.45".to_string());
+                map.insert("daily_total".to_string(), "12.87".to_string());
+                map.insert("block_cost".to_string(), "5.00".to_string());
+                map.insert("block_remaining".to_string(), "150".to_string());
+                map
+            },
+        },
+        SegmentId::BurnRate => SegmentData {
+            primary: "$4.20/hr".to_string(),
+            secondary: "\u{f0e7}".to_string(), // Lightning icon
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("cost_per_hour".to_string(), "4.20".to_string());
+                map.insert("tokens_per_minute".to_string(), "3500.0".to_string());
+                map.insert("trend".to_string(), "Increasing".to_string());
+                map
+            },
+        },
+        SegmentId::Block => SegmentData {
+            primary: "█████░░░░░".to_string(),
+            secondary: "2h 30m".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("block_remaining".to_string(), "150".to_string());
+                map.insert("block_progress".to_string(), "50".to_string());
+                map
+            },
+        },
+        SegmentId::BlockLimit => SegmentData {
+            primary: "42% of block limit".to_string(),
+            secondary: "370.0k/880.0k".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("block_limit_percent".to_string(), "42.0".to_string());
+                map
+            },
+        },
+        SegmentId::Weekly => SegmentData {
+            primary: "7.5M tokens · $42.50 this week".to_string(),
+            secondary: "75% of weekly limit".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("week_limit_percent".to_string(), "75.0".to_string());
+                map
+            },
+        },
+        SegmentId::ProjectCost => SegmentData {
+            primary: "$12.40 here".to_string(),
+            secondary: "of $48.75 this month (25%)".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("project_percent".to_string(), "25.4".to_string());
+                map
+            },
+        },
+        SegmentId::LastTurn => SegmentData {
+            primary: "$0.084 last turn".to_string(),
+            secondary: "· 62 tok/s".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("last_turn_cost".to_string(), "0.0840".to_string());
+                map.insert("output_tokens".to_string(), "1240".to_string());
+                map.insert("tokens_per_second".to_string(), "62.0".to_string());
+                map
+            },
+        },
+        SegmentId::SessionStats => SegmentData {
+            primary: "12 turns · 58 msgs · 31 tools".to_string(),
+            secondary: String::new(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("turns".to_string(), "12".to_string());
+                map.insert("messages".to_string(), "58".to_string());
+                map.insert("tool_calls".to_string(), "31".to_string());
+                map
+            },
+        },
+        SegmentId::ApiErrors => SegmentData {
+            primary: "529 overloaded".to_string(),
+            secondary: "×2 · 3m ago".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("status".to_string(), "529".to_string());
+                map.insert("error_count".to_string(), "2".to_string());
+                map.insert("severity".to_string(), "warning".to_string());
+                map
+            },
+        },
+        SegmentId::Condensed => SegmentData {
+            primary: "▄▄▆".to_string(),
+            secondary: String::new(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("context_percent".to_string(), "45.2".to_string());
+                map.insert("quota_percent".to_string(), "42.0".to_string());
+                map.insert("burn_percent".to_string(), "70.0".to_string());
+                map.insert("cell_colors".to_string(), "39,141,208".to_string());
+                map
+            },
+        },
+        SegmentId::Plugin => SegmentData {
+            primary: "plugin output".to_string(),
+            secondary: String::new(),
+            metadata: HashMap::new(),
+        },
+        SegmentId::Custom => SegmentData {
+            primary: segment_config
+                .name
+                .clone()
+                .unwrap_or_else(|| "custom".to_string()),
+            secondary: String::new(),
+            metadata: HashMap::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenarios() {
+        let config = Config::default();
+        let has_git = |scenario| {
+            mock_segments_data(&config, scenario)
+                .iter()
+                .any(|(segment, _)| segment.id == SegmentId::Git)
+        };
+        assert!(has_git(Scenario::Default));
+        assert!(!has_git(Scenario::NoGit));
+
+        let usage = |scenario| {
+            mock_segments_data(&config, scenario)
+                .into_iter()
+                .find(|(segment, _)| segment.id == SegmentId::Usage)
+                .map(|(_, data)| data.primary)
+        };
+        assert_eq!(usage(Scenario::NearCompaction).as_deref(), Some("88.4%"));
+        assert_eq!(usage(Scenario::Default).as_deref(), Some("78.2%"));
+    }
+}
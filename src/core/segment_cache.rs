@@ -0,0 +1,182 @@
+use crate::core::segments::SegmentData;
+use crate::utils::state_file;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Entries untouched for this long are dropped when the cache is saved
+const MAX_ENTRY_AGE_HOURS: i64 = 24;
+
+/// How long a started background collection is trusted to still be running
+/// before another one may be started
+const COLLECTING_STALE_AFTER_SECS: i64 = 120;
+
+const CACHE_FILE: &str = "segment_cache.json";
+const LAST_KNOWN_FILE: &str = "last_known.json";
+const LAST_KNOWN_LOCK: &str = "last_known.lock";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedSegment {
+    collected_at: DateTime<Utc>,
+    data: SegmentData,
+}
+
+/// Last collected value of segments with a `refresh_interval_secs` option
+/// (~/.claude/ccline/segment_cache.json), keyed by session and segment instance
+///
+/// Claude Code re-runs the statusline every few hundred milliseconds; this lets
+/// expensive segments reuse their previous output between refreshes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SegmentCache {
+    #[serde(default)]
+    entries: BTreeMap<String, CachedSegment>,
+    /// Keys whose collection was handed to a background worker, by when
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    collecting: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl SegmentCache {
+    pub fn load() -> Self {
+        state_file::load(CACHE_FILE)
+    }
+
+    /// The last good value of segments that can show stale data when
+    /// collecting fails (~/.claude/ccline/last_known.json), kept apart from
+    /// the refresh cache so the two are saved independently
+    pub fn load_last_known() -> Self {
+        state_file::load(LAST_KNOWN_FILE)
+    }
+
+    /// Load, change and save the last known values while holding a lock
+    /// (~/.claude/ccline/last_known.lock), so renders and background workers
+    /// saving at the same time don't drop each other's values; `update`
+    /// returns whether it changed anything, and nothing is written if not
+    pub fn update_last_known(
+        now: DateTime<Utc>,
+        update: impl FnOnce(&mut Self) -> bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let lock_path = state_file::path(LAST_KNOWN_LOCK).ok_or("Could not find home directory")?;
+        if let Some(parent) = lock_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let lock = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(lock_path)?;
+        lock.lock()?;
+
+        let mut last_known = Self::load_last_known();
+        if update(&mut last_known) {
+            last_known.save_last_known(now)?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&mut self, now: DateTime<Utc>) -> Result<(), Box<dyn std::error::Error>> {
+        self.save_file(CACHE_FILE, now)
+    }
+
+    pub fn save_last_known(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.save_file(LAST_KNOWN_FILE, now)
+    }
+
+    fn save_file(
+        &mut self,
+        file: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let cutoff = now - Duration::hours(MAX_ENTRY_AGE_HOURS);
+        self.entries.retain(|_, entry| entry.collected_at > cutoff);
+        let stale = now - Duration::seconds(COLLECTING_STALE_AFTER_SECS);
+        self.collecting.retain(|_, started| *started > stale);
+
+        state_file::save(file, &serde_json::to_string(self)?)
+    }
+
+    /// The cached value for `key` if it was collected less than `interval` ago
+    pub fn get_fresh(
+        &self,
+        key: &str,
+        interval: Duration,
+        now: DateTime<Utc>,
+    ) -> Option<&SegmentData> {
+        self.entries
+            .get(key)
+            .filter(|entry| entry.collected_at <= now && now - entry.collected_at < interval)
+            .map(|entry| &entry.data)
+    }
+
+    /// The cached value for `key` however old, with when it was collected
+    pub fn get_last(&self, key: &str) -> Option<(&SegmentData, DateTime<Utc>)> {
+        self.entries
+            .get(key)
+            .map(|entry| (&entry.data, entry.collected_at))
+    }
+
+    /// Record that `key` is being collected in the background, returning false
+    /// when an earlier collection appears to still be running
+    pub fn start_collecting(&mut self, key: &str, now: DateTime<Utc>) -> bool {
+        let running = self
+            .collecting
+            .get(key)
+            .is_some_and(|started| now - *started < Duration::seconds(COLLECTING_STALE_AFTER_SECS));
+        if !running {
+            self.collecting.insert(key.to_string(), now);
+        }
+        !running
+    }
+
+    pub fn finish_collecting(&mut self, key: &str) {
+        self.collecting.remove(key);
+    }
+
+    pub fn insert(&mut self, key: String, data: SegmentData, now: DateTime<Utc>) {
+        self.entries.insert(
+            key,
+            CachedSegment {
+                collected_at: now,
+                data,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_get_fresh_respects_interval() {
+        let mut cache = SegmentCache::default();
+        let now = Utc::now();
+        let data = SegmentData {
+            primary: "$1.23".to_string(),
+            secondary: String::new(),
+            metadata: HashMap::new(),
+        };
+        cache.insert("s1/cost".to_string(), data, now);
+
+        let interval = Duration::seconds(30);
+        let at = |secs: i64| cache.get_fresh("s1/cost", interval, now + Duration::seconds(secs));
+        assert_eq!(at(10).map(|d| d.primary.as_str()), Some("$1.23"));
+        assert!(at(30).is_none());
+        assert!(at(-5).is_none());
+        assert!(cache.get_fresh("s2/cost", interval, now).is_none());
+    }
+
+    #[test]
+    fn test_start_collecting() {
+        let mut cache = SegmentCache::default();
+        let now = Utc::now();
+        assert!(cache.start_collecting("s1/git", now));
+        assert!(!cache.start_collecting("s1/git", now + Duration::seconds(5)));
+        assert!(cache.start_collecting("s1/git", now + Duration::seconds(121)));
+        cache.finish_collecting("s1/git");
+        assert!(cache.start_collecting("s1/git", now));
+    }
+}
@@ -0,0 +1,192 @@
+use super::{Segment, SegmentData, SegmentError, SegmentResult};
+use crate::config::{InputData, SegmentConfig, SegmentId};
+use crate::utils::format::format_age;
+use crate::utils::transcript::{is_new_session, read_tail_lines};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Bytes read from the end of the transcript
+const TAIL_BYTES: u64 = 256 * 1024;
+
+/// Errors within the window at which the badge turns critical, a sign that
+/// Claude Code is stuck retrying
+const STORM_COUNT: usize = 3;
+
+/// A rate-limit or server error Claude Code logged in the transcript
+#[derive(Debug, Clone, PartialEq)]
+struct ApiError {
+    status: u16,
+    timestamp: DateTime<Utc>,
+}
+
+impl ApiError {
+    /// Read an error from a transcript entry: either a system entry carrying
+    /// the response (`error.status`) or the synthetic assistant message shown
+    /// to the user (`isApiErrorMessage`, text starting `API Error: 529 ...`)
+    fn from_entry(entry: &Value) -> Option<Self> {
+        let timestamp = DateTime::parse_from_rfc3339(entry.get("timestamp")?.as_str()?)
+            .ok()?
+            .with_timezone(&Utc);
+        let status = entry
+            .pointer("/error/status")
+            .and_then(|status| status.as_u64())
+            .or_else(|| {
+                if entry.get("isApiErrorMessage").and_then(|v| v.as_bool()) != Some(true) {
+                    return None;
+                }
+                message_text(entry)?
+                    .trim_start()
+                    .strip_prefix("API Error: ")?
+                    .get(..3)?
+                    .parse()
+                    .ok()
+            })?;
+        let status = u16::try_from(status).ok()?;
+        (status == 429 || (500..600).contains(&status)).then_some(ApiError { status, timestamp })
+    }
+
+    fn label(&self) -> &'static str {
+        match self.status {
+            429 => "rate limited",
+            529 => "overloaded",
+            _ => "server error",
+        }
+    }
+}
+
+/// Text of an entry's message, whether its content is a string or blocks
+fn message_text(entry: &Value) -> Option<&str> {
+    let content = entry.pointer("/message/content")?;
+    content.as_str().or_else(|| {
+        content
+            .as_array()?
+            .iter()
+            .find_map(|block| block.get("text")?.as_str())
+    })
+}
+
+/// Recent rate-limit (429) and server (5xx) errors of the current session,
+/// so retries that Claude Code otherwise handles silently become visible
+pub struct ApiErrorsSegment {
+    enabled: bool,
+    /// Errors older than this are ignored, hiding the segment
+    max_age: Duration,
+}
+
+impl ApiErrorsSegment {
+    pub fn new(config: &SegmentConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            max_age: Duration::minutes(
+                config
+                    .options
+                    .get("max_age_minutes")
+                    .and_then(|v| v.as_i64())
+                    .filter(|minutes| *minutes > 0)
+                    .unwrap_or(15),
+            ),
+        }
+    }
+
+    /// Badge for the errors in `lines` younger than `max_age` at `now`, if any
+    fn render(&self, lines: &[String], now: DateTime<Utc>) -> Option<SegmentData> {
+        let errors: Vec<ApiError> = lines
+            .iter()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .filter_map(|entry| ApiError::from_entry(&entry))
+            .filter(|error| now - error.timestamp <= self.max_age)
+            .collect();
+        let latest = errors.iter().max_by_key(|error| error.timestamp)?;
+
+        let mut metadata = HashMap::new();
+        metadata.insert("status".to_string(), latest.status.to_string());
+        metadata.insert("error_count".to_string(), errors.len().to_string());
+        metadata.insert("last_error".to_string(), latest.timestamp.to_rfc3339());
+        metadata.insert(
+            "severity".to_string(),
+            if errors.len() >= STORM_COUNT {
+                "critical"
+            } else {
+                "warning"
+            }
+            .to_string(),
+        );
+
+        let age = format_age(now - latest.timestamp);
+        Some(SegmentData {
+            primary: format!("{} {}", latest.status, latest.label()),
+            secondary: if errors.len() > 1 {
+                format!("×{} · {}", errors.len(), age)
+            } else {
+                age
+            },
+            metadata,
+        })
+    }
+}
+
+impl Segment for ApiErrorsSegment {
+    fn collect(&self, input: &InputData) -> SegmentResult {
+        if !self.enabled
+            || input.transcript_path.is_empty()
+            || is_new_session(&input.transcript_path)
+        {
+            return Ok(None);
+        }
+
+        let lines = read_tail_lines(Path::new(&input.transcript_path), TAIL_BYTES)
+            .map_err(|e| SegmentError::Data(format!("{}: {}", input.transcript_path, e)))?;
+        let data = self.render(&lines, Utc::now());
+        crate::trace_step!(
+            "{} API errors in the last {} minutes",
+            data.as_ref()
+                .and_then(|d| d.metadata.get("error_count"))
+                .map_or("No", String::as_str),
+            self.max_age.num_minutes()
+        );
+        Ok(data)
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::ApiErrors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::segments::test_config;
+    use serde_json::json;
+
+    #[test]
+    fn test_api_errors() {
+        let now = Utc::now();
+        let at = |minutes: i64| (now - Duration::minutes(minutes)).to_rfc3339();
+        let lines = [
+            json!({"type": "system", "timestamp": at(40), "error": {"status": 500}}),
+            json!({"type": "system", "timestamp": at(9), "error": {"status": 429}}),
+            json!({"type": "system", "timestamp": at(8), "error": {"status": 400}}),
+            json!({"type": "assistant", "timestamp": at(3), "isApiErrorMessage": true,
+                "message": {"content": [{"type": "text", "text": "API Error: 529 {\"type\":\"error\"}"}]}}),
+            json!({"type": "assistant", "timestamp": at(1),
+                "message": {"content": [{"type": "text", "text": "API Error: 500 is a status code"}]}}),
+        ]
+        .map(|entry| entry.to_string());
+
+        let config = test_config(SegmentId::ApiErrors, &[]);
+        let data = ApiErrorsSegment::new(&config).render(&lines, now).unwrap();
+        assert_eq!(data.primary, "529 overloaded");
+        assert_eq!(data.secondary, "×2 · 3m ago");
+        assert_eq!(data.metadata["severity"], "warning");
+
+        let config = test_config(SegmentId::ApiErrors, &[("max_age_minutes", json!(60))]);
+        let data = ApiErrorsSegment::new(&config).render(&lines, now).unwrap();
+        assert_eq!(data.metadata["error_count"], "3");
+        assert_eq!(data.metadata["severity"], "critical");
+
+        let config = test_config(SegmentId::ApiErrors, &[("max_age_minutes", json!(2))]);
+        assert!(ApiErrorsSegment::new(&config).render(&lines, now).is_none());
+    }
+}
@@ -0,0 +1,168 @@
+use super::{Segment, SegmentData, SegmentResult};
+use crate::billing::{
+    block::{find_active_block, identify_session_blocks_with_overrides},
+    calculator::format_remaining_time,
+    BillingBlock,
+};
+use crate::config::{InputData, SegmentConfig, SegmentId};
+use crate::utils::data_loader::EntryLoader;
+use std::collections::HashMap;
+
+/// Minutes left in the block below which the segment reports `critical`
+const CRITICAL_REMAINING_MINUTES: i64 = 10;
+
+/// Progress of the active 5-hour billing block, independent of cost tracking
+pub struct BlockSegment {
+    enabled: bool,
+    width: usize,
+    filled: String,
+    empty: String,
+    show_remaining: bool,
+    loader: EntryLoader,
+}
+
+impl BlockSegment {
+    pub fn new(config: &SegmentConfig) -> Self {
+        let option_str = |key: &str, default: &str| {
+            config
+                .options
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .unwrap_or(default)
+                .to_string()
+        };
+
+        Self {
+            enabled: config.enabled,
+            width: config
+                .options
+                .get("bar_width")
+                .and_then(|v| v.as_u64())
+                .map(|w| w.clamp(1, 50) as usize)
+                .unwrap_or(10),
+            filled: option_str("filled_glyph", "█"),
+            empty: option_str("empty_glyph", "░"),
+            show_remaining: config
+                .options
+                .get("show_remaining")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true),
+            loader: EntryLoader::from_options(&config.options, Some(1)),
+        }
+    }
+
+    /// Render a bar with `progress` (0.0–1.0) of its cells filled
+    fn render_bar(&self, progress: f64) -> String {
+        let filled =
+            ((progress.clamp(0.0, 1.0) * self.width as f64).round() as usize).min(self.width);
+        format!(
+            "{}{}",
+            self.filled.repeat(filled),
+            self.empty.repeat(self.width - filled)
+        )
+    }
+
+    fn render_block(&self, block: &BillingBlock) -> SegmentData {
+        let total_minutes = (block.end_time - block.start_time).num_minutes().max(1);
+        let remaining = block.remaining_minutes.clamp(0, total_minutes);
+        let progress = (total_minutes - remaining) as f64 / total_minutes as f64;
+
+        let mut metadata = HashMap::new();
+        metadata.insert("block_start".to_string(), block.start_time.to_rfc3339());
+        metadata.insert("block_remaining".to_string(), remaining.to_string());
+        metadata.insert(
+            "block_progress".to_string(),
+            format!("{:.0}", progress * 100.0),
+        );
+        if remaining < CRITICAL_REMAINING_MINUTES {
+            metadata.insert("severity".to_string(), "critical".to_string());
+        }
+
+        SegmentData {
+            primary: self.render_bar(progress),
+            secondary: if self.show_remaining {
+                format_remaining_time(remaining)
+            } else {
+                String::new()
+            },
+            metadata,
+        }
+    }
+}
+
+impl Segment for BlockSegment {
+    fn collect(&self, _input: &InputData) -> SegmentResult {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        // Costs are not needed to find block boundaries, so pricing is skipped
+        let entries = self.loader.load();
+        let blocks = identify_session_blocks_with_overrides(&entries);
+
+        Ok(Some(match find_active_block(&blocks) {
+            Some(block) => self.render_block(block),
+            None => {
+                let mut metadata = HashMap::new();
+                metadata.insert("status".to_string(), "no_active_block".to_string());
+                SegmentData {
+                    primary: self.render_bar(0.0),
+                    secondary: if self.show_remaining {
+                        "idle".to_string()
+                    } else {
+                        String::new()
+                    },
+                    metadata,
+                }
+            }
+        }))
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::billing::types::BlockStartSource;
+    use crate::core::segments::test_config;
+    use chrono::{Duration, Utc};
+
+    fn segment(options: &[(&str, serde_json::Value)]) -> BlockSegment {
+        BlockSegment::new(&test_config(SegmentId::Block, options))
+    }
+
+    #[test]
+    fn test_block_progress_bar() {
+        let start = Utc::now() - Duration::minutes(150);
+        let block = BillingBlock {
+            start_time: start,
+            end_time: start + Duration::hours(5),
+            cost: 0.0,
+            remaining_minutes: 150,
+            is_active: true,
+            session_count: 1,
+            total_tokens: 0,
+            start_time_source: BlockStartSource::Auto,
+            is_gap: false,
+        };
+
+        let data = segment(&[]).render_block(&block);
+        assert_eq!(data.primary, "█████░░░░░");
+        assert_eq!(data.secondary, "2h 30m");
+        assert_eq!(data.metadata.get("block_progress").unwrap(), "50");
+
+        let data = segment(&[
+            ("bar_width", serde_json::json!(4)),
+            ("filled_glyph", serde_json::json!("#")),
+            ("empty_glyph", serde_json::json!("-")),
+            ("show_remaining", serde_json::json!(false)),
+        ])
+        .render_block(&block);
+        assert_eq!(data.primary, "##--");
+        assert!(data.secondary.is_empty());
+    }
+}
@@ -0,0 +1,183 @@
+use super::{Segment, SegmentData, SegmentResult};
+use crate::billing::{
+    block::{find_active_block, identify_session_blocks_with_overrides},
+    BillingBlock, UsageEntry,
+};
+use crate::config::{context_limits::parse_token_count, InputData, SegmentConfig, SegmentId};
+use crate::utils::data_loader::EntryLoader;
+use std::collections::HashMap;
+
+/// Where the per-block token cap comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BlockTokenLimit {
+    Fixed(u64),
+    /// The largest total of any previous block, like ccusage's `--token-limit max`
+    Max,
+}
+
+/// Share of a per-block token cap used by the active billing block, for
+/// subscription plans where block limits matter more than dollar cost
+pub struct BlockLimitSegment {
+    enabled: bool,
+    limit: BlockTokenLimit,
+    warning_percent: f64,
+    critical_percent: f64,
+    loader: EntryLoader,
+}
+
+impl BlockLimitSegment {
+    pub fn new(config: &SegmentConfig) -> Self {
+        let limit = match config.options.get("block_token_limit") {
+            Some(value) if value.as_str() == Some("max") => BlockTokenLimit::Max,
+            Some(value) => value
+                .as_u64()
+                .or_else(|| {
+                    value
+                        .as_str()
+                        .and_then(|s| parse_token_count(s).ok())
+                        .map(u64::from)
+                })
+                .filter(|limit| *limit > 0)
+                .map(BlockTokenLimit::Fixed)
+                .unwrap_or(BlockTokenLimit::Max),
+            None => BlockTokenLimit::Max,
+        };
+        let percent = |key: &str, default: f64| {
+            config
+                .options
+                .get(key)
+                .and_then(|v| v.as_f64())
+                .unwrap_or(default)
+        };
+
+        Self {
+            enabled: config.enabled,
+            limit,
+            warning_percent: percent("warning_percent", 70.0),
+            critical_percent: percent("critical_percent", 90.0),
+            loader: EntryLoader::from_options(&config.options, Some(1)),
+        }
+    }
+
+    fn resolve_limit(&self, blocks: &[BillingBlock]) -> Option<u64> {
+        match self.limit {
+            BlockTokenLimit::Fixed(limit) => Some(limit),
+            BlockTokenLimit::Max => blocks
+                .iter()
+                .filter(|b| !b.is_gap && !b.is_active)
+                .map(|b| b.total_tokens)
+                .max()
+                .filter(|max| *max > 0),
+        }
+    }
+
+    /// The segment for already loaded entries
+    pub(crate) fn collect_from(&self, entries: &[UsageEntry]) -> SegmentData {
+        let blocks = identify_session_blocks_with_overrides(entries);
+        let used = find_active_block(&blocks)
+            .map(|b| b.total_tokens)
+            .unwrap_or(0);
+
+        match self.resolve_limit(&blocks) {
+            Some(limit) => self.render(used, limit),
+            None => {
+                let mut metadata = HashMap::new();
+                metadata.insert("status".to_string(), "no_limit".to_string());
+                SegmentData {
+                    primary: "no block limit".to_string(),
+                    secondary: String::new(),
+                    metadata,
+                }
+            }
+        }
+    }
+
+    fn render(&self, used: u64, limit: u64) -> SegmentData {
+        let percent = used as f64 / limit as f64 * 100.0;
+
+        let mut metadata = HashMap::new();
+        metadata.insert("block_tokens".to_string(), used.to_string());
+        metadata.insert("block_token_limit".to_string(), limit.to_string());
+        metadata.insert("block_limit_percent".to_string(), format!("{:.1}", percent));
+        if percent >= self.critical_percent {
+            metadata.insert("severity".to_string(), "critical".to_string());
+        } else if percent >= self.warning_percent {
+            metadata.insert("severity".to_string(), "warning".to_string());
+        }
+
+        SegmentData {
+            primary: format!("{:.0}% of block limit", percent),
+            secondary: format!("{}/{}", format_compact(used), format_compact(limit)),
+            metadata,
+        }
+    }
+}
+
+/// Format a token count as `950`, `12.5k` or `1.2M`
+pub(crate) fn format_compact(tokens: u64) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+impl Segment for BlockLimitSegment {
+    fn collect(&self, _input: &InputData) -> SegmentResult {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        Ok(Some(self.collect_from(&self.loader.load())))
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::BlockLimit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::segments::test_config;
+
+    fn segment(options: &[(&str, serde_json::Value)]) -> BlockLimitSegment {
+        BlockLimitSegment::new(&test_config(SegmentId::BlockLimit, options))
+    }
+
+    #[test]
+    fn test_block_limit_display_and_severity() {
+        let fixed = segment(&[("block_token_limit", serde_json::json!("1m"))]);
+        assert_eq!(fixed.limit, BlockTokenLimit::Fixed(1_000_000));
+
+        let data = fixed.render(420_000, 1_000_000);
+        assert_eq!(data.primary, "42% of block limit");
+        assert_eq!(data.secondary, "420.0k/1.0M");
+        assert!(!data.metadata.contains_key("severity"));
+
+        assert_eq!(
+            fixed
+                .render(750_000, 1_000_000)
+                .metadata
+                .get("severity")
+                .unwrap(),
+            "warning"
+        );
+        assert_eq!(
+            fixed
+                .render(950_000, 1_000_000)
+                .metadata
+                .get("severity")
+                .unwrap(),
+            "critical"
+        );
+
+        assert_eq!(
+            segment(&[("block_token_limit", serde_json::json!(500_000))]).limit,
+            BlockTokenLimit::Fixed(500_000)
+        );
+        assert_eq!(segment(&[]).limit, BlockTokenLimit::Max);
+    }
+}
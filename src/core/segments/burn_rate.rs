@@ -1,83 +1,142 @@
-use super::{Segment, SegmentData};
+use super::{Segment, SegmentData, SegmentResult};
 use crate::billing::{
     block::{find_active_block, identify_session_blocks_with_overrides},
+    burn_history::{sparkline, BurnHistory, BurnSample},
     calculator::calculate_burn_rate,
-    BurnRateThresholds, ModelPricing,
+    entries::price_entries,
+    BurnRate, BurnRateThresholds, UsageEntry,
 };
 use crate::config::{InputData, SegmentConfig, SegmentId};
-use crate::utils::{data_loader::DataLoader, data_loader_fast::FastDataLoader};
+use crate::utils::data_loader::EntryLoader;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
+/// What the BurnRate segment's main text shows (`display` option)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RateDisplay {
+    /// `$4.20/hr`
+    Cost,
+    /// `12.5k tok/min`
+    Tokens,
+    /// `$4.20/hr · 12.5k tok/min`
+    Both,
+}
+
+impl RateDisplay {
+    fn parse(value: Option<&str>) -> Self {
+        match value {
+            Some("tokens") => Self::Tokens,
+            Some("both") => Self::Both,
+            _ => Self::Cost,
+        }
+    }
+
+    fn format(self, cost_per_hour: Option<f64>, tokens_per_minute: Option<f64>) -> String {
+        let cost = cost_per_hour.map_or("—".to_string(), |cost| format!("${:.2}", cost));
+        let tokens = tokens_per_minute.map_or("—".to_string(), format_tokens);
+        match self {
+            Self::Cost => format!("{}/hr", cost),
+            Self::Tokens => format!("{} tok/min", tokens),
+            Self::Both => format!("{}/hr · {} tok/min", cost, tokens),
+        }
+    }
+}
+
+fn format_tokens(tokens: f64) -> String {
+    if tokens >= 1_000_000.0 {
+        format!("{:.1}M", tokens / 1_000_000.0)
+    } else if tokens >= 1_000.0 {
+        format!("{:.1}k", tokens / 1_000.0)
+    } else {
+        format!("{:.0}", tokens)
+    }
+}
+
 pub struct BurnRateSegment {
     enabled: bool,
+    display: RateDisplay,
     thresholds: BurnRateThresholds,
-    use_fast_loader: bool,
-    thread_multiplier: Option<f64>,
+    /// Glyphs for high, medium and normal burn rates
+    indicators: [String; 3],
+    loader: EntryLoader,
+    show_projection: bool,
+    /// Number of recent samples drawn as a sparkline, when enabled
+    sparkline: Option<usize>,
+    /// Plot `tokens_per_minute` instead of `cost_per_hour`
+    sparkline_tokens: bool,
 }
 
 impl BurnRateSegment {
     pub fn new(config: &SegmentConfig) -> Self {
         Self {
             enabled: config.enabled,
-            thresholds: BurnRateThresholds::from_env(),
-            use_fast_loader: config
+            display: RateDisplay::parse(config.options.get("display").and_then(|v| v.as_str())),
+            thresholds: BurnRateThresholds::from_options(&config.options),
+            indicators: [
+                ("high_indicator", "\u{ef76}"),   // 🔥 Fire (Nerd Font)
+                ("medium_indicator", "\u{f0e7}"), // ⚡ Lightning bolt (Nerd Font)
+                ("normal_indicator", "\u{f0e4}"), // 📊 Dashboard/gauge (Nerd Font)
+            ]
+            .map(|(key, default)| {
+                config
+                    .options
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(default)
+                    .to_string()
+            }),
+            loader: EntryLoader::from_options(&config.options, Some(1)),
+            show_projection: config
                 .options
-                .get("fast_loader")
+                .get("show_projection")
                 .and_then(|v| v.as_bool())
-                .unwrap_or(true),
-            thread_multiplier: config
+                .unwrap_or(false),
+            sparkline: config
                 .options
-                .get("thread_multiplier")
-                .and_then(|v| v.as_f64()),
+                .get("sparkline")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+                .then(|| {
+                    config
+                        .options
+                        .get("sparkline_samples")
+                        .and_then(|v| v.as_u64())
+                        .filter(|n| *n > 0)
+                        .unwrap_or(12) as usize
+                }),
+            sparkline_tokens: config
+                .options
+                .get("sparkline_metric")
+                .and_then(|v| v.as_str())
+                == Some("tokens"),
         }
     }
 
-    fn get_indicator(&self, tokens_per_minute: f64) -> &'static str {
+    fn get_indicator(&self, tokens_per_minute: f64) -> &str {
+        let [high, medium, normal] = &self.indicators;
         if tokens_per_minute > self.thresholds.high {
-            "\u{ef76}" // 🔥 Fire (Nerd Font)
+            high
         } else if tokens_per_minute > self.thresholds.medium {
-            "\u{f0e7}" // ⚡ Lightning bolt (Nerd Font)
+            medium
         } else {
-            "\u{f0e4}" // 📊 Dashboard/gauge (Nerd Font)
+            normal
         }
     }
 
-    fn collect_with_data(&self, _input: &InputData) -> SegmentData {
-        // Load all project data globally (like ccusage does)
-        let mut all_entries = if self.use_fast_loader {
-            // Use optimized fast loader with optional thread multiplier
-            let mut fast_loader = if let Some(multiplier) = self.thread_multiplier {
-                FastDataLoader::with_thread_multiplier(multiplier)
-            } else {
-                FastDataLoader::new()
-            };
-            fast_loader.load_all_projects()
-        } else {
-            // Use original loader
-            let mut data_loader = DataLoader::new();
-            data_loader.load_all_projects()
-        };
-
-        // Get pricing data (use global runtime to handle async)
-        let pricing_map =
-            crate::utils::block_on(async { ModelPricing::get_pricing_with_fallback().await });
-
-        // Calculate costs for entries
-        for entry in &mut all_entries {
-            if let Some(pricing) = ModelPricing::get_model_pricing(&pricing_map, &entry.model) {
-                entry.cost = Some(pricing.calculate_cost(entry));
-            }
-        }
-
+    /// The segment for already loaded and priced entries
+    pub(crate) fn collect_from(&self, all_entries: &[UsageEntry]) -> SegmentData {
         // Find active billing block using dynamic calculation
-        let blocks = identify_session_blocks_with_overrides(&all_entries);
+        let blocks = identify_session_blocks_with_overrides(all_entries);
         let active_block = find_active_block(&blocks);
 
         // Calculate burn rate
         let mut metadata = HashMap::new();
+        if let Some(block) = active_block {
+            metadata.insert("block_start".to_string(), block.start_time.to_rfc3339());
+        }
 
         let (primary, secondary) =
-            match active_block.and_then(|block| calculate_burn_rate(block, &all_entries)) {
+            match active_block.and_then(|block| calculate_burn_rate(block, all_entries)) {
                 Some(rate) => {
                     let indicator = self.get_indicator(rate.tokens_per_minute_for_indicator);
                     metadata.insert(
@@ -89,15 +148,36 @@ impl BurnRateSegment {
                         format!("{:.1}", rate.tokens_per_minute_for_indicator),
                     );
                     metadata.insert("trend".to_string(), format!("{:?}", rate.trend));
+                    metadata.insert(
+                        "projected_tokens".to_string(),
+                        rate.projection.total_tokens.to_string(),
+                    );
+                    metadata.insert(
+                        "projected_cost".to_string(),
+                        format!("{:.2}", rate.projection.total_cost),
+                    );
 
-                    (
-                        format!("${:.2}/hr", rate.cost_per_hour),
-                        indicator.to_string(),
-                    )
+                    let mut secondary = if self.show_projection {
+                        format!(
+                            "{} → ${:.2} by block end",
+                            indicator, rate.projection.total_cost
+                        )
+                    } else {
+                        indicator.to_string()
+                    };
+                    if let (Some(count), Some(block)) = (self.sparkline, active_block) {
+                        let trend = self.record_sample(block.start_time, &rate, count);
+                        secondary = format!("{} {}", trend, secondary);
+                    }
+                    let primary = self.display.format(
+                        Some(rate.cost_per_hour),
+                        Some(rate.tokens_per_minute_for_indicator),
+                    );
+                    (primary, secondary)
                 }
                 None => {
                     metadata.insert("status".to_string(), "no_data".to_string());
-                    ("—/hr".to_string(), "\u{f0e4}".to_string())
+                    (self.display.format(None, None), self.indicators[2].clone())
                 }
             };
 
@@ -107,28 +187,55 @@ impl BurnRateSegment {
             metadata,
         }
     }
+
+    /// Record this reading, saving the history when it starts a new sample,
+    /// and draw the last `count` samples as a sparkline
+    fn record_sample(&self, block_start: DateTime<Utc>, rate: &BurnRate, count: usize) -> String {
+        let mut history = BurnHistory::load();
+        let sample = BurnSample {
+            timestamp: Utc::now(),
+            cost_per_hour: rate.cost_per_hour,
+            tokens_per_minute: rate.tokens_per_minute_for_indicator,
+        };
+        if history.record(block_start, sample) {
+            if let Err(e) = history.save() {
+                crate::debug_println!("Could not save burn rate history: {}", e);
+            }
+        }
+
+        let values: Vec<f64> = history
+            .recent(count)
+            .iter()
+            .map(|sample| {
+                if self.sparkline_tokens {
+                    sample.tokens_per_minute
+                } else {
+                    sample.cost_per_hour
+                }
+            })
+            .collect();
+        sparkline(&values)
+    }
 }
 
 impl Segment for BurnRateSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, _input: &InputData) -> SegmentResult {
         if !self.enabled {
-            return None;
+            return Ok(None);
         }
 
-        // Handle potential errors gracefully
-        match std::panic::catch_unwind(|| self.collect_with_data(input)) {
-            Ok(result) => Some(result),
-            Err(_) => {
-                let mut metadata = HashMap::new();
-                metadata.insert("error".to_string(), "true".to_string());
-
-                Some(SegmentData {
-                    primary: "—/hr".to_string(),
-                    secondary: "\u{f0e4}".to_string(),
-                    metadata,
-                })
-            }
-        }
+        // Load all project data globally (like ccusage does)
+        let mut all_entries = self.loader.load();
+        price_entries(&mut all_entries);
+        Ok(Some(self.collect_from(&all_entries)))
+    }
+
+    fn placeholder(&self) -> Option<SegmentData> {
+        Some(SegmentData {
+            primary: self.display.format(None, None),
+            secondary: self.indicators[2].clone(),
+            metadata: HashMap::new(),
+        })
     }
 
     fn id(&self) -> SegmentId {
@@ -139,75 +246,59 @@ impl Segment for BurnRateSegment {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{
-        ColorConfig, IconConfig, Model, SegmentConfig, SegmentId, TextStyleConfig, Workspace,
-    };
-    use std::collections::HashMap;
-
-    fn create_test_config(enabled: bool) -> SegmentConfig {
-        SegmentConfig {
-            id: SegmentId::BurnRate,
-            enabled,
-            icon: IconConfig {
-                plain: "🔥".to_string(),
-                nerd_font: "\u{f1e2}".to_string(),
-            },
-            colors: ColorConfig {
-                icon: None,
-                text: None,
-                background: None,
-            },
-            styles: TextStyleConfig::default(),
-            options: {
-                let mut opts = HashMap::new();
-                opts.insert("fast_loader".to_string(), serde_json::json!(true));
-                opts
-            },
-        }
-    }
+    use crate::config::{Model, Workspace};
+    use crate::core::segments::test_config;
+    use serde_json::json;
 
     #[test]
     fn test_burn_rate_segment_disabled() {
-        let config = create_test_config(false);
+        let mut config = test_config(SegmentId::BurnRate, &[("fast_loader", json!(true))]);
+        config.enabled = false;
         let segment = BurnRateSegment::new(&config);
         let input = InputData {
             model: Model {
+                id: String::new(),
                 display_name: "test-model".to_string(),
             },
             workspace: Workspace {
                 current_dir: "/test".to_string(),
+                ..Default::default()
             },
             transcript_path: "/test/transcript.jsonl".to_string(),
             session_id: None,
             cost: None,
+            ..Default::default()
         };
 
-        assert!(segment.collect(&input).is_none());
+        assert!(segment.collect(&input).unwrap().is_none());
     }
 
     #[test]
     fn test_burn_rate_segment_enabled() {
-        let config = create_test_config(true);
+        let config = test_config(SegmentId::BurnRate, &[("fast_loader", json!(true))]);
         let segment = BurnRateSegment::new(&config);
         let input = InputData {
             model: Model {
+                id: String::new(),
                 display_name: "test-model".to_string(),
             },
             workspace: Workspace {
                 current_dir: "/test".to_string(),
+                ..Default::default()
             },
             transcript_path: "/test/transcript.jsonl".to_string(),
             session_id: None,
             cost: None,
+            ..Default::default()
         };
 
         // Should return Some data when enabled
-        assert!(segment.collect(&input).is_some());
+        assert!(segment.collect(&input).unwrap().is_some());
     }
 
     #[test]
     fn test_indicator_selection() {
-        let config = create_test_config(true);
+        let config = test_config(SegmentId::BurnRate, &[("fast_loader", json!(true))]);
         let segment = BurnRateSegment::new(&config);
 
         // Test high burn rate
@@ -219,4 +310,47 @@ mod tests {
         // Test normal burn rate
         assert_eq!(segment.get_indicator(1000.0), "\u{f0e4}"); // Dashboard
     }
+
+    #[test]
+    fn test_thresholds_from_options() {
+        let config = test_config(
+            SegmentId::BurnRate,
+            &[
+                ("high_threshold", json!(800)),
+                ("medium_threshold", json!(300.5)),
+                ("high_indicator", json!("!!")),
+            ],
+        );
+        let segment = BurnRateSegment::new(&config);
+
+        assert_eq!(segment.get_indicator(1000.0), "!!");
+        assert_eq!(segment.get_indicator(500.0), "\u{f0e7}");
+        assert_eq!(segment.get_indicator(300.0), "\u{f0e4}");
+    }
+
+    #[test]
+    fn test_display_modes() {
+        let config = test_config(SegmentId::BurnRate, &[]);
+        assert_eq!(
+            BurnRateSegment::new(&config).placeholder().unwrap().primary,
+            "—/hr"
+        );
+
+        let config = test_config(SegmentId::BurnRate, &[("display", json!("tokens"))]);
+        let segment = BurnRateSegment::new(&config);
+        assert_eq!(segment.placeholder().unwrap().primary, "— tok/min");
+        assert_eq!(
+            segment.display.format(Some(4.2), Some(12_480.0)),
+            "12.5k tok/min"
+        );
+
+        assert_eq!(
+            RateDisplay::parse(Some("both")).format(Some(4.2), Some(850.0)),
+            "$4.20/hr · 850 tok/min"
+        );
+        assert_eq!(
+            RateDisplay::parse(Some("cost")).format(Some(4.2), Some(850.0)),
+            "$4.20/hr"
+        );
+    }
 }
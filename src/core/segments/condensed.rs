@@ -0,0 +1,259 @@
+use super::{
+    BlockLimitSegment, BurnRateSegment, Segment, SegmentData, SegmentResult, UsageSegment,
+    WeeklySegment,
+};
+use crate::billing::{entries::price_entries, BurnRateThresholds, UsageEntry};
+use crate::config::{GlobalConfig, InputData, SegmentConfig, SegmentId};
+use crate::utils::data_loader::EntryLoader;
+use std::collections::HashMap;
+
+/// Fill levels for a cell, from idle to full
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// Shown for a metric with no data (no active block, no limit, ...)
+const NO_DATA: char = '·';
+
+/// A load figure that can feed one cell of the condensed bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CondensedMetric {
+    /// Share of the context window in use
+    Context,
+    /// Share of the per-block token cap (see the `block_limit` segment)
+    Quota,
+    /// Token burn rate relative to the high burn threshold
+    Burn,
+    /// Share of the weekly cap (see the `weekly` segment)
+    Weekly,
+}
+
+impl CondensedMetric {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "context" => Some(CondensedMetric::Context),
+            "quota" => Some(CondensedMetric::Quota),
+            "burn" => Some(CondensedMetric::Burn),
+            "weekly" => Some(CondensedMetric::Weekly),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CondensedMetric::Context => "context",
+            CondensedMetric::Quota => "quota",
+            CondensedMetric::Burn => "burn",
+            CondensedMetric::Weekly => "weekly",
+        }
+    }
+
+    fn default_color(&self) -> u8 {
+        match self {
+            CondensedMetric::Context => 39,
+            CondensedMetric::Quota => 141,
+            CondensedMetric::Burn => 208,
+            CondensedMetric::Weekly => 114,
+        }
+    }
+}
+
+/// Context, block quota and burn rate fused into a tiny bar of colored cells,
+/// one glyph per metric, for statuslines with little room
+pub struct CondensedSegment<'a> {
+    global: &'a GlobalConfig,
+    config: &'a SegmentConfig,
+    cells: Vec<CondensedMetric>,
+    colors: Vec<u8>,
+}
+
+impl<'a> CondensedSegment<'a> {
+    pub fn new(global: &'a GlobalConfig, config: &'a SegmentConfig) -> Self {
+        let cells: Vec<CondensedMetric> = config
+            .options
+            .get("cells")
+            .and_then(|v| v.as_array())
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| name.as_str().and_then(CondensedMetric::parse))
+                    .collect()
+            })
+            .filter(|cells: &Vec<CondensedMetric>| !cells.is_empty())
+            .unwrap_or_else(|| {
+                vec![
+                    CondensedMetric::Context,
+                    CondensedMetric::Quota,
+                    CondensedMetric::Burn,
+                ]
+            });
+        let custom_colors = config.options.get("cell_colors").and_then(|v| v.as_array());
+        let colors = cells
+            .iter()
+            .enumerate()
+            .map(|(i, metric)| {
+                custom_colors
+                    .and_then(|colors| colors.get(i))
+                    .and_then(|v| v.as_u64())
+                    .map(|c256| c256.min(255) as u8)
+                    .unwrap_or_else(|| metric.default_color())
+            })
+            .collect();
+
+        Self {
+            global,
+            config,
+            cells,
+            colors,
+        }
+    }
+
+    /// Usage entries for the quota, burn and weekly cells, loaded and priced
+    /// once for all of them
+    fn load_entries(&self) -> Vec<UsageEntry> {
+        if self.cells.iter().all(|m| *m == CondensedMetric::Context) {
+            return Vec::new();
+        }
+        let window_days = if self.cells.contains(&CondensedMetric::Weekly) {
+            7
+        } else {
+            1
+        };
+        let mut entries = EntryLoader::from_options(&self.config.options, Some(window_days)).load();
+        // Only the block quota does without costs
+        if self
+            .cells
+            .iter()
+            .any(|m| matches!(m, CondensedMetric::Burn | CondensedMetric::Weekly))
+        {
+            price_entries(&mut entries);
+        }
+        entries
+    }
+
+    /// Current value of a metric in percent, if there is one
+    fn measure(
+        &self,
+        metric: CondensedMetric,
+        input: &InputData,
+        entries: &[UsageEntry],
+    ) -> Option<f64> {
+        let percent = |data: SegmentData, key: &str| {
+            data.metadata.get(key).and_then(|v| v.parse::<f64>().ok())
+        };
+        match metric {
+            // A failed measurement leaves its cell empty
+            CondensedMetric::Context => UsageSegment::new(self.global)
+                .collect(input)
+                .ok()
+                .flatten()
+                .and_then(|data| percent(data, "percentage")),
+            CondensedMetric::Quota => percent(
+                BlockLimitSegment::new(self.config).collect_from(entries),
+                "block_limit_percent",
+            ),
+            CondensedMetric::Burn => {
+                let high = BurnRateThresholds::from_options(&self.config.options).high;
+                percent(
+                    BurnRateSegment::new(self.config).collect_from(entries),
+                    "tokens_per_minute",
+                )
+                .filter(|_| high > 0.0)
+                .map(|tokens_per_minute| tokens_per_minute / high * 100.0)
+            }
+            CondensedMetric::Weekly => percent(
+                WeeklySegment::new(self.config).collect_from(entries),
+                "week_limit_percent",
+            ),
+        }
+    }
+
+    fn render(&self, values: &[Option<f64>]) -> SegmentData {
+        let mut metadata = HashMap::new();
+        for (metric, value) in self.cells.iter().zip(values) {
+            if let Some(value) = value {
+                metadata.insert(
+                    format!("{}_percent", metric.as_str()),
+                    format!("{:.1}", value),
+                );
+            }
+        }
+        metadata.insert(
+            "cell_colors".to_string(),
+            self.colors
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+
+        SegmentData {
+            primary: values.iter().map(|value| cell_glyph(*value)).collect(),
+            secondary: String::new(),
+            metadata,
+        }
+    }
+}
+
+/// Glyph for a percentage: higher load fills more of the cell
+fn cell_glyph(percent: Option<f64>) -> char {
+    match percent {
+        Some(percent) => {
+            let level = (percent.clamp(0.0, 100.0) / 100.0 * (LEVELS.len() - 1) as f64).round();
+            LEVELS[level as usize]
+        }
+        None => NO_DATA,
+    }
+}
+
+impl Segment for CondensedSegment<'_> {
+    fn collect(&self, input: &InputData) -> SegmentResult {
+        if !self.config.enabled {
+            return Ok(None);
+        }
+
+        let entries = self.load_entries();
+        let values: Vec<Option<f64>> = self
+            .cells
+            .iter()
+            .map(|metric| self.measure(*metric, input, &entries))
+            .collect();
+        Ok(Some(self.render(&values)))
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Condensed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::segments::test_config;
+
+    fn config(options: &[(&str, serde_json::Value)]) -> SegmentConfig {
+        test_config(SegmentId::Condensed, options)
+    }
+
+    #[test]
+    fn test_condensed_cells() {
+        let global = GlobalConfig::default();
+        let default_config = config(&[]);
+        let segment = CondensedSegment::new(&global, &default_config);
+        let data = segment.render(&[Some(0.0), Some(50.0), None]);
+        assert_eq!(data.primary, "▁▅·");
+        assert_eq!(data.metadata.get("cell_colors").unwrap(), "39,141,208");
+        assert_eq!(data.metadata.get("quota_percent").unwrap(), "50.0");
+        assert!(!data.metadata.contains_key("burn_percent"));
+
+        // Cells follow the configured mapping; unknown metrics are ignored
+        let custom_config = config(&[
+            ("cells", serde_json::json!(["burn", "bogus", "weekly"])),
+            ("cell_colors", serde_json::json!([196])),
+        ]);
+        let segment = CondensedSegment::new(&global, &custom_config);
+        assert_eq!(
+            segment.cells,
+            vec![CondensedMetric::Burn, CondensedMetric::Weekly]
+        );
+        assert_eq!(segment.colors, vec![196, 114]);
+        assert_eq!(segment.render(&[Some(250.0), Some(99.0)]).primary, "██");
+    }
+}
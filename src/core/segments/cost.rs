@@ -1,22 +1,56 @@
-use super::{Segment, SegmentData};
+use super::{new_session_marker, Segment, SegmentData, SegmentResult};
 use crate::billing::{
     block::{find_active_block, identify_session_blocks_with_overrides},
-    calculator::{calculate_daily_total, calculate_session_cost, format_remaining_time},
-    ModelPricing,
+    calculator::{
+        calculate_daily_total, calculate_monthly_total, calculate_rolling_total,
+        calculate_session_cost, format_remaining_time, ROLLING_WINDOWS_DAYS,
+    },
+    entries::apply_subagent_mode,
+    price_changes::{PriceChangeLog, RECENT_HOURS},
+    pricing::{pricing_fallback, PricingFallback},
+    ModelPricing, UsageEntry,
 };
-use crate::config::{CostSource, InputData, SegmentConfig, SegmentId};
+use crate::config::{CostSource, InputData, SegmentConfig, SegmentId, StyleMode, SubagentMode};
+use crate::core::segment_cache::SegmentCache;
 use crate::utils::{
-    data_loader::DataLoader, data_loader_fast::FastDataLoader, transcript::extract_session_id,
+    data_loader::EntryLoader,
+    format::format_age,
+    index_state, profile, timezone,
+    transcript::{extract_session_id, is_new_session, project_key},
 };
+use chrono::{DateTime, FixedOffset, Local, Utc};
 use std::collections::HashMap;
-use std::time::Instant;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Seconds after which unchanged last known figures are saved again
+const LAST_KNOWN_REFRESH_SECS: i64 = 60;
 
 pub struct CostSegment {
     enabled: bool,
     show_timing: bool,
-    use_fast_loader: bool,
-    thread_multiplier: Option<f64>,
+    loader: EntryLoader,
     cost_source: CostSource,
+    cold_start_placeholder: bool,
+    /// Warn when native and calculated session costs differ by more than this
+    discrepancy_percent: Option<f64>,
+    show_models: bool,
+    show_monthly: bool,
+    /// Timezone where months start for `show_monthly`: `None` follows the
+    /// global `timezone`, `Some(None)` is the system's local time
+    month_offset: Option<Option<FixedOffset>>,
+    /// Only load the current workspace's project (`scope = "project"`), so
+    /// daily, block and rolling totals cover that project alone
+    project_scope: bool,
+    subagents: SubagentMode,
+    /// Key of this instance in the last-known cache
+    instance_key: String,
+    /// Marks last known values shown in place of fresh ones
+    stale_icon: String,
+    new_session_marker: &'static str,
+    /// Show the last known values when loading usage data takes longer
+    load_timeout: Option<Duration>,
 }
 
 impl CostSegment {
@@ -33,6 +67,13 @@ impl CostSegment {
                 _ => None,
             })
             .unwrap_or_default();
+        let show_monthly = config
+            .options
+            .get("show_monthly")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        // The rolling totals reach 30 days back, month-to-date up to 31
+        let window_days = if show_monthly { 32 } else { 31 };
 
         Self {
             enabled: config.enabled,
@@ -41,20 +82,104 @@ impl CostSegment {
                 .get("show_timing")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false),
-            use_fast_loader: config
+            loader: EntryLoader::from_options(&config.options, Some(window_days)),
+            cost_source,
+            cold_start_placeholder: config
                 .options
-                .get("fast_loader")
+                .get("cold_start_placeholder")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(true),
-            thread_multiplier: config
+            discrepancy_percent: config
                 .options
-                .get("thread_multiplier")
-                .and_then(|v| v.as_f64()),
-            cost_source,
+                .get("discrepancy_percent")
+                .and_then(|v| v.as_f64())
+                .filter(|p| *p > 0.0),
+            show_models: config
+                .options
+                .get("show_models")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            show_monthly,
+            month_offset: config
+                .options
+                .get("month_timezone")
+                .and_then(|v| v.as_str())
+                .and_then(|v| timezone::parse_timezone(v).ok()),
+            project_scope: config.options.get("scope").and_then(|v| v.as_str()) == Some("project"),
+            subagents: SubagentMode::default(),
+            instance_key: config.instance_key(),
+            stale_icon: config
+                .options
+                .get("stale_icon")
+                .and_then(|v| v.as_str())
+                .unwrap_or("◌")
+                .to_string(),
+            new_session_marker: "✨",
+            load_timeout: config
+                .options
+                .get("load_timeout_ms")
+                .and_then(|v| v.as_u64())
+                .filter(|ms| *ms > 0)
+                .map(Duration::from_millis),
         }
     }
 
-    fn collect_with_pricing(&self, input: &InputData) -> SegmentData {
+    pub fn with_subagents(mut self, subagents: SubagentMode) -> Self {
+        self.subagents = subagents;
+        self
+    }
+
+    /// Pick the new-session marker for the configured style mode and icons
+    pub fn with_style(mut self, mode: StyleMode, config: &SegmentConfig) -> Self {
+        self.new_session_marker = new_session_marker(mode, config);
+        self
+    }
+
+    /// Shown while the first full scan runs in the background
+    fn indexing_placeholder(&self, input: &InputData) -> SegmentData {
+        let mut metadata = HashMap::new();
+        metadata.insert("status".to_string(), "indexing".to_string());
+
+        SegmentData {
+            primary: match input.cost.as_ref() {
+                Some(cost) => format!("${:.2} session", cost.total_cost_usd),
+                None => "indexing…".to_string(),
+            },
+            secondary: "indexing usage data…".to_string(),
+            metadata,
+        }
+    }
+
+    /// `last` marked as stale: `kind` names what failed and `reason` how
+    fn stale(
+        &self,
+        (last, saved_at): (&SegmentData, DateTime<Utc>),
+        kind: &str,
+        reason: &str,
+    ) -> SegmentData {
+        let mut data = last.clone();
+        let marker = format!("{} {}", self.stale_icon, format_age(Utc::now() - saved_at));
+        data.secondary = if data.secondary.is_empty() {
+            marker
+        } else {
+            format!("{} · {}", data.secondary, marker)
+        };
+        data.metadata.insert("stale".to_string(), kind.to_string());
+        data.metadata
+            .insert("stale_reason".to_string(), reason.to_string());
+        data.metadata
+            .insert("stale_since".to_string(), saved_at.to_rfc3339());
+        data
+    }
+
+    /// Compute the segment, or fall back to `last_known` (its last good value
+    /// in this session) when loading times out or only the built-in pricing
+    /// table is available
+    fn collect_with_pricing(
+        &self,
+        input: &InputData,
+        last_known: Option<(&SegmentData, DateTime<Utc>)>,
+    ) -> SegmentData {
         // Performance timing
         let start = Instant::now();
         let mut timings = Vec::new();
@@ -62,28 +187,51 @@ impl CostSegment {
         // Get native cost if available
         let native_cost = input.cost.as_ref().map(|c| c.total_cost_usd);
 
-        // 1. Always load all project data
+        // 1. Load all project data, or only the workspace's project
         let load_start = Instant::now();
-        let mut all_entries = if self.use_fast_loader {
-            // Use optimized fast loader with optional thread multiplier
-            let mut fast_loader = if let Some(multiplier) = self.thread_multiplier {
-                FastDataLoader::with_thread_multiplier(multiplier)
-            } else {
-                FastDataLoader::new()
-            };
-            fast_loader.load_all_projects()
-        } else {
-            // Use original loader
-            let mut data_loader = DataLoader::new();
-            data_loader.load_all_projects()
+        let project = self
+            .project_scope
+            .then(|| project_key(input.workspace.roots()[0]));
+        if let Some(project) = &project {
+            crate::trace_step!("scope = project: only loading {}", project);
+        }
+        let loader = self.loader.clone().only_project(project.clone());
+        let (mut all_entries, other_entries) = match (self.load_timeout, last_known) {
+            (Some(timeout), Some(last)) => {
+                let (sender, receiver) = mpsc::channel();
+                std::thread::spawn(move || {
+                    let _ = sender.send((loader.load(), loader.load_other_tools()));
+                });
+                match receiver.recv_timeout(timeout) {
+                    Ok(entries) => entries,
+                    Err(_) => {
+                        let reason = format!(
+                            "loading usage data took longer than {}ms",
+                            timeout.as_millis()
+                        );
+                        crate::trace_step!("{}; showing the last known values", reason);
+                        return self.stale(last, "timeout", &reason);
+                    }
+                }
+            }
+            _ => (loader.load(), loader.load_other_tools()),
         };
-        timings.push(("L", load_start.elapsed().as_millis()));
+        apply_subagent_mode(&mut all_entries, self.subagents);
+        timings.push(("load", load_start.elapsed()));
 
         // 2. Get pricing data (use global runtime to handle async)
         let pricing_start = Instant::now();
         let pricing_map =
             crate::utils::block_on(async { ModelPricing::get_pricing_with_fallback().await });
-        timings.push(("P", pricing_start.elapsed().as_millis()));
+        timings.push(("pricing", pricing_start.elapsed()));
+        // The built-in table may lack or misprice models; old figures are closer
+        let fallback = pricing_fallback();
+        if let (Some(fallback @ PricingFallback::BuiltIn { .. }), Some(last)) =
+            (&fallback, last_known)
+        {
+            crate::trace_step!("Only built-in prices available; showing the last known values");
+            return self.stale(last, "pricing", fallback.error());
+        }
 
         // 3. Calculate costs for all entries
         let calc_start = Instant::now();
@@ -92,16 +240,44 @@ impl CostSegment {
                 entry.cost = Some(pricing.calculate_cost(entry));
             }
         }
-        timings.push(("C", calc_start.elapsed().as_millis()));
+        timings.push(("calculate", calc_start.elapsed()));
 
         // 4. Calculate session and daily costs
         let analyze_start = Instant::now();
         let transcript_path = std::path::Path::new(&input.transcript_path);
         let session_id = extract_session_id(transcript_path);
+        // In separate mode the session cost covers the main conversation only
+        let subagent_cost = (self.subagents == SubagentMode::Separate).then(|| {
+            all_entries
+                .iter()
+                .filter(|e| e.subagent && e.session_id == session_id)
+                .filter_map(|e| e.cost)
+                .sum::<f64>()
+        });
         let calculated_session_cost =
-            calculate_session_cost(&all_entries, &session_id, &pricing_map);
-        let daily_total = calculate_daily_total(&all_entries, &pricing_map);
-        timings.push(("A", analyze_start.elapsed().as_millis()));
+            calculate_session_cost(&all_entries, &session_id, &pricing_map)
+                - subagent_cost.unwrap_or(0.0);
+        if crate::utils::debug::is_tracing() {
+            trace_session_cost(&all_entries, &session_id, &pricing_map);
+        }
+        // Claude Code's own session cost includes subagent runs as well
+        let native_cost = native_cost.map(|cost| (cost - subagent_cost.unwrap_or(0.0)).max(0.0));
+        // Spend totals add the other tools' usage; blocks stay Claude-only
+        let spend_total =
+            |total: &dyn Fn(&[UsageEntry]) -> f64| total(&all_entries) + total(&other_entries);
+        let daily_total = spend_total(&|entries| calculate_daily_total(entries, &pricing_map));
+        let monthly_total = self.show_monthly.then(|| {
+            spend_total(&|entries| match self.month_offset {
+                Some(Some(offset)) => calculate_monthly_total(
+                    entries,
+                    &pricing_map,
+                    &Utc::now().with_timezone(&offset),
+                ),
+                Some(None) => calculate_monthly_total(entries, &pricing_map, &Local::now()),
+                None => calculate_monthly_total(entries, &pricing_map, &timezone::now()),
+            })
+        });
+        timings.push(("analyze", analyze_start.elapsed()));
 
         // Determine which session cost to use based on strategy
         let session_cost = match self.cost_source {
@@ -110,17 +286,105 @@ impl CostSegment {
             CostSource::Calculated => calculated_session_cost,
             CostSource::Both => calculated_session_cost, // Will show both in display
         };
+        crate::trace_step!(
+            "Session cost: native {}, calculated ${:.4}{}; cost_source = {} uses ${:.4}",
+            native_cost.map_or("unavailable".to_string(), |c| format!("${:.4}", c)),
+            calculated_session_cost,
+            subagent_cost.map_or(String::new(), |c| format!(
+                " (subagents ${:.4} shown separately)",
+                c
+            )),
+            format!("{:?}", self.cost_source).to_lowercase(),
+            session_cost
+        );
+        crate::trace_step!("Today's total across all sessions: ${:.4}", daily_total);
+        if let Some(monthly_total) = monthly_total {
+            crate::trace_step!("Month-to-date total: ${:.4}", monthly_total);
+        }
 
         // 5. Calculate dynamic blocks with override support
         let block_start = Instant::now();
         let blocks = identify_session_blocks_with_overrides(&all_entries);
         let active_block = find_active_block(&blocks);
-        timings.push(("B", block_start.elapsed().as_millis()));
+        match &active_block {
+            Some(block) => crate::trace_step!(
+                "Active block since {}: ${:.4}, {} minutes left",
+                block.start_time.to_rfc3339(),
+                block.cost,
+                block.remaining_minutes
+            ),
+            None => crate::trace_step!("No active block among {} blocks", blocks.len()),
+        }
+        timings.push(("blocks", block_start.elapsed()));
 
         // Build metadata
         let mut metadata = HashMap::new();
+        if let Some(project) = project {
+            metadata.insert("scope".to_string(), "project".to_string());
+            metadata.insert("project".to_string(), project);
+        }
         metadata.insert("session_cost".to_string(), format!("{:.2}", session_cost));
         metadata.insert("daily_total".to_string(), format!("{:.2}", daily_total));
+        if let Some(monthly_total) = monthly_total {
+            metadata.insert("monthly_total".to_string(), format!("{:.2}", monthly_total));
+        }
+        let now = Utc::now();
+        for days in ROLLING_WINDOWS_DAYS {
+            let total =
+                spend_total(&|entries| calculate_rolling_total(entries, &pricing_map, days, now));
+            metadata.insert(format!("rolling_{}d", days), format!("{:.2}", total));
+        }
+        metadata.insert(
+            "calculated_cost".to_string(),
+            format!("{:.2}", calculated_session_cost),
+        );
+        if let Some(native) = native_cost {
+            metadata.insert("native_cost".to_string(), format!("{:.2}", native));
+        }
+        let used = match self.cost_source {
+            CostSource::Auto | CostSource::Native if native_cost.is_some() => "native",
+            CostSource::Native => "none",
+            _ => "calculated",
+        };
+        metadata.insert("cost_source_used".to_string(), used.to_string());
+        if let Some(fallback) = &fallback {
+            metadata.insert(
+                "pricing_fallback".to_string(),
+                fallback.as_str().to_string(),
+            );
+            metadata.insert("pricing_error".to_string(), fallback.error().to_string());
+        }
+
+        // Separate mode leaves subagent spend out of the session figures
+        let model_costs = session_model_costs(all_entries.iter().filter(|e| {
+            e.session_id == session_id && !(e.subagent && self.subagents == SubagentMode::Separate)
+        }));
+        if !model_costs.is_empty() {
+            let breakdown: serde_json::Map<String, serde_json::Value> = model_costs
+                .iter()
+                .map(|(model, cost)| (model.clone(), serde_json::json!(round_cents(*cost))))
+                .collect();
+            metadata.insert(
+                "model_costs".to_string(),
+                serde_json::Value::Object(breakdown).to_string(),
+            );
+        }
+
+        // A large gap hints at pricing table drift or transcripts ccline can't read
+        let discrepancy = native_cost.and_then(|native| {
+            let percent = cost_discrepancy(native, calculated_session_cost)?;
+            let threshold = self.discrepancy_percent?;
+            (percent > threshold).then_some(percent)
+        });
+        if let Some(percent) = discrepancy {
+            crate::trace_step!(
+                "Native and calculated costs differ by {:.0}%, above the {:.0}% threshold",
+                percent,
+                self.discrepancy_percent.unwrap_or_default()
+            );
+            metadata.insert("cost_discrepancy".to_string(), format!("{:.0}", percent));
+            metadata.insert("severity".to_string(), "warning".to_string());
+        }
 
         if let Some(block) = &active_block {
             metadata.insert("block_cost".to_string(), format!("{:.2}", block.cost));
@@ -128,10 +392,16 @@ impl CostSegment {
                 "block_remaining".to_string(),
                 format!("{}", block.remaining_minutes),
             );
+            metadata.insert("block_start".to_string(), block.start_time.to_rfc3339());
         }
 
         // Format primary and secondary text based on cost source
+        let new_session = is_new_session(&input.transcript_path) && session_cost == 0.0;
+        if new_session {
+            metadata.insert("status".to_string(), "new_session".to_string());
+        }
         let primary = match self.cost_source {
+            _ if new_session => format!("{} $0.00 new session", self.new_session_marker),
             CostSource::Both if native_cost.is_some() => {
                 format!(
                     "${:.2} native / ${:.2} calc",
@@ -141,29 +411,70 @@ impl CostSegment {
             }
             _ => format!("${:.2} session", session_cost),
         };
+        // A single model would only repeat the session cost
+        let primary = if self.show_models && model_costs.len() > 1 && !new_session {
+            let breakdown: Vec<String> = model_costs
+                .iter()
+                .map(|(model, cost)| format!("{} ${:.2}", model_abbreviation(model), cost))
+                .collect();
+            format!("{} · {}", primary, breakdown.join(" · "))
+        } else {
+            primary
+        };
+        let primary = match subagent_cost {
+            Some(subagent_cost) => {
+                metadata.insert("subagent_cost".to_string(), format!("{:.2}", subagent_cost));
+                format!("{} · ${:.2} subagents", primary, subagent_cost)
+            }
+            None => primary,
+        };
 
+        let today = match monthly_total {
+            Some(monthly_total) => {
+                format!("${:.2} today · ${:.2} month", daily_total, monthly_total)
+            }
+            None => format!("${:.2} today", daily_total),
+        };
         let secondary = if let Some(block) = active_block {
             format!(
-                "${:.2} today · ${:.2} block ({})",
-                daily_total,
+                "{} · ${:.2} block ({})",
+                today,
                 block.cost,
                 format_remaining_time(block.remaining_minutes)
             )
         } else {
-            format!("${:.2} today · No active block", daily_total)
+            format!("{} · No active block", today)
+        };
+
+        let secondary = match discrepancy {
+            Some(percent) => format!("{} · ⚠ native/calc differ {:.0}%", secondary, percent),
+            None => secondary,
+        };
+
+        // Flag recent price changes, since they shift every calculated cost
+        let recent_changes = PriceChangeLog::load().recent(RECENT_HOURS).len();
+        let secondary = if recent_changes > 0 {
+            metadata.insert("price_changes".to_string(), recent_changes.to_string());
+            format!("{} · ⚠ prices changed", secondary)
+        } else {
+            secondary
         };
 
+        for (stage, elapsed) in &timings {
+            profile::record(&format!("cost.{}", stage), None, *elapsed);
+        }
+
         // Add performance timing to secondary if enabled
         let secondary_with_timing = if self.show_timing {
             let total_ms = start.elapsed().as_millis();
             let timing_str = format!(
                 " [{}ms: L{}|P{}|C{}|A{}|B{}]",
                 total_ms,
-                timings[0].1, // Load
-                timings[1].1, // Pricing
-                timings[2].1, // Calculate
-                timings[3].1, // Analyze
-                timings[4].1  // Block
+                timings[0].1.as_millis(), // Load
+                timings[1].1.as_millis(), // Pricing
+                timings[2].1.as_millis(), // Calculate
+                timings[3].1.as_millis(), // Analyze
+                timings[4].1.as_millis()  // Block
             );
             format!("{}{}", secondary, timing_str)
         } else {
@@ -178,31 +489,211 @@ impl CostSegment {
     }
 }
 
+/// Calculated cost per model, most expensive first
+fn session_model_costs<'a>(
+    entries: impl Iterator<Item = &'a crate::billing::UsageEntry>,
+) -> Vec<(String, f64)> {
+    let mut by_model: HashMap<&str, f64> = HashMap::new();
+    for entry in entries {
+        *by_model.entry(entry.model.as_str()).or_default() += entry.cost.unwrap_or(0.0);
+    }
+    let mut costs: Vec<(String, f64)> = by_model
+        .into_iter()
+        .map(|(model, cost)| (model.to_string(), cost))
+        .collect();
+    costs.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    costs
+}
+
+fn round_cents(cost: f64) -> f64 {
+    (cost * 100.0).round() / 100.0
+}
+
+/// Short model label for the breakdown: family initial plus version, e.g.
+/// `claude-opus-4-1-20250805` → `O4.1` and `claude-3-5-haiku-20241022` → `H3.5`
+pub(crate) fn model_abbreviation(model: &str) -> String {
+    let lower = model.to_lowercase();
+    let family = ["opus", "sonnet", "haiku"]
+        .into_iter()
+        .find(|family| lower.contains(family));
+    let Some(family) = family else {
+        return lower
+            .trim_start_matches("claude-")
+            .chars()
+            .take(8)
+            .collect();
+    };
+    // Version parts are short numbers; the release date is eight digits
+    let version: Vec<&str> = lower
+        .split(['-', '.', '@'])
+        .filter(|part| !part.is_empty() && part.len() <= 2)
+        .filter(|part| part.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    format!("{}{}", family[..1].to_uppercase(), version.join("."))
+}
+
+/// How far apart two cost figures are, as a percentage of the larger one;
+/// `None` when both are under a cent and the difference is meaningless
+fn cost_discrepancy(native: f64, calculated: f64) -> Option<f64> {
+    let larger = native.max(calculated);
+    if larger < 0.01 {
+        return None;
+    }
+    Some((native - calculated).abs() / larger * 100.0)
+}
+
+/// Trace how a session's calculated cost adds up, per model
+fn trace_session_cost(
+    entries: &[crate::billing::UsageEntry],
+    session_id: &str,
+    pricing_map: &HashMap<String, ModelPricing>,
+) {
+    let session: Vec<_> = entries
+        .iter()
+        .filter(|e| e.session_id == session_id)
+        .collect();
+    crate::trace_step!("Session {}: {} usage entries", session_id, session.len());
+
+    let mut models: Vec<&str> = session.iter().map(|e| e.model.as_str()).collect();
+    models.sort_unstable();
+    models.dedup();
+    for model in models {
+        let (mut input, mut output, mut creation, mut read) = (0u64, 0u64, 0u64, 0u64);
+        for entry in session.iter().filter(|e| e.model == model) {
+            input += entry.input_tokens;
+            output += entry.output_tokens;
+            creation += entry.cache_creation_tokens;
+            read += entry.cache_read_tokens;
+        }
+        let Some(pricing) = ModelPricing::get_model_pricing(pricing_map, model) else {
+            crate::trace_step!("  {}: no pricing found, counted as $0", model);
+            continue;
+        };
+        // Prices are per 1k tokens; show them per million like price lists do
+        let cost = |tokens: u64, per_1k: f64| tokens as f64 / 1000.0 * per_1k;
+        let per_m = |per_1k: f64| (per_1k * 1000.0 * 10000.0).round() / 10000.0;
+        crate::trace_step!(
+            "  {} (priced as {}): input {} × ${}/M + output {} × ${}/M + cache write {} × ${}/M + cache read {} × ${}/M = ${:.4}",
+            model,
+            pricing.model_name,
+            input,
+            per_m(pricing.input_cost_per_1k),
+            output,
+            per_m(pricing.output_cost_per_1k),
+            creation,
+            per_m(pricing.cache_creation_cost_per_1k),
+            read,
+            per_m(pricing.cache_read_cost_per_1k),
+            cost(input, pricing.input_cost_per_1k)
+                + cost(output, pricing.output_cost_per_1k)
+                + cost(creation, pricing.cache_creation_cost_per_1k)
+                + cost(read, pricing.cache_read_cost_per_1k)
+        );
+    }
+}
+
 impl Segment for CostSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, input: &InputData) -> SegmentResult {
         // Fast path: skip all expensive operations when disabled
         if !self.enabled {
-            return None;
-        }
-
-        // Handle potential errors gracefully
-        match std::panic::catch_unwind(|| self.collect_with_pricing(input)) {
-            Ok(result) => Some(result),
-            Err(_) => {
-                // Fallback display on error
-                let mut metadata = HashMap::new();
-                metadata.insert("error".to_string(), "true".to_string());
-
-                Some(SegmentData {
-                    primary: "$0.00 session".to_string(),
-                    secondary: "$0.00 today · Error loading data".to_string(),
-                    metadata,
-                })
+            return Ok(None);
+        }
+
+        // The first full scan can take seconds, so it runs detached on cold
+        // start; a single project is quick enough to scan inline
+        if self.cold_start_placeholder && !self.project_scope && !index_state::ensure_indexed() {
+            crate::trace_step!("First usage scan still running, showing the indexing placeholder");
+            return Ok(Some(self.indexing_placeholder(input)));
+        }
+
+        let key = format!(
+            "{}/{}",
+            extract_session_id(Path::new(&input.transcript_path)),
+            self.instance_key
+        );
+        let last_known = SegmentCache::load_last_known();
+        let data = self.collect_with_pricing(input, last_known.get_last(&key));
+
+        // Remember figures computed with real prices for the next failure
+        let fresh = !data.metadata.contains_key("stale")
+            && data.metadata.get("pricing_fallback").map(String::as_str) != Some("built_in");
+        if fresh {
+            let now = Utc::now();
+            // Unchanged figures are only rewritten now and then, to keep
+            // their age current
+            let saved = SegmentCache::update_last_known(now, |last_known| {
+                let current = last_known.get_last(&key).is_some_and(|(last, saved_at)| {
+                    *last == data
+                        && now - saved_at < chrono::Duration::seconds(LAST_KNOWN_REFRESH_SECS)
+                });
+                if !current {
+                    last_known.insert(key, data.clone(), now);
+                }
+                !current
+            });
+            if let Err(e) = saved {
+                crate::debug_println!("Could not save last known cost: {}", e);
             }
         }
+        Ok(Some(data))
+    }
+
+    fn placeholder(&self) -> Option<SegmentData> {
+        Some(SegmentData {
+            primary: "$0.00 session".to_string(),
+            secondary: "$0.00 today · Error loading data".to_string(),
+            metadata: HashMap::new(),
+        })
     }
 
     fn id(&self) -> SegmentId {
         SegmentId::Cost
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost_discrepancy() {
+        assert_eq!(cost_discrepancy(0.001, 0.0), None);
+        assert_eq!(cost_discrepancy(2.0, 1.5), Some(25.0));
+        assert_eq!(cost_discrepancy(1.5, 2.0), Some(25.0));
+        assert_eq!(cost_discrepancy(0.0, 1.0), Some(100.0));
+    }
+
+    #[test]
+    fn test_model_abbreviation() {
+        assert_eq!(model_abbreviation("claude-sonnet-4-20250514"), "S4");
+        assert_eq!(model_abbreviation("claude-opus-4-1-20250805"), "O4.1");
+        assert_eq!(model_abbreviation("claude-3-5-haiku-20241022"), "H3.5");
+        assert_eq!(model_abbreviation("gpt-4o-mini"), "gpt-4o-m");
+    }
+
+    #[test]
+    fn test_stale() {
+        let mut config = crate::config::Config::default()
+            .segments
+            .into_iter()
+            .find(|s| s.id == SegmentId::Cost)
+            .unwrap();
+        config
+            .options
+            .insert("stale_icon".to_string(), serde_json::json!("⚠"));
+        let segment = CostSegment::new(&config);
+        let last = SegmentData {
+            primary: "$4.20".to_string(),
+            secondary: "today".to_string(),
+            metadata: HashMap::new(),
+        };
+        let saved_at = Utc::now() - chrono::Duration::minutes(5);
+
+        let data = segment.stale((&last, saved_at), "pricing", "HTTP 503");
+        assert_eq!(data.primary, "$4.20");
+        assert_eq!(data.secondary, "today · ⚠ 5m ago");
+        assert_eq!(data.metadata["stale"], "pricing");
+        assert_eq!(data.metadata["stale_reason"], "HTTP 503");
+        assert_eq!(data.metadata["stale_since"], saved_at.to_rfc3339());
+    }
+}
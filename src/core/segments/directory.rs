@@ -1,13 +1,70 @@
-use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId};
+use super::{Segment, SegmentData, SegmentResult};
+use crate::config::{InputData, SegmentConfig, SegmentId};
 use std::collections::HashMap;
 
+/// Seconds each root is shown for in `roots = "cycle"` mode
+const DEFAULT_CYCLE_SECONDS: u64 = 10;
+
+/// How a workspace with directories added via `--add-dir` is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RootsDisplay {
+    /// `CCometixLine +2`
+    #[default]
+    Count,
+    /// Each root in turn, e.g. `docs [2/3]`
+    Cycle,
+    /// Only the current directory
+    None,
+}
+
 #[derive(Default)]
-pub struct DirectorySegment;
+pub struct DirectorySegment {
+    roots: RootsDisplay,
+    cycle_seconds: u64,
+}
 
 impl DirectorySegment {
     pub fn new() -> Self {
-        Self
+        Self {
+            roots: RootsDisplay::Count,
+            cycle_seconds: DEFAULT_CYCLE_SECONDS,
+        }
+    }
+
+    /// Apply the segment's `roots` and `cycle_seconds` options
+    pub fn with_options(mut self, config: &SegmentConfig) -> Self {
+        match config.options.get("roots").and_then(|v| v.as_str()) {
+            Some("cycle") => self.roots = RootsDisplay::Cycle,
+            Some("none") => self.roots = RootsDisplay::None,
+            _ => {}
+        }
+        if let Some(seconds) = config
+            .options
+            .get("cycle_seconds")
+            .and_then(|v| v.as_u64())
+            .filter(|s| *s > 0)
+        {
+            self.cycle_seconds = seconds;
+        }
+        self
+    }
+
+    /// Primary and secondary text for a workspace with several roots
+    fn render_roots(&self, current: String, roots: &[&str], now_secs: u64) -> (String, String) {
+        if roots.len() < 2 {
+            return (current, String::new());
+        }
+        match self.roots {
+            RootsDisplay::Count => (current, format!("+{}", roots.len() - 1)),
+            RootsDisplay::Cycle => {
+                let index = (now_secs / self.cycle_seconds) as usize % roots.len();
+                (
+                    Self::extract_directory_name(roots[index]),
+                    format!("[{}/{}]", index + 1, roots.len()),
+                )
+            }
+            RootsDisplay::None => (current, String::new()),
+        }
     }
 
     /// Extract directory name from path, handling both Unix and Windows separators
@@ -37,7 +94,7 @@ impl DirectorySegment {
 }
 
 impl Segment for DirectorySegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, input: &InputData) -> SegmentResult {
         let current_dir = &input.workspace.current_dir;
 
         // Handle cross-platform path separators manually for better compatibility
@@ -47,14 +104,63 @@ impl Segment for DirectorySegment {
         let mut metadata = HashMap::new();
         metadata.insert("full_path".to_string(), current_dir.clone());
 
-        Some(SegmentData {
-            primary: dir_name,
-            secondary: String::new(),
+        let roots = input.workspace.roots();
+        if roots.len() > 1 {
+            metadata.insert("roots".to_string(), roots.len().to_string());
+            metadata.insert("root_paths".to_string(), roots.join(","));
+            if let Some(active) = input.workspace.active_root() {
+                metadata.insert("active_root".to_string(), roots[active].to_string());
+            }
+        }
+        let now_secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let (primary, secondary) = self.render_roots(dir_name, &roots, now_secs);
+
+        Ok(Some(SegmentData {
+            primary,
+            secondary,
             metadata,
-        })
+        }))
     }
 
     fn id(&self) -> SegmentId {
         SegmentId::Directory
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Workspace;
+
+    #[test]
+    fn test_multi_root_workspace() {
+        let workspace: Workspace = serde_json::from_str(
+            r#"{"current_dir":"/src/app/server","project_dir":"/src/app","added_dirs":["/src/docs","/src/app/"]}"#,
+        )
+        .unwrap();
+        assert_eq!(workspace.roots(), vec!["/src/app", "/src/docs"]);
+        assert_eq!(workspace.active_root(), Some(0));
+
+        let roots = workspace.roots();
+        let segment = DirectorySegment::new();
+        assert_eq!(
+            segment.render_roots("server".to_string(), &roots, 0),
+            ("server".to_string(), "+1".to_string())
+        );
+
+        let cycling = DirectorySegment {
+            roots: RootsDisplay::Cycle,
+            cycle_seconds: 10,
+        };
+        assert_eq!(
+            cycling.render_roots("server".to_string(), &roots, 15).0,
+            "docs"
+        );
+        assert_eq!(
+            cycling.render_roots("server".to_string(), &roots, 25).1,
+            "[1/2]"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Segment, SegmentData};
+use super::{Segment, SegmentData, SegmentResult};
 use crate::config::{InputData, SegmentId};
 use std::collections::HashMap;
 use std::process::Command;
@@ -172,8 +172,11 @@ impl GitSegment {
 }
 
 impl Segment for GitSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
-        let git_info = self.get_git_info(&input.workspace.current_dir)?;
+    fn collect(&self, input: &InputData) -> SegmentResult {
+        // Outside a repository there is nothing to show
+        let Some(git_info) = self.get_git_info(&input.workspace.current_dir) else {
+            return Ok(None);
+        };
 
         let mut metadata = HashMap::new();
         metadata.insert("branch".to_string(), git_info.branch.clone());
@@ -205,11 +208,11 @@ impl Segment for GitSegment {
             status_parts.push(sha.clone());
         }
 
-        Some(SegmentData {
+        Ok(Some(SegmentData {
             primary,
             secondary: status_parts.join(" "),
             metadata,
-        })
+        }))
     }
 
     fn id(&self) -> SegmentId {
@@ -0,0 +1,230 @@
+use super::{Segment, SegmentData, SegmentError, SegmentResult};
+use crate::billing::ModelPricing;
+use crate::config::{InputData, NormalizedUsage, SegmentConfig, SegmentId, TranscriptEntry};
+use crate::utils::transcript::{extract_usage_entry, is_new_session, read_tail_lines};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Bytes read from the end of the transcript; a single response rarely
+/// spans more
+const TAIL_BYTES: u64 = 256 * 1024;
+
+/// The most recent assistant response of a transcript
+#[derive(Debug)]
+struct LastTurn {
+    model: String,
+    usage: NormalizedUsage,
+    /// Cost Claude Code recorded for the message, if any
+    cost_usd: Option<f64>,
+    timestamp: Option<String>,
+    /// From the entry before the response (prompt or tool result) to the
+    /// response's last entry
+    duration_secs: Option<f64>,
+}
+
+fn parse_timestamp(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp?)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Find the last assistant message with usage in `lines`, oldest first
+///
+/// Claude Code writes one entry per content block of a response, all with the
+/// same message id, so the latest entry carries the final usage and the entry
+/// preceding the first one marks when the request was sent.
+fn find_last_turn(lines: &[String]) -> Option<LastTurn> {
+    let entries: Vec<TranscriptEntry> = lines
+        .iter()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let last = entries.iter().rposition(|entry| {
+        entry.r#type.as_deref() == Some("assistant")
+            && entry.message.as_ref().is_some_and(|m| m.usage.is_some())
+    })?;
+    let entry = &entries[last];
+    let message = entry.message.as_ref()?;
+
+    let same_message = |other: &TranscriptEntry| {
+        other.r#type.as_deref() == Some("assistant")
+            && message.id.is_some()
+            && other.message.as_ref().and_then(|m| m.id.as_ref()) == message.id.as_ref()
+    };
+    let start = entries[..last]
+        .iter()
+        .rev()
+        .find(|other| !same_message(other))
+        .and_then(|other| parse_timestamp(other.timestamp.as_deref()));
+    let end = parse_timestamp(entry.timestamp.as_deref());
+    let duration_secs = match (start, end) {
+        (Some(start), Some(end)) if end > start => {
+            Some((end - start).num_milliseconds() as f64 / 1000.0)
+        }
+        _ => None,
+    };
+
+    Some(LastTurn {
+        model: message.model.clone().unwrap_or_default(),
+        usage: message.usage.clone()?.normalize(),
+        cost_usd: entry.cost_usd,
+        timestamp: entry.timestamp.clone(),
+        duration_secs,
+    })
+}
+
+/// Cost and output speed of the most recent response in the current
+/// transcript, to see per-turn spend right after each reply
+pub struct LastTurnSegment {
+    enabled: bool,
+    show_speed: bool,
+}
+
+impl LastTurnSegment {
+    pub fn new(config: &SegmentConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            show_speed: config
+                .options
+                .get("show_speed")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true),
+        }
+    }
+
+    fn cost(turn: &LastTurn) -> Option<f64> {
+        if let Some(cost) = turn.cost_usd {
+            return Some(cost);
+        }
+        let pricing_map =
+            crate::utils::block_on(async { ModelPricing::get_pricing_with_fallback().await });
+        let pricing = ModelPricing::get_model_pricing(&pricing_map, &turn.model)?;
+        let entry = extract_usage_entry(
+            &turn.usage,
+            "",
+            turn.timestamp.as_deref(),
+            Some(&turn.model),
+        )?;
+        Some(pricing.calculate_cost(&entry))
+    }
+
+    fn render(&self, turn: &LastTurn, cost: Option<f64>) -> SegmentData {
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "output_tokens".to_string(),
+            turn.usage.output_tokens.to_string(),
+        );
+        let primary = match cost {
+            Some(cost) => {
+                metadata.insert("last_turn_cost".to_string(), format!("{:.4}", cost));
+                format!("{} last turn", format_cost(cost))
+            }
+            None => "— last turn".to_string(),
+        };
+
+        let mut secondary = String::new();
+        if let Some(duration) = turn.duration_secs {
+            let speed = turn.usage.output_tokens as f64 / duration;
+            metadata.insert("duration_secs".to_string(), format!("{:.1}", duration));
+            metadata.insert("tokens_per_second".to_string(), format!("{:.1}", speed));
+            if self.show_speed {
+                secondary = format!("· {:.0} tok/s", speed);
+            }
+        }
+
+        SegmentData {
+            primary,
+            secondary,
+            metadata,
+        }
+    }
+}
+
+/// Dollars with enough precision for the cents-or-less a turn often costs
+fn format_cost(cost: f64) -> String {
+    if cost < 0.1 {
+        format!("${:.3}", cost)
+    } else {
+        format!("${:.2}", cost)
+    }
+}
+
+impl Segment for LastTurnSegment {
+    fn collect(&self, input: &InputData) -> SegmentResult {
+        if !self.enabled
+            || input.transcript_path.is_empty()
+            || is_new_session(&input.transcript_path)
+        {
+            return Ok(None);
+        }
+
+        let lines = read_tail_lines(Path::new(&input.transcript_path), TAIL_BYTES)
+            .map_err(|e| SegmentError::Data(format!("{}: {}", input.transcript_path, e)))?;
+        let Some(turn) = find_last_turn(&lines) else {
+            crate::trace_step!("No assistant message with usage in the transcript tail");
+            return Ok(None);
+        };
+        crate::trace_step!(
+            "Last turn: {} output tokens from {} in {:?}s",
+            turn.usage.output_tokens,
+            turn.model,
+            turn.duration_secs
+        );
+
+        let cost = Self::cost(&turn);
+        Ok(Some(self.render(&turn, cost)))
+    }
+
+    fn placeholder(&self) -> Option<SegmentData> {
+        Some(SegmentData {
+            primary: "— last turn".to_string(),
+            secondary: String::new(),
+            metadata: HashMap::new(),
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::LastTurn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::segments::test_config;
+
+    fn line(kind: &str, id: &str, timestamp: &str, output_tokens: u32) -> String {
+        serde_json::json!({
+            "type": kind,
+            "timestamp": timestamp,
+            "message": {
+                "id": id,
+                "model": "claude-sonnet-4",
+                "usage": { "input_tokens": 10, "output_tokens": output_tokens },
+            },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_find_last_turn() {
+        let lines = vec![
+            line("assistant", "msg_1", "2025-01-01T10:00:00Z", 50),
+            line("user", "", "2025-01-01T10:00:30Z", 0),
+            line("assistant", "msg_2", "2025-01-01T10:00:36Z", 20),
+            line("assistant", "msg_2", "2025-01-01T10:00:40Z", 400),
+            "not json".to_string(),
+        ];
+        let turn = find_last_turn(&lines).unwrap();
+        assert_eq!(turn.usage.output_tokens, 400);
+        assert_eq!(turn.duration_secs, Some(10.0));
+
+        let segment = LastTurnSegment::new(&test_config(SegmentId::LastTurn, &[]));
+        let data = segment.render(&turn, Some(0.0123));
+        assert_eq!(data.primary, "$0.012 last turn");
+        assert_eq!(data.secondary, "· 40 tok/s");
+        assert_eq!(data.metadata["tokens_per_second"], "40.0");
+
+        assert!(find_last_turn(&lines[1..2]).is_none());
+    }
+}
@@ -1,21 +1,159 @@
+pub mod api_errors;
+pub mod block;
+pub mod block_limit;
 pub mod burn_rate;
+pub mod condensed;
 pub mod cost;
 pub mod directory;
 pub mod git;
+pub mod last_turn;
 pub mod model;
+pub mod plugin;
+pub mod project_cost;
+pub mod session_stats;
 pub mod update;
 pub mod usage;
+pub mod weekly;
 
-use crate::config::{InputData, SegmentId};
+use crate::config::{InputData, SegmentConfig, SegmentId, StyleMode};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Marker in front of the Usage and Cost new-session placeholders: a sparkle,
+/// or `*` in ASCII mode and with the ASCII plain icon set
+pub fn new_session_marker(mode: StyleMode, config: &SegmentConfig) -> &'static str {
+    match mode {
+        StyleMode::Ascii => "*",
+        StyleMode::Plain if config.icon.plain.is_ascii() => "*",
+        _ => "✨",
+    }
+}
+
+/// Config of the `id` segment from the default theme, enabled and with only
+/// the given options, for segment tests
+#[cfg(test)]
+pub(crate) fn test_config(id: SegmentId, options: &[(&str, serde_json::Value)]) -> SegmentConfig {
+    let mut config = crate::config::Config::default()
+        .segments
+        .into_iter()
+        .find(|s| s.id == id)
+        .expect("the default theme has every built-in segment");
+    config.enabled = true;
+    config.options = options
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.clone()))
+        .collect();
+    config
+}
+
+/// What a segment's `collect` returns: `Ok(None)` hides the segment on
+/// purpose (disabled, nothing to show), `Err` reports a failure that the
+/// segment's `on_error` policy decides how to show
+pub type SegmentResult = Result<Option<SegmentData>, SegmentError>;
+
 // New Segment trait for data collection only
 pub trait Segment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData>;
+    fn collect(&self, input: &InputData) -> SegmentResult;
     fn id(&self) -> SegmentId;
+
+    /// Shown in place of the segment when collecting fails and its policy is
+    /// `placeholder`; segments without one are hidden instead
+    fn placeholder(&self) -> Option<SegmentData> {
+        None
+    }
+}
+
+/// Why a segment could not produce output
+#[derive(Debug, Clone, PartialEq)]
+pub enum SegmentError {
+    /// Usage data, a transcript or another input could not be read
+    Data(String),
+    /// Something the segment depends on (pricing, git, a plugin command) failed
+    Unavailable(String),
+    /// The segment panicked while collecting
+    Panicked(String),
+    /// Collecting was still running when the `max_render_ms` budget (in
+    /// milliseconds) ran out
+    TimedOut(u64),
+}
+
+impl SegmentError {
+    /// One word for the error badge
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SegmentError::Data(_) => "data",
+            SegmentError::Unavailable(_) => "unavailable",
+            SegmentError::Panicked(_) => "crashed",
+            SegmentError::TimedOut(_) => "slow",
+        }
+    }
+}
+
+impl std::fmt::Display for SegmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SegmentError::Data(message) => write!(f, "could not read data: {}", message),
+            SegmentError::Unavailable(message) => write!(f, "unavailable: {}", message),
+            SegmentError::Panicked(message) => write!(f, "panicked: {}", message),
+            SegmentError::TimedOut(budget_ms) => {
+                write!(
+                    f,
+                    "still collecting after the {}ms render budget",
+                    budget_ms
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SegmentError {}
+
+/// How a failed segment is shown, from its `on_error` option
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// Leave the segment out
+    Hide,
+    /// Show the segment's placeholder, or hide it if it has none
+    #[default]
+    Placeholder,
+    /// Show a `⚠` badge naming the kind of error
+    Badge,
 }
 
-#[derive(Debug, Clone)]
+impl ErrorPolicy {
+    pub fn from_config(config: &SegmentConfig) -> Self {
+        match config.options.get("on_error").and_then(|v| v.as_str()) {
+            Some("hide") => ErrorPolicy::Hide,
+            Some("badge") => ErrorPolicy::Badge,
+            _ => ErrorPolicy::Placeholder,
+        }
+    }
+
+    /// What to show for a segment that failed with `error`
+    pub fn apply(
+        self,
+        error: &SegmentError,
+        placeholder: Option<SegmentData>,
+    ) -> Option<SegmentData> {
+        let mut data = match self {
+            ErrorPolicy::Hide => return None,
+            ErrorPolicy::Placeholder => placeholder?,
+            ErrorPolicy::Badge => {
+                let mut metadata = HashMap::new();
+                metadata.insert("severity".to_string(), "warning".to_string());
+                SegmentData {
+                    primary: format!("⚠ {}", error.kind()),
+                    secondary: String::new(),
+                    metadata,
+                }
+            }
+        };
+        data.metadata.insert("error".to_string(), error.to_string());
+        Some(data)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SegmentData {
     pub primary: String,
     pub secondary: String,
@@ -23,10 +161,47 @@ pub struct SegmentData {
 }
 
 // Re-export all segment types
+pub use api_errors::ApiErrorsSegment;
+pub use block::BlockSegment;
+pub use block_limit::BlockLimitSegment;
 pub use burn_rate::BurnRateSegment;
+pub use condensed::{CondensedMetric, CondensedSegment};
 pub use cost::CostSegment;
 pub use directory::DirectorySegment;
 pub use git::GitSegment;
+pub use last_turn::LastTurnSegment;
 pub use model::ModelSegment;
+pub use plugin::PluginSegment;
+pub use project_cost::ProjectCostSegment;
+pub use session_stats::{SessionStatsSegment, StatCounter};
 pub use update::UpdateSegment;
-pub use usage::UsageSegment;
+pub use usage::{UsageDisplay, UsageSegment};
+pub use weekly::WeeklySegment;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_policy() {
+        let error = SegmentError::Unavailable("pricing".to_string());
+        let placeholder = || {
+            Some(SegmentData {
+                primary: "$0.00 session".to_string(),
+                secondary: String::new(),
+                metadata: HashMap::new(),
+            })
+        };
+
+        assert!(ErrorPolicy::Hide.apply(&error, placeholder()).is_none());
+        assert!(ErrorPolicy::Placeholder.apply(&error, None).is_none());
+        let shown = ErrorPolicy::Placeholder
+            .apply(&error, placeholder())
+            .unwrap();
+        assert_eq!(shown.primary, "$0.00 session");
+        assert_eq!(shown.metadata["error"], "unavailable: pricing");
+        let badge = ErrorPolicy::Badge.apply(&error, placeholder()).unwrap();
+        assert_eq!(badge.primary, "⚠ unavailable");
+        assert_eq!(badge.metadata["severity"], "warning");
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Segment, SegmentData};
+use super::{Segment, SegmentData, SegmentResult};
 use crate::config::{InputData, SegmentId};
 use std::collections::HashMap;
 
@@ -12,12 +12,12 @@ impl ModelSegment {
 }
 
 impl Segment for ModelSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
-        Some(SegmentData {
+    fn collect(&self, input: &InputData) -> SegmentResult {
+        Ok(Some(SegmentData {
             primary: self.format_model_name(&input.model.display_name),
             secondary: String::new(),
             metadata: HashMap::new(),
-        })
+        }))
     }
 
     fn id(&self) -> SegmentId {
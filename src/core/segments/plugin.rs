@@ -0,0 +1,120 @@
+use super::{Segment, SegmentData, SegmentError, SegmentResult};
+use crate::config::{ColorConfig, Config, InputData, SegmentConfig, SegmentId};
+use crate::core::commands::{plugin_command, run_command_with_io, CommandApprovals, CommandStatus};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// What a plugin prints on stdout
+#[derive(Debug, Default, Deserialize)]
+struct PluginResponse {
+    text: String,
+    #[serde(default)]
+    secondary: String,
+    /// Overrides for the segment's configured colors
+    #[serde(default)]
+    colors: Option<ColorConfig>,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+/// A segment rendered by an external program: the Claude Code JSON goes to
+/// its stdin and a small JSON response comes back on stdout
+///
+/// The program must be declared under `[commands]` and approved with
+/// `ccline audit --approve <name>`, like hook commands.
+pub struct PluginSegment<'a> {
+    config: &'a Config,
+    segment_config: &'a SegmentConfig,
+}
+
+impl<'a> PluginSegment<'a> {
+    pub fn new(config: &'a Config, segment_config: &'a SegmentConfig) -> Self {
+        Self {
+            config,
+            segment_config,
+        }
+    }
+
+    fn run(&self, input: &InputData) -> Result<Option<SegmentData>, Box<dyn std::error::Error>> {
+        let name = plugin_command(self.segment_config).ok_or("no `command` option set")?;
+        let command = self
+            .config
+            .commands
+            .get(name)
+            .ok_or_else(|| format!("command '{}' is not declared", name))?;
+        let status = CommandApprovals::load().status(name, command);
+        if status != CommandStatus::Approved {
+            return Err(format!("command '{}' is {}", name, status.label()).into());
+        }
+
+        // Segment options reach the plugin as JSON in CCLINE_OPTIONS
+        let options = serde_json::to_string(&self.segment_config.options)?;
+        let env = [("CCLINE_OPTIONS".to_string(), options)];
+        let output = run_command_with_io(command, &env, &serde_json::to_vec(input)?)?;
+        Ok(parse_response(&output)?)
+    }
+}
+
+/// Turn a plugin's stdout into segment data; empty text hides the segment
+fn parse_response(output: &[u8]) -> Result<Option<SegmentData>, serde_json::Error> {
+    let response: PluginResponse = serde_json::from_slice(output)?;
+    if response.text.is_empty() {
+        return Ok(None);
+    }
+
+    let mut metadata = response.metadata;
+    if let Some(colors) = response.colors {
+        for (key, color) in [
+            ("icon_color", colors.icon),
+            ("text_color", colors.text),
+            ("background_color", colors.background),
+        ] {
+            if let Some(color) = color {
+                metadata.insert(key.to_string(), serde_json::to_string(&color)?);
+            }
+        }
+    }
+
+    Ok(Some(SegmentData {
+        primary: response.text,
+        secondary: response.secondary,
+        metadata,
+    }))
+}
+
+impl Segment for PluginSegment<'_> {
+    fn collect(&self, input: &InputData) -> SegmentResult {
+        if !self.segment_config.enabled {
+            return Ok(None);
+        }
+
+        self.run(input)
+            .map_err(|e| SegmentError::Unavailable(e.to_string()))
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Plugin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let data = parse_response(
+            br#"{"text":"21C","secondary":"Berlin","colors":{"text":{"c256":214}},"metadata":{"severity":"warning"}}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(data.primary, "21C");
+        assert_eq!(data.secondary, "Berlin");
+        assert_eq!(data.metadata.get("text_color").unwrap(), r#"{"c256":214}"#);
+        assert_eq!(data.metadata.get("severity").unwrap(), "warning");
+        assert!(!data.metadata.contains_key("icon_color"));
+
+        assert!(parse_response(br#"{"text":""}"#).unwrap().is_none());
+        assert!(parse_response(b"not json").is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use super::{Segment, SegmentData, SegmentResult};
+use crate::billing::{
+    entries::{apply_subagent_mode, price_entries},
+    UsageEntry,
+};
+use crate::config::{InputData, SegmentConfig, SegmentId, SubagentMode};
+use crate::utils::{data_loader::EntryLoader, timezone, transcript::project_key};
+use chrono::{DateTime, Datelike, Utc};
+use std::collections::HashMap;
+
+/// Span of time the project and total spend are summed over
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CostPeriod {
+    Today,
+    Month,
+    All,
+}
+
+impl CostPeriod {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "today" => Some(CostPeriod::Today),
+            "month" => Some(CostPeriod::Month),
+            "all" => Some(CostPeriod::All),
+            _ => None,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            CostPeriod::Today => "today",
+            CostPeriod::Month => "this month",
+            CostPeriod::All => "overall",
+        }
+    }
+
+    /// Days of usage the period needs, `None` for all of it
+    fn window_days(&self) -> Option<u64> {
+        match self {
+            CostPeriod::Today => Some(1),
+            CostPeriod::Month => Some(32),
+            CostPeriod::All => None,
+        }
+    }
+
+    /// Start of the period in the configured timezone
+    fn start(&self) -> Option<DateTime<Utc>> {
+        let today = timezone::today();
+        let first = match self {
+            CostPeriod::Today => today,
+            CostPeriod::Month => today.with_day(1)?,
+            CostPeriod::All => return None,
+        };
+        first.and_hms_opt(0, 0, 0).and_then(timezone::from_local)
+    }
+}
+
+/// Spend of the current workspace's project next to the spend across all
+/// projects, for billing clients per repository
+pub struct ProjectCostSegment {
+    enabled: bool,
+    period: CostPeriod,
+    loader: EntryLoader,
+    subagents: SubagentMode,
+}
+
+impl ProjectCostSegment {
+    pub fn new(config: &SegmentConfig) -> Self {
+        let period = config
+            .options
+            .get("period")
+            .and_then(|v| v.as_str())
+            .and_then(CostPeriod::parse)
+            .unwrap_or(CostPeriod::Month);
+        Self {
+            enabled: config.enabled,
+            period,
+            loader: EntryLoader::from_options(&config.options, period.window_days()),
+            subagents: SubagentMode::default(),
+        }
+    }
+
+    pub fn with_subagents(mut self, subagents: SubagentMode) -> Self {
+        self.subagents = subagents;
+        self
+    }
+
+    /// Cost of `project` and of all projects over the configured period
+    fn totals(&self, entries: &[UsageEntry], project: &str) -> (f64, f64) {
+        let start = self.period.start();
+        let mut here = 0.0;
+        let mut total = 0.0;
+        for entry in entries
+            .iter()
+            .filter(|e| start.is_none_or(|start| e.timestamp >= start))
+        {
+            let cost = entry.cost.unwrap_or(0.0);
+            total += cost;
+            if entry.project == project {
+                here += cost;
+            }
+        }
+        (here, total)
+    }
+
+    fn render(&self, project: &str, here: f64, total: f64) -> SegmentData {
+        let mut metadata = HashMap::new();
+        metadata.insert("project".to_string(), project.to_string());
+        metadata.insert("project_cost".to_string(), format!("{:.2}", here));
+        metadata.insert("total_cost".to_string(), format!("{:.2}", total));
+
+        let secondary = if total > 0.0 {
+            let percent = here / total * 100.0;
+            metadata.insert("project_percent".to_string(), format!("{:.1}", percent));
+            format!("of ${:.2} {} ({:.0}%)", total, self.period.label(), percent)
+        } else {
+            format!("of ${:.2} {}", total, self.period.label())
+        };
+
+        SegmentData {
+            primary: format!("${:.2} here", here),
+            secondary,
+            metadata,
+        }
+    }
+}
+
+impl Segment for ProjectCostSegment {
+    fn collect(&self, input: &InputData) -> SegmentResult {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        let project = project_key(input.workspace.roots()[0]);
+        let mut entries = self.loader.load();
+        entries.extend(self.loader.load_other_tools());
+        apply_subagent_mode(&mut entries, self.subagents);
+
+        price_entries(&mut entries);
+        crate::trace_step!("Project directory {} over {}", project, self.period.label());
+
+        let (here, total) = self.totals(&entries, &project);
+        Ok(Some(self.render(&project, here, total)))
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::ProjectCost
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::segments::test_config;
+
+    fn segment(period: &str) -> ProjectCostSegment {
+        ProjectCostSegment::new(&test_config(
+            SegmentId::ProjectCost,
+            &[("period", serde_json::json!(period))],
+        ))
+    }
+
+    #[test]
+    fn test_project_cost() {
+        let entry = |project: &str, days_ago: i64, cost: f64| UsageEntry {
+            timestamp: Utc::now() - chrono::Duration::days(days_ago),
+            input_tokens: 100,
+            output_tokens: 10,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            model: "claude-sonnet-4".to_string(),
+            cost: Some(cost),
+            session_id: "s".to_string(),
+            subagent: false,
+            project: project.to_string(),
+        };
+        let entries = vec![
+            entry("-work-app", 0, 1.0),
+            entry("-work-site", 0, 3.0),
+            entry("-work-app", 400, 10.0),
+        ];
+
+        let all = segment("all");
+        assert_eq!(all.totals(&entries, "-work-app"), (11.0, 14.0));
+        let data = all.render("-work-app", 11.0, 14.0);
+        assert_eq!(data.primary, "$11.00 here");
+        assert_eq!(data.secondary, "of $14.00 overall (79%)");
+
+        let month = segment("month");
+        assert_eq!(month.period, CostPeriod::Month);
+        assert_eq!(month.totals(&entries, "-work-app"), (1.0, 4.0));
+        assert_eq!(segment("bogus").period, CostPeriod::Month);
+    }
+}
@@ -0,0 +1,284 @@
+use super::{Segment, SegmentData, SegmentError, SegmentResult};
+use crate::config::{InputData, SegmentConfig, SegmentId};
+use crate::utils::{transcript::is_new_session, usage_cache};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// A count the `session_stats` segment can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatCounter {
+    /// Prompts typed by the user
+    Turns,
+    /// Prompts plus assistant responses
+    Messages,
+    /// Tool calls made by the assistant
+    Tools,
+}
+
+impl StatCounter {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "turns" => Some(StatCounter::Turns),
+            "messages" => Some(StatCounter::Messages),
+            "tools" => Some(StatCounter::Tools),
+            _ => None,
+        }
+    }
+
+    fn metadata_key(&self) -> &'static str {
+        match self {
+            StatCounter::Turns => "turns",
+            StatCounter::Messages => "messages",
+            StatCounter::Tools => "tool_calls",
+        }
+    }
+
+    fn label(&self, count: usize) -> &'static str {
+        match (self, count == 1) {
+            (StatCounter::Turns, true) => "turn",
+            (StatCounter::Turns, false) => "turns",
+            (StatCounter::Messages, true) => "msg",
+            (StatCounter::Messages, false) => "msgs",
+            (StatCounter::Tools, true) => "tool",
+            (StatCounter::Tools, false) => "tools",
+        }
+    }
+}
+
+/// The parts of a transcript entry the counters look at
+#[derive(Deserialize)]
+struct StatsEntry {
+    r#type: Option<String>,
+    #[serde(default, alias = "isSidechain")]
+    is_sidechain: Option<bool>,
+    #[serde(default, alias = "isMeta")]
+    is_meta: Option<bool>,
+    message: Option<StatsMessage>,
+}
+
+#[derive(Deserialize)]
+struct StatsMessage {
+    id: Option<String>,
+    #[serde(default)]
+    content: serde_json::Value,
+}
+
+impl StatsMessage {
+    /// Content blocks of the given type
+    fn blocks<'a>(&'a self, kind: &'a str) -> impl Iterator<Item = &'a serde_json::Value> {
+        self.content
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(move |block| block.get("type").and_then(|t| t.as_str()) == Some(kind))
+    }
+}
+
+/// Counts over a whole transcript
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct SessionStats {
+    turns: usize,
+    messages: usize,
+    tool_calls: usize,
+}
+
+impl SessionStats {
+    /// Count a transcript's main conversation; subagent (sidechain) entries
+    /// are left out
+    ///
+    /// Claude Code writes a response as several entries sharing a message id,
+    /// and tool results as user entries, so responses and tool calls are
+    /// counted by id and prompts are user entries without tool results.
+    fn from_lines<I: IntoIterator<Item = String>>(lines: I) -> Self {
+        let mut stats = SessionStats::default();
+        let mut responses = HashSet::new();
+        let mut tool_ids = HashSet::new();
+        for line in lines {
+            let Ok(entry) = serde_json::from_str::<StatsEntry>(&line) else {
+                continue;
+            };
+            let Some(message) = &entry.message else {
+                continue;
+            };
+            if entry.is_sidechain == Some(true) {
+                continue;
+            }
+            match entry.r#type.as_deref() {
+                Some("user")
+                    if entry.is_meta != Some(true)
+                        && message.blocks("tool_result").next().is_none() =>
+                {
+                    stats.turns += 1;
+                    stats.messages += 1;
+                }
+                Some("assistant") => {
+                    let new_response = match &message.id {
+                        Some(id) => responses.insert(id.clone()),
+                        None => true,
+                    };
+                    if new_response {
+                        stats.messages += 1;
+                    }
+                    for block in message.blocks("tool_use") {
+                        let new_call = match block.get("id").and_then(|id| id.as_str()) {
+                            Some(id) => tool_ids.insert(id.to_string()),
+                            None => true,
+                        };
+                        if new_call {
+                            stats.tool_calls += 1;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        stats
+    }
+
+    fn get(&self, counter: StatCounter) -> usize {
+        match counter {
+            StatCounter::Turns => self.turns,
+            StatCounter::Messages => self.messages,
+            StatCounter::Tools => self.tool_calls,
+        }
+    }
+}
+
+/// Prompt, message and tool-call counts of the current session
+pub struct SessionStatsSegment {
+    enabled: bool,
+    counters: Vec<StatCounter>,
+}
+
+impl SessionStatsSegment {
+    pub fn new(config: &SegmentConfig) -> Self {
+        let counters = config
+            .options
+            .get("counters")
+            .and_then(|v| v.as_array())
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| name.as_str().and_then(StatCounter::parse))
+                    .collect()
+            })
+            .filter(|counters: &Vec<StatCounter>| !counters.is_empty())
+            .unwrap_or_else(|| {
+                vec![
+                    StatCounter::Turns,
+                    StatCounter::Messages,
+                    StatCounter::Tools,
+                ]
+            });
+
+        Self {
+            enabled: config.enabled,
+            counters,
+        }
+    }
+
+    fn render(&self, stats: &SessionStats) -> SegmentData {
+        let mut metadata = HashMap::new();
+        for counter in [
+            StatCounter::Turns,
+            StatCounter::Messages,
+            StatCounter::Tools,
+        ] {
+            metadata.insert(
+                counter.metadata_key().to_string(),
+                stats.get(counter).to_string(),
+            );
+        }
+        let primary = self
+            .counters
+            .iter()
+            .map(|counter| {
+                let count = stats.get(*counter);
+                format!("{} {}", count, counter.label(count))
+            })
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+        SegmentData {
+            primary,
+            secondary: String::new(),
+            metadata,
+        }
+    }
+}
+
+impl Segment for SessionStatsSegment {
+    fn collect(&self, input: &InputData) -> SegmentResult {
+        if !self.enabled || input.transcript_path.is_empty() {
+            return Ok(None);
+        }
+        if is_new_session(&input.transcript_path) {
+            return Ok(Some(self.render(&SessionStats::default())));
+        }
+
+        // Counted again only once the transcript changes
+        let path = Path::new(&input.transcript_path);
+        let stats = usage_cache::load_or_compute(path, "session_stats", || {
+            let file = std::fs::File::open(path)?;
+            Ok(SessionStats::from_lines(
+                BufReader::new(file).lines().map_while(Result::ok),
+            ))
+        })
+        .map_err(|e| SegmentError::Data(format!("{}: {}", input.transcript_path, e)))?;
+        crate::trace_step!(
+            "{} turns, {} messages, {} tool calls in {}",
+            stats.turns,
+            stats.messages,
+            stats.tool_calls,
+            input.transcript_path
+        );
+        Ok(Some(self.render(&stats)))
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::SessionStats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::segments::test_config;
+    use serde_json::json;
+
+    #[test]
+    fn test_session_stats() {
+        let lines = [
+            json!({"type": "user", "message": {"role": "user", "content": "fix the tests"}}),
+            json!({"type": "assistant", "message": {"id": "m1", "content": [{"type": "text"}]}}),
+            json!({"type": "assistant", "message": {"id": "m1", "content": [
+                {"type": "tool_use", "id": "t1"}, {"type": "tool_use", "id": "t2"}
+            ]}}),
+            json!({"type": "user", "message": {"content": [{"type": "tool_result"}]}}),
+            json!({"type": "user", "isMeta": true, "message": {"content": "caveat"}}),
+            json!({"type": "assistant", "isSidechain": true, "message": {"id": "s1",
+                "content": [{"type": "tool_use", "id": "t3"}]}}),
+            json!({"type": "assistant", "message": {"id": "m2", "content": [{"type": "text"}]}}),
+        ]
+        .map(|entry| entry.to_string());
+        let stats = SessionStats::from_lines(lines);
+        assert_eq!(
+            stats,
+            SessionStats {
+                turns: 1,
+                messages: 3,
+                tool_calls: 2,
+            }
+        );
+
+        let config = test_config(
+            SegmentId::SessionStats,
+            &[("counters", json!(["tools", "bogus", "turns"]))],
+        );
+        let data = SessionStatsSegment::new(&config).render(&stats);
+        assert_eq!(data.primary, "2 tools · 1 turn");
+        assert_eq!(data.metadata["messages"], "3");
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Segment, SegmentData};
+use super::{Segment, SegmentData, SegmentResult};
 use crate::config::{InputData, SegmentId};
 use crate::updater::UpdateState;
 
@@ -12,15 +12,15 @@ impl UpdateSegment {
 }
 
 impl Segment for UpdateSegment {
-    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+    fn collect(&self, _input: &InputData) -> SegmentResult {
         // Load update state and check for update status
         let update_state = UpdateState::load();
 
-        update_state.status_text().map(|status_text| SegmentData {
+        Ok(update_state.status_text().map(|status_text| SegmentData {
             primary: status_text,
             secondary: String::new(),
             metadata: std::collections::HashMap::new(),
-        })
+        }))
     }
 
     fn id(&self) -> SegmentId {
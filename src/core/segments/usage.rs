@@ -1,34 +1,76 @@
-use super::{Segment, SegmentData};
-use crate::config::{GlobalConfig, InputData, SegmentId, TranscriptEntry};
+use super::{new_session_marker, Segment, SegmentData, SegmentResult};
+use crate::config::{GlobalConfig, InputData, SegmentConfig, SegmentId, StyleMode};
+use crate::utils::compaction::{assistant_usage, compaction_stats_for_file, CompactionStats};
+use crate::utils::transcript::{is_new_session, lines_rev, read_transcript_bytes};
 use std::collections::HashMap;
-use std::fs;
-use std::io::{BufRead, BufReader};
 use std::path::Path;
 
+/// Claude Code auto-compacts at roughly this share of the context window
+const DEFAULT_COMPACT_THRESHOLD: f64 = 80.0;
+/// Percentage points below the threshold at which the warning starts
+const DEFAULT_COMPACT_WARNING: f64 = 10.0;
+/// Share of the context window that is critical even when a higher
+/// `compact_threshold` is configured
+const CRITICAL_CONTEXT_PERCENT: f64 = 90.0;
+
+/// What the Usage segment shows as its primary text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageDisplay {
+    /// `54% · 108k tokens`
+    Percentage,
+    /// `48k left · 76%`
+    Remaining,
+    /// `52k tokens until compact`
+    UntilCompact,
+}
+
 pub struct UsageSegment {
-    context_limit: u32,
+    global: GlobalConfig,
+    display: UsageDisplay,
+    compact_threshold: f64,
+    compact_warning: f64,
+    new_session_marker: &'static str,
 }
 
 impl UsageSegment {
     pub fn new(global_config: &GlobalConfig) -> Self {
         Self {
-            context_limit: global_config.context_limit,
+            global: global_config.clone(),
+            display: UsageDisplay::Percentage,
+            compact_threshold: DEFAULT_COMPACT_THRESHOLD,
+            compact_warning: DEFAULT_COMPACT_WARNING,
+            new_session_marker: "✨",
         }
     }
-}
 
-impl Segment for UsageSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
-        let context_used_token = if input.transcript_path == "mock_preview" {
-            // Hardcoded mock data for preview
-            150000
-        } else {
-            parse_transcript_usage(&input.transcript_path)
-        };
+    /// Pick the new-session marker for the configured style mode and icons
+    pub fn with_style(mut self, mode: StyleMode, config: &SegmentConfig) -> Self {
+        self.new_session_marker = new_session_marker(mode, config);
+        self
+    }
+
+    /// Apply the segment's `display`, `compact_threshold` and `compact_warning` options
+    pub fn with_options(mut self, config: &SegmentConfig) -> Self {
+        let percent = |key: &str| config.options.get(key).and_then(|v| v.as_f64());
+
+        match config.options.get("display").and_then(|v| v.as_str()) {
+            Some("remaining") => self.display = UsageDisplay::Remaining,
+            Some("until_compact") => self.display = UsageDisplay::UntilCompact,
+            _ => {}
+        }
+        if let Some(threshold) = percent("compact_threshold").filter(|t| *t > 0.0) {
+            self.compact_threshold = threshold.min(100.0);
+        }
+        if let Some(warning) = percent("compact_warning").filter(|w| *w >= 0.0) {
+            self.compact_warning = warning;
+        }
+        self
+    }
 
+    fn render(&self, context_used_token: u32, context_limit: u32) -> SegmentData {
         // Safe division to prevent panic on zero
-        let context_used_rate = if self.context_limit > 0 {
-            (context_used_token as f64 / self.context_limit as f64) * 100.0
+        let context_used_rate = if context_limit > 0 {
+            (context_used_token as f64 / context_limit as f64) * 100.0
         } else {
             0.0
         };
@@ -39,27 +81,122 @@ impl Segment for UsageSegment {
             format!("{:.1}%", context_used_rate)
         };
 
-        let tokens_display = if context_used_token >= 1000 {
-            let k_value = context_used_token as f64 / 1000.0;
-            if k_value.fract() == 0.0 {
-                format!("{}k", k_value as u32)
-            } else {
-                format!("{:.1}k", k_value)
-            }
-        } else {
-            context_used_token.to_string()
-        };
+        let compact_at = (context_limit as f64 * self.compact_threshold / 100.0) as u32;
+        let until_compact = compact_at.saturating_sub(context_used_token);
 
         let mut metadata = HashMap::new();
         metadata.insert("tokens".to_string(), context_used_token.to_string());
         metadata.insert("percentage".to_string(), context_used_rate.to_string());
-        metadata.insert("limit".to_string(), self.context_limit.to_string());
+        metadata.insert("limit".to_string(), context_limit.to_string());
+        metadata.insert("compact_at".to_string(), compact_at.to_string());
+        metadata.insert("until_compact".to_string(), until_compact.to_string());
+        metadata.insert(
+            "remaining".to_string(),
+            context_limit.saturating_sub(context_used_token).to_string(),
+        );
+
+        // Warn once usage is within `compact_warning` points of auto-compaction
+        let approaching =
+            context_limit > 0 && context_used_rate >= self.compact_threshold - self.compact_warning;
+        let critical_at = self.compact_threshold.min(CRITICAL_CONTEXT_PERCENT);
+        if context_limit > 0 && context_used_rate >= critical_at {
+            metadata.insert("severity".to_string(), "critical".to_string());
+        } else if approaching {
+            metadata.insert("severity".to_string(), "warning".to_string());
+        }
+
+        let mut primary = match self.display {
+            UsageDisplay::Percentage => format!(
+                "{} · {} tokens",
+                percentage_display,
+                format_tokens(context_used_token)
+            ),
+            UsageDisplay::Remaining => format!(
+                "{} left · {}",
+                format_tokens(context_limit.saturating_sub(context_used_token)),
+                percentage_display
+            ),
+            UsageDisplay::UntilCompact => {
+                format!("{} tokens until compact", format_tokens(until_compact))
+            }
+        };
+        if approaching {
+            primary.push_str(" ⚠");
+        }
 
-        Some(SegmentData {
-            primary: format!("{} · {} tokens", percentage_display, tokens_display),
+        SegmentData {
+            primary,
             secondary: String::new(),
             metadata,
-        })
+        }
+    }
+
+    /// Shown before the transcript exists, so a fresh session reads as such
+    /// rather than as usage that failed to load
+    fn render_new_session(&self, context_limit: u32) -> SegmentData {
+        let mut data = self.render(0, context_limit);
+        data.primary = format!("{} 0% · new session", self.new_session_marker);
+        data.metadata
+            .insert("status".to_string(), "new_session".to_string());
+        data
+    }
+}
+
+fn format_tokens(tokens: u32) -> String {
+    if tokens >= 1000 {
+        let k_value = tokens as f64 / 1000.0;
+        if k_value.fract() == 0.0 {
+            format!("{}k", k_value as u32)
+        } else {
+            format!("{:.1}k", k_value)
+        }
+    } else {
+        tokens.to_string()
+    }
+}
+
+impl Segment for UsageSegment {
+    fn collect(&self, input: &InputData) -> SegmentResult {
+        let context_limit = self.global.context_limit_for(&input.model.id);
+        if is_new_session(&input.transcript_path) {
+            crate::trace_step!("Transcript {} doesn't exist yet", input.transcript_path);
+            return Ok(Some(self.render_new_session(context_limit)));
+        }
+
+        let (context_used_token, compaction) = if input.transcript_path == "mock_preview" {
+            // Hardcoded mock data for preview
+            (150000, CompactionStats::default())
+        } else {
+            parse_transcript_usage(&input.transcript_path)
+        };
+
+        crate::trace_step!(
+            "Context limit for {}: {} tokens",
+            input.model.id,
+            context_limit
+        );
+        crate::trace_step!(
+            "{} / {} = {:.1}%, auto-compact at {}%",
+            context_used_token,
+            context_limit,
+            if context_limit > 0 {
+                context_used_token as f64 / context_limit as f64 * 100.0
+            } else {
+                0.0
+            },
+            self.compact_threshold
+        );
+
+        let mut data = self.render(context_used_token, context_limit);
+        if compaction.events > 0 {
+            data.metadata
+                .insert("compactions".to_string(), compaction.events.to_string());
+            data.metadata.insert(
+                "compaction_saved".to_string(),
+                compaction.saved_tokens.to_string(),
+            );
+        }
+        Ok(Some(data))
     }
 
     fn id(&self) -> SegmentId {
@@ -67,35 +204,123 @@ impl Segment for UsageSegment {
     }
 }
 
-fn parse_transcript_usage<P: AsRef<Path>>(transcript_path: P) -> u32 {
-    let file = match fs::File::open(&transcript_path) {
-        Ok(file) => file,
-        Err(_) => return 0,
+/// Current context size and compaction history of a transcript
+///
+/// The transcript is scanned from its end, so only the lines after the last
+/// assistant usage are parsed however long the session has grown.
+fn parse_transcript_usage<P: AsRef<Path>>(transcript_path: P) -> (u32, CompactionStats) {
+    let content = match read_transcript_bytes(transcript_path.as_ref()) {
+        Ok(content) => content,
+        Err(e) => {
+            crate::trace_step!(
+                "Could not read {}: {}",
+                transcript_path.as_ref().display(),
+                e
+            );
+            return (0, CompactionStats::default());
+        }
     };
+    let bytes = content.as_bytes();
+    let compaction = compaction_stats_for_file(transcript_path.as_ref());
+    crate::trace_step!(
+        "Read {} bytes from {}",
+        bytes.len(),
+        transcript_path.as_ref().display()
+    );
 
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap_or_default();
+    if let Some(normalized) = lines_rev(bytes).find_map(assistant_usage) {
+        crate::trace_step!(
+            "Last assistant usage: input {} + cache read {} + cache creation {} + output {} = {} context tokens",
+            normalized.input_tokens,
+            normalized.cache_read_input_tokens,
+            normalized.cache_creation_input_tokens,
+            normalized.output_tokens,
+            normalized.display_tokens()
+        );
+        return (normalized.display_tokens(), compaction);
+    }
 
-    for line in lines.iter().rev() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+    crate::trace_step!("No assistant message with usage found");
+    (0, compaction)
+}
 
-        if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) {
-            if entry.r#type.as_deref() == Some("assistant") {
-                if let Some(message) = &entry.message {
-                    if let Some(raw_usage) = &message.usage {
-                        let normalized = raw_usage.clone().normalize();
-                        return normalized.display_tokens();
-                    }
-                }
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::segments::test_config;
+
+    fn segment(options: &[(&str, serde_json::Value)]) -> UsageSegment {
+        UsageSegment::new(&GlobalConfig::default())
+            .with_options(&test_config(SegmentId::Usage, options))
+    }
+
+    #[test]
+    fn test_compact_threshold_warning() {
+        let usage = segment(&[]);
+        let data = usage.render(100_000, 200_000);
+        assert_eq!(data.primary, "50% · 100k tokens");
+        assert!(!data.metadata.contains_key("severity"));
+
+        let data = usage.render(150_000, 200_000);
+        assert_eq!(data.primary, "75% · 150k tokens ⚠");
+        assert_eq!(data.metadata.get("severity").unwrap(), "warning");
+
+        let data = usage.render(170_000, 200_000);
+        assert_eq!(data.metadata.get("severity").unwrap(), "critical");
+        assert_eq!(data.metadata.get("until_compact").unwrap(), "0");
+
+        let custom = segment(&[
+            ("compact_threshold", serde_json::json!(95)),
+            ("display", serde_json::json!("until_compact")),
+        ]);
+        let data = custom.render(150_000, 200_000);
+        assert_eq!(data.primary, "40k tokens until compact");
+        assert!(!data.metadata.contains_key("severity"));
+
+        // 90% of the window is critical even before a later compaction
+        let data = custom.render(180_000, 200_000);
+        assert_eq!(data.metadata.get("severity").unwrap(), "critical");
+    }
+
+    #[test]
+    fn test_new_session_placeholder() {
+        let config = test_config(
+            SegmentId::Usage,
+            &[("display", serde_json::json!("remaining"))],
+        );
+        let data = UsageSegment::new(&GlobalConfig::default())
+            .with_options(&config)
+            .with_style(StyleMode::NerdFont, &config)
+            .render_new_session(200_000);
+        assert_eq!(data.primary, "✨ 0% · new session");
+        assert_eq!(data.metadata["status"], "new_session");
+        assert_eq!(data.metadata["tokens"], "0");
+        assert!(!data.metadata.contains_key("severity"));
+
+        let data = UsageSegment::new(&GlobalConfig::default())
+            .with_style(StyleMode::Ascii, &config)
+            .render_new_session(200_000);
+        assert_eq!(data.primary, "* 0% · new session");
+
+        let mut plain_ascii = config.clone();
+        plain_ascii.icon.plain = "C".to_string();
+        let data = UsageSegment::new(&GlobalConfig::default())
+            .with_style(StyleMode::Plain, &plain_ascii)
+            .render_new_session(200_000);
+        assert_eq!(data.primary, "* 0% · new session");
     }
 
-    0
+    #[test]
+    fn test_remaining_display() {
+        let remaining = segment(&[("display", serde_json::json!("remaining"))]);
+        assert_eq!(
+            remaining.render(152_000, 200_000).primary,
+            "48k left · 76% ⚠"
+        );
+        assert_eq!(remaining.render(20_000, 200_000).primary, "180k left · 10%");
+        assert_eq!(
+            remaining.render(250_000, 200_000).metadata["remaining"],
+            "0"
+        );
+    }
 }
@@ -0,0 +1,155 @@
+use super::block_limit::format_compact;
+use super::{Segment, SegmentData, SegmentResult};
+use crate::billing::{
+    entries::price_entries,
+    weekly::{parse_week_start, week_to_date, WeekUsage},
+    UsageEntry,
+};
+use crate::config::{context_limits::parse_token_count, InputData, SegmentConfig, SegmentId};
+use crate::utils::{data_loader::EntryLoader, timezone};
+use chrono::Weekday;
+use std::collections::HashMap;
+
+/// Week-to-date tokens and cost, optionally as a share of a weekly cap
+pub struct WeeklySegment {
+    enabled: bool,
+    week_start: Weekday,
+    token_limit: Option<u64>,
+    cost_limit: Option<f64>,
+    warning_percent: f64,
+    critical_percent: f64,
+    loader: EntryLoader,
+}
+
+impl WeeklySegment {
+    pub fn new(config: &SegmentConfig) -> Self {
+        let percent = |key: &str, default: f64| {
+            config
+                .options
+                .get(key)
+                .and_then(|v| v.as_f64())
+                .unwrap_or(default)
+        };
+
+        Self {
+            enabled: config.enabled,
+            week_start: config
+                .options
+                .get("week_start")
+                .and_then(|v| v.as_str())
+                .and_then(|day| parse_week_start(day).ok())
+                .unwrap_or(Weekday::Mon),
+            token_limit: config
+                .options
+                .get("weekly_token_limit")
+                .and_then(|v| {
+                    v.as_u64().or_else(|| {
+                        v.as_str()
+                            .and_then(|s| parse_token_count(s).ok())
+                            .map(u64::from)
+                    })
+                })
+                .filter(|limit| *limit > 0),
+            cost_limit: config
+                .options
+                .get("weekly_cost_limit")
+                .and_then(|v| v.as_f64())
+                .filter(|limit| *limit > 0.0),
+            warning_percent: percent("warning_percent", 70.0),
+            critical_percent: percent("critical_percent", 90.0),
+            loader: EntryLoader::from_options(&config.options, Some(7)),
+        }
+    }
+
+    /// The segment for already loaded and priced entries
+    pub(crate) fn collect_from(&self, entries: &[UsageEntry]) -> SegmentData {
+        self.render(&week_to_date(entries, timezone::now(), self.week_start))
+    }
+
+    fn render(&self, usage: &WeekUsage) -> SegmentData {
+        let mut metadata = HashMap::new();
+        metadata.insert("week_start".to_string(), usage.start.to_string());
+        metadata.insert("week_tokens".to_string(), usage.tokens.to_string());
+        metadata.insert("week_cost".to_string(), format!("{:.2}", usage.cost));
+
+        // A token cap takes precedence over a cost cap when both are set
+        let percent = match (self.token_limit, self.cost_limit) {
+            (Some(limit), _) => Some(usage.tokens as f64 / limit as f64 * 100.0),
+            (None, Some(limit)) => Some(usage.cost / limit * 100.0),
+            (None, None) => None,
+        };
+
+        let secondary = match percent {
+            Some(percent) => {
+                metadata.insert("week_limit_percent".to_string(), format!("{:.1}", percent));
+                if percent >= self.critical_percent {
+                    metadata.insert("severity".to_string(), "critical".to_string());
+                } else if percent >= self.warning_percent {
+                    metadata.insert("severity".to_string(), "warning".to_string());
+                }
+                format!("{:.0}% of weekly limit", percent)
+            }
+            None => String::new(),
+        };
+
+        SegmentData {
+            primary: format!(
+                "{} tokens · ${:.2} this week",
+                format_compact(usage.tokens),
+                usage.cost
+            ),
+            secondary,
+            metadata,
+        }
+    }
+}
+
+impl Segment for WeeklySegment {
+    fn collect(&self, _input: &InputData) -> SegmentResult {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        let mut entries = self.loader.load();
+        price_entries(&mut entries);
+        Ok(Some(self.collect_from(&entries)))
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Weekly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::segments::test_config;
+    use chrono::NaiveDate;
+
+    fn segment(options: &[(&str, serde_json::Value)]) -> WeeklySegment {
+        WeeklySegment::new(&test_config(SegmentId::Weekly, options))
+    }
+
+    #[test]
+    fn test_weekly_display() {
+        let usage = WeekUsage {
+            start: NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+            tokens: 7_500_000,
+            cost: 42.5,
+            entries: 10,
+        };
+
+        let data = segment(&[("week_start", serde_json::json!("sunday"))]).render(&usage);
+        assert_eq!(data.primary, "7.5M tokens · $42.50 this week");
+        assert!(data.secondary.is_empty());
+
+        let limited = segment(&[("weekly_token_limit", serde_json::json!("10m"))]);
+        let data = limited.render(&usage);
+        assert_eq!(data.secondary, "75% of weekly limit");
+        assert_eq!(data.metadata.get("severity").unwrap(), "warning");
+
+        let data = segment(&[("weekly_cost_limit", serde_json::json!(45.0))]).render(&usage);
+        assert_eq!(data.secondary, "94% of weekly limit");
+        assert_eq!(data.metadata.get("severity").unwrap(), "critical");
+    }
+}
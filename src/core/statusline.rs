@@ -1,52 +1,197 @@
 use crate::config::{AnsiColor, Config, SegmentConfig, StyleMode};
+use crate::core::color_rules;
 use crate::core::segments::SegmentData;
+use crate::utils::color::{downgrade, ensure_contrast, MIN_CONTRAST};
+use crate::utils::profile;
+use crate::utils::sanitize_text;
+use crate::utils::terminal::{color_support, ColorSupport};
+pub use crate::utils::width::strip_ansi;
+use crate::utils::width::{char_width, display_width};
+
+/// Ends a styled run inside a segment with a background: turns off every text
+/// attribute and the foreground color but keeps the background
+const RESET_KEEP_BACKGROUND: &str = "\x1b[22;23;24;25;29;39m";
+
+/// Replace the symbols segments put in their text with ASCII stand-ins and
+/// drop icon glyphs (Nerd Font private use characters and emoji), keeping
+/// other text such as CJK directory names as it is
+pub fn to_ascii(text: &str) -> String {
+    let mut ascii = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '·' | '•' => ascii.push('-'),
+            '…' => ascii.push_str("..."),
+            '—' | '–' => ascii.push('-'),
+            '✓' | '✔' => ascii.push_str("ok"),
+            '✗' | '✘' | '×' => ascii.push('x'),
+            '↑' => ascii.push('^'),
+            '↓' => ascii.push('v'),
+            '→' => ascii.push_str("->"),
+            '●' => ascii.push('*'),
+            '◌' => ascii.push('~'),
+            '⚠' => ascii.push('!'),
+            '✨' => ascii.push('*'),
+            '│' => ascii.push('|'),
+            '\u{e0b0}' => ascii.push('>'),
+            '█' | '▓' | '▇' => ascii.push('#'),
+            '▆' | '▅' => ascii.push('='),
+            '▄' | '▃' => ascii.push('-'),
+            '▂' | '▁' => ascii.push('_'),
+            '▒' => ascii.push('='),
+            '░' => ascii.push('-'),
+            '\u{e000}'..='\u{f8ff}'
+            | '\u{f0000}'..='\u{10ffff}'
+            | '\u{1f000}'..='\u{1faff}'
+            | '\u{2600}'..='\u{27bf}'
+            | '\u{fe0f}' => {}
+            _ => ascii.push(ch),
+        }
+    }
+    ascii
+}
+
+/// Shorten text to at most `max_width` columns by replacing its middle with `…`,
+/// keeping both ends visible (useful for paths and branch names)
+pub fn truncate_middle(text: &str, max_width: usize) -> String {
+    if display_width(text) <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let keep = max_width - 1;
+    let chars: Vec<char> = text.chars().collect();
+    let take = |budget: usize, chars: &mut dyn Iterator<Item = &char>| {
+        let mut used = 0;
+        chars
+            .take_while(|ch| {
+                used += char_width(**ch);
+                used <= budget
+            })
+            .copied()
+            .collect::<Vec<char>>()
+    };
+    let head = take(keep - keep / 2, &mut chars.iter());
+    let mut tail = take(keep / 2, &mut chars.iter().rev());
+    tail.reverse();
+
+    let mut result: String = head.into_iter().collect();
+    result.push('…');
+    result.extend(tail);
+    result
+}
+
+/// Apply a segment's `max_width` option to its text, shortening the primary text
+/// first and only dropping the secondary text when it cannot fit on its own
+fn fit_to_width(data: &SegmentData, max_width: usize) -> SegmentData {
+    let primary_width = display_width(&data.primary);
+    let secondary_width = display_width(&data.secondary);
+    let total = if data.secondary.is_empty() {
+        primary_width
+    } else {
+        primary_width + 1 + secondary_width
+    };
+    if total <= max_width {
+        return data.clone();
+    }
+
+    let mut fitted = data.clone();
+    if !data.secondary.is_empty() && secondary_width + 1 < max_width {
+        fitted.primary = truncate_middle(&data.primary, max_width - secondary_width - 1);
+    } else {
+        fitted.primary = truncate_middle(&data.primary, max_width);
+        fitted.secondary.clear();
+    }
+    fitted
+}
+
+/// Shell prompt dialects that need non-printing sequences marked in PS1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptShell {
+    Zsh,
+    Bash,
+}
+
+/// Wrap ANSI escape sequences in the shell's non-printing markers (`%{...%}` for
+/// zsh, `\[...\]` for bash) and escape prompt-special characters in visible text,
+/// so the line can be embedded in PS1 without corrupting the cursor position
+pub fn escape_for_prompt(text: &str, shell: PromptShell) -> String {
+    let (open, close) = match shell {
+        PromptShell::Zsh => ("%{", "%}"),
+        PromptShell::Bash => ("\\[", "\\]"),
+    };
 
-/// Strip ANSI escape sequences and return visible text length
-fn visible_width(text: &str) -> usize {
-    let mut visible = String::new();
-    let mut in_escape = false;
+    let mut output = String::with_capacity(text.len());
     let mut chars = text.chars().peekable();
 
     while let Some(ch) = chars.next() {
         if ch == '\x1b' {
-            // Start of ANSI escape sequence
-            in_escape = true;
-            // Skip the [ character
-            if chars.peek() == Some(&'[') {
-                chars.next();
-            }
-        } else if in_escape {
-            // Skip until we find the end of the escape sequence (letter)
-            if ch.is_alphabetic() {
-                in_escape = false;
+            // Group consecutive escape sequences into a single non-printing run
+            output.push_str(open);
+            output.push(ch);
+            while let Some(c) = chars.next() {
+                output.push(c);
+                if c.is_ascii_alphabetic() {
+                    match chars.next_if_eq(&'\x1b') {
+                        Some(esc) => output.push(esc),
+                        None => break,
+                    }
+                }
             }
+            output.push_str(close);
         } else {
-            // Regular character
-            visible.push(ch);
+            match (shell, ch) {
+                (PromptShell::Zsh, '%') => output.push_str("%%"),
+                (PromptShell::Bash, '\\') => output.push_str("\\\\"),
+                _ => output.push(ch),
+            }
         }
     }
 
-    visible.chars().count()
+    output
 }
 
 pub struct StatusLineGenerator {
     config: Config,
+    color_support: ColorSupport,
 }
 
 impl StatusLineGenerator {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            color_support: color_support(),
+        }
+    }
+
+    /// Render colors for a terminal with the given support instead of the
+    /// detected one
+    pub fn with_color_support(mut self, color_support: ColorSupport) -> Self {
+        self.color_support = color_support;
+        self
     }
 
     pub fn generate(&self, segments: Vec<(SegmentConfig, SegmentData)>) -> String {
         let mut output = Vec::new();
-        let enabled_segments: Vec<_> = segments
+        let mut enabled_segments: Vec<_> = segments
             .into_iter()
             .filter(|(config, _)| config.enabled)
+            .map(with_color_overrides)
+            .map(color_rules::apply)
             .collect();
+        if self.config.global.urgency_sort {
+            sort_by_urgency(&mut enabled_segments);
+        }
 
         for (config, data) in enabled_segments.iter() {
-            let rendered = self.render_segment(config, data);
+            let rendered = if profile::is_profiling() {
+                profile::time("render", Some(&config.instance_key()), || {
+                    self.render_segment(config, data)
+                })
+            } else {
+                self.render_segment(config, data)
+            };
             if !rendered.is_empty() {
                 output.push(rendered);
             }
@@ -57,11 +202,16 @@ impl StatusLineGenerator {
         }
 
         // Handle Powerline arrow separators with color transition
-        if self.config.style.separator == "\u{e0b0}" {
+        let line = if self.config.style.separator == "\u{e0b0}" {
             self.join_with_powerline_arrows(&output, &enabled_segments)
         } else {
             // For all other separators, use white color and simple join
             self.join_with_white_separators(&output)
+        };
+        if self.config.style.mode == StyleMode::Ascii {
+            to_ascii(&line)
+        } else {
+            line
         }
     }
 
@@ -98,10 +248,14 @@ impl StatusLineGenerator {
         use ansi_to_tui::IntoText;
         use ratatui::text::{Line, Span, Text};
 
-        let enabled_segments: Vec<_> = segments
+        let mut enabled_segments: Vec<_> = segments
             .into_iter()
             .filter(|(config, _)| config.enabled)
+            .map(with_color_overrides)
             .collect();
+        if self.config.global.urgency_sort {
+            sort_by_urgency(&mut enabled_segments);
+        }
 
         if enabled_segments.is_empty() {
             return Text::from(vec![Line::default()]);
@@ -150,7 +304,7 @@ impl StatusLineGenerator {
 
         for i in 0..rendered_segments.len() {
             let segment = &rendered_segments[i];
-            let segment_width = visible_width(segment);
+            let segment_width = display_width(segment);
 
             // Check if adding this segment would exceed max_width
             if current_width > 0 && current_width + segment_width > max_w {
@@ -167,12 +321,12 @@ impl StatusLineGenerator {
             // Handle separator if not the last segment
             if i < separators.len() {
                 let separator = &separators[i];
-                let separator_width = visible_width(separator);
+                let separator_width = display_width(separator);
 
                 // Check if next segment exists
                 if i + 1 < rendered_segments.len() {
                     let next_segment = &rendered_segments[i + 1];
-                    let next_width = visible_width(next_segment);
+                    let next_width = display_width(next_segment);
 
                     // Check if separator AND next segment both fit
                     if current_width + separator_width + next_width <= max_w {
@@ -218,36 +372,48 @@ impl StatusLineGenerator {
     fn render_segment(&self, config: &SegmentConfig, data: &SegmentData) -> String {
         let icon = self.get_icon(config);
 
+        // Segment text may come from untrusted sources (branch names, directories,
+        // model names), so strip anything that could drive the terminal
+        let mut data = SegmentData {
+            primary: sanitize_text(&data.primary),
+            secondary: sanitize_text(&data.secondary),
+            metadata: data.metadata.clone(),
+        };
+
+        // Enforce the segment's width limit centrally so no segment can blow up the line
+        if let Some(max_width) = config.options.get("max_width").and_then(|v| v.as_u64()) {
+            data = fit_to_width(&data, max_width as usize);
+        }
+        let data = &data;
+
+        let text_color = severity_color(config, data).or_else(|| config.colors.text.clone());
+        let text_color = self.readable(text_color, config);
+        let text_color = text_color.as_ref();
+        let icon_color = self.readable(config.colors.icon.clone(), config);
+
+        let icon_styles = config.styles.sgr_codes(true);
+        let text_styles = config.styles.sgr_codes(false);
+
         // Apply background color to the entire segment if set
         if let Some(bg_color) = &config.colors.background {
             let bg_code = self.apply_background_color(bg_color);
 
-            // Build the entire segment content first
-            let icon_colored = if let Some(icon_color) = &config.colors.icon {
-                self.apply_color(&icon, Some(icon_color))
-                    .replace("\x1b[0m", "")
-            } else {
-                icon.clone()
-            };
+            // Build the entire segment content first, ending each styled part
+            // without resetting the background
+            let icon_colored = self
+                .apply_style(&icon, icon_color.as_ref(), &icon_styles)
+                .replace("\x1b[0m", RESET_KEEP_BACKGROUND);
 
             let text_styled = self
-                .apply_style(
-                    &data.primary,
-                    config.colors.text.as_ref(),
-                    config.styles.text_bold,
-                )
-                .replace("\x1b[0m", "");
+                .style_primary(data, text_color, &text_styles)
+                .replace("\x1b[0m", RESET_KEEP_BACKGROUND);
 
             let mut segment_content = format!(" {} {} ", icon_colored, text_styled);
 
             if !data.secondary.is_empty() {
                 let secondary_styled = self
-                    .apply_style(
-                        &data.secondary,
-                        config.colors.text.as_ref(),
-                        config.styles.text_bold,
-                    )
-                    .replace("\x1b[0m", "");
+                    .apply_style(&data.secondary, text_color, &text_styles)
+                    .replace("\x1b[0m", RESET_KEEP_BACKGROUND);
                 segment_content.push_str(&format!("{} ", secondary_styled));
             }
 
@@ -255,23 +421,15 @@ impl StatusLineGenerator {
             format!("{}{}\x1b[49m", bg_code, segment_content)
         } else {
             // No background color, use original logic
-            let icon_colored = self.apply_color(&icon, config.colors.icon.as_ref());
-            let text_styled = self.apply_style(
-                &data.primary,
-                config.colors.text.as_ref(),
-                config.styles.text_bold,
-            );
+            let icon_colored = self.apply_style(&icon, icon_color.as_ref(), &icon_styles);
+            let text_styled = self.style_primary(data, text_color, &text_styles);
 
             let mut segment = format!("{} {}", icon_colored, text_styled);
 
             if !data.secondary.is_empty() {
                 segment.push_str(&format!(
                     " {}",
-                    self.apply_style(
-                        &data.secondary,
-                        config.colors.text.as_ref(),
-                        config.styles.text_bold
-                    )
+                    self.apply_style(&data.secondary, text_color, &text_styles)
                 ));
             }
 
@@ -279,40 +437,36 @@ impl StatusLineGenerator {
         }
     }
 
+    /// Adjust a foreground color for the segment's background when the
+    /// style's `auto_contrast` option is on
+    fn readable(&self, color: Option<AnsiColor>, config: &SegmentConfig) -> Option<AnsiColor> {
+        match (&color, &config.colors.background) {
+            (Some(color), Some(background)) if self.config.style.auto_contrast => {
+                Some(ensure_contrast(color, background, MIN_CONTRAST))
+            }
+            _ => color,
+        }
+    }
+
     fn get_icon(&self, config: &SegmentConfig) -> String {
         match self.config.style.mode {
             StyleMode::Plain => config.icon.plain.clone(),
             StyleMode::NerdFont => config.icon.nerd_font.clone(),
             StyleMode::Powerline => config.icon.nerd_font.clone(), // Future: use Powerline icons
-        }
-    }
-
-    fn apply_color(&self, text: &str, color: Option<&AnsiColor>) -> String {
-        match color {
-            Some(AnsiColor::Color16 { c16 }) => {
-                let code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
-                format!("\x1b[{}m{}\x1b[0m", code, text)
-            }
-            Some(AnsiColor::Color256 { c256 }) => {
-                format!("\x1b[38;5;{}m{}\x1b[0m", c256, text)
+            StyleMode::Ascii if !config.icon.plain.is_empty() && config.icon.plain.is_ascii() => {
+                config.icon.plain.clone()
             }
-            Some(AnsiColor::Rgb { r, g, b }) => {
-                format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, text)
-            }
-            None => text.to_string(),
+            StyleMode::Ascii => config.id.ascii_label().to_string(),
         }
     }
 
-    fn apply_style(&self, text: &str, color: Option<&AnsiColor>, bold: bool) -> String {
-        let mut codes = Vec::new();
-
-        // Add style codes
-        if bold {
-            codes.push("1".to_string()); // Bold: \x1b[1m
-        }
+    fn apply_style(&self, text: &str, color: Option<&AnsiColor>, styles: &[&str]) -> String {
+        // Add style codes (bold, italic, ...)
+        let mut codes: Vec<String> = styles.iter().map(|code| code.to_string()).collect();
 
         // Add color codes
-        match color {
+        let color = color.map(|color| downgrade(color, self.color_support));
+        match color.as_ref() {
             Some(AnsiColor::Color16 { c16 }) => {
                 let color_code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
                 codes.push(color_code.to_string());
@@ -339,10 +493,34 @@ impl StatusLineGenerator {
         }
     }
 
+    /// Style the primary text, giving each character its own color when the
+    /// segment supplies a `cell_colors` list of 256-color indices
+    fn style_primary(
+        &self,
+        data: &SegmentData,
+        color: Option<&AnsiColor>,
+        styles: &[&str],
+    ) -> String {
+        let Some(cell_colors) = data.metadata.get("cell_colors") else {
+            return self.apply_style(&data.primary, color, styles);
+        };
+        let mut cell_colors = cell_colors.split(',').map(|c| c.trim().parse::<u8>().ok());
+        data.primary
+            .chars()
+            .map(|ch| {
+                let cell_color = cell_colors
+                    .next()
+                    .flatten()
+                    .map(|c256| AnsiColor::Color256 { c256 });
+                self.apply_style(&ch.to_string(), cell_color.as_ref().or(color), styles)
+            })
+            .collect()
+    }
+
     fn apply_background_color(&self, color: &AnsiColor) -> String {
-        match color {
+        match downgrade(color, self.color_support) {
             AnsiColor::Color16 { c16 } => {
-                let code = if *c16 < 8 { 40 + c16 } else { 100 + (c16 - 8) };
+                let code = if c16 < 8 { 40 + c16 } else { 100 + (c16 - 8) };
                 format!("\x1b[{}m", code)
             }
             AnsiColor::Color256 { c256 } => {
@@ -436,70 +614,267 @@ impl StatusLineGenerator {
 
     /// Convert AnsiColor to foreground color code
     fn color_to_foreground_code(&self, color: &AnsiColor) -> String {
-        match color {
-            AnsiColor::Color16 { c16 } => {
-                let code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
-                format!("\x1b[{}m", code)
-            }
-            AnsiColor::Color256 { c256 } => {
-                format!("\x1b[38;5;{}m", c256)
-            }
-            AnsiColor::Rgb { r, g, b } => {
-                format!("\x1b[38;2;{};{};{}m", r, g, b)
-            }
-        }
+        downgrade(color, self.color_support).foreground_code()
+    }
+}
+
+/// Text color override for segments reporting a `severity` of `warning` or
+/// `critical`, configurable per segment with `warning_color`/`critical_color` (256-color index)
+///
+/// A matching `color_rules` entry takes precedence.
+fn severity_color(config: &SegmentConfig, data: &SegmentData) -> Option<AnsiColor> {
+    if data.metadata.contains_key("color_rule") {
+        return None;
+    }
+    let (key, default) = match data.metadata.get("severity").map(String::as_str) {
+        Some("warning") => ("warning_color", 11),
+        Some("critical") => ("critical_color", 9),
+        _ => return None,
+    };
+    Some(match config.options.get(key).and_then(|v| v.as_u64()) {
+        Some(c256) => AnsiColor::Color256 {
+            c256: c256.min(255) as u8,
+        },
+        None => AnsiColor::Color16 { c16: default },
+    })
+}
+
+/// Move segments reporting a `critical` severity to the front, keeping the
+/// configured order otherwise, so narrow terminals truncate the calm ones
+pub fn sort_by_urgency(segments: &mut [(SegmentConfig, SegmentData)]) {
+    segments.sort_by_key(|(_, data)| {
+        data.metadata.get("severity").map(String::as_str) != Some("critical")
+    });
+}
+
+/// Apply colors a segment chose at collection time (`icon_color`, `text_color`
+/// and `background_color` metadata holding color JSON such as `{"c256":214}`)
+/// over its configured ones
+fn with_color_overrides(
+    (mut config, data): (SegmentConfig, SegmentData),
+) -> (SegmentConfig, SegmentData) {
+    let color = |key: &str| {
+        data.metadata
+            .get(key)
+            .and_then(|value| serde_json::from_str::<AnsiColor>(value).ok())
+    };
+    if let Some(icon) = color("icon_color") {
+        config.colors.icon = Some(icon);
     }
+    if let Some(text) = color("text_color") {
+        config.colors.text = Some(text);
+    }
+    if let Some(background) = color("background_color") {
+        config.colors.background = Some(background);
+    }
+    (config, data)
 }
 
+/// Collect data for every configured segment, in config order
+///
+/// `CCLINE_DISABLE_SEGMENTS` and `CCLINE_ONLY_SEGMENTS` (comma-separated
+/// segment ids or instance keys) skip segments for this run without editing
+/// the config.
 pub fn collect_all_segments(
     config: &Config,
     input: &crate::config::InputData,
 ) -> Vec<(SegmentConfig, SegmentData)> {
-    use crate::core::segments::*;
+    let disable = std::env::var("CCLINE_DISABLE_SEGMENTS").ok();
+    let only = std::env::var("CCLINE_ONLY_SEGMENTS").ok();
+    crate::core::pipeline::Pipeline::new(config)
+        .with_pre_collect(move |segments, _| {
+            filter_segments(segments, disable.as_deref(), only.as_deref())
+        })
+        .collect(input)
+}
 
-    let mut results = Vec::new();
+/// Drop segments named in `disable`, and when `only` is given, every segment
+/// not named in it; a name is a segment id (all its instances) or an instance
+/// key such as `git:sha`
+pub fn filter_segments(
+    segments: &mut Vec<SegmentConfig>,
+    disable: Option<&str>,
+    only: Option<&str>,
+) {
+    let names = |list: Option<&str>| -> Option<Vec<String>> {
+        list.map(|list| {
+            list.split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+    };
+    let matches = |segment: &SegmentConfig, names: &[String]| {
+        names
+            .iter()
+            .any(|name| name == segment.id.as_str() || *name == segment.instance_key())
+    };
+
+    if let Some(disable) = names(disable) {
+        segments.retain(|segment| !matches(segment, &disable));
+    }
+    if let Some(only) = names(only).filter(|only| !only.is_empty()) {
+        segments.retain(|segment| matches(segment, &only));
+    }
+}
 
-    for segment_config in &config.segments {
-        let segment_data = match segment_config.id {
-            crate::config::SegmentId::Model => {
-                let segment = ModelSegment::new();
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Directory => {
-                let segment = DirectorySegment::new();
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Git => {
-                let show_sha = segment_config
-                    .options
-                    .get("show_sha")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(false);
-                let segment = GitSegment::new().with_sha(show_sha);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Usage => {
-                let segment = UsageSegment::new(&config.global);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Update => {
-                let segment = UpdateSegment::new();
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Cost => {
-                let segment = CostSegment::new(segment_config);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::BurnRate => {
-                let segment = BurnRateSegment::new(segment_config);
-                segment.collect(input)
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_for_prompt_zsh() {
+        let line = "\x1b[1m\x1b[38;5;14mSonnet 4\x1b[0m 42%";
+        assert_eq!(
+            escape_for_prompt(line, PromptShell::Zsh),
+            "%{\x1b[1m\x1b[38;5;14m%}Sonnet 4%{\x1b[0m%} 42%%"
+        );
+    }
+
+    #[test]
+    fn test_escape_for_prompt_bash() {
+        let line = "\x1b[32mmain\x1b[0m a\\b";
+        assert_eq!(
+            escape_for_prompt(line, PromptShell::Bash),
+            "\\[\x1b[32m\\]main\\[\x1b[0m\\] a\\\\b"
+        );
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        assert_eq!(
+            to_ascii("\u{f0c9} 42% · 84k ↑ ✓ 项目 🤖\u{fe0f}"),
+            " 42% - 84k ^ ok 项目 "
+        );
+        assert_eq!(to_ascii("✨ $0.00 new session"), "* $0.00 new session");
+    }
+
+    #[test]
+    fn test_truncate_middle() {
+        assert_eq!(truncate_middle("main", 10), "main");
+        assert_eq!(
+            truncate_middle("feature/very-long-branch-name", 12),
+            "featur…-name"
+        );
+        assert_eq!(truncate_middle("abcdef", 1), "…");
+        // Wide characters take two columns each
+        assert_eq!(truncate_middle("项目/前端/组件", 9), "项目…组件");
+    }
+
+    #[test]
+    fn test_fit_to_width_keeps_secondary() {
+        let data = SegmentData {
+            primary: "feature/very-long-branch-name".to_string(),
+            secondary: "✓".to_string(),
+            metadata: Default::default(),
         };
 
-        if let Some(data) = segment_data {
-            results.push((segment_config.clone(), data));
-        }
+        let fitted = fit_to_width(&data, 14);
+        assert_eq!(fitted.primary, "featur…-name");
+        assert_eq!(fitted.secondary, "✓");
+
+        let fitted = fit_to_width(&data, 2);
+        assert_eq!(fitted.primary, "f…");
+        assert!(fitted.secondary.is_empty());
+    }
+
+    #[test]
+    fn test_filter_segments() {
+        // The six segments every theme started with
+        let mut config = Config::default();
+        config.segments.truncate(6);
+        let mut sha = config.segments[2].clone();
+        sha.name = Some("sha".to_string());
+        let ids = |disable: Option<&str>, only: Option<&str>| {
+            let mut segments = config.segments.clone();
+            segments.push(sha.clone());
+            filter_segments(&mut segments, disable, only);
+            segments
+                .iter()
+                .map(|s| s.instance_key())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(Some("cost, burn_rate"), None),
+            vec!["model", "directory", "git", "usage", "git:sha"]
+        );
+        assert_eq!(ids(None, Some("model,git:sha")), vec!["model", "git:sha"]);
+        assert_eq!(ids(Some("git:sha"), Some("git")), vec!["git"]);
+        assert_eq!(ids(Some(""), Some(" ")).len(), 7);
+    }
+
+    #[test]
+    fn test_sort_by_urgency_is_stable() {
+        let config = Config::default();
+        let mut segments: Vec<_> = config
+            .segments
+            .iter()
+            .take(4)
+            .map(|segment| {
+                (
+                    segment.clone(),
+                    SegmentData {
+                        primary: String::new(),
+                        secondary: String::new(),
+                        metadata: Default::default(),
+                    },
+                )
+            })
+            .collect();
+        segments[2]
+            .1
+            .metadata
+            .insert("severity".to_string(), "critical".to_string());
+        segments[3]
+            .1
+            .metadata
+            .insert("severity".to_string(), "warning".to_string());
+
+        sort_by_urgency(&mut segments);
+        let order: Vec<_> = segments.iter().map(|(c, _)| c.instance_key()).collect();
+        assert_eq!(order, vec!["git", "model", "directory", "usage"]);
     }
 
-    results
+    #[test]
+    fn test_generate_downgrades_rgb() {
+        let mut segment = Config::default().segments[0].clone();
+        segment.colors.text = Some(AnsiColor::Rgb {
+            r: 0xeb,
+            g: 0xbc,
+            b: 0xba,
+        });
+        let data = SegmentData {
+            primary: "Sonnet 4".to_string(),
+            secondary: String::new(),
+            metadata: Default::default(),
+        };
+
+        let line = StatusLineGenerator::new(Config::default())
+            .with_color_support(ColorSupport::Ansi256)
+            .generate(vec![(segment, data)]);
+        assert!(line.contains("38;5;181"), "{:?}", line);
+        assert!(!line.contains("38;2;"), "{:?}", line);
+    }
+
+    #[test]
+    fn test_icon_and_text_styles() {
+        let mut segment = Config::default().segments[0].clone();
+        segment.colors.icon = None;
+        segment.colors.text = None;
+        segment.colors.background = Some(AnsiColor::Color16 { c16: 4 });
+        segment.styles.icon_italic = true;
+        segment.styles.text_underline = true;
+        let data = SegmentData {
+            primary: "Sonnet 4".to_string(),
+            secondary: String::new(),
+            metadata: Default::default(),
+        };
+
+        let line = StatusLineGenerator::new(Config::default()).generate(vec![(segment, data)]);
+        assert!(line.contains("\x1b[4mSonnet 4"), "{:?}", line);
+        // The icon's italic ends before the text without dropping the background
+        assert!(line.contains(RESET_KEEP_BACKGROUND), "{:?}", line);
+        assert!(line.starts_with("\x1b[44m \x1b[3m"), "{:?}", line);
+    }
 }
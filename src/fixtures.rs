@@ -0,0 +1,260 @@
+//! Synthetic Claude Code transcripts for demos, screenshots and benchmarks.
+//!
+//! Used by `ccline gen-fixtures`. Output is deterministic for a given seed and
+//! end time, so generated data sets can be rebuilt instead of shared.
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use std::path::{Path, PathBuf};
+
+/// Models with the share of sessions that use them, summing to 100
+const MODELS: [(&str, u64); 3] = [
+    ("claude-sonnet-4-20250514", 70),
+    ("claude-opus-4-1-20250805", 20),
+    ("claude-3-5-haiku-20241022", 10),
+];
+
+const PROJECTS: [&str; 5] = [
+    "web-app",
+    "api-server",
+    "cli-tool",
+    "docs-site",
+    "data-pipeline",
+];
+
+/// Context size at which a generated session compacts, like Claude Code's
+/// auto-compact
+const COMPACT_AT_TOKENS: u64 = 160_000;
+
+/// What to generate
+#[derive(Debug, Clone)]
+pub struct FixtureSpec {
+    pub days: u32,
+    pub sessions: u32,
+    pub seed: u64,
+}
+
+/// Summary of a generated data set
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixtureSummary {
+    pub projects_dir: PathBuf,
+    pub sessions: usize,
+    pub entries: usize,
+}
+
+/// Small deterministic generator (SplitMix64); fixtures only need
+/// reproducibility, not statistical quality
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `low..=high`
+    fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next() % (high - low + 1)
+    }
+
+    fn uuid(&mut self) -> String {
+        let a = self.next();
+        let b = self.next();
+        format!(
+            "{:08x}-{:04x}-4{:03x}-{:04x}-{:012x}",
+            a >> 32,
+            (a >> 16) & 0xffff,
+            a & 0xfff,
+            0x8000 | ((b >> 48) & 0x3fff),
+            b & 0xffff_ffff_ffff
+        )
+    }
+
+    fn model(&mut self) -> &'static str {
+        let mut pick = self.range(0, 99);
+        for (model, share) in MODELS {
+            if pick < share {
+                return model;
+            }
+            pick -= share;
+        }
+        MODELS[0].0
+    }
+}
+
+/// Write `spec.sessions` transcripts spread over the `spec.days` days before
+/// `now` into `<out>/.claude/projects`, so `HOME=<out>` (or
+/// `CLAUDE_CONFIG_DIR=<out>/.claude`) points ccline at them
+///
+/// The newest session ends a few minutes before `now`, giving the statusline
+/// an active block to show.
+pub fn generate(
+    out: &Path,
+    spec: &FixtureSpec,
+    now: DateTime<Utc>,
+) -> Result<FixtureSummary, Box<dyn std::error::Error>> {
+    if spec.days == 0 {
+        return Err("--days must be at least 1".into());
+    }
+    let projects_dir = out.join(".claude").join("projects");
+    let mut rng = Rng(spec.seed);
+    let mut entries = 0;
+
+    for index in 0..spec.sessions {
+        let project = PROJECTS[rng.range(0, PROJECTS.len() as u64 - 1) as usize];
+        let session_id = rng.uuid();
+        let model = rng.model();
+        let messages = rng.range(5, 80);
+        let start = if index == 0 {
+            now - Duration::minutes(messages as i64 * 3 + 5)
+        } else {
+            let day = rng.range(0, spec.days as u64 - 1) as i64;
+            let minute = rng.range(8 * 60, 22 * 60) as i64;
+            let start = (now - Duration::days(day))
+                .date_naive()
+                .and_hms_opt(0, 0, 0);
+            let start = start.map(|t| t.and_utc()).unwrap_or(now) + Duration::minutes(minute);
+            // Keep every session in the past
+            start.min(now - Duration::minutes(messages as i64 * 3 + 10))
+        };
+
+        let cwd = format!("/home/demo/{}", project);
+        let dir = projects_dir.join(cwd.replace('/', "-"));
+        std::fs::create_dir_all(&dir)?;
+
+        let lines = session_lines(&mut rng, &session_id, model, &cwd, start, messages);
+        entries += lines.len() / 2;
+        std::fs::write(
+            dir.join(format!("{}.jsonl", session_id)),
+            lines.join("\n") + "\n",
+        )?;
+    }
+
+    Ok(FixtureSummary {
+        projects_dir,
+        sessions: spec.sessions as usize,
+        entries,
+    })
+}
+
+/// One user line and one assistant line per message; the context grows
+/// through cache reads until it compacts
+fn session_lines(
+    rng: &mut Rng,
+    session_id: &str,
+    model: &str,
+    cwd: &str,
+    start: DateTime<Utc>,
+    messages: u64,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut time = start;
+    let mut context = 0u64;
+
+    for i in 0..messages {
+        let (cache_creation, cache_read) = if context == 0 {
+            // The first turn writes the system prompt and tools to the cache
+            (rng.range(12_000, 30_000), 0)
+        } else {
+            (rng.range(200, 6_000), context)
+        };
+        let input = rng.range(3, 400);
+        let output = rng.range(40, 2_500);
+
+        lines.push(
+            serde_json::json!({
+                "type": "user",
+                "sessionId": session_id,
+                "cwd": cwd,
+                "uuid": rng.uuid(),
+                "timestamp": time.to_rfc3339_opts(SecondsFormat::Millis, true),
+                "message": { "role": "user", "content": format!("Demo request {}", i + 1) }
+            })
+            .to_string(),
+        );
+        time += Duration::seconds(rng.range(5, 90) as i64);
+        lines.push(
+            serde_json::json!({
+                "type": "assistant",
+                "sessionId": session_id,
+                "cwd": cwd,
+                "uuid": rng.uuid(),
+                "requestId": format!("req_{:016x}", rng.next()),
+                "timestamp": time.to_rfc3339_opts(SecondsFormat::Millis, true),
+                "message": {
+                    "id": format!("msg_{:016x}", rng.next()),
+                    "role": "assistant",
+                    "model": model,
+                    "usage": {
+                        "input_tokens": input,
+                        "output_tokens": output,
+                        "cache_creation_input_tokens": cache_creation,
+                        "cache_read_input_tokens": cache_read
+                    }
+                }
+            })
+            .to_string(),
+        );
+        time += Duration::seconds(rng.range(10, 60) as i64);
+
+        context = cache_read + cache_creation + input + output;
+        if context >= COMPACT_AT_TOKENS {
+            context = rng.range(15_000, 30_000);
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TranscriptEntry;
+
+    #[test]
+    fn test_generate_is_reproducible() {
+        let root = std::env::temp_dir().join(format!("ccline-fixtures-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let spec = FixtureSpec {
+            days: 3,
+            sessions: 4,
+            seed: 7,
+        };
+        let now = Utc::now();
+
+        let summary = generate(&root.join("a"), &spec, now).unwrap();
+        generate(&root.join("b"), &spec, now).unwrap();
+        assert_eq!(summary.sessions, 4);
+
+        let mut files: Vec<PathBuf> =
+            glob::glob(&format!("{}/**/*.jsonl", root.join("a").display()))
+                .unwrap()
+                .flatten()
+                .collect();
+        files.sort();
+        assert_eq!(files.len(), 4);
+
+        let mut assistant = 0;
+        for file in &files {
+            let content = std::fs::read_to_string(file).unwrap();
+            let twin = root
+                .join("b")
+                .join(file.strip_prefix(root.join("a")).unwrap());
+            assert_eq!(content, std::fs::read_to_string(twin).unwrap());
+
+            for line in content.lines() {
+                let entry: TranscriptEntry = serde_json::from_str(line).unwrap();
+                let timestamp: DateTime<Utc> = entry.timestamp.unwrap().parse().unwrap();
+                assert!(timestamp <= now && timestamp > now - Duration::days(4));
+                if entry.r#type.as_deref() == Some("assistant") {
+                    assert!(entry.message.unwrap().usage.is_some());
+                    assistant += 1;
+                }
+            }
+        }
+        assert_eq!(assistant, summary.entries);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -2,8 +2,14 @@ pub mod billing;
 pub mod cli;
 pub mod config;
 pub mod core;
+pub mod fixtures;
+pub mod report;
+pub mod selftest;
 pub mod ui;
 
 #[cfg(feature = "self-update")]
 pub mod updater;
 pub mod utils;
+
+pub use config::{Config, InputData, InputDataBuilder};
+pub use core::{generate, RenderedLine};
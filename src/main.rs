@@ -1,11 +1,83 @@
-use ccometixline::cli::Cli;
+use ccometixline::cli::{
+    CacheAction, CacheKind, Cli, Command, ContextAction, OutputFormat, PreviewArgs, ThemeAction,
+    WatchArgs,
+};
+use ccometixline::config::context_limits::parse_token_count;
 use ccometixline::config::{BlockOverrideManager, Config, InputData};
-use ccometixline::core::{collect_all_segments, StatusLineGenerator};
-use chrono::{Local, NaiveDate, Utc};
-use std::io;
+use ccometixline::core::branch_ledger::record_branch;
+use ccometixline::core::hooks::run_hooks;
+use ccometixline::core::metrics_log::record_metrics;
+use ccometixline::core::render_cache::RenderCache;
+use ccometixline::core::{
+    collect_all_segments, escape_for_prompt, strip_ansi, PromptShell, StatusLineGenerator,
+};
+use ccometixline::utils::recording::Recording;
+use ccometixline::utils::timezone;
+use ccometixline::utils::{crash, last_input, profile};
+use chrono::{NaiveDate, Utc};
+use std::io::{self, IsTerminal};
+use std::panic::AssertUnwindSafe;
+use std::time::Instant;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse_args();
+    ccometixline::utils::terminal::enable_ansi_support();
+    ccometixline::utils::terminal::set_no_color(cli.no_color);
+
+    // Handle subcommands
+    if let Some(command) = &cli.command {
+        let config = Config::load().unwrap_or_else(|_| Config::default());
+        config.apply_global_settings();
+        match command {
+            Command::Report(args) => ccometixline::report::run(args, &config)?,
+            Command::Dashboard => ccometixline::report::run_dashboard(&config)?,
+            Command::Sessions(args) => ccometixline::report::run_sessions(args, &config)?,
+            Command::Blocks(args) => ccometixline::report::run_blocks(args, &config)?,
+            Command::Audit { approve, all } => {
+                ccometixline::core::commands::audit(&config, approve.as_deref(), *all)?
+            }
+            Command::Context { action } => handle_context_command(config, action)?,
+            Command::Theme { action } => handle_theme_command(action)?,
+            Command::Cache { action } => handle_cache_command(action)?,
+            Command::Preview(args) => run_preview(args, &cli, config)?,
+            Command::Completions { shell } => Cli::print_completions(*shell)?,
+            Command::Watch(args) => run_watch(args, cli.output)?,
+            Command::SelfTest => run_self_test()?,
+            Command::Explain { segment } => run_explain(&config, segment)?,
+            Command::GenFixtures {
+                days,
+                sessions,
+                seed,
+                until,
+                out,
+            } => {
+                let spec = ccometixline::fixtures::FixtureSpec {
+                    days: *days,
+                    sessions: *sessions,
+                    seed: *seed,
+                };
+                let summary =
+                    ccometixline::fixtures::generate(out, &spec, until.unwrap_or_else(Utc::now))?;
+                println!(
+                    "Wrote {} sessions ({} usage entries) to {}",
+                    summary.sessions,
+                    summary.entries,
+                    summary.projects_dir.display()
+                );
+                println!("Try: HOME={} ccline report", out.display());
+            }
+            Command::Index => {
+                let entries = ccometixline::utils::index_state::build_index()?;
+                println!("Indexed {} usage entries", entries);
+            }
+            Command::RunCommand { name } => run_declared_command(&config, name)?,
+            Command::CollectSegment { key } => {
+                let input: InputData = serde_json::from_reader(io::stdin().lock())?;
+                ccometixline::core::pipeline::collect_in_background(&config, key, &input)?;
+            }
+        }
+        return Ok(());
+    }
 
     // Handle configuration commands
     if cli.init {
@@ -18,7 +90,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Apply theme override if provided
         if let Some(theme) = cli.theme {
-            config = ccometixline::ui::themes::ThemePresets::get_theme(&theme);
+            config.apply_theme(
+                &ccometixline::ui::themes::ThemePresets::get_theme(&theme),
+                cli.reset_options,
+            );
         }
 
         config.print()?;
@@ -29,13 +104,44 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let config = Config::load()?;
         config.check()?;
         println!("✓ Configuration valid");
+        for dir in &config.global.transcript_dirs {
+            let path = dir.projects_dir();
+            let name = dir.label.as_deref().unwrap_or(&dir.path);
+            if path.is_dir() {
+                println!("  Transcripts: {} → {}", name, path.display());
+            } else {
+                println!(
+                    "  ⚠ Transcripts: {} → {} does not exist",
+                    name,
+                    path.display()
+                );
+            }
+        }
+        if config.global.include_codex {
+            match ccometixline::utils::codex::sessions_dir() {
+                Some(dir) if dir.is_dir() => println!("  Codex sessions: {}", dir.display()),
+                Some(dir) => println!("  ⚠ Codex sessions: {} does not exist", dir.display()),
+                None => println!("  ⚠ Codex sessions: home directory not found"),
+            }
+        }
+        if config.global.include_gemini {
+            match ccometixline::utils::gemini::chats_root() {
+                Some(dir) if dir.is_dir() => println!("  Gemini chats: {}", dir.display()),
+                Some(dir) => println!("  ⚠ Gemini chats: {} does not exist", dir.display()),
+                None => println!("  ⚠ Gemini chats: home directory not found"),
+            }
+        }
+        println!(
+            "  Terminal colors: {}",
+            ccometixline::utils::terminal::color_support().as_str()
+        );
         return Ok(());
     }
 
     if cli.config {
         #[cfg(feature = "tui")]
         {
-            ccometixline::ui::run_configurator()?;
+            ccometixline::ui::run_configurator(cli.reset_options)?;
         }
         #[cfg(not(feature = "tui"))]
         {
@@ -59,19 +165,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Handle block start time management
     if cli.set_block_start.is_some() || cli.clear_block_start || cli.show_block_status {
+        let config = Config::load().unwrap_or_else(|_| Config::default());
+        config.apply_global_settings();
         handle_block_management(&cli)?;
         return Ok(());
     }
 
     // Handle context limit setting
-    if let Some(context_limit) = cli.context_limit {
-        if context_limit == 0 {
-            eprintln!("Error: Context limit must be greater than 0");
-            std::process::exit(1);
+    if let Some(context_limit) = &cli.context_limit {
+        let mut config = Config::load().unwrap_or_else(|_| Config::default());
+
+        if context_limit.trim().eq_ignore_ascii_case("auto") {
+            config.global.auto_context_limit = Some(true);
+            config.save()?;
+            println!(
+                "Context limit will be resolved per model (falling back to {} tokens)",
+                config.global.context_limit
+            );
+            return Ok(());
         }
 
-        let mut config = Config::load().unwrap_or_else(|_| Config::default());
+        let context_limit = match parse_token_count(context_limit) {
+            Ok(limit) => limit,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        };
         config.global.context_limit = context_limit;
+        config.global.auto_context_limit = Some(false);
 
         // Validate the configuration
         if let Err(e) = config.global.validate() {
@@ -84,27 +206,443 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    // Leave a crash report and a fallback line instead of a blank statusline
+    crash::install_panic_hook();
+
+    let profiling = cli.profile || profile::enabled_by_env();
+    let started_at = Utc::now();
+    let started = Instant::now();
+    if profiling {
+        profile::start();
+    }
+
     // Load configuration
-    let mut config = Config::load().unwrap_or_else(|_| Config::default());
+    let mut config = profile::time("config", None, || {
+        Config::load().unwrap_or_else(|_| Config::default())
+    });
 
-    // Apply theme override if provided
+    // Apply theme visuals for this invocation only (never saved)
     if let Some(theme) = cli.theme {
-        config = ccometixline::ui::themes::ThemePresets::get_theme(&theme);
+        config.apply_theme(
+            &ccometixline::ui::themes::ThemePresets::get_theme(&theme),
+            cli.reset_options,
+        );
     }
 
-    // Read Claude Code data from stdin
+    config.apply_global_settings();
+    crash::record_config(&config);
+
+    // Read Claude Code data from a replayed recording or stdin, or infer it
+    // from the current directory when run from a shell
+    let replay = cli.replay.as_deref().map(Recording::load).transpose()?;
     let stdin = io::stdin();
-    let input: InputData = serde_json::from_reader(stdin.lock())?;
+    let raw_input: Option<serde_json::Value> = match &replay {
+        Some(recording) => Some(recording.input.clone()),
+        None if stdin.is_terminal() => None,
+        None => Some(serde_json::from_reader(stdin.lock())?),
+    };
+    let input: InputData = match &raw_input {
+        Some(raw) => serde_json::from_value(raw.clone())?,
+        None => last_input::from_cwd(),
+    };
+    if replay.is_none() && raw_input.is_some() {
+        last_input::save(&input);
+    }
+    crash::record_input(&input);
+
+    // Reuse a line rendered moments ago; recording and profiling always render
+    let render_cache = match &raw_input {
+        Some(raw) if replay.is_none() && cli.record.is_none() && !profiling => {
+            RenderCache::new(&config, raw)
+        }
+        _ => None,
+    };
+    if let Some(line) = render_cache
+        .as_ref()
+        .and_then(|cache| cache.get(started_at))
+    {
+        println!("{}", format_for_output(line, cli.output));
+        return Ok(());
+    }
+
+    let rendered = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        // Collect segment data
+        let segments_data = collect_all_segments(&config, &input);
 
-    // Collect segment data
-    let segments_data = collect_all_segments(&config, &input);
+        // Fire threshold hooks in the background
+        profile::time("hooks", None, || run_hooks(&config, &segments_data));
 
-    // Render statusline
-    let generator = StatusLineGenerator::new(config);
-    let statusline = generator.generate(segments_data);
+        // Remember which branch this session is on for `ccline report branches`
+        profile::time("side_effects", None, || {
+            record_branch(&input, &segments_data);
+            record_metrics(&config, &input, &segments_data);
+        });
+
+        // Render statusline
+        let generator = StatusLineGenerator::new(config.clone());
+        generator.generate(segments_data)
+    }));
+    let statusline = match rendered {
+        Ok(line) => {
+            if let Some(cache) = &render_cache {
+                if let Err(e) = cache.store(&line, started_at) {
+                    ccometixline::debug_println!("Could not cache statusline: {}", e);
+                }
+            }
+            line
+        }
+        Err(_) => crash::fallback_line(Some(&input)),
+    };
+    let statusline = format_for_output(statusline, cli.output);
 
     println!("{}", statusline);
 
+    if let Some(path) = &cli.record {
+        let raw = match raw_input {
+            Some(raw) => raw,
+            None => serde_json::to_value(&input)?,
+        };
+        Recording::new(raw, statusline.clone()).save(path)?;
+        eprintln!("Recorded input and output to {}", path.display());
+    }
+
+    if profiling {
+        match profile::write(&profile::finish(started_at, started.elapsed())) {
+            Ok(path) => eprintln!("Profile written to {}", path.display()),
+            Err(e) => eprintln!("Could not write profile: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Wrap escape sequences for the shell prompt named by `--output`, or drop
+/// them with `--no-color`
+fn format_for_output(statusline: String, output: OutputFormat) -> String {
+    let statusline = if ccometixline::utils::terminal::no_color() {
+        strip_ansi(&statusline)
+    } else {
+        statusline
+    };
+    match output {
+        OutputFormat::Ansi => statusline,
+        OutputFormat::Zsh => escape_for_prompt(&statusline, PromptShell::Zsh),
+        OutputFormat::Bash => escape_for_prompt(&statusline, PromptShell::Bash),
+    }
+}
+
+/// Run one segment with stdin, the last captured input or a synthetic one,
+/// printing each step it traced and the data it produced
+fn run_explain(config: &Config, segment: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use ccometixline::core::pipeline::Pipeline;
+    use ccometixline::utils::debug::{start_trace, take_trace};
+
+    let (input, source) = if !io::stdin().is_terminal() {
+        let input: InputData = serde_json::from_reader(io::stdin().lock())?;
+        (input, "stdin")
+    } else if let Some(input) = last_input::load() {
+        (input, "the last statusline input from Claude Code")
+    } else {
+        (
+            last_input::synthetic(),
+            "a synthetic input for this directory",
+        )
+    };
+
+    let mut config = config.clone();
+    // Wait for the segment however long it takes, to trace every step
+    config.global.max_render_ms = 0;
+    config
+        .segments
+        .retain(|s| s.id.as_str() == segment || s.instance_key() == segment);
+    if config.segments.is_empty() {
+        return Err(format!(
+            "No segment '{}' in the config; use a segment id or instance key",
+            segment
+        )
+        .into());
+    }
+    for segment in &mut config.segments {
+        if !segment.enabled {
+            println!(
+                "{} is disabled in the config; enabled for this run",
+                segment.instance_key()
+            );
+            segment.enabled = true;
+        }
+    }
+
+    println!("Input: {}", source);
+    start_trace();
+    let collected = Pipeline::new(&config).collect(&input);
+    let steps = take_trace();
+
+    println!();
+    if steps.is_empty() {
+        println!("(this segment records no trace steps)");
+    }
+    for (i, step) in steps.iter().enumerate() {
+        println!("{:>3}. {}", i + 1, step);
+    }
+
+    println!();
+    if collected.is_empty() {
+        println!("Result: nothing rendered (segment hidden)");
+    }
+    for (segment, data) in collected {
+        println!("Result for {}:", segment.instance_key());
+        println!("  primary:   {}", data.primary);
+        println!("  secondary: {}", data.secondary);
+        let mut metadata: Vec<_> = data.metadata.into_iter().collect();
+        metadata.sort();
+        for (key, value) in metadata {
+            println!("  {} = {}", key, value);
+        }
+    }
+    Ok(())
+}
+
+/// Render the configured statusline, with `--theme` applied, over mock data
+/// for one or all scenarios
+fn run_preview(
+    args: &PreviewArgs,
+    cli: &Cli,
+    mut config: Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use ccometixline::core::scenarios::{mock_segments_data, Scenario};
+    use clap::ValueEnum;
+
+    if let Some(theme) = &cli.theme {
+        config.apply_theme(
+            &ccometixline::ui::themes::ThemePresets::get_theme(theme),
+            cli.reset_options,
+        );
+    }
+    let scenarios = if args.all {
+        Scenario::value_variants().to_vec()
+    } else {
+        vec![args.scenario]
+    };
+
+    let generator = StatusLineGenerator::new(config.clone());
+    for scenario in scenarios {
+        let line = format_for_output(
+            generator.generate(mock_segments_data(&config, scenario)),
+            cli.output,
+        );
+        match scenario.to_possible_value().filter(|_| args.all) {
+            Some(name) => println!("{:<16} {}", name.get_name(), line),
+            None => println!("{}", line),
+        }
+    }
+    Ok(())
+}
+
+/// Re-render the statusline every `interval` seconds, and on transcript
+/// changes with `--on-change`, refreshing one terminal line or rewriting a file
+///
+/// Input comes from stdin when piped; otherwise the last input captured from
+/// Claude Code is re-read each time, so the line follows the active session.
+fn run_watch(args: &WatchArgs, output: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    use notify::{RecursiveMode, Watcher};
+    use std::io::{Read, Write};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    if args.interval == 0 {
+        return Err("--interval must be at least 1 second".into());
+    }
+    let mut stdin = String::new();
+    if !io::stdin().is_terminal() {
+        io::stdin().read_to_string(&mut stdin)?;
+    }
+    let piped: Option<InputData> = match stdin.trim() {
+        "" => None,
+        json => Some(serde_json::from_str(json)?),
+    };
+
+    let (changed, changes) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if event.is_ok_and(|event| !event.kind.is_access()) {
+            let _ = changed.send(());
+        }
+    })?;
+    if args.on_change {
+        for dir in ccometixline::utils::data_dirs::find_claude_dirs() {
+            watcher.watch(&dir, RecursiveMode::Recursive)?;
+        }
+    }
+
+    let interval = Duration::from_secs(args.interval);
+    loop {
+        let config = Config::load().unwrap_or_else(|_| Config::default());
+        let input = piped
+            .clone()
+            .or_else(last_input::load)
+            .unwrap_or_else(last_input::synthetic);
+
+        let segments_data = collect_all_segments(&config, &input);
+        let statusline = format_for_output(
+            StatusLineGenerator::new(config).generate(segments_data),
+            output,
+        );
+
+        match &args.out {
+            // Replace the file in one step so readers never see a partial line
+            Some(path) => {
+                let tmp = path.with_extension("tmp");
+                std::fs::write(&tmp, format!("{}\n", statusline))?;
+                std::fs::rename(&tmp, path)?;
+            }
+            None => {
+                let mut stdout = io::stdout().lock();
+                write!(stdout, "\r\x1b[2K{}", statusline)?;
+                stdout.flush()?;
+            }
+        }
+
+        // Wait out the interval unless a transcript changes first, then let a
+        // burst of writes settle into one render
+        if changes.recv_timeout(interval).is_ok() {
+            std::thread::sleep(Duration::from_millis(250));
+            while changes.try_recv().is_ok() {}
+        }
+    }
+}
+
+/// Handle `ccline theme` subcommands
+fn handle_theme_command(action: &ThemeAction) -> Result<(), Box<dyn std::error::Error>> {
+    use ccometixline::ui::themes::{lint_theme, ThemePresets};
+
+    match action {
+        ThemeAction::Lint { name } => {
+            if !ThemePresets::list_available_themes().contains(name) {
+                return Err(format!("Unknown theme '{}'", name).into());
+            }
+            let warnings = lint_theme(&ThemePresets::get_theme(name));
+            if warnings.is_empty() {
+                println!("✓ Theme '{}' has no issues", name);
+            }
+            for warning in &warnings {
+                println!("⚠ {}", warning);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Handle `ccline cache` maintenance
+fn handle_cache_command(action: &CacheAction) -> Result<(), Box<dyn std::error::Error>> {
+    let files = |count: usize| format!("{} file{}", count, if count == 1 { "" } else { "s" });
+    match action {
+        CacheAction::Status => {
+            for kind in CacheKind::ALL {
+                let location = kind
+                    .location()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default();
+                println!("{:<8} {}", kind.as_str(), kind.status().summary());
+                println!("{:<8} {}", "", location);
+            }
+        }
+        CacheAction::Clear { cache } => {
+            let kinds = cache.map_or(CacheKind::ALL.to_vec(), |kind| vec![kind]);
+            for kind in kinds {
+                println!("{:<8} removed {}", kind.as_str(), files(kind.clear()?));
+            }
+        }
+        CacheAction::Prune => {
+            for kind in CacheKind::ALL {
+                println!("{:<8} removed {}", kind.as_str(), files(kind.prune()?));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Handle `ccline context` per-model limit management
+fn handle_context_command(
+    mut config: Config,
+    action: &ContextAction,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let limits = &mut config.global.context_limits;
+    match action {
+        ContextAction::Set { pattern, limit } => {
+            glob::Pattern::new(pattern)
+                .map_err(|e| format!("Invalid model pattern '{}': {}", pattern, e))?;
+            let limit = parse_token_count(limit)?;
+            limits.insert(pattern.clone(), limit);
+            config.save()?;
+            println!("Context limit for '{}' set to {} tokens", pattern, limit);
+        }
+        ContextAction::Unset { pattern } => {
+            if limits.remove(pattern).is_none() {
+                return Err(format!("No context limit set for '{}'", pattern).into());
+            }
+            config.save()?;
+            println!("Context limit for '{}' removed", pattern);
+        }
+        ContextAction::List => {
+            for (pattern, limit) in limits.iter() {
+                println!("{:<32} {}", pattern, limit);
+            }
+            println!(
+                "{:<32} {}{}",
+                "(default)",
+                config.global.context_limit,
+                if config.global.auto_context_limit() {
+                    " (auto per model)"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Run the golden-path checks against this binary in a scratch directory
+fn run_self_test() -> Result<(), Box<dyn std::error::Error>> {
+    let exe = std::env::current_exe()?;
+    let root = std::env::temp_dir().join(format!("ccline-self-test-{}", std::process::id()));
+    let results = ccometixline::selftest::run_checks(&exe, &root);
+    let _ = std::fs::remove_dir_all(&root);
+
+    let mut failed = 0;
+    for check in &results {
+        match &check.result {
+            Ok(()) => println!("✓ {}", check.name),
+            Err(reason) => {
+                failed += 1;
+                println!("✗ {}: {}", check.name, reason);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} of {} checks failed", failed, results.len()).into());
+    }
+    println!("\nAll {} checks passed", results.len());
+    Ok(())
+}
+
+/// Run a declared command on behalf of a hook, passing along the `CCLINE_*` values
+fn run_declared_command(config: &Config, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use ccometixline::core::commands::{run_command, CommandApprovals, CommandStatus};
+
+    let command = config
+        .commands
+        .get(name)
+        .ok_or_else(|| format!("Command '{}' is not declared", name))?;
+    let status = CommandApprovals::load().status(name, command);
+    if status != CommandStatus::Approved {
+        return Err(format!("Command '{}' is {}", name, status.label()).into());
+    }
+
+    let env: Vec<(String, String)> = std::env::vars()
+        .filter(|(key, _)| key.starts_with("CCLINE_"))
+        .collect();
+    run_command(command, &env)?;
     Ok(())
 }
 
@@ -123,25 +661,37 @@ fn handle_block_management(cli: &Cli) -> io::Result<()> {
         eprintln!("Warning: Failed to load existing configuration: {}", e);
     }
 
-    let today = Local::now().date_naive();
+    let date = match &cli.date {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+            eprintln!("Error: Invalid date '{}', expected YYYY-MM-DD", date);
+            io::Error::new(io::ErrorKind::InvalidInput, "invalid date")
+        })?,
+        None => timezone::today(),
+    };
+    let day_label = if date == timezone::today() {
+        "Today"
+    } else {
+        "Date"
+    };
 
     // Handle set block start time
     if let Some(time_input) = &cli.set_block_start {
-        match BlockOverrideManager::parse_time_input(time_input) {
+        match BlockOverrideManager::parse_time_input_on(time_input, date) {
             Ok(start_time) => {
                 let notes = Some(format!(
                     "Set via CLI at {}",
                     Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
                 ));
 
-                match manager.set_override(today, start_time, "manual".to_string(), notes) {
+                match manager.set_override(date, start_time, "manual".to_string(), notes) {
                     Ok(()) => {
-                        let local_start_time = start_time.with_timezone(&Local);
+                        let local_start_time = timezone::to_local(start_time);
                         println!(
-                            "✓ Block start time set to {} ({} local) for {}",
+                            "✓ Block start time set to {} ({} {}) for {}",
                             start_time.format("%Y-%m-%d %H:%M UTC"),
-                            local_start_time.format("%H:%M %Z"),
-                            today.format("%Y-%m-%d")
+                            local_start_time.format("%H:%M"),
+                            timezone::label(),
+                            date.format("%Y-%m-%d")
                         );
                         println!("  Configuration saved to: {:?}", manager.get_config_path());
                     }
@@ -153,13 +703,13 @@ fn handle_block_management(cli: &Cli) -> io::Result<()> {
             }
             Err(e) => {
                 eprintln!("Error: Invalid time format: {}", e);
-                let now_local = Local::now();
                 eprintln!(
                     "Valid formats: single hour (0-23), HH:MM, or ISO timestamp (YYYY-MM-DDTHH:MM:SSZ)"
                 );
                 eprintln!(
-                    "Times are interpreted as local time (current: {})",
-                    now_local.format("%H:%M %Z")
+                    "Times are interpreted in {} time (current: {})",
+                    timezone::label(),
+                    timezone::now().format("%H:%M")
                 );
                 return Err(io::Error::new(io::ErrorKind::InvalidInput, e));
             }
@@ -168,17 +718,17 @@ fn handle_block_management(cli: &Cli) -> io::Result<()> {
 
     // Handle clear block start
     if cli.clear_block_start {
-        match manager.clear_override(today) {
+        match manager.clear_override(date) {
             Ok(true) => {
                 println!(
                     "✓ Block start time override cleared for {}",
-                    today.format("%Y-%m-%d")
+                    date.format("%Y-%m-%d")
                 );
             }
             Ok(false) => {
                 println!(
                     "ℹ No block start time override was set for {}",
-                    today.format("%Y-%m-%d")
+                    date.format("%Y-%m-%d")
                 );
             }
             Err(e) => {
@@ -194,25 +744,28 @@ fn handle_block_management(cli: &Cli) -> io::Result<()> {
         println!("  Configuration file: {:?}", manager.get_config_path());
         println!("  Total overrides: {}", manager.override_count());
 
-        if let Some(override_config) = manager.get_override(today) {
-            println!("\n  Today ({}):", today.format("%Y-%m-%d"));
+        if let Some(override_config) = manager.effective_override(date) {
+            println!("\n  {} ({}):", day_label, date.format("%Y-%m-%d"));
             println!("    ✓ Override active");
-            let local_start_time = override_config.start_time.with_timezone(&Local);
+            let local_start_time = timezone::to_local(override_config.start_time);
             println!(
-                "    ⏰ Block starts at: {} ({} local)",
+                "    ⏰ Block starts at: {} ({} {})",
                 override_config.start_time.format("%H:%M UTC"),
-                local_start_time.format("%H:%M %Z")
+                local_start_time.format("%H:%M"),
+                timezone::label()
             );
             println!("    📝 Source: {}", override_config.source);
-            println!(
-                "    🕐 Created: {}",
-                override_config.created_at.format("%Y-%m-%d %H:%M UTC")
-            );
+            if override_config.source != "schedule" {
+                println!(
+                    "    🕐 Created: {}",
+                    override_config.created_at.format("%Y-%m-%d %H:%M UTC")
+                );
+            }
             if let Some(ref notes) = override_config.notes {
                 println!("    📋 Notes: {}", notes);
             }
         } else {
-            println!("\n  Today ({}):", today.format("%Y-%m-%d"));
+            println!("\n  {} ({}):", day_label, date.format("%Y-%m-%d"));
             println!("    ⏱️ No override set (will use automatic detection)");
         }
 
@@ -235,6 +788,13 @@ fn handle_block_management(cli: &Cli) -> io::Result<()> {
                 }
             }
         }
+
+        if !manager.schedule().is_empty() {
+            println!("\n  Recurring schedule (days without an override):");
+            for rule in manager.schedule() {
+                println!("    {} at {}", rule.days, rule.start);
+            }
+        }
     }
 
     Ok(())
@@ -0,0 +1,172 @@
+use super::table::{format_currency, TablePalette};
+use crate::billing::BillingBlock;
+use crate::utils::timezone;
+use crate::utils::width::display_width;
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::BTreeMap;
+
+const CELL_WIDTH: usize = 9;
+const SHADES: [&str; 4] = ["░", "▒", "▓", "█"];
+
+/// Block activity for a single local calendar day
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DayBlocks {
+    pub blocks: usize,
+    pub tokens: u64,
+    pub cost: f64,
+}
+
+/// Parse a `YYYY-MM` month into its first day
+pub fn parse_month(month: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid month '{}', expected YYYY-MM", month))
+}
+
+/// Group non-gap blocks by the day they started on, in the configured timezone
+pub fn aggregate_block_days(blocks: &[BillingBlock]) -> BTreeMap<NaiveDate, DayBlocks> {
+    let mut days: BTreeMap<NaiveDate, DayBlocks> = BTreeMap::new();
+    for block in blocks.iter().filter(|b| !b.is_gap) {
+        let day = days.entry(timezone::date_of(block.start_time)).or_default();
+        day.blocks += 1;
+        day.tokens += block.total_tokens;
+        day.cost += block.cost;
+    }
+    days
+}
+
+/// Render a Monday-first month grid with per-day block counts and utilization shading
+///
+/// Shading is relative to the busiest day of the month; each week row ends with
+/// the week's block count and cost.
+pub fn render_calendar(
+    first: NaiveDate,
+    days: &BTreeMap<NaiveDate, DayBlocks>,
+    palette: &TablePalette,
+) -> String {
+    let next_month = (first + Duration::days(32)).with_day(1).unwrap_or(first);
+    let busiest = days
+        .range(first..next_month)
+        .map(|(_, d)| d.tokens)
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = vec![first.format("%B %Y").to_string(), String::new()];
+    let header: Vec<String> = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+        .iter()
+        .map(|d| format!("{:<width$}", d, width = CELL_WIDTH))
+        .collect();
+    lines.push(format!("{}│ Week", header.concat()));
+
+    let mut week_start = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+    while week_start < next_month {
+        let mut numbers = String::new();
+        let mut shading = String::new();
+        let mut week = DayBlocks::default();
+
+        for offset in 0..7 {
+            let date = week_start + Duration::days(offset);
+            if date < first || date >= next_month {
+                numbers.push_str(&" ".repeat(CELL_WIDTH));
+                shading.push_str(&" ".repeat(CELL_WIDTH));
+                continue;
+            }
+
+            numbers.push_str(&format!("{:<width$}", date.day(), width = CELL_WIDTH));
+            match days.get(&date).filter(|d| d.blocks > 0) {
+                Some(day) => {
+                    week.blocks += day.blocks;
+                    week.cost += day.cost;
+                    let cell = format!("{} {}", shade(day.tokens, busiest).repeat(3), day.blocks);
+                    let padding = CELL_WIDTH.saturating_sub(display_width(&cell));
+                    shading.push_str(&paint(&cell, palette));
+                    shading.push_str(&" ".repeat(padding));
+                }
+                None => shading.push_str(&format!("{:<width$}", "·", width = CELL_WIDTH)),
+            }
+        }
+
+        lines.push(format!("{}│", numbers));
+        lines.push(if week.blocks > 0 {
+            format!(
+                "{}│ {} blk {}",
+                shading,
+                week.blocks,
+                format_currency(week.cost)
+            )
+        } else {
+            format!("{}│", shading)
+        });
+        week_start += Duration::days(7);
+    }
+
+    lines.push(String::new());
+    lines.push(format!(
+        "{} <25%  {} <50%  {} <75%  {} ≥75% of the busiest day",
+        SHADES[0], SHADES[1], SHADES[2], SHADES[3]
+    ));
+    lines.join("\n")
+}
+
+fn shade(tokens: u64, busiest: u64) -> &'static str {
+    if busiest == 0 {
+        return SHADES[0];
+    }
+    let ratio = tokens as f64 / busiest as f64;
+    SHADES[((ratio * 4.0) as usize).min(3)]
+}
+
+fn paint(text: &str, palette: &TablePalette) -> String {
+    match palette.tokens.as_ref().filter(|_| palette.enabled) {
+        Some(color) => format!("{}{}\x1b[0m", color.foreground_code(), text),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_month() {
+        assert_eq!(
+            parse_month("2025-06"),
+            Ok(NaiveDate::from_ymd_opt(2025, 6, 1).unwrap())
+        );
+        assert!(parse_month("June").is_err());
+    }
+
+    #[test]
+    fn test_render_calendar_grid() {
+        let first = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let mut days = BTreeMap::new();
+        days.insert(
+            NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+            DayBlocks {
+                blocks: 2,
+                tokens: 1_000,
+                cost: 3.0,
+            },
+        );
+        days.insert(
+            NaiveDate::from_ymd_opt(2025, 6, 3).unwrap(),
+            DayBlocks {
+                blocks: 1,
+                tokens: 100,
+                cost: 0.5,
+            },
+        );
+
+        let rendered = render_calendar(first, &days, &TablePalette::default());
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines[0], "June 2025");
+        // June 1st 2025 is a Sunday, so the first week row only holds one day
+        assert!(lines[3].trim_end_matches('│').trim_end().ends_with('1'));
+        assert!(lines[3].starts_with(&" ".repeat(CELL_WIDTH * 6)));
+        // Second week: busiest day fully shaded, lighter day lightly shaded
+        assert!(lines[6].starts_with("███ 2    ░░░ 1    ·"));
+        assert!(lines[6].ends_with("│ 3 blk $3.50"));
+        // June 2025 spans 6 Monday-first weeks
+        assert_eq!(lines.len(), 3 + 6 * 2 + 2);
+    }
+}
@@ -0,0 +1,179 @@
+use super::data::DailyUsage;
+use crate::config::{AnsiColor, Config, SegmentId};
+use plotters::prelude::*;
+
+/// Bar colors for exported charts, taken from the active theme
+#[derive(Debug, Clone, Copy)]
+pub struct ChartColors {
+    pub cost: RGBColor,
+    pub tokens: RGBColor,
+}
+
+impl Default for ChartColors {
+    fn default() -> Self {
+        Self {
+            cost: RGBColor(0, 170, 102),
+            tokens: RGBColor(38, 139, 210),
+        }
+    }
+}
+
+impl ChartColors {
+    /// Use the Cost and Usage segments' text colors, falling back to the defaults
+    pub fn from_config(config: &Config) -> Self {
+        let color = |id: SegmentId| {
+            config
+                .segments
+                .iter()
+                .find(|s| s.id == id)
+                .and_then(|s| s.colors.text.as_ref())
+                .map(rgb)
+        };
+
+        let defaults = Self::default();
+        Self {
+            cost: color(SegmentId::Cost).unwrap_or(defaults.cost),
+            tokens: color(SegmentId::Usage).unwrap_or(defaults.tokens),
+        }
+    }
+}
+
+fn rgb(color: &AnsiColor) -> RGBColor {
+    let (r, g, b) = color.to_rgb();
+    RGBColor(r, g, b)
+}
+
+/// Render daily cost and token bar charts as an SVG document
+pub fn daily_chart_svg(
+    days: &[DailyUsage],
+    colors: ChartColors,
+    size: (u32, u32),
+) -> Result<String, Box<dyn std::error::Error>> {
+    if days.is_empty() {
+        return Err("No usage data in the selected range".into());
+    }
+
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, size).into_drawing_area();
+        root.fill(&WHITE)?;
+        let (top, bottom) = root.split_vertically(size.1 / 2);
+
+        let costs: Vec<f64> = days.iter().map(|d| d.cost).collect();
+        draw_bars(&top, "Daily Cost", days, &costs, colors.cost, |v| {
+            format!("${:.0}", v)
+        })?;
+
+        let tokens: Vec<f64> = days.iter().map(|d| d.tokens.total() as f64).collect();
+        draw_bars(
+            &bottom,
+            "Daily Tokens",
+            days,
+            &tokens,
+            colors.tokens,
+            compact_tokens,
+        )?;
+
+        root.present()?;
+    }
+    Ok(svg)
+}
+
+fn draw_bars(
+    area: &DrawingArea<SVGBackend, plotters::coord::Shift>,
+    title: &str,
+    days: &[DailyUsage],
+    values: &[f64],
+    color: RGBColor,
+    format_value: impl Fn(f64) -> String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    let y_max = if max > 0.0 { max * 1.1 } else { 1.0 };
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(title, ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d((0..days.len()).into_segmented(), 0.0..y_max)?;
+
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_labels(days.len().min(15))
+        .x_label_formatter(&|v| match v {
+            SegmentValue::CenterOf(i) => days
+                .get(*i)
+                .map(|d| d.date.format("%m-%d").to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        })
+        .y_label_formatter(&|v| format_value(*v))
+        .axis_style(RGBColor(128, 128, 128))
+        .light_line_style(RGBColor(230, 230, 230))
+        .draw()?;
+
+    chart.draw_series(
+        Histogram::vertical(&chart)
+            .style(color.filled())
+            .margin(4)
+            .data(values.iter().enumerate().map(|(i, v)| (i, *v))),
+    )?;
+    Ok(())
+}
+
+fn compact_tokens(value: f64) -> String {
+    if value >= 1_000_000.0 {
+        format!("{:.1}M", value / 1_000_000.0)
+    } else if value >= 1_000.0 {
+        format!("{:.0}k", value / 1_000.0)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::data::TokenTotals;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_daily_chart_svg() {
+        let days = vec![
+            DailyUsage {
+                date: NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+                tokens: TokenTotals {
+                    input: 1_000,
+                    output: 500,
+                    ..Default::default()
+                },
+                cost: 1.25,
+                subagent_cost: 0.0,
+                models: vec![],
+            },
+            DailyUsage {
+                date: NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+                tokens: TokenTotals {
+                    input: 4_000,
+                    ..Default::default()
+                },
+                cost: 3.5,
+                subagent_cost: 0.0,
+                models: vec![],
+            },
+        ];
+
+        let colors = ChartColors {
+            cost: RGBColor(255, 0, 0),
+            tokens: RGBColor(0, 0, 255),
+        };
+        let svg = daily_chart_svg(&days, colors, (800, 600)).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Daily Cost"));
+        assert!(svg.contains("06-02"));
+        assert!(svg.contains("#FF0000"));
+
+        assert!(daily_chart_svg(&[], colors, (800, 600)).is_err());
+    }
+}
@@ -0,0 +1,518 @@
+use crate::billing::calculator::calculate_rolling_total;
+use crate::billing::{BillingBlock, ModelPricing, UsageEntry};
+use crate::core::branch_ledger::BranchLedger;
+use crate::utils::compaction::{compaction_stats_for_file, CompactionStats};
+use crate::utils::data_dirs::find_transcript;
+use crate::utils::timezone;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// Token counts split by kind, summed over many entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct TokenTotals {
+    pub input: u64,
+    pub output: u64,
+    pub cache_creation: u64,
+    pub cache_read: u64,
+}
+
+impl TokenTotals {
+    pub fn add_entry(&mut self, entry: &UsageEntry) {
+        self.input += entry.input_tokens;
+        self.output += entry.output_tokens;
+        self.cache_creation += entry.cache_creation_tokens;
+        self.cache_read += entry.cache_read_tokens;
+    }
+
+    pub fn add(&mut self, other: &TokenTotals) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_creation += other.cache_creation;
+        self.cache_read += other.cache_read;
+    }
+
+    pub fn total(&self) -> u64 {
+        self.input + self.output + self.cache_creation + self.cache_read
+    }
+}
+
+/// Usage for a single local calendar day
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyUsage {
+    pub date: NaiveDate,
+    pub tokens: TokenTotals,
+    pub cost: f64,
+    /// Part of `cost` spent by subagents
+    pub subagent_cost: f64,
+    pub models: Vec<String>,
+}
+
+/// Usage for a single Claude Code session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub project: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub tokens: TokenTotals,
+    pub cost: f64,
+    /// Part of `cost` spent by subagents
+    pub subagent_cost: f64,
+    pub models: Vec<String>,
+    /// Cost per model, for the model mix of `ccline sessions`
+    pub model_costs: BTreeMap<String, f64>,
+    /// Filled from the session transcript by `add_compaction_stats`
+    pub compaction: CompactionStats,
+}
+
+/// Usage attributed to one git branch of a repository
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchUsage {
+    pub branch: String,
+    pub sessions: usize,
+    pub last_seen: DateTime<Utc>,
+    pub tokens: TokenTotals,
+    pub cost: f64,
+}
+
+/// Usage of all sessions stored under one project directory
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectUsage {
+    pub project: String,
+    pub sessions: usize,
+    pub last_seen: DateTime<Utc>,
+    pub tokens: TokenTotals,
+    pub cost: f64,
+}
+
+/// Usage over a rolling window of days ending now
+#[derive(Debug, Clone, Serialize)]
+pub struct RollingUsage {
+    pub days: i64,
+    pub tokens: TokenTotals,
+    pub cost: f64,
+    pub daily_average: f64,
+}
+
+/// Serializable view of a billing block
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockSummary {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub is_active: bool,
+    pub session_count: usize,
+    pub total_tokens: u64,
+    pub cost: f64,
+    pub start_time_source: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_gap: bool,
+}
+
+impl From<&BillingBlock> for BlockSummary {
+    fn from(block: &BillingBlock) -> Self {
+        Self {
+            start_time: block.start_time,
+            end_time: block.end_time,
+            is_active: block.is_active,
+            session_count: block.session_count,
+            total_tokens: block.total_tokens,
+            cost: block.cost,
+            start_time_source: format!("{:?}", block.start_time_source).to_lowercase(),
+            is_gap: block.is_gap,
+        }
+    }
+}
+
+/// Usage for each rolling window in `windows` (days), shortest first
+pub fn aggregate_rolling(
+    entries: &[UsageEntry],
+    pricing_map: &HashMap<String, ModelPricing>,
+    windows: &[i64],
+    now: DateTime<Utc>,
+) -> Vec<RollingUsage> {
+    let mut windows = windows.to_vec();
+    windows.sort_unstable();
+    windows.dedup();
+    windows
+        .into_iter()
+        .filter(|days| *days > 0)
+        .map(|days| {
+            let start = now - chrono::Duration::days(days);
+            let mut tokens = TokenTotals::default();
+            for entry in entries
+                .iter()
+                .filter(|e| e.timestamp > start && e.timestamp <= now)
+            {
+                tokens.add_entry(entry);
+            }
+            let cost = calculate_rolling_total(entries, pricing_map, days, now);
+            RollingUsage {
+                days,
+                tokens,
+                cost,
+                daily_average: cost / days as f64,
+            }
+        })
+        .collect()
+}
+
+/// Blocks that ended within the last `days` days, oldest first
+pub fn recent_blocks(blocks: &[BillingBlock], days: i64, include_gaps: bool) -> Vec<BlockSummary> {
+    let cutoff = Utc::now() - chrono::Duration::days(days);
+    blocks
+        .iter()
+        .filter(|b| b.end_time >= cutoff && (include_gaps || !b.is_gap))
+        .map(BlockSummary::from)
+        .collect()
+}
+
+/// Keep only entries from the last `days` days
+pub fn filter_recent(entries: &[UsageEntry], days: i64) -> Vec<UsageEntry> {
+    let cutoff = Utc::now() - chrono::Duration::days(days);
+    entries
+        .iter()
+        .filter(|e| e.timestamp >= cutoff)
+        .cloned()
+        .collect()
+}
+
+/// Group entries by calendar day in the configured timezone, oldest first
+pub fn aggregate_daily(entries: &[UsageEntry]) -> Vec<DailyUsage> {
+    let mut days: BTreeMap<NaiveDate, DailyUsage> = BTreeMap::new();
+    let mut day_models: BTreeMap<NaiveDate, BTreeSet<String>> = BTreeMap::new();
+
+    for entry in entries {
+        let date = timezone::date_of(entry.timestamp);
+        let day = days.entry(date).or_insert_with(|| DailyUsage {
+            date,
+            tokens: TokenTotals::default(),
+            cost: 0.0,
+            subagent_cost: 0.0,
+            models: Vec::new(),
+        });
+        day.tokens.add_entry(entry);
+        day.cost += entry.cost.unwrap_or(0.0);
+        if entry.subagent {
+            day.subagent_cost += entry.cost.unwrap_or(0.0);
+        }
+        if !entry.model.is_empty() {
+            day_models
+                .entry(date)
+                .or_default()
+                .insert(entry.model.clone());
+        }
+    }
+
+    days.into_values()
+        .map(|mut day| {
+            if let Some(models) = day_models.remove(&day.date) {
+                day.models = models.into_iter().collect();
+            }
+            day
+        })
+        .collect()
+}
+
+/// Group entries by session, ordered by last activity
+pub fn aggregate_sessions(entries: &[UsageEntry]) -> Vec<SessionSummary> {
+    let mut sessions: HashMap<&str, SessionSummary> = HashMap::new();
+    let mut session_models: HashMap<&str, BTreeSet<String>> = HashMap::new();
+
+    for entry in entries {
+        let summary = sessions
+            .entry(entry.session_id.as_str())
+            .or_insert_with(|| SessionSummary {
+                session_id: entry.session_id.clone(),
+                project: entry.project.clone(),
+                first_seen: entry.timestamp,
+                last_seen: entry.timestamp,
+                tokens: TokenTotals::default(),
+                cost: 0.0,
+                subagent_cost: 0.0,
+                models: Vec::new(),
+                model_costs: BTreeMap::new(),
+                compaction: CompactionStats::default(),
+            });
+        summary.first_seen = summary.first_seen.min(entry.timestamp);
+        summary.last_seen = summary.last_seen.max(entry.timestamp);
+        summary.tokens.add_entry(entry);
+        summary.cost += entry.cost.unwrap_or(0.0);
+        if entry.subagent {
+            summary.subagent_cost += entry.cost.unwrap_or(0.0);
+        }
+
+        if !entry.model.is_empty() {
+            *summary.model_costs.entry(entry.model.clone()).or_default() +=
+                entry.cost.unwrap_or(0.0);
+            session_models
+                .entry(entry.session_id.as_str())
+                .or_default()
+                .insert(entry.model.clone());
+        }
+    }
+
+    let mut result: Vec<SessionSummary> = sessions
+        .into_iter()
+        .map(|(id, mut summary)| {
+            if let Some(models) = session_models.remove(id) {
+                summary.models = models.into_iter().collect();
+            }
+            summary
+        })
+        .collect();
+    result.sort_by_key(|s| s.last_seen);
+    result
+}
+
+/// The `top` most expensive sessions, most expensive first
+pub fn top_sessions(entries: &[UsageEntry], top: usize) -> Vec<SessionSummary> {
+    let mut sessions = aggregate_sessions(entries);
+    sessions.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+    sessions.truncate(top);
+    sessions
+}
+
+/// Estimate compaction savings of each session from its transcript
+pub fn add_compaction_stats(sessions: &mut [SessionSummary]) {
+    for session in sessions {
+        if let Some(path) = find_transcript(&session.session_id) {
+            session.compaction = compaction_stats_for_file(&path);
+        }
+    }
+}
+
+/// Group entries of sessions recorded in `repo` by the branch they were on at
+/// the time, most expensive branch first
+pub fn aggregate_branches(
+    entries: &[UsageEntry],
+    ledger: &BranchLedger,
+    repo: &Path,
+) -> Vec<BranchUsage> {
+    let mut branches: BTreeMap<&str, (BranchUsage, BTreeSet<&str>)> = BTreeMap::new();
+
+    for entry in entries {
+        let Some(record) = ledger
+            .branch_at(&entry.session_id, entry.timestamp)
+            .filter(|record| record.repo == repo)
+        else {
+            continue;
+        };
+
+        let (usage, sessions) = branches.entry(record.branch.as_str()).or_insert_with(|| {
+            (
+                BranchUsage {
+                    branch: record.branch.clone(),
+                    sessions: 0,
+                    last_seen: entry.timestamp,
+                    tokens: TokenTotals::default(),
+                    cost: 0.0,
+                },
+                BTreeSet::new(),
+            )
+        });
+        usage.last_seen = usage.last_seen.max(entry.timestamp);
+        usage.tokens.add_entry(entry);
+        usage.cost += entry.cost.unwrap_or(0.0);
+        sessions.insert(entry.session_id.as_str());
+    }
+
+    let mut result: Vec<BranchUsage> = branches
+        .into_values()
+        .map(|(mut usage, sessions)| {
+            usage.sessions = sessions.len();
+            usage
+        })
+        .collect();
+    result.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+    result
+}
+
+/// Group entries by the project directory their transcript was found in,
+/// most expensive project first
+pub fn aggregate_projects(entries: &[UsageEntry]) -> Vec<ProjectUsage> {
+    let mut projects: BTreeMap<&str, (ProjectUsage, BTreeSet<&str>)> = BTreeMap::new();
+
+    for entry in entries {
+        let (usage, sessions) = projects.entry(entry.project.as_str()).or_insert_with(|| {
+            (
+                ProjectUsage {
+                    project: entry.project.clone(),
+                    sessions: 0,
+                    last_seen: entry.timestamp,
+                    tokens: TokenTotals::default(),
+                    cost: 0.0,
+                },
+                BTreeSet::new(),
+            )
+        });
+        usage.last_seen = usage.last_seen.max(entry.timestamp);
+        usage.tokens.add_entry(entry);
+        usage.cost += entry.cost.unwrap_or(0.0);
+        sessions.insert(entry.session_id.as_str());
+    }
+
+    let mut result: Vec<ProjectUsage> = projects
+        .into_values()
+        .map(|(mut usage, sessions)| {
+            usage.sessions = sessions.len();
+            usage
+        })
+        .collect();
+    result.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn entry(session: &str, hour: u32, input: u64, cost: f64) -> UsageEntry {
+        UsageEntry {
+            timestamp: Local
+                .with_ymd_and_hms(2025, 6, 1, hour, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc),
+            input_tokens: input,
+            output_tokens: 10,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            model: "claude-sonnet-4".to_string(),
+            cost: Some(cost),
+            session_id: session.to_string(),
+            subagent: false,
+            project: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_daily() {
+        let entries = vec![entry("a", 9, 100, 0.5), entry("b", 15, 200, 1.0)];
+        let days = aggregate_daily(&entries);
+
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].tokens.input, 300);
+        assert_eq!(days[0].tokens.total(), 320);
+        assert!((days[0].cost - 1.5).abs() < 1e-9);
+        assert_eq!(days[0].models, vec!["claude-sonnet-4".to_string()]);
+    }
+
+    #[test]
+    fn test_subagent_cost_share() {
+        let mut subagent = entry("a", 11, 100, 0.25);
+        subagent.subagent = true;
+        let entries = vec![entry("a", 9, 100, 0.5), subagent];
+
+        let days = aggregate_daily(&entries);
+        assert!((days[0].cost - 0.75).abs() < 1e-9);
+        assert!((days[0].subagent_cost - 0.25).abs() < 1e-9);
+
+        let sessions = aggregate_sessions(&entries);
+        assert_eq!(sessions.len(), 1);
+        assert!((sessions[0].subagent_cost - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aggregate_branches() {
+        let repo = Path::new("/work/app");
+        let at = |hour| {
+            Local
+                .with_ymd_and_hms(2025, 6, 1, hour, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let mut ledger = BranchLedger::default();
+        ledger.record("a", repo, "main", at(8));
+        ledger.record("a", repo, "refactor", at(12));
+        ledger.record("b", repo, "refactor", at(8));
+        ledger.record("c", Path::new("/work/other"), "main", at(8));
+
+        let entries = vec![
+            entry("a", 9, 100, 0.5),
+            entry("a", 13, 100, 2.0),
+            entry("b", 14, 100, 1.0),
+            entry("c", 9, 100, 5.0),
+            entry("unrecorded", 9, 100, 9.0),
+        ];
+        let branches = aggregate_branches(&entries, &ledger, repo);
+
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].branch, "refactor");
+        assert_eq!(branches[0].sessions, 2);
+        assert!((branches[0].cost - 3.0).abs() < 1e-9);
+        assert_eq!(branches[1].branch, "main");
+        assert!((branches[1].cost - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aggregate_projects() {
+        let in_project = |project: &str, session, cost| UsageEntry {
+            project: project.to_string(),
+            ..entry(session, 9, 100, cost)
+        };
+        let entries = vec![
+            in_project("-work-app", "a", 0.5),
+            in_project("-work-app", "b", 1.0),
+            in_project("-work-site", "c", 2.0),
+        ];
+        let projects = aggregate_projects(&entries);
+
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].project, "-work-site");
+        assert_eq!(projects[1].project, "-work-app");
+        assert_eq!(projects[1].sessions, 2);
+        assert!((projects[1].cost - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aggregate_sessions_sorted_by_last_activity() {
+        let entries = vec![
+            entry("late", 20, 100, 0.1),
+            entry("early", 8, 100, 0.2),
+            entry("early", 10, 100, 0.3),
+        ];
+        let sessions = aggregate_sessions(&entries);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].session_id, "early");
+        assert_eq!(sessions[0].tokens.input, 200);
+        assert!((sessions[0].cost - 0.5).abs() < 1e-9);
+        assert_eq!(sessions[1].session_id, "late");
+
+        let top = top_sessions(&entries, 1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].session_id, "early");
+        assert!((top[0].model_costs["claude-sonnet-4"] - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_recent_blocks() {
+        use crate::billing::types::BlockStartSource;
+        let block = |hours_ago: i64, is_gap: bool| BillingBlock {
+            start_time: Utc::now() - chrono::Duration::hours(hours_ago),
+            end_time: Utc::now() - chrono::Duration::hours(hours_ago - 5),
+            cost: 1.0,
+            remaining_minutes: 0,
+            is_active: hours_ago < 5,
+            session_count: 1,
+            total_tokens: 100,
+            start_time_source: BlockStartSource::Manual,
+            is_gap,
+        };
+        let blocks = vec![
+            block(60, false),
+            block(40, true),
+            block(30, false),
+            block(2, false),
+        ];
+
+        let listed = recent_blocks(&blocks, 7, false);
+        assert_eq!(listed.len(), 3);
+        assert!(listed[2].is_active && !listed[1].is_active);
+        assert_eq!(listed[0].start_time_source, "manual");
+        assert_eq!(recent_blocks(&blocks, 7, true).len(), 4);
+        assert_eq!(recent_blocks(&blocks, 1, false).len(), 1);
+    }
+}
@@ -0,0 +1,705 @@
+pub mod calendar;
+pub mod chart;
+pub mod data;
+pub mod table;
+
+use crate::billing::block::identify_session_blocks_with_overrides;
+use crate::billing::calculator::{format_remaining_time, ROLLING_WINDOWS_DAYS};
+use crate::billing::entries::{apply_subagent_mode, load_priced_entries};
+use crate::billing::price_changes::PriceChangeLog;
+use crate::cli::{BlocksAction, BlocksArgs, ReportArgs, ReportKind, SessionsArgs};
+use crate::config::{BlockOverrideManager, Config};
+use crate::core::branch_ledger::{find_repo_root, BranchLedger};
+use crate::core::segments::cost::model_abbreviation;
+use crate::utils::timezone;
+use crate::utils::transcript::project_key;
+use chrono::Datelike;
+use data::{
+    add_compaction_stats, aggregate_branches, aggregate_daily, aggregate_projects,
+    aggregate_rolling, aggregate_sessions, filter_recent, recent_blocks, top_sessions,
+    BlockSummary, TokenTotals,
+};
+use table::{format_currency, format_tokens, ColumnKind, Table, TablePalette};
+
+/// Run a `ccline report` command
+pub fn run(args: &ReportArgs, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    // Price changes come from the pricing log, not from usage entries
+    if args.kind == Some(ReportKind::Prices) {
+        let log = PriceChangeLog::load();
+        let output = if args.json {
+            serde_json::to_string_pretty(&log.events)?
+        } else if log.events.is_empty() {
+            "No price changes detected since ccline started tracking them".to_string()
+        } else {
+            price_change_table(&log, TablePalette::from_config(config)).render()
+        };
+        println!("{}", output);
+        return Ok(());
+    }
+
+    let kind = args.kind.clone().unwrap_or(ReportKind::Daily);
+    // Billing blocks are Claude's; other tools only add to the spend reports
+    let include_other_tools = kind != ReportKind::Block;
+    let (mut entries, pricing_map) = load_priced_entries(true, None, include_other_tools);
+    apply_subagent_mode(&mut entries, config.global.subagents);
+
+    // Rolling windows pick their own ranges, so they skip the `--days` filter
+    if kind == ReportKind::Rolling && !args.interactive {
+        let mut windows = ROLLING_WINDOWS_DAYS.to_vec();
+        windows.push(args.days);
+        let rolling = aggregate_rolling(&entries, &pricing_map, &windows, chrono::Utc::now());
+        let output = if args.json {
+            serde_json::to_string_pretty(&rolling)?
+        } else {
+            rolling_table(&rolling, TablePalette::from_config(config)).render()
+        };
+        println!("{}", output);
+        return Ok(());
+    }
+
+    if args.interactive {
+        return run_interactive(entries, args.days, kind);
+    }
+
+    let entries = filter_recent(&entries, args.days);
+    let palette = TablePalette::from_config(config);
+
+    let output = match kind {
+        ReportKind::Daily => {
+            let days = aggregate_daily(&entries);
+            if args.json {
+                serde_json::to_string_pretty(&days)?
+            } else {
+                daily_table(&days, palette).render()
+            }
+        }
+        ReportKind::Session => {
+            let mut sessions = aggregate_sessions(&entries);
+            add_compaction_stats(&mut sessions);
+            if args.json {
+                serde_json::to_string_pretty(&sessions)?
+            } else {
+                let mut output = session_table(&sessions, palette).render();
+                if let Some(line) = compaction_summary(&sessions) {
+                    output.push_str(&format!("\n{}", line));
+                }
+                output
+            }
+        }
+        ReportKind::Block => {
+            let blocks: Vec<BlockSummary> = identify_session_blocks_with_overrides(&entries)
+                .iter()
+                .filter(|b| !b.is_gap)
+                .map(BlockSummary::from)
+                .collect();
+            if args.json {
+                serde_json::to_string_pretty(&blocks)?
+            } else {
+                block_table(&blocks, palette).render()
+            }
+        }
+        ReportKind::Branches => {
+            let cwd = std::env::current_dir()?;
+            let repo = find_repo_root(&cwd)
+                .ok_or_else(|| format!("{} is not inside a git repository", cwd.display()))?;
+            let branches = aggregate_branches(&entries, &BranchLedger::load(), &repo);
+            if args.json {
+                serde_json::to_string_pretty(&branches)?
+            } else if branches.is_empty() {
+                format!(
+                    "No branch history recorded for {} yet (the Git segment must be enabled)",
+                    repo.display()
+                )
+            } else {
+                branch_table(&branches, palette).render()
+            }
+        }
+        ReportKind::Projects => {
+            let projects = aggregate_projects(&entries);
+            if args.json {
+                serde_json::to_string_pretty(&projects)?
+            } else {
+                let cwd = std::env::current_dir()?;
+                let current = project_key(&cwd.to_string_lossy());
+                project_table(&projects, &current, palette).render()
+            }
+        }
+        ReportKind::Prices => unreachable!("handled before loading entries"),
+        ReportKind::Rolling => unreachable!("handled before filtering entries"),
+        ReportKind::Chart { out, width, height } => {
+            let days = aggregate_daily(&entries);
+            let svg = chart::daily_chart_svg(
+                &days,
+                chart::ChartColors::from_config(config),
+                (width, height),
+            )?;
+            std::fs::write(&out, svg)?;
+            format!("Chart written to {}", out.display())
+        }
+    };
+
+    println!("{}", output);
+    Ok(())
+}
+
+/// Run `ccline sessions`
+pub fn run_sessions(
+    args: &SessionsArgs,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut entries, _) = load_priced_entries(true, None, true);
+    apply_subagent_mode(&mut entries, config.global.subagents);
+    let sessions = top_sessions(&filter_recent(&entries, args.days), args.top);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&sessions)?);
+    } else if sessions.is_empty() {
+        println!("No sessions in the last {} days", args.days);
+    } else {
+        println!(
+            "{}",
+            ranking_table(&sessions, TablePalette::from_config(config)).render()
+        );
+    }
+    Ok(())
+}
+
+/// Run a `ccline blocks` command
+pub fn run_blocks(args: &BlocksArgs, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    match &args.action {
+        None => {
+            let (entries, _) = load_priced_entries(true, None, false);
+            let blocks = recent_blocks(
+                &identify_session_blocks_with_overrides(&entries),
+                args.days,
+                args.gaps,
+            );
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&blocks)?);
+            } else {
+                println!(
+                    "{}",
+                    block_table(&blocks, TablePalette::from_config(config)).render()
+                );
+            }
+        }
+        Some(BlocksAction::Calendar { month }) => {
+            let first = match month {
+                Some(month) => calendar::parse_month(month)?,
+                None => {
+                    let today = timezone::today();
+                    today - chrono::Duration::days(today.day0() as i64)
+                }
+            };
+
+            let (entries, _) = load_priced_entries(true, None, false);
+            let blocks = identify_session_blocks_with_overrides(&entries);
+            let days = calendar::aggregate_block_days(&blocks);
+            let palette = TablePalette::from_config(config);
+            println!("{}", calendar::render_calendar(first, &days, &palette));
+        }
+        Some(BlocksAction::Export { out }) => {
+            let mut manager = BlockOverrideManager::new()?;
+            manager.load()?;
+            let json = serde_json::to_string_pretty(&manager.export())?;
+            match out {
+                Some(path) => {
+                    std::fs::write(path, json + "\n")?;
+                    eprintln!(
+                        "Exported {} block override(s) to {}",
+                        manager.override_count(),
+                        path.display()
+                    );
+                }
+                None => println!("{}", json),
+            }
+        }
+        Some(BlocksAction::Import { file, strategy }) => {
+            let content = std::fs::read_to_string(file)
+                .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            let mut manager = BlockOverrideManager::new()?;
+            manager.load()?;
+            let summary = manager.import_json(&content, *strategy)?;
+            println!(
+                "Imported block overrides: {} added, {} updated, {} unchanged",
+                summary.added, summary.updated, summary.unchanged
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Run `ccline dashboard`
+pub fn run_dashboard(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut entries, _) = load_priced_entries(true, None, false);
+    apply_subagent_mode(&mut entries, config.global.subagents);
+    open_dashboard(entries)
+}
+
+#[cfg(feature = "tui")]
+fn open_dashboard(
+    entries: Vec<crate::billing::UsageEntry>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    crate::ui::dashboard::Dashboard::new(entries).run()
+}
+
+#[cfg(not(feature = "tui"))]
+fn open_dashboard(
+    _entries: Vec<crate::billing::UsageEntry>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Err("The dashboard requires the tui feature".into())
+}
+
+#[cfg(feature = "tui")]
+fn run_interactive(
+    entries: Vec<crate::billing::UsageEntry>,
+    days: i64,
+    kind: ReportKind,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::ui::report_explorer::{ExplorerTab, ReportExplorer};
+
+    let tab = match kind {
+        ReportKind::Daily | ReportKind::Chart { .. } | ReportKind::Prices | ReportKind::Rolling => {
+            ExplorerTab::Daily
+        }
+        ReportKind::Branches | ReportKind::Projects => ExplorerTab::Sessions,
+        ReportKind::Session => ExplorerTab::Sessions,
+        ReportKind::Block => ExplorerTab::Blocks,
+    };
+    ReportExplorer::new(entries, days, tab).run()
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_interactive(
+    _entries: Vec<crate::billing::UsageEntry>,
+    _days: i64,
+    _kind: ReportKind,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Err("Interactive reports require the tui feature".into())
+}
+
+pub fn daily_table(days: &[data::DailyUsage], palette: TablePalette) -> Table {
+    // The subagent column only appears once some subagent spend was recorded
+    let show_subagents = days.iter().any(|day| day.subagent_cost > 0.0);
+    let subagent_column = |row: &mut Vec<String>, value: String| {
+        if show_subagents {
+            row.insert(7, value);
+        }
+    };
+
+    let mut columns = vec![
+        ("Date", ColumnKind::Text),
+        ("Input", ColumnKind::Tokens),
+        ("Output", ColumnKind::Tokens),
+        ("Cache Write", ColumnKind::Tokens),
+        ("Cache Read", ColumnKind::Tokens),
+        ("Total Tokens", ColumnKind::Tokens),
+        ("Cost", ColumnKind::Currency),
+        ("Models", ColumnKind::Text),
+    ];
+    if show_subagents {
+        columns.insert(7, ("Subagents", ColumnKind::Currency));
+    }
+    let mut table = Table::new(&columns, palette);
+
+    let mut totals = TokenTotals::default();
+    let mut total_cost = 0.0;
+    let mut total_subagent_cost = 0.0;
+    for day in days {
+        totals.add(&day.tokens);
+        total_cost += day.cost;
+        total_subagent_cost += day.subagent_cost;
+        let mut row = vec![
+            day.date.format("%Y-%m-%d").to_string(),
+            format_tokens(day.tokens.input),
+            format_tokens(day.tokens.output),
+            format_tokens(day.tokens.cache_creation),
+            format_tokens(day.tokens.cache_read),
+            format_tokens(day.tokens.total()),
+            format_currency(day.cost),
+            day.models.join(", "),
+        ];
+        subagent_column(&mut row, format_currency(day.subagent_cost));
+        table.add_row(row);
+    }
+
+    let mut footer = vec![
+        "Total".to_string(),
+        format_tokens(totals.input),
+        format_tokens(totals.output),
+        format_tokens(totals.cache_creation),
+        format_tokens(totals.cache_read),
+        format_tokens(totals.total()),
+        format_currency(total_cost),
+        String::new(),
+    ];
+    subagent_column(&mut footer, format_currency(total_subagent_cost));
+    table.set_footer(footer);
+    table
+}
+
+pub fn rolling_table(windows: &[data::RollingUsage], palette: TablePalette) -> Table {
+    let mut table = Table::new(
+        &[
+            ("Window", ColumnKind::Text),
+            ("Input", ColumnKind::Tokens),
+            ("Output", ColumnKind::Tokens),
+            ("Cache Write", ColumnKind::Tokens),
+            ("Cache Read", ColumnKind::Tokens),
+            ("Total Tokens", ColumnKind::Tokens),
+            ("Cost", ColumnKind::Currency),
+            ("Per Day", ColumnKind::Currency),
+        ],
+        palette,
+    );
+    for window in windows {
+        table.add_row(vec![
+            format!("Last {} days", window.days),
+            format_tokens(window.tokens.input),
+            format_tokens(window.tokens.output),
+            format_tokens(window.tokens.cache_creation),
+            format_tokens(window.tokens.cache_read),
+            format_tokens(window.tokens.total()),
+            format_currency(window.cost),
+            format_currency(window.daily_average),
+        ]);
+    }
+    table
+}
+
+pub fn session_table(sessions: &[data::SessionSummary], palette: TablePalette) -> Table {
+    let show_subagents = sessions.iter().any(|session| session.subagent_cost > 0.0);
+    let subagent_column = |row: &mut Vec<String>, value: String| {
+        if show_subagents {
+            row.insert(4, value);
+        }
+    };
+
+    let mut columns = vec![
+        ("Session", ColumnKind::Text),
+        ("Last Activity", ColumnKind::Text),
+        ("Total Tokens", ColumnKind::Tokens),
+        ("Cost", ColumnKind::Currency),
+        ("Models", ColumnKind::Text),
+    ];
+    if show_subagents {
+        columns.insert(4, ("Subagents", ColumnKind::Currency));
+    }
+    let mut table = Table::new(&columns, palette);
+
+    let mut total_tokens = 0;
+    let mut total_cost = 0.0;
+    let mut total_subagent_cost = 0.0;
+    for session in sessions {
+        total_tokens += session.tokens.total();
+        total_cost += session.cost;
+        total_subagent_cost += session.subagent_cost;
+        let mut row = vec![
+            session.session_id.chars().take(8).collect(),
+            timezone::to_local(session.last_seen)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            format_tokens(session.tokens.total()),
+            format_currency(session.cost),
+            session.models.join(", "),
+        ];
+        subagent_column(&mut row, format_currency(session.subagent_cost));
+        table.add_row(row);
+    }
+
+    let mut footer = vec![
+        format!("{} sessions", sessions.len()),
+        String::new(),
+        format_tokens(total_tokens),
+        format_currency(total_cost),
+        String::new(),
+    ];
+    subagent_column(&mut footer, format_currency(total_subagent_cost));
+    table.set_footer(footer);
+    table
+}
+
+/// Sessions ranked by cost with their project, model mix and duration
+pub fn ranking_table(sessions: &[data::SessionSummary], palette: TablePalette) -> Table {
+    let mut table = Table::new(
+        &[
+            ("#", ColumnKind::Tokens),
+            ("Session", ColumnKind::Text),
+            ("Project", ColumnKind::Text),
+            ("Models", ColumnKind::Text),
+            ("Total Tokens", ColumnKind::Tokens),
+            ("Cost", ColumnKind::Currency),
+            ("Duration", ColumnKind::Text),
+        ],
+        palette,
+    );
+
+    let mut total_tokens = 0;
+    let mut total_cost = 0.0;
+    for (rank, session) in sessions.iter().enumerate() {
+        total_tokens += session.tokens.total();
+        total_cost += session.cost;
+        table.add_row(vec![
+            (rank + 1).to_string(),
+            session.session_id.chars().take(8).collect(),
+            session.project.clone(),
+            model_mix(session),
+            format_tokens(session.tokens.total()),
+            format_currency(session.cost),
+            format_session_duration((session.last_seen - session.first_seen).num_minutes()),
+        ]);
+    }
+
+    table.set_footer(vec![
+        String::new(),
+        format!("{} sessions", sessions.len()),
+        String::new(),
+        String::new(),
+        format_tokens(total_tokens),
+        format_currency(total_cost),
+        String::new(),
+    ]);
+    table
+}
+
+/// Models by share of the session's cost, e.g. `S4 80% · O4.1 20%`
+fn model_mix(session: &data::SessionSummary) -> String {
+    let mut models: Vec<(&String, &f64)> = session.model_costs.iter().collect();
+    models.sort_by(|a, b| b.1.total_cmp(a.1));
+    if models.len() == 1 || session.cost <= 0.0 {
+        return models
+            .iter()
+            .map(|(model, _)| model_abbreviation(model))
+            .collect::<Vec<_>>()
+            .join(" · ");
+    }
+    models
+        .iter()
+        .map(|(model, cost)| {
+            format!(
+                "{} {:.0}%",
+                model_abbreviation(model),
+                *cost / session.cost * 100.0
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+fn format_session_duration(minutes: i64) -> String {
+    if minutes < 1 {
+        "<1m".to_string()
+    } else {
+        format_remaining_time(minutes)
+    }
+}
+
+/// One-line total of what compaction freed across the listed sessions
+fn compaction_summary(sessions: &[data::SessionSummary]) -> Option<String> {
+    let (events, saved) = sessions.iter().fold((0, 0), |(events, saved), session| {
+        (
+            events + session.compaction.events,
+            saved + session.compaction.saved_tokens,
+        )
+    });
+    (events > 0).then(|| {
+        format!(
+            "Compaction freed ~{} tokens of context across {} compaction{}",
+            format_tokens(saved),
+            events,
+            if events == 1 { "" } else { "s" }
+        )
+    })
+}
+
+pub fn branch_table(branches: &[data::BranchUsage], palette: TablePalette) -> Table {
+    let mut table = Table::new(
+        &[
+            ("Branch", ColumnKind::Text),
+            ("Sessions", ColumnKind::Tokens),
+            ("Last Activity", ColumnKind::Text),
+            ("Total Tokens", ColumnKind::Tokens),
+            ("Cost", ColumnKind::Currency),
+        ],
+        palette,
+    );
+
+    let mut total_tokens = 0;
+    let mut total_cost = 0.0;
+    for branch in branches {
+        total_tokens += branch.tokens.total();
+        total_cost += branch.cost;
+        table.add_row(vec![
+            branch.branch.clone(),
+            branch.sessions.to_string(),
+            timezone::to_local(branch.last_seen)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            format_tokens(branch.tokens.total()),
+            format_currency(branch.cost),
+        ]);
+    }
+
+    table.set_footer(vec![
+        format!("{} branches", branches.len()),
+        String::new(),
+        String::new(),
+        format_tokens(total_tokens),
+        format_currency(total_cost),
+    ]);
+    table
+}
+
+/// Projects by cost; `current` (the working directory's project key) is
+/// marked with `*`
+pub fn project_table(
+    projects: &[data::ProjectUsage],
+    current: &str,
+    palette: TablePalette,
+) -> Table {
+    let mut table = Table::new(
+        &[
+            ("Project", ColumnKind::Text),
+            ("Sessions", ColumnKind::Tokens),
+            ("Last Activity", ColumnKind::Text),
+            ("Total Tokens", ColumnKind::Tokens),
+            ("Cost", ColumnKind::Currency),
+            ("Share", ColumnKind::Text),
+        ],
+        palette,
+    );
+
+    let total_tokens: u64 = projects.iter().map(|p| p.tokens.total()).sum();
+    let total_cost: f64 = projects.iter().map(|p| p.cost).sum();
+    for project in projects {
+        let name = if project.project == current {
+            format!("* {}", project.project)
+        } else {
+            project.project.clone()
+        };
+        let share = if total_cost > 0.0 {
+            format!("{:.1}%", project.cost / total_cost * 100.0)
+        } else {
+            "-".to_string()
+        };
+        table.add_row(vec![
+            name,
+            project.sessions.to_string(),
+            timezone::to_local(project.last_seen)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            format_tokens(project.tokens.total()),
+            format_currency(project.cost),
+            share,
+        ]);
+    }
+
+    table.set_footer(vec![
+        format!("{} projects", projects.len()),
+        String::new(),
+        String::new(),
+        format_tokens(total_tokens),
+        format_currency(total_cost),
+        String::new(),
+    ]);
+    table
+}
+
+/// Per-million prices can have more than two meaningful decimals, e.g. $0.075
+fn format_price(per_m: f64) -> String {
+    format!("${}", (per_m * 10_000.0).round() / 10_000.0)
+}
+
+pub fn price_change_table(log: &PriceChangeLog, palette: TablePalette) -> Table {
+    let mut table = Table::new(
+        &[
+            ("Detected", ColumnKind::Text),
+            ("Model", ColumnKind::Text),
+            ("Price", ColumnKind::Text),
+            ("Old per 1M", ColumnKind::Currency),
+            ("New per 1M", ColumnKind::Currency),
+            ("Change", ColumnKind::Tokens),
+        ],
+        palette,
+    );
+
+    // Newest first
+    for event in log.events.iter().rev() {
+        let detected = timezone::to_local(event.detected_at)
+            .format("%Y-%m-%d %H:%M")
+            .to_string();
+        for change in &event.changes {
+            table.add_row(vec![
+                detected.clone(),
+                change.model.clone(),
+                change.price.clone(),
+                format_price(change.old_per_m),
+                format_price(change.new_per_m),
+                change
+                    .percent()
+                    .map_or("new".to_string(), |p| format!("{:+.1}%", p)),
+            ]);
+        }
+    }
+    table
+}
+
+pub fn block_table(blocks: &[BlockSummary], palette: TablePalette) -> Table {
+    let mut table = Table::new(
+        &[
+            ("Start", ColumnKind::Text),
+            ("End", ColumnKind::Text),
+            ("Status", ColumnKind::Text),
+            ("Start Source", ColumnKind::Text),
+            ("Sessions", ColumnKind::Tokens),
+            ("Total Tokens", ColumnKind::Tokens),
+            ("Cost", ColumnKind::Currency),
+        ],
+        palette,
+    );
+
+    let mut total_tokens = 0;
+    let mut total_cost = 0.0;
+    let mut active_blocks = 0;
+    for block in blocks {
+        if !block.is_gap {
+            active_blocks += 1;
+        }
+        total_tokens += block.total_tokens;
+        total_cost += block.cost;
+        table.add_row(vec![
+            timezone::to_local(block.start_time)
+                .format("%Y-%m-%d %H:%M")
+                .to_string(),
+            timezone::to_local(block.end_time)
+                .format("%H:%M")
+                .to_string(),
+            if block.is_gap {
+                "gap"
+            } else if block.is_active {
+                "active"
+            } else {
+                "done"
+            }
+            .to_string(),
+            if block.is_gap {
+                String::new()
+            } else {
+                block.start_time_source.clone()
+            },
+            block.session_count.to_string(),
+            format_tokens(block.total_tokens),
+            format_currency(block.cost),
+        ]);
+    }
+
+    table.set_footer(vec![
+        format!("{} blocks", active_blocks),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        format_tokens(total_tokens),
+        format_currency(total_cost),
+    ]);
+    table
+}
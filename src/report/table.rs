@@ -0,0 +1,249 @@
+use crate::config::{AnsiColor, Config, SegmentId};
+use crate::utils::color::downgrade;
+use crate::utils::terminal::{color_support, no_color};
+use crate::utils::width::display_width;
+
+/// Column alignment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// Kind of values held by a column, used to pick alignment and color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Text,
+    Tokens,
+    Currency,
+}
+
+impl ColumnKind {
+    fn align(&self) -> Align {
+        match self {
+            ColumnKind::Text => Align::Left,
+            ColumnKind::Tokens | ColumnKind::Currency => Align::Right,
+        }
+    }
+}
+
+/// Colors used when rendering report tables, derived from the active theme
+#[derive(Debug, Clone, Default)]
+pub struct TablePalette {
+    /// Whether any escape sequences are emitted at all
+    pub enabled: bool,
+    pub header: Option<AnsiColor>,
+    pub tokens: Option<AnsiColor>,
+    pub currency: Option<AnsiColor>,
+    pub border: Option<AnsiColor>,
+}
+
+impl TablePalette {
+    /// Build a palette from the theme's segment colors, reduced to what the
+    /// terminal can show, or a plain palette when `NO_COLOR` is set
+    pub fn from_config(config: &Config) -> Self {
+        if no_color() {
+            return Self::default();
+        }
+
+        let text_color = |id: SegmentId| {
+            config
+                .segments
+                .iter()
+                .find(|s| s.id == id)
+                .and_then(|s| s.colors.text.as_ref())
+                .map(|color| downgrade(color, color_support()))
+        };
+
+        Self {
+            enabled: true,
+            header: text_color(SegmentId::Model),
+            tokens: text_color(SegmentId::Usage),
+            currency: text_color(SegmentId::Cost),
+            border: Some(AnsiColor::Color16 { c16: 8 }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowRole {
+    Header,
+    Body,
+    Footer,
+}
+
+/// Simple aligned table renderer for report output
+pub struct Table {
+    headers: Vec<String>,
+    kinds: Vec<ColumnKind>,
+    rows: Vec<Vec<String>>,
+    footer: Option<Vec<String>>,
+    palette: TablePalette,
+}
+
+impl Table {
+    pub fn new(columns: &[(&str, ColumnKind)], palette: TablePalette) -> Self {
+        Self {
+            headers: columns.iter().map(|(h, _)| h.to_string()).collect(),
+            kinds: columns.iter().map(|(_, k)| *k).collect(),
+            rows: Vec::new(),
+            footer: None,
+            palette,
+        }
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Set a totals row rendered below a separator
+    pub fn set_footer(&mut self, row: Vec<String>) {
+        self.footer = Some(row);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        let widths = self.column_widths();
+        let mut lines = Vec::new();
+
+        lines.push(self.render_line(&self.headers, &widths, RowRole::Header));
+        lines.push(self.render_separator(&widths));
+        for row in &self.rows {
+            lines.push(self.render_line(row, &widths, RowRole::Body));
+        }
+        if let Some(footer) = &self.footer {
+            lines.push(self.render_separator(&widths));
+            lines.push(self.render_line(footer, &widths, RowRole::Footer));
+        }
+
+        lines.join("\n")
+    }
+
+    fn column_widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| display_width(h)).collect();
+        for row in self.rows.iter().chain(self.footer.iter()) {
+            for (i, cell) in row.iter().enumerate() {
+                if let Some(width) = widths.get_mut(i) {
+                    *width = (*width).max(display_width(cell));
+                }
+            }
+        }
+        widths
+    }
+
+    fn render_line(&self, cells: &[String], widths: &[usize], role: RowRole) -> String {
+        let mut parts = Vec::with_capacity(widths.len());
+        for (i, width) in widths.iter().enumerate() {
+            let cell = cells.get(i).map(String::as_str).unwrap_or("");
+            let kind = self.kinds.get(i).copied().unwrap_or(ColumnKind::Text);
+            // Left-aligned last column needs no padding, avoiding trailing spaces
+            let padded = if i + 1 == widths.len() && kind.align() == Align::Left {
+                cell.to_string()
+            } else {
+                pad(cell, *width, kind.align())
+            };
+
+            if !self.palette.enabled {
+                parts.push(padded);
+                continue;
+            }
+
+            let color = match (role, kind) {
+                (RowRole::Header, _) => self.palette.header.as_ref(),
+                (_, ColumnKind::Text) => None,
+                (_, ColumnKind::Tokens) => self.palette.tokens.as_ref(),
+                (_, ColumnKind::Currency) => self.palette.currency.as_ref(),
+            };
+            parts.push(paint(&padded, color, role != RowRole::Body));
+        }
+        parts.join("  ")
+    }
+
+    fn render_separator(&self, widths: &[usize]) -> String {
+        let line = widths
+            .iter()
+            .map(|w| "─".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("  ");
+        if self.palette.enabled {
+            paint(&line, self.palette.border.as_ref(), false)
+        } else {
+            line
+        }
+    }
+}
+
+/// Pad to `width` columns; `format!` padding counts characters, which
+/// misaligns wide CJK and emoji text
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let padding = " ".repeat(width.saturating_sub(display_width(text)));
+    match align {
+        Align::Left => format!("{}{}", text, padding),
+        Align::Right => format!("{}{}", padding, text),
+    }
+}
+
+fn paint(text: &str, color: Option<&AnsiColor>, bold: bool) -> String {
+    let mut prefix = String::new();
+    if bold {
+        prefix.push_str("\x1b[1m");
+    }
+    if let Some(color) = color {
+        prefix.push_str(&color.foreground_code());
+    }
+    if prefix.is_empty() {
+        text.to_string()
+    } else {
+        format!("{}{}\x1b[0m", prefix, text)
+    }
+}
+
+/// Format a token count with thousands separators, e.g. `1,234,567`
+pub fn format_tokens(tokens: u64) -> String {
+    let digits = tokens.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Format a dollar amount with two decimals, e.g. `$12.34`
+pub fn format_currency(amount: f64) -> String {
+    format!("${:.2}", amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_tokens() {
+        assert_eq!(format_tokens(0), "0");
+        assert_eq!(format_tokens(999), "999");
+        assert_eq!(format_tokens(1_000), "1,000");
+        assert_eq!(format_tokens(1_234_567), "1,234,567");
+    }
+
+    #[test]
+    fn test_plain_table_alignment() {
+        let mut table = Table::new(
+            &[("Date", ColumnKind::Text), ("Cost", ColumnKind::Currency)],
+            TablePalette::default(),
+        );
+        table.add_row(vec!["2025-06-01".to_string(), format_currency(1.5)]);
+        table.add_row(vec!["2025-06-02".to_string(), format_currency(120.0)]);
+
+        let rendered = table.render();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], format!("{:<10}  {:>7}", "Date", "Cost"));
+        assert_eq!(lines[2], "2025-06-01    $1.50");
+        assert_eq!(lines[3], "2025-06-02  $120.00");
+    }
+}
@@ -0,0 +1,234 @@
+//! Golden-path checks that run the real binary the way Claude Code does: fixture
+//! JSON on stdin, a throwaway home directory holding transcripts and config.
+//!
+//! Used by `ccline self-test` and by the integration tests in `tests/`.
+
+use crate::config::{ColorConfig, Config, IconConfig, SegmentConfig, SegmentId, TextStyleConfig};
+use chrono::{Duration, SecondsFormat, Utc};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+const FIXTURE_MODEL_ID: &str = "claude-sonnet-4-20250514";
+const FIXTURE_MODEL_NAME: &str = "Sonnet 4";
+const FIXTURE_PROJECT: &str = "fixture-project";
+
+/// A fake home directory with one project transcript and a config enabling
+/// the segments that need no network or git
+pub struct Fixture {
+    pub home: PathBuf,
+    pub project_dir: PathBuf,
+    pub transcript: PathBuf,
+}
+
+impl Fixture {
+    pub fn create(root: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let home = root.join("home");
+        let project_dir = root.join(FIXTURE_PROJECT);
+        let projects = home
+            .join(".claude")
+            .join("projects")
+            .join("-fixture-project");
+        std::fs::create_dir_all(&projects)?;
+        std::fs::create_dir_all(&project_dir)?;
+
+        let transcript = projects.join("fixture-session.jsonl");
+        let now = Utc::now();
+        let lines: Vec<String> = [(20, 1_200, 300), (10, 2_400, 600)]
+            .iter()
+            .enumerate()
+            .map(|(i, (minutes_ago, input, output))| {
+                serde_json::json!({
+                    "type": "assistant",
+                    "sessionId": "fixture-session",
+                    "requestId": format!("req-{}", i),
+                    "timestamp": (now - Duration::minutes(*minutes_ago))
+                        .to_rfc3339_opts(SecondsFormat::Millis, true),
+                    "message": {
+                        "id": format!("msg-{}", i),
+                        "model": FIXTURE_MODEL_ID,
+                        "usage": {
+                            "input_tokens": input,
+                            "output_tokens": output,
+                            "cache_creation_input_tokens": 5_000,
+                            "cache_read_input_tokens": 20_000
+                        }
+                    }
+                })
+                .to_string()
+            })
+            .collect();
+        std::fs::write(&transcript, lines.join("\n") + "\n")?;
+
+        let mut config = Config::default();
+        for segment in config.segments.iter_mut() {
+            segment.enabled = matches!(
+                segment.id,
+                SegmentId::Model | SegmentId::Directory | SegmentId::Usage | SegmentId::Block
+            );
+        }
+        // The Block segment reads transcripts without pricing, so it shows whether
+        // the loaders found the fixture data
+        if !config.segments.iter().any(|s| s.id == SegmentId::Block) {
+            config.segments.push(SegmentConfig {
+                id: SegmentId::Block,
+                name: None,
+                enabled: true,
+                icon: IconConfig {
+                    plain: "⏳".to_string(),
+                    nerd_font: "\u{f252}".to_string(),
+                },
+                colors: ColorConfig {
+                    icon: None,
+                    text: None,
+                    background: None,
+                },
+                styles: TextStyleConfig::default(),
+                options: HashMap::new(),
+            });
+        }
+        let config_dir = home.join(".claude").join("ccline");
+        std::fs::create_dir_all(&config_dir)?;
+        std::fs::write(config_dir.join("config.toml"), toml::to_string(&config)?)?;
+
+        Ok(Self {
+            home,
+            project_dir,
+            transcript,
+        })
+    }
+
+    /// Statusline input as Claude Code would send it
+    pub fn stdin_json(&self) -> String {
+        serde_json::json!({
+            "session_id": "fixture-session",
+            "transcript_path": self.transcript.to_string_lossy(),
+            "model": { "id": FIXTURE_MODEL_ID, "display_name": FIXTURE_MODEL_NAME },
+            "workspace": { "current_dir": self.project_dir.to_string_lossy() }
+        })
+        .to_string()
+    }
+
+    /// Run `exe` with this fixture as its home directory
+    pub fn run(&self, exe: &Path, args: &[&str], stdin: &str) -> std::io::Result<Output> {
+        let mut child = Command::new(exe)
+            .args(args)
+            .env("HOME", &self.home)
+            .env("USERPROFILE", &self.home)
+            .env_remove("CLAUDE_CONFIG_DIR")
+            .env_remove("CCLINE_DEBUG")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut input) = child.stdin.take() {
+            input.write_all(stdin.as_bytes())?;
+        }
+        child.wait_with_output()
+    }
+}
+
+type Check = fn(&Fixture, &Path) -> Result<(), String>;
+
+/// Outcome of one golden-path check
+pub struct CheckResult {
+    pub name: &'static str,
+    pub result: Result<(), String>,
+}
+
+/// Run every golden-path check against the binary at `exe`, using `root` as a
+/// scratch directory
+pub fn run_checks(exe: &Path, root: &Path) -> Vec<CheckResult> {
+    let fixture = match Fixture::create(root) {
+        Ok(fixture) => fixture,
+        Err(e) => {
+            return vec![CheckResult {
+                name: "create fixture",
+                result: Err(e.to_string()),
+            }]
+        }
+    };
+
+    let checks: [(&'static str, Check); 4] = [
+        ("statusline renders from stdin", check_statusline),
+        ("transcripts found under home", check_transcripts_found),
+        ("config validates", check_config),
+        ("malformed input fails cleanly", check_malformed_input),
+    ];
+    checks
+        .iter()
+        .map(|(name, check)| CheckResult {
+            name,
+            result: check(&fixture, exe),
+        })
+        .collect()
+}
+
+fn run_statusline(fixture: &Fixture, exe: &Path) -> Result<String, String> {
+    let output = fixture
+        .run(exe, &[], &fixture.stdin_json())
+        .map_err(|e| format!("failed to run {}: {}", exe.display(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "exit status {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|_| "output is not UTF-8".to_string())
+}
+
+fn check_statusline(fixture: &Fixture, exe: &Path) -> Result<(), String> {
+    let stdout = run_statusline(fixture, exe)?;
+    let line = stdout.trim_end_matches(['\r', '\n']);
+    if line.lines().count() != 1 {
+        return Err(format!("expected a single line, got {:?}", stdout));
+    }
+    for expected in [FIXTURE_MODEL_NAME, FIXTURE_PROJECT, "tokens"] {
+        if !line.contains(expected) {
+            return Err(format!("missing '{}' in {:?}", expected, line));
+        }
+    }
+    Ok(())
+}
+
+fn check_transcripts_found(fixture: &Fixture, exe: &Path) -> Result<(), String> {
+    // The Block segment reports "idle" when no usage data was found
+    let stdout = run_statusline(fixture, exe)?;
+    if stdout.contains("idle") {
+        return Err(format!(
+            "no active block found from {}: {:?}",
+            fixture.transcript.display(),
+            stdout.trim()
+        ));
+    }
+    Ok(())
+}
+
+fn check_config(fixture: &Fixture, exe: &Path) -> Result<(), String> {
+    let output = fixture
+        .run(exe, &["--check"], "")
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "--check failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn check_malformed_input(fixture: &Fixture, exe: &Path) -> Result<(), String> {
+    let output = fixture
+        .run(exe, &[], "{not json")
+        .map_err(|e| e.to_string())?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() {
+        return Err("expected a non-zero exit status".to_string());
+    }
+    if stderr.contains("panicked") {
+        return Err(format!("panicked: {}", stderr.trim()));
+    }
+    Ok(())
+}
@@ -36,6 +36,7 @@ pub fn create_powerline_theme(
     // Model segment
     segments.push(SegmentConfig {
         id: SegmentId::Model,
+        name: None,
         enabled: true,
         icon: IconConfig {
             plain: "🔮".to_string(),
@@ -55,6 +56,7 @@ pub fn create_powerline_theme(
     // Directory segment
     segments.push(SegmentConfig {
         id: SegmentId::Directory,
+        name: None,
         enabled: true,
         icon: IconConfig {
             plain: "📁".to_string(),
@@ -74,6 +76,7 @@ pub fn create_powerline_theme(
     // Git segment
     segments.push(SegmentConfig {
         id: SegmentId::Git,
+        name: None,
         enabled: true,
         icon: IconConfig {
             plain: "🔗".to_string(),
@@ -94,6 +97,7 @@ pub fn create_powerline_theme(
     if let Some(usage_colors) = usage_colors {
         segments.push(SegmentConfig {
             id: SegmentId::Usage,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "💰".to_string(),
@@ -115,6 +119,7 @@ pub fn create_powerline_theme(
     if let Some(update_colors) = update_colors {
         segments.push(SegmentConfig {
             id: SegmentId::Update,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "⬆️".to_string(),
@@ -1,4 +1,4 @@
-use crate::config::{Config, SegmentId, StyleMode};
+use crate::config::{Config, StyleMode};
 use crate::ui::components::{
     color_picker::{ColorPickerComponent, NavDirection},
     help::HelpComponent,
@@ -9,6 +9,8 @@ use crate::ui::components::{
     segment_list::{FieldSelection, Panel, SegmentListComponent},
     separator_editor::SeparatorEditorComponent,
     settings::SettingsComponent,
+    style_editor::StyleEditorComponent,
+    theme_manager::{ThemeManagerAction, ThemeManagerComponent},
     theme_selector::ThemeSelectorComponent,
 };
 use crossterm::{
@@ -39,9 +41,12 @@ pub struct App {
     segment_list: SegmentListComponent,
     separator_editor: SeparatorEditorComponent,
     settings: SettingsComponent,
+    style_editor: StyleEditorComponent,
+    theme_manager: ThemeManagerComponent,
     theme_selector: ThemeSelectorComponent,
     help: HelpComponent,
     status_message: Option<String>,
+    reset_options: bool,
 }
 
 impl App {
@@ -60,15 +65,18 @@ impl App {
             segment_list: SegmentListComponent::new(),
             separator_editor: SeparatorEditorComponent::new(),
             settings: SettingsComponent::new(),
+            style_editor: StyleEditorComponent::new(),
+            theme_manager: ThemeManagerComponent::new(),
             theme_selector: ThemeSelectorComponent::new(),
             help: HelpComponent::new(),
             status_message: None,
+            reset_options: false,
         };
         app.preview.update_preview(&config);
         app
     }
 
-    pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    pub fn run(reset_options: bool) -> Result<(), Box<dyn std::error::Error>> {
         // Ensure themes directory and built-in themes exist
         if let Err(e) = crate::config::loader::ConfigLoader::init_themes() {
             eprintln!("Warning: Failed to initialize themes: {}", e);
@@ -94,6 +102,7 @@ impl App {
         let mut terminal = Terminal::new(backend)?;
 
         let mut app = App::new(config);
+        app.reset_options = reset_options;
 
         // Main loop
         let result = loop {
@@ -106,7 +115,54 @@ impl App {
                 }
 
                 // Handle popup events first
-                if app.name_input.is_open {
+                if app.theme_manager.is_naming() {
+                    match key.code {
+                        KeyCode::Esc => app.theme_manager.cancel(),
+                        KeyCode::Enter => {
+                            if let Some(action) = app.theme_manager.confirm_name() {
+                                app.apply_theme_action(action);
+                            }
+                        }
+                        KeyCode::Char(c) => app.theme_manager.input_char(c),
+                        KeyCode::Backspace => app.theme_manager.backspace(),
+                        _ => {}
+                    }
+                } else if app.theme_manager.is_open {
+                    let action = match key.code {
+                        KeyCode::Esc => {
+                            app.theme_manager.close();
+                            None
+                        }
+                        KeyCode::Up => {
+                            app.theme_manager.move_selection(-1);
+                            None
+                        }
+                        KeyCode::Down => {
+                            app.theme_manager.move_selection(1);
+                            None
+                        }
+                        KeyCode::Enter => app.theme_manager.switch_selected(),
+                        KeyCode::Char('n') | KeyCode::Char('N') => {
+                            app.theme_manager.start_save_as();
+                            None
+                        }
+                        KeyCode::Char('r') | KeyCode::Char('R') => {
+                            app.theme_manager.start_rename();
+                            None
+                        }
+                        KeyCode::Char('d') | KeyCode::Char('D') => {
+                            app.theme_manager.start_duplicate();
+                            None
+                        }
+                        KeyCode::Char('x') | KeyCode::Char('X') | KeyCode::Delete => {
+                            app.theme_manager.delete_selected()
+                        }
+                        _ => None,
+                    };
+                    if let Some(action) = action {
+                        app.apply_theme_action(action);
+                    }
+                } else if app.name_input.is_open {
                     match key.code {
                         KeyCode::Esc => app.name_input.close(),
                         KeyCode::Enter => {
@@ -164,6 +220,8 @@ impl App {
                         KeyCode::Up => app.icon_selector.move_selection(-1),
                         KeyCode::Down => app.icon_selector.move_selection(1),
                         KeyCode::Tab => app.icon_selector.toggle_style(),
+                        KeyCode::Left => app.icon_selector.switch_category(-1),
+                        KeyCode::Right => app.icon_selector.switch_category(1),
                         KeyCode::Char('c') => app.icon_selector.start_custom_input(),
                         KeyCode::Enter => {
                             if app.icon_selector.editing_custom {
@@ -183,6 +241,40 @@ impl App {
                         }
                         _ => {}
                     }
+                } else if app.style_editor.is_open {
+                    match key.code {
+                        KeyCode::Esc => app.style_editor.close(),
+                        KeyCode::Up => app.style_editor.move_selection(-1),
+                        KeyCode::Down => app.style_editor.move_selection(1),
+                        KeyCode::Enter | KeyCode::Char(' ') => {
+                            if let Some(segment) = app.config.segments.get_mut(app.selected_segment)
+                            {
+                                let message = app.style_editor.toggle_current(&mut segment.styles);
+                                app.status_message = Some(message);
+                                app.preview.update_preview(&app.config);
+                            }
+                        }
+                        _ => {}
+                    }
+                } else if app.options_editor.is_editing() {
+                    // Inline editing of a string, number or list option
+                    match key.code {
+                        KeyCode::Esc => app.options_editor.cancel_edit(),
+                        KeyCode::Enter => {
+                            if let Some((key, value)) = app.options_editor.commit_edit() {
+                                if let Some(segment) =
+                                    app.config.segments.get_mut(app.selected_segment)
+                                {
+                                    segment.options.insert(key.clone(), value);
+                                    app.status_message = Some(format!("{} updated", key));
+                                    app.preview.update_preview(&app.config);
+                                }
+                            }
+                        }
+                        KeyCode::Char(c) => app.options_editor.input_char(c),
+                        KeyCode::Backspace => app.options_editor.backspace(),
+                        _ => {}
+                    }
                 } else if app.options_editor.is_open {
                     // Handle options editor events
                     match key.code {
@@ -192,6 +284,18 @@ impl App {
                         }
                         KeyCode::Up => app.options_editor.move_selection(-1),
                         KeyCode::Down => app.options_editor.move_selection(1),
+                        KeyCode::Left | KeyCode::Right => {
+                            let step = if key.code == KeyCode::Left { -1 } else { 1 };
+                            if let Some((key, value)) = app.options_editor.cycle_current(step) {
+                                if let Some(segment) =
+                                    app.config.segments.get_mut(app.selected_segment)
+                                {
+                                    segment.options.insert(key.clone(), value);
+                                    app.status_message = Some(format!("{} changed", key));
+                                    app.preview.update_preview(&app.config);
+                                }
+                            }
+                        }
                         KeyCode::Enter | KeyCode::Char(' ') => {
                             if let Some((key, value)) = app.options_editor.toggle_current() {
                                 // Update the config with the new value
@@ -251,7 +355,15 @@ impl App {
                         KeyCode::Char('4') => app.switch_to_theme("nord"),
                         KeyCode::Char('p') => app.cycle_theme(),
                         KeyCode::Char('r') => app.reset_to_theme_defaults(),
+                        KeyCode::Char('o') | KeyCode::Char('O') => app.toggle_reset_options(),
                         KeyCode::Char('e') | KeyCode::Char('E') => app.open_separator_editor(),
+                        KeyCode::Char('m') | KeyCode::Char('M') => {
+                            app.theme_manager.open(&app.config.theme);
+                        }
+                        KeyCode::Char('l') | KeyCode::Char('L') => {
+                            app.status_message = Some(app.preview.toggle_live());
+                            app.preview.update_preview(&app.config);
+                        }
                         _ => {}
                     }
                 }
@@ -319,6 +431,7 @@ impl App {
         } else if self.icon_selector.is_open {
             vec![
                 "[↑↓] Navigate",
+                "[←→] Category",
                 "[Tab] Style",
                 "[C] Custom",
                 "[Enter] Select",
@@ -333,6 +446,8 @@ impl App {
                 "[P] Switch Theme",
                 "[R] Reset",
                 "[E] Edit Separator",
+                "[M] Manage Themes",
+                "[L] Live Preview",
                 "[S] Save Config",
                 "[W] Write Theme",
                 "[Ctrl+S] Save Theme",
@@ -459,7 +574,10 @@ impl App {
             f,
             layout[4],
             self.status_message.as_deref(),
-            self.color_picker.is_open || self.icon_selector.is_open || self.options_editor.is_open,
+            self.color_picker.is_open
+                || self.icon_selector.is_open
+                || self.options_editor.is_open
+                || self.style_editor.is_open,
             false, // Keep the original signature for now
         );
 
@@ -479,6 +597,14 @@ impl App {
         if self.options_editor.is_open {
             self.options_editor.render(f, f.area());
         }
+        if self.style_editor.is_open {
+            if let Some(segment) = self.config.segments.get(self.selected_segment) {
+                self.style_editor.render(f, f.area(), &segment.styles);
+            }
+        }
+        if self.theme_manager.is_open {
+            self.theme_manager.render(f, f.area(), &self.config.theme);
+        }
     }
 
     fn move_selection(&mut self, delta: i32) {
@@ -522,15 +648,7 @@ impl App {
                 // Toggle segment enabled/disabled in segment list
                 if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
                     segment.enabled = !segment.enabled;
-                    let segment_name = match segment.id {
-                        SegmentId::Model => "Model",
-                        SegmentId::Directory => "Directory",
-                        SegmentId::Git => "Git",
-                        SegmentId::Usage => "Usage",
-                        SegmentId::Update => "Update",
-                        SegmentId::Cost => "Cost",
-                        SegmentId::BurnRate => "BurnRate",
-                    };
+                    let segment_name = segment.display_name();
                     let is_enabled = segment.enabled;
                     self.status_message = Some(format!(
                         "{} segment {}",
@@ -547,15 +665,7 @@ impl App {
                         // Toggle enabled state in settings panel too
                         if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
                             segment.enabled = !segment.enabled;
-                            let segment_name = match segment.id {
-                                SegmentId::Model => "Model",
-                                SegmentId::Directory => "Directory",
-                                SegmentId::Git => "Git",
-                                SegmentId::Usage => "Usage",
-                                SegmentId::Update => "Update",
-                                SegmentId::Cost => "Cost",
-                                SegmentId::BurnRate => "BurnRate",
-                            };
+                            let segment_name = segment.display_name();
                             let is_enabled = segment.enabled;
                             self.status_message = Some(format!(
                                 "{} segment {}",
@@ -570,19 +680,10 @@ impl App {
                     | FieldSelection::TextColor
                     | FieldSelection::BackgroundColor => self.open_color_picker(),
                     FieldSelection::TextStyle => {
-                        // Toggle text bold style
-                        if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
-                            segment.styles.text_bold = !segment.styles.text_bold;
-                            self.status_message = Some(format!(
-                                "Text bold {}",
-                                if segment.styles.text_bold {
-                                    "enabled"
-                                } else {
-                                    "disabled"
-                                }
-                            ));
-                            self.preview.update_preview(&self.config);
-                        }
+                        self.style_editor.open();
+                        self.status_message = Some(
+                            "Editing text styles - use Space to toggle, Esc to exit".to_string(),
+                        );
                     }
                     FieldSelection::Options => {
                         // Open options editor popup
@@ -636,7 +737,7 @@ impl App {
     fn apply_selected_icon(&mut self, icon: String) {
         if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
             match self.config.style.mode {
-                StyleMode::Plain => segment.icon.plain = icon,
+                StyleMode::Plain | StyleMode::Ascii => segment.icon.plain = icon,
                 StyleMode::NerdFont | StyleMode::Powerline => segment.icon.nerd_font = icon,
             }
             self.preview.update_preview(&self.config);
@@ -655,12 +756,26 @@ impl App {
     }
 
     fn switch_to_theme(&mut self, theme_name: &str) {
-        self.config = crate::ui::themes::ThemePresets::get_theme(theme_name);
+        let mut config = crate::ui::themes::ThemePresets::get_theme(theme_name);
+        if !self.reset_options {
+            config.preserve_options_from(&self.config);
+        }
+        self.config = config;
         self.selected_segment = 0;
         self.preview.update_preview(&self.config);
         self.status_message = Some(format!("Switched to {} theme", theme_name));
     }
 
+    /// Toggle whether theme switches keep or reset user segment options
+    fn toggle_reset_options(&mut self) {
+        self.reset_options = !self.reset_options;
+        self.status_message = Some(if self.reset_options {
+            "Theme switch will reset segment options".to_string()
+        } else {
+            "Theme switch will keep segment options".to_string()
+        });
+    }
+
     /// Reset current theme to its default configuration
     fn reset_to_theme_defaults(&mut self) {
         let current_theme = self.config.theme.clone();
@@ -727,6 +842,47 @@ impl App {
         }
     }
 
+    /// Carry out a theme manager request and refresh its list
+    fn apply_theme_action(&mut self, action: ThemeManagerAction) {
+        use crate::ui::themes::ThemePresets;
+
+        let (result, select) = match action {
+            ThemeManagerAction::Switch(name) => {
+                self.switch_to_theme(&name);
+                (Ok(()), name)
+            }
+            ThemeManagerAction::SaveAs(name) => {
+                self.save_as_new_theme(&name);
+                (Ok(()), name)
+            }
+            ThemeManagerAction::Rename { from, to } => {
+                let result = ThemePresets::rename_theme(&from, &to).map(|_| {
+                    if self.config.theme == from {
+                        self.config.theme = to.clone();
+                    }
+                    self.status_message = Some(format!("Renamed theme {} to {}", from, to));
+                });
+                (result, to)
+            }
+            ThemeManagerAction::Duplicate { from, to } => {
+                let result = ThemePresets::duplicate_theme(&from, &to).map(|_| {
+                    self.status_message = Some(format!("Duplicated theme {} as {}", from, to));
+                });
+                (result, to)
+            }
+            ThemeManagerAction::Delete(name) => {
+                let result = ThemePresets::delete_theme(&name).map(|_| {
+                    self.status_message = Some(format!("Deleted theme {}", name));
+                });
+                (result, self.config.theme.clone())
+            }
+        };
+        if let Err(e) = result {
+            self.status_message = Some(format!("Theme action failed: {}", e));
+        }
+        self.theme_manager.refresh(Some(&select));
+    }
+
     /// Open separator editor with current separator
     fn open_separator_editor(&mut self) {
         self.status_message = Some("Opening separator editor...".to_string());
@@ -30,6 +30,7 @@ impl HelpComponent {
         } else if icon_selector_open {
             vec![
                 "[↑↓] Navigate",
+                "[←→] Category",
                 "[Tab] Style",
                 "[C] Custom",
                 "[Enter] Select",
@@ -43,7 +44,10 @@ impl HelpComponent {
                 "[1-4] Theme",
                 "[P] Switch Theme",
                 "[R] Reset",
+                "[O] Keep/Reset Options",
                 "[E] Edit Separator",
+                "[M] Manage Themes",
+                "[L] Live Preview",
                 "[S] Save Config",
                 "[W] Write Theme",
                 "[Ctrl+S] Save Theme",
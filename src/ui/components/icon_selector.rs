@@ -21,6 +21,8 @@ pub struct IconSelectorComponent {
     pub icon_style: IconStyle,
     pub selected_plain: usize,
     pub selected_nerd: usize,
+    /// Index into `NERD_FONT_CATEGORIES`
+    pub nerd_category: usize,
     pub custom_input: String,
     pub editing_custom: bool,
     pub current_icon: Option<String>,
@@ -39,13 +41,14 @@ impl Default for IconSelectorComponent {
 impl IconSelectorComponent {
     pub fn new() -> Self {
         let plain_icons = get_plain_icons();
-        let nerd_icons = get_nerd_font_icons();
+        let nerd_icons = NERD_FONT_CATEGORIES[0].icons;
 
         Self {
             is_open: false,
             icon_style: IconStyle::Plain,
             selected_plain: 0,
             selected_nerd: 0,
+            nerd_category: 0,
             custom_input: String::new(),
             editing_custom: false,
             current_icon: None,
@@ -59,7 +62,7 @@ impl IconSelectorComponent {
     pub fn open(&mut self, current_style: StyleMode) {
         self.is_open = true;
         self.icon_style = match current_style {
-            StyleMode::Plain => IconStyle::Plain,
+            StyleMode::Plain | StyleMode::Ascii => IconStyle::Plain,
             StyleMode::NerdFont | StyleMode::Powerline => IconStyle::NerdFont,
         };
         self.update_current_icon();
@@ -78,6 +81,23 @@ impl IconSelectorComponent {
         self.update_current_icon();
     }
 
+    /// Move to the previous or next Nerd Font category
+    pub fn switch_category(&mut self, delta: i32) {
+        if self.editing_custom || self.icon_style != IconStyle::NerdFont {
+            return;
+        }
+        let count = NERD_FONT_CATEGORIES.len() as i32;
+        self.nerd_category = (self.nerd_category as i32 + delta).rem_euclid(count) as usize;
+        self.selected_nerd = 0;
+        self.nerd_list_state = ListState::default().with_selected(Some(0));
+        self.nerd_scrollbar_state = ScrollbarState::new(self.nerd_icons().len());
+        self.update_current_icon();
+    }
+
+    fn nerd_icons(&self) -> &'static [IconInfo] {
+        NERD_FONT_CATEGORIES[self.nerd_category].icons
+    }
+
     /// Adjust scrolling offset for plain icons list
     fn adjust_plain_offset(&mut self, view_height: usize) {
         let selected = self.selected_plain;
@@ -122,7 +142,11 @@ impl IconSelectorComponent {
     pub fn finish_custom_input(&mut self) {
         self.editing_custom = false;
         if !self.custom_input.is_empty() {
-            self.current_icon = Some(self.custom_input.clone());
+            let bare_hex = self.icon_style == IconStyle::NerdFont;
+            let icon = parse_codepoint(&self.custom_input, bare_hex)
+                .map(String::from)
+                .unwrap_or_else(|| self.custom_input.clone());
+            self.current_icon = Some(icon);
         }
     }
 
@@ -155,7 +179,7 @@ impl IconSelectorComponent {
                 // Note: adjust_plain_offset will be called in render with actual view height
             }
             IconStyle::NerdFont => {
-                let nerd_icons = get_nerd_font_icons();
+                let nerd_icons = self.nerd_icons();
                 let new_selection = (self.selected_nerd as i32 + delta)
                     .max(0)
                     .min((nerd_icons.len() - 1) as i32)
@@ -177,8 +201,7 @@ impl IconSelectorComponent {
                 }
             }
             IconStyle::NerdFont => {
-                let nerd_icons = get_nerd_font_icons();
-                if let Some(icon) = nerd_icons.get(self.selected_nerd) {
+                if let Some(icon) = self.nerd_icons().get(self.selected_nerd) {
                     self.current_icon = Some(icon.icon.to_string());
                 }
             }
@@ -233,10 +256,12 @@ impl IconSelectorComponent {
         }
 
         // Custom input
-        let custom_text = if self.editing_custom {
-            format!("> {} <", self.custom_input)
-        } else {
-            "[Enter text to input custom icon]".to_string()
+        let bare_hex = self.icon_style == IconStyle::NerdFont;
+        let custom_text = match parse_codepoint(&self.custom_input, bare_hex) {
+            Some(glyph) if self.editing_custom => format!("> {} < → {}", self.custom_input, glyph),
+            _ if self.editing_custom => format!("> {} <", self.custom_input),
+            _ if bare_hex => "[Enter text or a codepoint such as f02a2 or U+F02A2]".to_string(),
+            _ => "[Enter text to input custom icon]".to_string(),
         };
 
         f.render_widget(
@@ -254,7 +279,12 @@ impl IconSelectorComponent {
         let actions = if self.editing_custom {
             "[Enter] Confirm  [Esc] Cancel"
         } else {
-            "[Enter] Select  [Tab] Switch Style  [c] Custom  [Esc] Cancel"
+            match self.icon_style {
+                IconStyle::Plain => "[Enter] Select  [Tab] Switch Style  [c] Custom  [Esc] Cancel",
+                IconStyle::NerdFont => {
+                    "[Enter] Select  [←→] Category  [Tab] Switch Style  [c] Custom  [Esc] Cancel"
+                }
+            }
         };
 
         f.render_widget(
@@ -298,18 +328,26 @@ impl IconSelectorComponent {
     }
 
     fn render_nerd_icons(&mut self, f: &mut Frame, area: Rect) {
-        let icons = get_nerd_font_icons();
-        let items: Vec<ListItem> = icons
+        let category = &NERD_FONT_CATEGORIES[self.nerd_category];
+        let items: Vec<ListItem> = category
+            .icons
             .iter()
             .map(|icon_info| {
-                let line = format!("{} {}", icon_info.icon, icon_info.name);
+                let codepoint = icon_info.icon.chars().next().map_or(0, u32::from);
+                let line = format!(
+                    "{}  U+{:04X}  {}",
+                    icon_info.icon, codepoint, icon_info.name
+                );
                 ListItem::new(line)
             })
             .collect();
 
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .title("Nerd Font Icons");
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            "Nerd Font Icons ‹ {} › {}/{}",
+            category.name,
+            self.nerd_category + 1,
+            NERD_FONT_CATEGORIES.len()
+        ));
 
         let inner = block.inner(area);
         let view_height = inner.height.saturating_sub(0) as usize; // No title inside list
@@ -445,101 +483,247 @@ fn get_plain_icons() -> Vec<IconInfo> {
     ]
 }
 
-fn get_nerd_font_icons() -> Vec<IconInfo> {
-    vec![
-        IconInfo {
-            icon: "\u{e26d}",
-            name: "Robot (Model)",
-        },
-        IconInfo {
-            icon: "\u{f02a2}",
-            name: "Git Branch",
-        },
-        IconInfo {
-            icon: "\u{f024b}",
-            name: "Folder",
-        },
-        IconInfo {
-            icon: "\u{f111}",
-            name: "Circle",
-        },
-        IconInfo {
-            icon: "\u{f135}",
-            name: "Rocket",
-        },
-        IconInfo {
-            icon: "\u{f49b}",
-            name: "Chart",
-        },
-        IconInfo {
-            icon: "\u{f0c6}",
-            name: "Database",
-        },
-        IconInfo {
-            icon: "\u{f0c9}",
-            name: "List",
-        },
-        IconInfo {
-            icon: "\u{f013}",
-            name: "Cog",
-        },
-        IconInfo {
-            icon: "\u{f015}",
-            name: "Home",
-        },
-        IconInfo {
-            icon: "\u{f07b}",
-            name: "Folder Open",
-        },
-        IconInfo {
-            icon: "\u{f0e7}",
-            name: "Lightning",
-        },
-        IconInfo {
-            icon: "\u{f121}",
-            name: "Code",
-        },
-        IconInfo {
-            icon: "\u{f126}",
-            name: "Code Fork",
-        },
-        IconInfo {
-            icon: "\u{f1c0}",
-            name: "Database",
-        },
-        IconInfo {
-            icon: "\u{f251}",
-            name: "Headphones",
-        },
-        IconInfo {
-            icon: "\u{f252}",
-            name: "Terminal",
-        },
-        IconInfo {
-            icon: "\u{f269}",
-            name: "Map",
-        },
-        IconInfo {
-            icon: "\u{f2d0}",
-            name: "Chrome",
-        },
-        IconInfo {
-            icon: "\u{f31b}",
-            name: "Github",
-        },
-        IconInfo {
-            icon: "\u{efc8}",
-            name: "Money Bill Wave (Cost)",
-        },
-        IconInfo {
-            icon: "\u{f0d6}",
-            name: "Money Bill (Cost)",
-        },
-        IconInfo {
-            icon: "\u{f06d}",
-            name: "Fire (Burn Rate)",
-        },
-    ]
+/// Nerd Font glyphs grouped for browsing, switched with ←/→
+struct IconCategory {
+    name: &'static str,
+    icons: &'static [IconInfo],
+}
+
+const NERD_FONT_CATEGORIES: &[IconCategory] = &[
+    IconCategory {
+        name: "Models & AI",
+        icons: &[
+            IconInfo {
+                icon: "\u{e26d}",
+                name: "Robot",
+            },
+            IconInfo {
+                icon: "\u{f06a9}",
+                name: "Robot (Material)",
+            },
+            IconInfo {
+                icon: "\u{f0e7}",
+                name: "Lightning",
+            },
+            IconInfo {
+                icon: "\u{f135}",
+                name: "Rocket",
+            },
+            IconInfo {
+                icon: "\u{f005}",
+                name: "Star",
+            },
+            IconInfo {
+                icon: "\u{f0eb}",
+                name: "Lightbulb",
+            },
+        ],
+    },
+    IconCategory {
+        name: "Files & Folders",
+        icons: &[
+            IconInfo {
+                icon: "\u{f024b}",
+                name: "Folder",
+            },
+            IconInfo {
+                icon: "\u{f07b}",
+                name: "Folder (Font Awesome)",
+            },
+            IconInfo {
+                icon: "\u{f07c}",
+                name: "Folder Open",
+            },
+            IconInfo {
+                icon: "\u{e5ff}",
+                name: "Folder (Seti)",
+            },
+            IconInfo {
+                icon: "\u{f15b}",
+                name: "File",
+            },
+            IconInfo {
+                icon: "\u{f121}",
+                name: "Code",
+            },
+            IconInfo {
+                icon: "\u{f015}",
+                name: "Home",
+            },
+        ],
+    },
+    IconCategory {
+        name: "Git",
+        icons: &[
+            IconInfo {
+                icon: "\u{f02a2}",
+                name: "Git Branch",
+            },
+            IconInfo {
+                icon: "\u{e725}",
+                name: "Git Branch (Devicons)",
+            },
+            IconInfo {
+                icon: "\u{e702}",
+                name: "Git",
+            },
+            IconInfo {
+                icon: "\u{e727}",
+                name: "Git Merge",
+            },
+            IconInfo {
+                icon: "\u{e729}",
+                name: "Git Commit",
+            },
+            IconInfo {
+                icon: "\u{f126}",
+                name: "Code Fork",
+            },
+            IconInfo {
+                icon: "\u{f09b}",
+                name: "GitHub",
+            },
+        ],
+    },
+    IconCategory {
+        name: "Status",
+        icons: &[
+            IconInfo {
+                icon: "\u{f00c}",
+                name: "Check",
+            },
+            IconInfo {
+                icon: "\u{f00d}",
+                name: "Cross",
+            },
+            IconInfo {
+                icon: "\u{f071}",
+                name: "Warning",
+            },
+            IconInfo {
+                icon: "\u{f05a}",
+                name: "Info",
+            },
+            IconInfo {
+                icon: "\u{f111}",
+                name: "Circle",
+            },
+            IconInfo {
+                icon: "\u{f06d}",
+                name: "Fire (Burn Rate)",
+            },
+        ],
+    },
+    IconCategory {
+        name: "Usage & Cost",
+        icons: &[
+            IconInfo {
+                icon: "\u{efc8}",
+                name: "Money Bill Wave (Cost)",
+            },
+            IconInfo {
+                icon: "\u{f0d6}",
+                name: "Money Bill (Cost)",
+            },
+            IconInfo {
+                icon: "\u{f155}",
+                name: "Dollar",
+            },
+            IconInfo {
+                icon: "\u{f080}",
+                name: "Bar Chart",
+            },
+            IconInfo {
+                icon: "\u{f0e4}",
+                name: "Gauge",
+            },
+            IconInfo {
+                icon: "\u{f1c0}",
+                name: "Database",
+            },
+            IconInfo {
+                icon: "\u{f240}",
+                name: "Battery Full",
+            },
+            IconInfo {
+                icon: "\u{f243}",
+                name: "Battery Quarter",
+            },
+        ],
+    },
+    IconCategory {
+        name: "Time",
+        icons: &[
+            IconInfo {
+                icon: "\u{f017}",
+                name: "Clock",
+            },
+            IconInfo {
+                icon: "\u{f251}",
+                name: "Hourglass Start",
+            },
+            IconInfo {
+                icon: "\u{f252}",
+                name: "Hourglass Half",
+            },
+            IconInfo {
+                icon: "\u{f253}",
+                name: "Hourglass End",
+            },
+            IconInfo {
+                icon: "\u{f073}",
+                name: "Calendar",
+            },
+            IconInfo {
+                icon: "\u{f1da}",
+                name: "History",
+            },
+        ],
+    },
+    IconCategory {
+        name: "Tools",
+        icons: &[
+            IconInfo {
+                icon: "\u{f013}",
+                name: "Cog",
+            },
+            IconInfo {
+                icon: "\u{f120}",
+                name: "Terminal",
+            },
+            IconInfo {
+                icon: "\u{f0ad}",
+                name: "Wrench",
+            },
+            IconInfo {
+                icon: "\u{f0c9}",
+                name: "List",
+            },
+        ],
+    },
+];
+
+/// Read a typed codepoint such as `U+F02A2`, `\u{f02a2}` or `0xf02a2` (and a
+/// bare `f02a2` when `bare_hex` is set) as the character it names
+fn parse_codepoint(input: &str, bare_hex: bool) -> Option<char> {
+    let input = input.trim();
+    let lower = input.to_ascii_lowercase();
+    let hex = if let Some(rest) = lower.strip_prefix("\\u{") {
+        rest.strip_suffix('}')?
+    } else if let Some(rest) = lower
+        .strip_prefix("u+")
+        .or_else(|| lower.strip_prefix("0x"))
+    {
+        rest
+    } else if bare_hex {
+        lower.as_str()
+    } else {
+        return None;
+    };
+    if !(4..=6).contains(&hex.len()) {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -561,3 +745,32 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_codepoint() {
+        assert_eq!(parse_codepoint("U+F02A2", false), Some('\u{f02a2}'));
+        assert_eq!(parse_codepoint("\\u{e0b0}", false), Some('\u{e0b0}'));
+        assert_eq!(parse_codepoint("0xf0e7", false), Some('\u{f0e7}'));
+        assert_eq!(parse_codepoint(" f02a2 ", true), Some('\u{f02a2}'));
+        // Bare hex could be a word outside Nerd Font mode
+        assert_eq!(parse_codepoint("face", false), None);
+        assert_eq!(parse_codepoint("xyz", true), None);
+        assert_eq!(parse_codepoint("d800", true), None);
+    }
+
+    #[test]
+    fn test_switch_category_wraps() {
+        let mut selector = IconSelectorComponent::new();
+        selector.icon_style = IconStyle::NerdFont;
+        selector.switch_category(-1);
+        assert_eq!(selector.nerd_category, NERD_FONT_CATEGORIES.len() - 1);
+        assert_eq!(
+            selector.get_selected_icon().as_deref(),
+            Some(NERD_FONT_CATEGORIES.last().unwrap().icons[0].icon)
+        );
+    }
+}
@@ -8,4 +8,6 @@ pub mod preview;
 pub mod segment_list;
 pub mod separator_editor;
 pub mod settings;
+pub mod style_editor;
+pub mod theme_manager;
 pub mod theme_selector;
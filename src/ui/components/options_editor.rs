@@ -7,11 +7,71 @@ use ratatui::{
 };
 use std::collections::HashMap;
 
+/// String options that cycle through a fixed set of values; the first value is
+/// the segment's default and is listed even when the option is not set
+const CHOICE_OPTIONS: &[(SegmentId, &str, &[&str])] = &[
+    (
+        SegmentId::Usage,
+        "display",
+        &["percentage", "remaining", "until_compact"],
+    ),
+    (
+        SegmentId::Cost,
+        "cost_source",
+        &["auto", "native", "calculated", "both"],
+    ),
+    (SegmentId::Cost, "scope", &["all", "project"]),
+    (SegmentId::BurnRate, "display", &["cost", "tokens", "both"]),
+    (SegmentId::Directory, "roots", &["count", "cycle", "none"]),
+    (SegmentId::ProjectCost, "period", &["month", "today", "all"]),
+    (
+        SegmentId::Weekly,
+        "week_start",
+        &[
+            "monday",
+            "tuesday",
+            "wednesday",
+            "thursday",
+            "friday",
+            "saturday",
+            "sunday",
+        ],
+    ),
+];
+
+/// Options listed with their default (as JSON) even when not set, so they can
+/// be edited without knowing their names
+const DEFAULTED_OPTIONS: &[(SegmentId, &str, &str)] = &[
+    (SegmentId::BurnRate, "high_threshold", "5000"),
+    (SegmentId::BurnRate, "medium_threshold", "2000"),
+    (SegmentId::BurnRate, "high_indicator", "\"\\uef76\""),
+    (SegmentId::BurnRate, "medium_indicator", "\"\\uf0e7\""),
+    (SegmentId::BurnRate, "normal_indicator", "\"\\uf0e4\""),
+];
+
+/// `on_error` applies to every segment
+const ERROR_POLICY_CHOICES: &[&str] = &["placeholder", "hide", "badge"];
+
+fn choices_for(segment_id: Option<SegmentId>, key: &str) -> Option<&'static [&'static str]> {
+    if key == "on_error" {
+        return Some(ERROR_POLICY_CHOICES);
+    }
+    CHOICE_OPTIONS
+        .iter()
+        .find(|(id, option, _)| Some(*id) == segment_id && *option == key)
+        .map(|(_, _, choices)| *choices)
+}
+
 pub struct OptionsEditorComponent {
     pub is_open: bool,
     selected_option: usize,
     current_segment_id: Option<SegmentId>,
+    current_segment_name: Option<String>,
     current_options: Vec<(String, serde_json::Value)>,
+    /// Text being typed for the selected string, number or list option
+    edit_buffer: Option<String>,
+    /// Why the last edit was rejected
+    edit_error: Option<String>,
 }
 
 impl Default for OptionsEditorComponent {
@@ -26,7 +86,10 @@ impl OptionsEditorComponent {
             is_open: false,
             selected_option: 0,
             current_segment_id: None,
+            current_segment_name: None,
             current_options: Vec::new(),
+            edit_buffer: None,
+            edit_error: None,
         }
     }
 
@@ -34,6 +97,7 @@ impl OptionsEditorComponent {
         self.is_open = true;
         self.selected_option = 0;
         self.current_segment_id = Some(segment.id);
+        self.current_segment_name = Some(segment.display_name());
 
         // Convert HashMap to sorted Vec for consistent ordering
         self.current_options = segment
@@ -41,12 +105,35 @@ impl OptionsEditorComponent {
             .iter()
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect();
+        for (id, key, choices) in CHOICE_OPTIONS {
+            if *id == segment.id && !segment.options.contains_key(*key) {
+                self.current_options
+                    .push((key.to_string(), serde_json::json!(choices[0])));
+            }
+        }
+        for (id, key, default) in DEFAULTED_OPTIONS {
+            if *id == segment.id && !segment.options.contains_key(*key) {
+                if let Ok(value) = serde_json::from_str(default) {
+                    self.current_options.push((key.to_string(), value));
+                }
+            }
+        }
+        if !segment.options.contains_key("on_error") {
+            self.current_options.push((
+                "on_error".to_string(),
+                serde_json::json!(ERROR_POLICY_CHOICES[0]),
+            ));
+        }
         self.current_options.sort_by_key(|(k, _)| k.clone());
+        self.edit_buffer = None;
+        self.edit_error = None;
     }
 
     pub fn close(&mut self) {
         self.is_open = false;
         self.current_options.clear();
+        self.edit_buffer = None;
+        self.edit_error = None;
     }
 
     pub fn move_selection(&mut self, delta: i32) {
@@ -60,6 +147,8 @@ impl OptionsEditorComponent {
         self.selected_option = new_selection;
     }
 
+    /// Toggle a boolean or step to the next choice; any other value starts
+    /// inline editing instead and `None` is returned
     pub fn toggle_current(&mut self) -> Option<(String, serde_json::Value)> {
         if let Some((key, value)) = self.current_options.get_mut(self.selected_option) {
             // Toggle boolean values
@@ -67,10 +156,72 @@ impl OptionsEditorComponent {
                 *value = serde_json::json!(!bool_val);
                 return Some((key.clone(), value.clone()));
             }
+            // Cycle through known choices
+            if choices_for(self.current_segment_id, key).is_some() {
+                return self.cycle_current(1);
+            }
+            self.edit_buffer = Some(match value {
+                serde_json::Value::String(text) => text.clone(),
+                other => other.to_string(),
+            });
+            self.edit_error = None;
         }
         None
     }
 
+    /// Step a choice option forwards or backwards through its values
+    pub fn cycle_current(&mut self, step: i32) -> Option<(String, serde_json::Value)> {
+        let (key, value) = self.current_options.get_mut(self.selected_option)?;
+        let choices = choices_for(self.current_segment_id, key)?;
+        let len = choices.len() as i32;
+        let next = match choices.iter().position(|c| Some(*c) == value.as_str()) {
+            Some(i) => (i as i32 + step).rem_euclid(len),
+            None => 0,
+        };
+        *value = serde_json::json!(choices[next as usize]);
+        Some((key.clone(), value.clone()))
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.edit_buffer.is_some()
+    }
+
+    pub fn input_char(&mut self, c: char) {
+        if let Some(buffer) = &mut self.edit_buffer {
+            buffer.push(c);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(buffer) = &mut self.edit_buffer {
+            buffer.pop();
+        }
+    }
+
+    pub fn cancel_edit(&mut self) {
+        self.edit_buffer = None;
+        self.edit_error = None;
+    }
+
+    /// Validate the typed text against the option's current type and store it;
+    /// on failure editing continues and the reason is shown
+    pub fn commit_edit(&mut self) -> Option<(String, serde_json::Value)> {
+        let buffer = self.edit_buffer.clone()?;
+        let (key, value) = self.current_options.get_mut(self.selected_option)?;
+        match parse_edited(value, &buffer) {
+            Ok(parsed) => {
+                *value = parsed;
+                self.edit_buffer = None;
+                self.edit_error = None;
+                Some((key.clone(), value.clone()))
+            }
+            Err(e) => {
+                self.edit_error = Some(e);
+                None
+            }
+        }
+    }
+
     pub fn get_updated_options(&self) -> HashMap<String, serde_json::Value> {
         self.current_options.iter().cloned().collect()
     }
@@ -87,18 +238,7 @@ impl OptionsEditorComponent {
         f.render_widget(Clear, popup_area);
 
         // Get segment name for title
-        let segment_name = self
-            .current_segment_id
-            .map(|id| match id {
-                SegmentId::Model => "Model",
-                SegmentId::Directory => "Directory",
-                SegmentId::Git => "Git",
-                SegmentId::Usage => "Usage",
-                SegmentId::Update => "Update",
-                SegmentId::Cost => "Cost",
-                SegmentId::BurnRate => "BurnRate",
-            })
-            .unwrap_or("Unknown");
+        let segment_name = self.current_segment_name.as_deref().unwrap_or("Unknown");
 
         let popup_block = Block::default()
             .borders(Borders::ALL)
@@ -132,7 +272,10 @@ impl OptionsEditorComponent {
 
                     // Format the option display
                     let formatted_key = key.replace('_', " ");
-                    let value_str = value.to_string();
+                    let value_str = match (&self.edit_buffer, is_selected) {
+                        (Some(buffer), true) => format!("[{}▏]", buffer),
+                        _ => value.to_string(),
+                    };
                     let value_display = if let Some(bool_val) = value.as_bool() {
                         if bool_val {
                             "[✓]"
@@ -161,15 +304,53 @@ impl OptionsEditorComponent {
             f.render_widget(list, chunks[0]);
         }
 
-        // Render help text
-        let help_text = "↑/↓: Navigate  Space/Enter: Toggle  Esc: Close";
+        // Render help text, or why the last edit was rejected
+        let (help_text, help_color) = match (&self.edit_error, self.is_editing()) {
+            (Some(error), _) => (error.clone(), Color::Red),
+            (None, true) => (
+                "Type a value  Enter: Save  Esc: Cancel".to_string(),
+                Color::DarkGray,
+            ),
+            (None, false) => (
+                "↑/↓: Navigate  Space/Enter: Toggle/Edit  ←/→: Cycle  Esc: Close".to_string(),
+                Color::DarkGray,
+            ),
+        };
         let help = Paragraph::new(help_text)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(help_color))
             .block(Block::default().borders(Borders::TOP));
         f.render_widget(help, chunks[1]);
     }
 }
 
+/// Parse edited text into a value of the same kind as `current`: strings are
+/// taken as typed, numbers and lists must parse as JSON of the same kind
+fn parse_edited(current: &serde_json::Value, text: &str) -> Result<serde_json::Value, String> {
+    use serde_json::Value;
+
+    if current.is_string() {
+        return Ok(Value::String(text.to_string()));
+    }
+    let parsed: Value =
+        serde_json::from_str(text.trim()).map_err(|_| format!("'{}' is not valid", text))?;
+    let same_kind = matches!(
+        (current, &parsed),
+        (Value::Number(_), Value::Number(_))
+            | (Value::Array(_), Value::Array(_))
+            | (Value::Object(_), Value::Object(_))
+            | (Value::Null, _)
+    );
+    if !same_kind {
+        let expected = match current {
+            Value::Number(_) => "a number",
+            Value::Array(_) => "a list like [1, 2]",
+            _ => "a JSON object",
+        };
+        return Err(format!("'{}' is not {}", text, expected));
+    }
+    Ok(parsed)
+}
+
 /// Helper function to create a centered rect
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -190,3 +371,29 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_edited() {
+        assert_eq!(parse_edited(&json!(1.5), " 2.25 "), Ok(json!(2.25)));
+        assert!(parse_edited(&json!(1.5), "fast").is_err());
+        assert_eq!(parse_edited(&json!("880k"), "1m"), Ok(json!("1m")));
+        assert_eq!(parse_edited(&json!([39]), "[1, 2]"), Ok(json!([1, 2])));
+        assert!(parse_edited(&json!([39]), "7").is_err());
+    }
+
+    #[test]
+    fn test_choice_cycling() {
+        let mut editor = OptionsEditorComponent::new();
+        editor.current_segment_id = Some(SegmentId::Cost);
+        editor.current_options = vec![("cost_source".to_string(), json!("auto"))];
+
+        assert_eq!(editor.cycle_current(-1).unwrap().1, json!("both"));
+        assert_eq!(editor.toggle_current().unwrap().1, json!("auto"));
+        assert!(!editor.is_editing());
+    }
+}
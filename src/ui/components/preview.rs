@@ -1,17 +1,31 @@
-use crate::config::{Config, SegmentId};
+use crate::config::{Config, InputData, SegmentConfig};
+use crate::core::scenarios::{mock_segments_data, Scenario};
 use crate::core::segments::SegmentData;
+use crate::core::statusline::collect_all_segments;
 use crate::core::StatusLineGenerator;
+use crate::utils::last_input;
 use ratatui::{
     layout::Rect,
     text::{Line, Text},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 pub struct PreviewComponent {
     preview_cache: String,
     preview_text: Text<'static>,
+    /// Input for live previews; `None` shows mock data
+    live_input: Option<LiveInput>,
+    /// Segment data collected for the live input, keyed by the settings that
+    /// affect collection so color and icon tweaks don't collect again
+    live_cache: Option<(String, Vec<(SegmentConfig, SegmentData)>)>,
+}
+
+struct LiveInput {
+    input: InputData,
+    /// Whether the input was captured from Claude Code rather than made up
+    captured: bool,
 }
 
 impl Default for PreviewComponent {
@@ -25,6 +39,8 @@ impl PreviewComponent {
         Self {
             preview_cache: String::new(),
             preview_text: Text::default(),
+            live_input: None,
+            live_cache: None,
         }
     }
 
@@ -33,8 +49,12 @@ impl PreviewComponent {
     }
 
     pub fn update_preview_with_width(&mut self, config: &Config, width: u16) {
-        // Generate mock segments data directly for preview
-        let segments_data = self.generate_mock_segments_data(config);
+        // Real segment data in live mode, mock data otherwise
+        let segments_data = if self.live_input.is_some() {
+            self.live_segments_data(config)
+        } else {
+            self.generate_mock_segments_data(config)
+        };
 
         // Generate both string and TUI text versions
         let renderer = StatusLineGenerator::new(config.clone());
@@ -70,9 +90,76 @@ impl PreviewComponent {
         ((line_count + 2).max(3) as u16).min(8)
     }
 
+    /// Switch between mock data and live data from the last input captured
+    /// from Claude Code (or a synthetic one for the current directory),
+    /// returning a status message
+    pub fn toggle_live(&mut self) -> String {
+        self.live_cache = None;
+        if self.live_input.take().is_some() {
+            return "Preview shows mock data".to_string();
+        }
+
+        let live = match last_input::load() {
+            Some(input) => LiveInput {
+                input,
+                captured: true,
+            },
+            None => LiveInput {
+                input: last_input::synthetic(),
+                captured: false,
+            },
+        };
+        let message = if live.captured {
+            "Live preview using the last input from Claude Code"
+        } else {
+            "Live preview using synthetic input (no statusline rendered yet)"
+        };
+        self.live_input = Some(live);
+        message.to_string()
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.live_input.is_some()
+    }
+
+    /// Collect real segment data for the live input, reusing the last
+    /// collection while only styling has changed
+    fn live_segments_data(&mut self, config: &Config) -> Vec<(SegmentConfig, SegmentData)> {
+        let Some(live) = &self.live_input else {
+            return Vec::new();
+        };
+        let key = collection_key(config);
+        let collected = match &self.live_cache {
+            Some((cached_key, data)) if *cached_key == key => data.clone(),
+            _ => {
+                let data = collect_all_segments(config, &live.input);
+                self.live_cache = Some((key, data.clone()));
+                data
+            }
+        };
+
+        // Render with the current styling of each collected segment
+        collected
+            .into_iter()
+            .filter_map(|(collected_config, data)| {
+                let key = collected_config.instance_key();
+                config
+                    .segments
+                    .iter()
+                    .find(|segment| segment.instance_key() == key)
+                    .map(|segment| (segment.clone(), data))
+            })
+            .collect()
+    }
+
     pub fn render(&self, f: &mut Frame, area: Rect) {
+        let title = match &self.live_input {
+            Some(live) if live.captured => "Preview (live)",
+            Some(_) => "Preview (live, synthetic input)",
+            None => "Preview",
+        };
         let preview = Paragraph::new(self.preview_text.clone())
-            .block(Block::default().borders(Borders::ALL).title("Preview"))
+            .block(Block::default().borders(Borders::ALL).title(title))
             .wrap(ratatui::widgets::Wrap { trim: false });
         f.render_widget(preview, area);
     }
@@ -87,96 +174,41 @@ impl PreviewComponent {
         &self,
         config: &Config,
     ) -> Vec<(crate::config::SegmentConfig, SegmentData)> {
-        let mut segments_data = Vec::new();
+        mock_segments_data(config, Scenario::Default)
+    }
+}
 
-        for segment_config in &config.segments {
-            if !segment_config.enabled {
-                continue;
-            }
+/// The parts of a config that change what segments collect, as opposed to how
+/// they are drawn
+fn collection_key(config: &Config) -> String {
+    let parts: Vec<_> = config
+        .segments
+        .iter()
+        .map(|segment| {
+            let options: BTreeMap<_, _> = segment.options.iter().collect();
+            (segment.instance_key(), segment.enabled, options)
+        })
+        .collect();
+    serde_json::to_string(&(parts, &config.global)).unwrap_or_default()
+}
 
-            let mock_data = match segment_config.id {
-                SegmentId::Model => SegmentData {
-                    primary: "Sonnet 4".to_string(),
-                    secondary: "".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("model".to_string(), "claude-4-sonnet-20250512".to_string());
-                        map
-                    },
-                },
-                SegmentId::Directory => SegmentData {
-                    primary: "CCometixLine".to_string(),
-                    secondary: "".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("current_dir".to_string(), "~/CCometixLine".to_string());
-                        map
-                    },
-                },
-                SegmentId::Git => SegmentData {
-                    primary: "master".to_string(),
-                    secondary: "✓".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("branch".to_string(), "master".to_string());
-                        map.insert("status".to_string(), "Clean".to_string());
-                        map.insert("ahead".to_string(), "0".to_string());
-                        map.insert("behind".to_string(), "0".to_string());
-                        map
-                    },
-                },
-                SegmentId::Usage => SegmentData {
-                    primary: "78.2%".to_string(),
-                    secondary: "· 156.4k".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("total_tokens".to_string(), "156400".to_string());
-                        map.insert("percentage".to_string(), "78.2".to_string());
-                        map.insert("session_tokens".to_string(), "48200".to_string());
-                        map
-                    },
-                },
-                SegmentId::Update => SegmentData {
-                    primary: format!("v{}", env!("CARGO_PKG_VERSION")),
-                    secondary: "".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert(
-                            "current_version".to_string(),
-                            env!("CARGO_PKG_VERSION").to_string(),
-                        );
-                        map.insert("update_available".to_string(), "false".to_string());
-                        map
-                    },
-                },
-                SegmentId::Cost => SegmentData {
-                    primary: "$2.45 session".to_string(),
-                    secondary: "$12.87 today · $5.00 block (2h 30m)".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("session_cost".to_string(), "2.45".to_string());
-                        map.insert("daily_total".to_string(), "12.87".to_string());
-                        map.insert("block_cost".to_string(), "5.00".to_string());
-                        map.insert("block_remaining".to_string(), "150".to_string());
-                        map
-                    },
-                },
-                SegmentId::BurnRate => SegmentData {
-                    primary: "$4.20/hr".to_string(),
-                    secondary: "\u{f0e7}".to_string(), // Lightning icon
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("cost_per_hour".to_string(), "4.20".to_string());
-                        map.insert("tokens_per_minute".to_string(), "3500.0".to_string());
-                        map.insert("trend".to_string(), "Increasing".to_string());
-                        map
-                    },
-                },
-            };
-
-            segments_data.push((segment_config.clone(), mock_data));
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AnsiColor;
+
+    #[test]
+    fn test_collection_key_ignores_styling() {
+        let mut config = Config::default();
+        let key = collection_key(&config);
+
+        config.segments[0].colors.text = Some(AnsiColor::Color256 { c256: 196 });
+        config.segments[0].icon.plain = "*".to_string();
+        assert_eq!(collection_key(&config), key);
 
-        segments_data
+        config.segments[0]
+            .options
+            .insert("display".to_string(), serde_json::json!("remaining"));
+        assert_ne!(collection_key(&config), key);
     }
 }
@@ -1,5 +1,5 @@
 use super::segment_list::{FieldSelection, Panel};
-use crate::config::{Config, SegmentId, StyleMode};
+use crate::config::{Config, StyleMode};
 use ratatui::{
     layout::Rect,
     style::{Color, Style},
@@ -26,17 +26,9 @@ impl SettingsComponent {
         selected_field: &FieldSelection,
     ) {
         if let Some(segment) = config.segments.get(selected_segment) {
-            let segment_name = match segment.id {
-                SegmentId::Model => "Model",
-                SegmentId::Directory => "Directory",
-                SegmentId::Git => "Git",
-                SegmentId::Usage => "Usage",
-                SegmentId::Update => "Update",
-                SegmentId::Cost => "Cost",
-                SegmentId::BurnRate => "BurnRate",
-            };
+            let segment_name = segment.display_name();
             let current_icon = match config.style.mode {
-                StyleMode::Plain => &segment.icon.plain,
+                StyleMode::Plain | StyleMode::Ascii => &segment.icon.plain,
                 StyleMode::NerdFont | StyleMode::Powerline => &segment.icon.nerd_font,
             };
             // Convert AnsiColor to ratatui Color
@@ -256,12 +248,9 @@ impl SettingsComponent {
                 create_field_line(
                     FieldSelection::TextStyle,
                     vec![Span::raw(format!(
-                        "├─ Text Style: Bold {}",
-                        if segment.styles.text_bold {
-                            "[✓]"
-                        } else {
-                            "[ ]"
-                        }
+                        "├─ Styles: Icon {} · Text {}",
+                        segment.styles.describe(true),
+                        segment.styles.describe(false)
                     ))],
                 ),
                 create_field_line(
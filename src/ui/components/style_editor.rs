@@ -0,0 +1,133 @@
+use crate::config::{TextStyle, TextStyleConfig};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    Frame,
+};
+
+/// Popup toggling a segment's icon and text attributes (bold, dim, italic,
+/// underline, strikethrough, blink)
+#[derive(Debug, Clone, Default)]
+pub struct StyleEditorComponent {
+    pub is_open: bool,
+    /// Row under the cursor: the icon attributes first, then the text ones
+    selected: usize,
+}
+
+impl StyleEditorComponent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.selected = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+    }
+
+    pub fn move_selection(&mut self, delta: i32) {
+        let rows = TextStyle::ALL.len() as i32 * 2;
+        self.selected = (self.selected as i32 + delta).rem_euclid(rows) as usize;
+    }
+
+    /// Whether the selected row is an icon attribute, and which one
+    fn selected_row(&self) -> (bool, TextStyle) {
+        let count = TextStyle::ALL.len();
+        (self.selected < count, TextStyle::ALL[self.selected % count])
+    }
+
+    /// Flip the selected attribute, returning a status message
+    pub fn toggle_current(&self, styles: &mut TextStyleConfig) -> String {
+        let (icon, style) = self.selected_row();
+        let flag = styles.flag_mut(icon, style);
+        *flag = !*flag;
+        format!(
+            "{} {} {}",
+            if icon { "Icon" } else { "Text" },
+            style.display_name().to_lowercase(),
+            if *flag { "enabled" } else { "disabled" }
+        )
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, styles: &TextStyleConfig) {
+        if !self.is_open {
+            return;
+        }
+
+        let popup_height = TextStyle::ALL.len() as u16 * 2 + 7;
+        let popup_width = 44;
+        let popup_area = Rect {
+            x: (area.width.saturating_sub(popup_width)) / 2,
+            y: (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width.min(area.width),
+            height: popup_height.min(area.height),
+        };
+        f.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default()
+            .borders(Borders::ALL)
+            .title("Text Styles")
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = popup_block.inner(popup_area);
+        f.render_widget(popup_block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(2)])
+            .split(inner);
+
+        let mut items = Vec::new();
+        for (group, icon) in [("Icon", true), ("Text", false)] {
+            items.push(ListItem::new(group).style(Style::default().add_modifier(Modifier::BOLD)));
+            for (i, style) in TextStyle::ALL.iter().enumerate() {
+                let row = if icon { i } else { TextStyle::ALL.len() + i };
+                let is_selected = row == self.selected;
+                let line = format!(
+                    "{} {} {}",
+                    if is_selected { "▶" } else { " " },
+                    if styles.is_set(icon, *style) {
+                        "[✓]"
+                    } else {
+                        "[ ]"
+                    },
+                    style.display_name()
+                );
+                items.push(if is_selected {
+                    ListItem::new(line).style(Style::default().fg(Color::Cyan))
+                } else {
+                    ListItem::new(line)
+                });
+            }
+        }
+        f.render_widget(List::new(items), chunks[0]);
+
+        let help = Paragraph::new("↑/↓: Navigate  Space/Enter: Toggle  Esc: Close")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().borders(Borders::TOP));
+        f.render_widget(help, chunks[1]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_styles() {
+        let mut editor = StyleEditorComponent::new();
+        let mut styles = TextStyleConfig::default();
+        editor.open();
+        editor.move_selection(2);
+        assert_eq!(editor.toggle_current(&mut styles), "Icon italic enabled");
+        editor.move_selection(-3);
+        assert_eq!(editor.toggle_current(&mut styles), "Text blink enabled");
+
+        assert_eq!(styles.sgr_codes(true), vec!["3"]);
+        assert_eq!(styles.sgr_codes(false), vec!["5"]);
+        assert_eq!(styles.describe(false), "Blink");
+    }
+}
@@ -0,0 +1,280 @@
+use super::name_input::NameInputComponent;
+use crate::ui::themes::ThemePresets;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Change requested from the theme manager, carried out by the app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThemeManagerAction {
+    Switch(String),
+    SaveAs(String),
+    Rename { from: String, to: String },
+    Duplicate { from: String, to: String },
+    Delete(String),
+}
+
+/// What a typed name will be used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Naming {
+    SaveAs,
+    Rename,
+    Duplicate,
+}
+
+/// Popup listing every theme, for switching, saving the current config as a
+/// new theme, and renaming, duplicating or deleting theme files
+pub struct ThemeManagerComponent {
+    pub is_open: bool,
+    themes: Vec<String>,
+    list_state: ListState,
+    naming: Option<Naming>,
+    name_input: NameInputComponent,
+    confirm_delete: bool,
+}
+
+impl Default for ThemeManagerComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThemeManagerComponent {
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            themes: Vec::new(),
+            list_state: ListState::default(),
+            naming: None,
+            name_input: NameInputComponent::new(),
+            confirm_delete: false,
+        }
+    }
+
+    pub fn open(&mut self, current_theme: &str) {
+        self.is_open = true;
+        self.refresh(Some(current_theme));
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        self.naming = None;
+        self.confirm_delete = false;
+        self.name_input.close();
+    }
+
+    /// Reload the theme list, selecting `select` when given
+    pub fn refresh(&mut self, select: Option<&str>) {
+        let previous = self.selected_theme().map(str::to_string);
+        self.themes = ThemePresets::list_available_themes();
+        let wanted = select.map(str::to_string).or(previous);
+        let index = wanted
+            .and_then(|name| self.themes.iter().position(|t| *t == name))
+            .unwrap_or(0)
+            .min(self.themes.len().saturating_sub(1));
+        self.list_state.select(Some(index));
+    }
+
+    fn selected_theme(&self) -> Option<&str> {
+        self.list_state
+            .selected()
+            .and_then(|i| self.themes.get(i))
+            .map(String::as_str)
+    }
+
+    pub fn move_selection(&mut self, delta: i32) {
+        if self.themes.is_empty() || self.naming.is_some() {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0) as i32;
+        let next = (current + delta).clamp(0, self.themes.len() as i32 - 1);
+        self.list_state.select(Some(next as usize));
+        self.confirm_delete = false;
+    }
+
+    /// Whether key presses are going to the name input
+    pub fn is_naming(&self) -> bool {
+        self.naming.is_some()
+    }
+
+    pub fn start_save_as(&mut self) {
+        self.start_naming(Naming::SaveAs, "Save Current Config as Theme");
+    }
+
+    pub fn start_rename(&mut self) {
+        self.start_naming(Naming::Rename, "Rename Theme");
+    }
+
+    pub fn start_duplicate(&mut self) {
+        self.start_naming(Naming::Duplicate, "Duplicate Theme");
+    }
+
+    fn start_naming(&mut self, naming: Naming, title: &str) {
+        self.confirm_delete = false;
+        self.naming = Some(naming);
+        self.name_input.open(title, "Enter theme name");
+    }
+
+    pub fn input_char(&mut self, c: char) {
+        self.name_input.input_char(c);
+    }
+
+    pub fn backspace(&mut self) {
+        self.name_input.backspace();
+    }
+
+    pub fn cancel(&mut self) {
+        self.naming = None;
+        self.confirm_delete = false;
+        self.name_input.close();
+    }
+
+    /// Finish typing a name and turn it into an action
+    pub fn confirm_name(&mut self) -> Option<ThemeManagerAction> {
+        let naming = self.naming.take()?;
+        let name = self.name_input.get_input();
+        self.name_input.close();
+        let name = name?;
+        let from = self.selected_theme().unwrap_or_default().to_string();
+        Some(match naming {
+            Naming::SaveAs => ThemeManagerAction::SaveAs(name),
+            Naming::Rename => ThemeManagerAction::Rename { from, to: name },
+            Naming::Duplicate => ThemeManagerAction::Duplicate { from, to: name },
+        })
+    }
+
+    pub fn switch_selected(&self) -> Option<ThemeManagerAction> {
+        self.selected_theme()
+            .map(|name| ThemeManagerAction::Switch(name.to_string()))
+    }
+
+    /// First press asks for confirmation, the second returns the delete action
+    pub fn delete_selected(&mut self) -> Option<ThemeManagerAction> {
+        if !self.confirm_delete {
+            self.confirm_delete = true;
+            return None;
+        }
+        self.confirm_delete = false;
+        self.selected_theme()
+            .map(|name| ThemeManagerAction::Delete(name.to_string()))
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, current_theme: &str) {
+        if !self.is_open {
+            return;
+        }
+
+        let popup_area = centered_rect(50, 60, area);
+        f.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default()
+            .borders(Borders::ALL)
+            .title("Manage Themes");
+        let inner = popup_block.inner(popup_area);
+        f.render_widget(popup_block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(3)])
+            .split(inner);
+
+        let items: Vec<ListItem> = self
+            .themes
+            .iter()
+            .map(|theme| {
+                let marker = if theme == current_theme { "✓" } else { " " };
+                let kind = if ThemePresets::is_builtin(theme) {
+                    "built-in"
+                } else {
+                    "custom"
+                };
+                ListItem::new(format!("{} {:<24} {}", marker, theme, kind))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Themes"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, chunks[0], &mut self.list_state);
+
+        let (actions, color) = if self.confirm_delete {
+            (
+                format!(
+                    "Delete '{}'? [X] again to confirm  [Esc] Cancel",
+                    self.selected_theme().unwrap_or_default()
+                ),
+                Color::Red,
+            )
+        } else {
+            (
+                "[Enter] Use  [N] Save As  [R] Rename  [D] Duplicate  [X] Delete  [Esc] Close"
+                    .to_string(),
+                Color::Reset,
+            )
+        };
+        f.render_widget(
+            Paragraph::new(actions)
+                .style(Style::default().fg(color))
+                .block(Block::default().borders(Borders::ALL)),
+            chunks[1],
+        );
+
+        if self.naming.is_some() {
+            self.name_input.render(f, area);
+        }
+    }
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_needs_confirmation() {
+        let mut manager = ThemeManagerComponent::new();
+        manager.themes = vec!["default".to_string(), "mine".to_string()];
+        manager.list_state.select(Some(1));
+
+        assert_eq!(manager.delete_selected(), None);
+        assert_eq!(
+            manager.delete_selected(),
+            Some(ThemeManagerAction::Delete("mine".to_string()))
+        );
+
+        manager.start_rename();
+        assert!(manager.is_naming());
+        for c in "ours".chars() {
+            manager.input_char(c);
+        }
+        assert_eq!(
+            manager.confirm_name(),
+            Some(ThemeManagerAction::Rename {
+                from: "mine".to_string(),
+                to: "ours".to_string()
+            })
+        );
+        assert!(!manager.is_naming());
+    }
+}
@@ -0,0 +1,479 @@
+// Live usage overview for `ccline dashboard`
+
+use crate::billing::block::{find_active_block, identify_session_blocks_with_overrides};
+use crate::billing::calculator::{calculate_burn_rate, format_remaining_time};
+use crate::billing::{BillingBlock, UsageEntry};
+use crate::report::data::{aggregate_sessions, SessionSummary};
+use crate::report::table::{format_currency, format_tokens};
+use crate::utils::timezone;
+use chrono::{DateTime, Duration, Utc};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Sparkline, Table},
+    Frame, Terminal,
+};
+use std::collections::HashMap;
+use std::io;
+
+/// Timeline spans cycled with `-` / `+`
+const TIMELINE_HOURS: [i64; 5] = [6, 12, 24, 48, 72];
+
+/// Minutes per sparkline bar
+const BURN_BUCKET_MINUTES: i64 = 10;
+
+/// Sessions listed in the top sessions panel
+const TOP_SESSIONS: usize = 8;
+
+/// What occupies one cell of the block timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimelineCell {
+    Empty,
+    Gap,
+    Block,
+    Active,
+}
+
+/// Cost and tokens spent on one model
+#[derive(Debug, Clone, PartialEq)]
+struct ModelCost {
+    model: String,
+    cost: f64,
+    tokens: u64,
+}
+
+pub struct Dashboard {
+    entries: Vec<UsageEntry>,
+    blocks: Vec<BillingBlock>,
+    timeline_hours: i64,
+    should_quit: bool,
+}
+
+impl Dashboard {
+    pub fn new(entries: Vec<UsageEntry>) -> Self {
+        let blocks = identify_session_blocks_with_overrides(&entries);
+        Self {
+            entries,
+            blocks,
+            timeline_hours: 24,
+            should_quit: false,
+        }
+    }
+
+    pub fn run(mut self) -> Result<(), Box<dyn std::error::Error>> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend)?;
+
+        let result: Result<(), Box<dyn std::error::Error>> = loop {
+            if let Err(e) = terminal.draw(|f| self.ui(f, Utc::now())) {
+                break Err(e.into());
+            }
+
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    self.handle_key(key.code);
+                }
+                Ok(_) => {}
+                Err(e) => break Err(e.into()),
+            }
+
+            if self.should_quit {
+                break Ok(());
+            }
+        };
+
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+
+        result
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('-') => self.change_timeline(-1),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.change_timeline(1),
+            _ => {}
+        }
+    }
+
+    fn change_timeline(&mut self, delta: i32) {
+        let index = TIMELINE_HOURS
+            .iter()
+            .position(|h| *h >= self.timeline_hours)
+            .unwrap_or(TIMELINE_HOURS.len() - 1) as i32;
+        let next = (index + delta).clamp(0, TIMELINE_HOURS.len() as i32 - 1) as usize;
+        self.timeline_hours = TIMELINE_HOURS[next];
+    }
+
+    /// Entries from the current local day
+    fn today(&self) -> Vec<UsageEntry> {
+        let today = timezone::today();
+        self.entries
+            .iter()
+            .filter(|e| timezone::date_of(e.timestamp) == today)
+            .cloned()
+            .collect()
+    }
+
+    fn ui(&self, f: &mut Frame, now: DateTime<Utc>) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(5),
+                Constraint::Length(7),
+                Constraint::Min(6),
+                Constraint::Length(3),
+            ])
+            .split(f.area());
+
+        let today = self.today();
+        let today_cost: f64 = today.iter().filter_map(|e| e.cost).sum();
+        let title = Paragraph::new(format!(
+            "{}  ·  today {}  ·  {} sessions",
+            timezone::to_local(now).format("%Y-%m-%d %H:%M"),
+            format_currency(today_cost),
+            aggregate_sessions(&today).len()
+        ))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("CCometixLine Dashboard"),
+        )
+        .style(Style::default().fg(Color::Cyan));
+        f.render_widget(title, layout[0]);
+
+        self.render_timeline(f, layout[1], now);
+        self.render_burn_rate(f, layout[2], now);
+
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(layout[3]);
+        render_model_costs(f, bottom[0], &model_costs(&today));
+        render_top_sessions(f, bottom[1], &top_sessions(&today, TOP_SESSIONS));
+
+        let help = Paragraph::new("[-/+] Timeline span  [Q] Quit")
+            .block(Block::default().borders(Borders::ALL).title("Help"));
+        f.render_widget(help, layout[4]);
+    }
+
+    fn render_timeline(&self, f: &mut Frame, area: Rect, now: DateTime<Utc>) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Billing Blocks · last {}h", self.timeline_hours));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let start = now - Duration::hours(self.timeline_hours);
+        let cells = timeline_cells(&self.blocks, start, now, inner.width as usize);
+        let bar: Vec<Span> = cells
+            .iter()
+            .map(|cell| match cell {
+                TimelineCell::Empty => Span::raw(" "),
+                TimelineCell::Gap => Span::styled("·", Style::default().fg(Color::DarkGray)),
+                TimelineCell::Block => Span::styled("█", Style::default().fg(Color::Blue)),
+                TimelineCell::Active => Span::styled("█", Style::default().fg(Color::Green)),
+            })
+            .collect();
+
+        // Times before today carry their weekday
+        let today = timezone::date_of(now);
+        let label = |time: DateTime<Utc>| {
+            let local = timezone::to_local(time);
+            if local.date_naive() == today {
+                local.format("%H:%M").to_string()
+            } else {
+                local.format("%a %H:%M").to_string()
+            }
+        };
+        let start_label = label(start);
+        let scale = format!(
+            "{}{:>width$}",
+            start_label,
+            "now",
+            width = (inner.width as usize).saturating_sub(start_label.len())
+        );
+
+        let summary: Vec<String> = self
+            .blocks
+            .iter()
+            .filter(|b| b.end_time > start && b.start_time < now)
+            .map(|b| {
+                if b.is_gap {
+                    format!("gap {}–{}", label(b.start_time), label(b.end_time))
+                } else if b.is_active {
+                    format!(
+                        "{}– {} · {} left",
+                        label(b.start_time),
+                        format_currency(b.cost),
+                        format_remaining_time(b.remaining_minutes)
+                    )
+                } else {
+                    format!(
+                        "{}–{} {}",
+                        label(b.start_time),
+                        label(b.end_time),
+                        format_currency(b.cost)
+                    )
+                }
+            })
+            .collect();
+
+        let lines = vec![
+            Line::from(bar),
+            Line::from(Span::styled(scale, Style::default().fg(Color::DarkGray))),
+            Line::from(summary.join("  │  ")),
+        ];
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+
+    fn render_burn_rate(&self, f: &mut Frame, area: Rect, now: DateTime<Utc>) {
+        let rate = find_active_block(&self.blocks)
+            .and_then(|block| calculate_burn_rate(block, &self.entries))
+            .map(|rate| {
+                format!(
+                    " · now {} tok/min, {}/h",
+                    format_tokens(rate.tokens_per_minute_for_indicator as u64),
+                    format_currency(rate.cost_per_hour)
+                )
+            })
+            .unwrap_or_default();
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            "Burn Rate · input+output tokens per {} min{}",
+            BURN_BUCKET_MINUTES, rate
+        ));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let buckets = burn_buckets(&self.entries, now, inner.width as usize);
+        let sparkline = Sparkline::default()
+            .data(&buckets)
+            .style(Style::default().fg(Color::Yellow));
+        f.render_widget(sparkline, inner);
+    }
+}
+
+/// Split `start..end` into `width` cells and mark which hold a billing block
+fn timeline_cells(
+    blocks: &[BillingBlock],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    width: usize,
+) -> Vec<TimelineCell> {
+    if width == 0 {
+        return Vec::new();
+    }
+    let span = (end - start).num_seconds().max(1) as f64;
+    (0..width)
+        .map(|i| {
+            let offset = span * (i as f64 + 0.5) / width as f64;
+            let time = start + Duration::seconds(offset as i64);
+            match blocks
+                .iter()
+                .find(|b| b.start_time <= time && time < b.end_time)
+            {
+                Some(b) if b.is_gap => TimelineCell::Gap,
+                Some(b) if b.is_active => TimelineCell::Active,
+                Some(_) => TimelineCell::Block,
+                None => TimelineCell::Empty,
+            }
+        })
+        .collect()
+}
+
+/// Input and output tokens per bucket for the `count` buckets ending at `now`,
+/// oldest first
+fn burn_buckets(entries: &[UsageEntry], now: DateTime<Utc>, count: usize) -> Vec<u64> {
+    let mut buckets = vec![0u64; count];
+    for entry in entries {
+        let age = (now - entry.timestamp).num_minutes();
+        if age < 0 {
+            continue;
+        }
+        let index = (age / BURN_BUCKET_MINUTES) as usize;
+        if index < count {
+            buckets[count - 1 - index] += entry.input_tokens + entry.output_tokens;
+        }
+    }
+    buckets
+}
+
+/// Cost per model, most expensive first
+fn model_costs(entries: &[UsageEntry]) -> Vec<ModelCost> {
+    let mut by_model: HashMap<&str, ModelCost> = HashMap::new();
+    for entry in entries {
+        let model = by_model
+            .entry(entry.model.as_str())
+            .or_insert_with(|| ModelCost {
+                model: entry.model.clone(),
+                cost: 0.0,
+                tokens: 0,
+            });
+        model.cost += entry.cost.unwrap_or(0.0);
+        model.tokens += entry.input_tokens
+            + entry.output_tokens
+            + entry.cache_creation_tokens
+            + entry.cache_read_tokens;
+    }
+    let mut models: Vec<ModelCost> = by_model.into_values().collect();
+    models.sort_by(|a, b| b.cost.total_cmp(&a.cost).then(a.model.cmp(&b.model)));
+    models
+}
+
+/// The `limit` most expensive sessions
+fn top_sessions(entries: &[UsageEntry], limit: usize) -> Vec<SessionSummary> {
+    let mut sessions = aggregate_sessions(entries);
+    sessions.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+    sessions.truncate(limit);
+    sessions
+}
+
+fn render_model_costs(f: &mut Frame, area: Rect, models: &[ModelCost]) {
+    let max = models.iter().map(|m| m.cost).fold(0.0, f64::max);
+    let rows = models.iter().map(|m| {
+        let filled = if max > 0.0 {
+            (m.cost / max * 10.0).round() as usize
+        } else {
+            0
+        };
+        Row::new(vec![
+            Cell::from(m.model.clone()),
+            Cell::from(format_tokens(m.tokens)),
+            Cell::from(format_currency(m.cost)),
+            Cell::from("█".repeat(filled)).style(Style::default().fg(Color::Magenta)),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(12),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(10),
+        ],
+    )
+    .header(
+        Row::new(vec!["Model", "Tokens", "Cost", ""]).style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Cost by Model · today"),
+    );
+    f.render_widget(table, area);
+}
+
+fn render_top_sessions(f: &mut Frame, area: Rect, sessions: &[SessionSummary]) {
+    let rows = sessions.iter().map(|s| {
+        Row::new(vec![
+            s.session_id.chars().take(8).collect::<String>(),
+            timezone::to_local(s.last_seen).format("%H:%M").to_string(),
+            format_tokens(s.tokens.total()),
+            format_currency(s.cost),
+            s.models.join(", "),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(5),
+            Constraint::Length(12),
+            Constraint::Length(9),
+            Constraint::Min(10),
+        ],
+    )
+    .header(
+        Row::new(vec!["Session", "Last", "Tokens", "Cost", "Models"]).style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+    )
+    .column_spacing(2)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Top Sessions · today"),
+    );
+    f.render_widget(table, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::billing::types::BlockStartSource;
+
+    fn entry(session: &str, model: &str, minutes_ago: i64, tokens: u64, cost: f64) -> UsageEntry {
+        UsageEntry {
+            timestamp: Utc::now() - Duration::minutes(minutes_ago),
+            input_tokens: tokens,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            model: model.to_string(),
+            cost: Some(cost),
+            session_id: session.to_string(),
+            subagent: false,
+            project: String::new(),
+        }
+    }
+
+    fn block(start: DateTime<Utc>, hours: i64, is_gap: bool, is_active: bool) -> BillingBlock {
+        BillingBlock {
+            start_time: start,
+            end_time: start + Duration::hours(hours),
+            cost: 0.0,
+            remaining_minutes: 0,
+            is_active,
+            session_count: 0,
+            total_tokens: 0,
+            start_time_source: BlockStartSource::Auto,
+            is_gap,
+        }
+    }
+
+    #[test]
+    fn test_timeline_and_aggregates() {
+        let now = Utc::now();
+        let start = now - Duration::hours(12);
+        let blocks = vec![
+            block(start, 5, false, false),
+            block(start + Duration::hours(5), 2, true, false),
+            block(start + Duration::hours(7), 5, false, true),
+        ];
+        let cells = timeline_cells(&blocks, start, now, 12);
+        assert_eq!(cells[0], TimelineCell::Block);
+        assert_eq!(cells[5], TimelineCell::Gap);
+        assert_eq!(cells[11], TimelineCell::Active);
+
+        let entries = vec![
+            entry("a", "claude-opus-4", 1, 100, 2.0),
+            entry("a", "claude-sonnet-4", 15, 50, 0.5),
+            entry("b", "claude-sonnet-4", 2, 10, 0.25),
+        ];
+        assert_eq!(burn_buckets(&entries, Utc::now(), 3), vec![0, 50, 110]);
+
+        let models = model_costs(&entries);
+        assert_eq!(models[0].model, "claude-opus-4");
+        assert_eq!(models[1].cost, 0.75);
+        assert_eq!(top_sessions(&entries, 1)[0].session_id, "a");
+    }
+}
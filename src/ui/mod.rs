@@ -3,22 +3,26 @@ pub mod app;
 #[cfg(feature = "tui")]
 pub mod components;
 #[cfg(feature = "tui")]
+pub mod dashboard;
+#[cfg(feature = "tui")]
 pub mod events;
 #[cfg(feature = "tui")]
 pub mod layout;
 #[cfg(feature = "tui")]
+pub mod report_explorer;
+#[cfg(feature = "tui")]
 pub mod themes;
 
 #[cfg(feature = "tui")]
 pub use app::App;
 
 #[cfg(feature = "tui")]
-pub fn run_configurator() -> Result<(), Box<dyn std::error::Error>> {
-    App::run()
+pub fn run_configurator(reset_options: bool) -> Result<(), Box<dyn std::error::Error>> {
+    App::run(reset_options)
 }
 
 #[cfg(not(feature = "tui"))]
-pub fn run_configurator() -> Result<(), Box<dyn std::error::Error>> {
+pub fn run_configurator(_reset_options: bool) -> Result<(), Box<dyn std::error::Error>> {
     eprintln!("TUI feature is not enabled. Please install with --features tui");
     std::process::exit(1);
 }
@@ -0,0 +1,504 @@
+// Interactive report explorer for `ccline report --interactive`
+
+use crate::billing::block::identify_session_blocks_with_overrides;
+use crate::billing::UsageEntry;
+use crate::report::data::{aggregate_daily, aggregate_sessions, filter_recent};
+use crate::report::table::{format_currency, format_tokens};
+use crate::utils::timezone;
+use chrono::NaiveDate;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Frame, Terminal,
+};
+use std::io;
+
+/// Date range presets cycled with `-` / `+`
+const RANGE_PRESETS: [i64; 6] = [1, 7, 14, 30, 90, 365];
+
+/// Top-level tabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplorerTab {
+    Daily,
+    Sessions,
+    Blocks,
+}
+
+/// A screen in the drill-down stack
+#[derive(Debug, Clone, PartialEq)]
+enum View {
+    Daily,
+    Sessions { date: Option<NaiveDate> },
+    Blocks,
+    Entries { session_id: String },
+}
+
+/// Value used for sorting a column
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+enum SortKey {
+    Text(String),
+    Number(f64),
+}
+
+struct ExplorerRow {
+    cells: Vec<String>,
+    keys: Vec<SortKey>,
+    drill: Option<View>,
+}
+
+pub struct ReportExplorer {
+    entries: Vec<UsageEntry>,
+    range_days: i64,
+    stack: Vec<View>,
+    /// Rows of the current view, rebuilt when the view or range changes
+    rows: Vec<ExplorerRow>,
+    table_state: TableState,
+    sort_column: usize,
+    sort_desc: bool,
+    should_quit: bool,
+}
+
+impl ReportExplorer {
+    pub fn new(entries: Vec<UsageEntry>, range_days: i64, tab: ExplorerTab) -> Self {
+        let mut explorer = Self {
+            entries,
+            range_days,
+            stack: Vec::new(),
+            rows: Vec::new(),
+            table_state: TableState::default(),
+            sort_column: 0,
+            sort_desc: true,
+            should_quit: false,
+        };
+        explorer.switch_tab(tab);
+        explorer
+    }
+
+    pub fn run(mut self) -> Result<(), Box<dyn std::error::Error>> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend)?;
+
+        let result: Result<(), Box<dyn std::error::Error>> = loop {
+            if let Err(e) = terminal.draw(|f| self.ui(f)) {
+                break Err(e.into());
+            }
+
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    self.handle_key(key.code);
+                }
+                Ok(_) => {}
+                Err(e) => break Err(e.into()),
+            }
+
+            if self.should_quit {
+                break Ok(());
+            }
+        };
+
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+
+        result
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Esc | KeyCode::Backspace => {
+                if self.stack.len() > 1 {
+                    self.stack.pop();
+                    self.reset_selection();
+                    self.refresh_rows();
+                } else if code == KeyCode::Esc {
+                    self.should_quit = true;
+                }
+            }
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::Enter => self.drill_down(),
+            KeyCode::Char('1') => self.switch_tab(ExplorerTab::Daily),
+            KeyCode::Char('2') => self.switch_tab(ExplorerTab::Sessions),
+            KeyCode::Char('3') => self.switch_tab(ExplorerTab::Blocks),
+            KeyCode::Left => self.cycle_sort_column(-1),
+            KeyCode::Right => self.cycle_sort_column(1),
+            KeyCode::Char('s') => {
+                self.sort_desc = !self.sort_desc;
+                self.sort_rows();
+            }
+            KeyCode::Char('-') => self.change_range(-1),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.change_range(1),
+            _ => {}
+        }
+    }
+
+    fn switch_tab(&mut self, tab: ExplorerTab) {
+        self.stack = vec![match tab {
+            ExplorerTab::Daily => View::Daily,
+            ExplorerTab::Sessions => View::Sessions { date: None },
+            ExplorerTab::Blocks => View::Blocks,
+        }];
+        self.reset_selection();
+        self.refresh_rows();
+    }
+
+    fn reset_selection(&mut self) {
+        self.sort_column = 0;
+        self.sort_desc = true;
+        self.table_state.select(Some(0));
+    }
+
+    fn current_view(&self) -> &View {
+        self.stack.last().unwrap_or(&View::Daily)
+    }
+
+    fn move_selection(&mut self, delta: i32) {
+        let len = self.rows.len();
+        if len == 0 {
+            return;
+        }
+        let current = self.table_state.selected().unwrap_or(0) as i32;
+        let next = (current + delta).clamp(0, len as i32 - 1) as usize;
+        self.table_state.select(Some(next));
+    }
+
+    fn drill_down(&mut self) {
+        if let Some(view) = self
+            .table_state
+            .selected()
+            .and_then(|i| self.rows.get(i))
+            .and_then(|row| row.drill.clone())
+        {
+            self.stack.push(view);
+            self.reset_selection();
+            self.refresh_rows();
+        }
+    }
+
+    fn cycle_sort_column(&mut self, delta: i32) {
+        let columns = self.headers().len() as i32;
+        self.sort_column = (self.sort_column as i32 + delta).rem_euclid(columns) as usize;
+        self.sort_rows();
+    }
+
+    fn change_range(&mut self, delta: i32) {
+        let index = RANGE_PRESETS
+            .iter()
+            .position(|d| *d >= self.range_days)
+            .unwrap_or(RANGE_PRESETS.len() - 1) as i32;
+        let next = (index + delta).clamp(0, RANGE_PRESETS.len() as i32 - 1) as usize;
+        self.range_days = RANGE_PRESETS[next];
+        self.table_state.select(Some(0));
+        self.refresh_rows();
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        match self.current_view() {
+            View::Daily => vec!["Date", "Sessions", "Total Tokens", "Cost", "Models"],
+            View::Sessions { .. } => vec!["Session", "Last Activity", "Total Tokens", "Cost"],
+            View::Blocks => vec!["Start", "End", "Status", "Sessions", "Total Tokens", "Cost"],
+            View::Entries { .. } => vec![
+                "Time",
+                "Model",
+                "Input",
+                "Output",
+                "Cache Write",
+                "Cache Read",
+                "Cost",
+            ],
+        }
+    }
+
+    /// Aggregate the current view's rows; drawing and navigation reuse them
+    fn refresh_rows(&mut self) {
+        let entries = filter_recent(&self.entries, self.range_days);
+        self.rows = match self.current_view() {
+            View::Daily => daily_rows(&entries),
+            View::Sessions { date } => session_rows(&entries, *date),
+            View::Blocks => block_rows(&entries),
+            View::Entries { session_id } => entry_rows(&entries, session_id),
+        };
+        self.sort_rows();
+    }
+
+    fn sort_rows(&mut self) {
+        let column = self.sort_column;
+        let sort_desc = self.sort_desc;
+        self.rows.sort_by(|a, b| {
+            let ordering = a.keys[column]
+                .partial_cmp(&b.keys[column])
+                .unwrap_or(std::cmp::Ordering::Equal);
+            if sort_desc {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    fn breadcrumb(&self) -> String {
+        self.stack
+            .iter()
+            .map(|view| match view {
+                View::Daily => "Daily".to_string(),
+                View::Sessions { date: Some(date) } => format!("Sessions {}", date),
+                View::Sessions { date: None } => "Sessions".to_string(),
+                View::Blocks => "Blocks".to_string(),
+                View::Entries { session_id } => {
+                    format!("Entries {}", session_id.chars().take(8).collect::<String>())
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" › ")
+    }
+
+    fn ui(&mut self, f: &mut Frame) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(5),
+                Constraint::Length(3),
+            ])
+            .split(f.area());
+
+        let title = Paragraph::new(format!(
+            "{}  ·  last {} day{}",
+            self.breadcrumb(),
+            self.range_days,
+            if self.range_days == 1 { "" } else { "s" }
+        ))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("CCometixLine Report Explorer"),
+        )
+        .style(Style::default().fg(Color::Cyan));
+        f.render_widget(title, layout[0]);
+
+        let headers = self.headers();
+        let rows = &self.rows;
+        let header_cells = headers.iter().enumerate().map(|(i, h)| {
+            let label = if i == self.sort_column {
+                format!("{} {}", h, if self.sort_desc { "▼" } else { "▲" })
+            } else {
+                h.to_string()
+            };
+            Cell::from(label).style(Style::default().add_modifier(Modifier::BOLD))
+        });
+        let widths: Vec<Constraint> = headers
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                let content = rows
+                    .iter()
+                    .map(|r| r.cells[i].chars().count())
+                    .max()
+                    .unwrap_or(0);
+                Constraint::Length((content.max(h.chars().count() + 2)) as u16)
+            })
+            .collect();
+
+        let table = Table::new(rows.iter().map(|r| Row::new(r.cells.clone())), widths)
+            .header(Row::new(header_cells).style(Style::default().fg(Color::Yellow)))
+            .block(Block::default().borders(Borders::ALL))
+            .row_highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("▶ ")
+            .column_spacing(2);
+        f.render_stateful_widget(table, layout[1], &mut self.table_state);
+
+        let help = Paragraph::new(
+            "[↑↓] Navigate  [Enter] Drill down  [Esc] Back  [1-3] Daily/Sessions/Blocks  [←→] Sort column  [S] Reverse  [-/+] Range  [Q] Quit",
+        )
+        .block(Block::default().borders(Borders::ALL).title("Help"))
+        .wrap(ratatui::widgets::Wrap { trim: false });
+        f.render_widget(help, layout[2]);
+    }
+}
+
+fn daily_rows(entries: &[UsageEntry]) -> Vec<ExplorerRow> {
+    aggregate_daily(entries)
+        .into_iter()
+        .map(|day| {
+            let sessions = aggregate_sessions(
+                &entries
+                    .iter()
+                    .filter(|e| timezone::date_of(e.timestamp) == day.date)
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            )
+            .len();
+            ExplorerRow {
+                cells: vec![
+                    day.date.format("%Y-%m-%d").to_string(),
+                    sessions.to_string(),
+                    format_tokens(day.tokens.total()),
+                    format_currency(day.cost),
+                    day.models.join(", "),
+                ],
+                keys: vec![
+                    SortKey::Text(day.date.to_string()),
+                    SortKey::Number(sessions as f64),
+                    SortKey::Number(day.tokens.total() as f64),
+                    SortKey::Number(day.cost),
+                    SortKey::Text(day.models.join(",")),
+                ],
+                drill: Some(View::Sessions {
+                    date: Some(day.date),
+                }),
+            }
+        })
+        .collect()
+}
+
+fn session_rows(entries: &[UsageEntry], date: Option<NaiveDate>) -> Vec<ExplorerRow> {
+    let filtered: Vec<UsageEntry> = entries
+        .iter()
+        .filter(|e| date.is_none_or(|d| timezone::date_of(e.timestamp) == d))
+        .cloned()
+        .collect();
+
+    aggregate_sessions(&filtered)
+        .into_iter()
+        .map(|session| ExplorerRow {
+            cells: vec![
+                session.session_id.chars().take(8).collect(),
+                timezone::to_local(session.last_seen)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                format_tokens(session.tokens.total()),
+                format_currency(session.cost),
+            ],
+            keys: vec![
+                SortKey::Text(session.session_id.clone()),
+                SortKey::Number(session.last_seen.timestamp() as f64),
+                SortKey::Number(session.tokens.total() as f64),
+                SortKey::Number(session.cost),
+            ],
+            drill: Some(View::Entries {
+                session_id: session.session_id,
+            }),
+        })
+        .collect()
+}
+
+fn block_rows(entries: &[UsageEntry]) -> Vec<ExplorerRow> {
+    identify_session_blocks_with_overrides(entries)
+        .into_iter()
+        .filter(|b| !b.is_gap)
+        .map(|block| ExplorerRow {
+            cells: vec![
+                timezone::to_local(block.start_time)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                timezone::to_local(block.end_time)
+                    .format("%H:%M")
+                    .to_string(),
+                if block.is_active { "active" } else { "done" }.to_string(),
+                block.session_count.to_string(),
+                format_tokens(block.total_tokens),
+                format_currency(block.cost),
+            ],
+            keys: vec![
+                SortKey::Number(block.start_time.timestamp() as f64),
+                SortKey::Number(block.end_time.timestamp() as f64),
+                SortKey::Text(block.is_active.to_string()),
+                SortKey::Number(block.session_count as f64),
+                SortKey::Number(block.total_tokens as f64),
+                SortKey::Number(block.cost),
+            ],
+            drill: None,
+        })
+        .collect()
+}
+
+fn entry_rows(entries: &[UsageEntry], session_id: &str) -> Vec<ExplorerRow> {
+    entries
+        .iter()
+        .filter(|e| e.session_id == session_id)
+        .map(|entry| {
+            let cost = entry.cost.unwrap_or(0.0);
+            ExplorerRow {
+                cells: vec![
+                    timezone::to_local(entry.timestamp)
+                        .format("%m-%d %H:%M:%S")
+                        .to_string(),
+                    entry.model.clone(),
+                    format_tokens(entry.input_tokens),
+                    format_tokens(entry.output_tokens),
+                    format_tokens(entry.cache_creation_tokens),
+                    format_tokens(entry.cache_read_tokens),
+                    format_currency(cost),
+                ],
+                keys: vec![
+                    SortKey::Number(entry.timestamp.timestamp() as f64),
+                    SortKey::Text(entry.model.clone()),
+                    SortKey::Number(entry.input_tokens as f64),
+                    SortKey::Number(entry.output_tokens as f64),
+                    SortKey::Number(entry.cache_creation_tokens as f64),
+                    SortKey::Number(entry.cache_read_tokens as f64),
+                    SortKey::Number(cost),
+                ],
+                drill: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry(session: &str, minutes_ago: i64, input: u64) -> UsageEntry {
+        UsageEntry {
+            timestamp: Utc::now() - chrono::Duration::minutes(minutes_ago),
+            input_tokens: input,
+            output_tokens: 0,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
+            model: "claude-sonnet-4".to_string(),
+            cost: Some(0.01),
+            session_id: session.to_string(),
+            subagent: false,
+            project: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_sort_and_drill_down_to_entries() {
+        let entries = vec![entry("a", 1, 100), entry("a", 2, 50), entry("b", 3, 10)];
+        let mut explorer = ReportExplorer::new(entries, 7, ExplorerTab::Sessions);
+        assert_eq!(explorer.rows.len(), 2);
+
+        // Sort sessions by total tokens, largest first
+        explorer.cycle_sort_column(2);
+        explorer.handle_key(KeyCode::Enter);
+        assert_eq!(
+            explorer.current_view(),
+            &View::Entries {
+                session_id: "a".to_string()
+            }
+        );
+        assert_eq!(explorer.rows.len(), 2);
+
+        explorer.handle_key(KeyCode::Esc);
+        assert_eq!(explorer.current_view(), &View::Sessions { date: None });
+        assert!(!explorer.should_quit);
+        explorer.handle_key(KeyCode::Esc);
+        assert!(explorer.should_quit);
+    }
+}
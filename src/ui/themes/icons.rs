@@ -0,0 +1,130 @@
+// Platform-specific plain icon sets for the built-in themes
+
+use crate::config::{Config, SegmentId};
+
+/// Which glyphs the built-in themes use for Plain mode icons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlainIconSet {
+    /// Color emoji, which macOS and modern terminals render well
+    Emoji,
+    /// Single-width symbols found in Linux console fonts
+    Text,
+    /// Plain ASCII for the Windows console host
+    Ascii,
+}
+
+impl PlainIconSet {
+    /// The set for this platform, overridable with `CCLINE_ICON_SET=emoji|text|ascii`
+    pub fn detect() -> Self {
+        if let Some(set) = std::env::var("CCLINE_ICON_SET")
+            .ok()
+            .and_then(|name| Self::parse(&name))
+        {
+            return set;
+        }
+
+        if cfg!(target_os = "macos") {
+            PlainIconSet::Emoji
+        } else if cfg!(windows) {
+            // Windows Terminal sets WT_SESSION and handles emoji; conhost does not
+            if std::env::var_os("WT_SESSION").is_some() {
+                PlainIconSet::Emoji
+            } else {
+                PlainIconSet::Ascii
+            }
+        } else {
+            PlainIconSet::Text
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "emoji" => Some(PlainIconSet::Emoji),
+            "text" => Some(PlainIconSet::Text),
+            "ascii" => Some(PlainIconSet::Ascii),
+            _ => None,
+        }
+    }
+
+    /// Plain icon of a built-in theme segment in this set
+    pub fn icon(&self, id: SegmentId) -> Option<&'static str> {
+        let (emoji, text, ascii) = match id {
+            SegmentId::Model => ("🤖", "♦", "*"),
+            SegmentId::Directory => ("📁", "§", "~"),
+            SegmentId::Git => ("🌿", "±", "+"),
+            SegmentId::Usage => ("⚡", "%", "%"),
+            SegmentId::Cost => ("💰", "¤", "$"),
+            SegmentId::BurnRate => ("🔥", "≈", "^"),
+            SegmentId::Update => ("🔄", "↑", "u"),
+            SegmentId::Block => ("⏳", "÷", "@"),
+            SegmentId::BlockLimit => ("🚦", "¬", "#"),
+            SegmentId::Weekly => ("📅", "¶", "w"),
+            SegmentId::ProjectCost => ("💼", "¢", "&"),
+            SegmentId::LastTurn => ("💬", "»", ">"),
+            SegmentId::SessionStats => ("📊", "∑", "="),
+            SegmentId::ApiErrors => ("🚨", "×", "!"),
+            SegmentId::Condensed => ("📋", "≡", "|"),
+            _ => return None,
+        };
+        Some(match self {
+            PlainIconSet::Emoji => emoji,
+            PlainIconSet::Text => text,
+            PlainIconSet::Ascii => ascii,
+        })
+    }
+
+    /// Swap the emoji icons of a built-in theme for this set; with the ASCII
+    /// set every non-ASCII plain icon is replaced, since conhost shows tofu
+    pub fn apply(&self, config: &mut Config) {
+        if *self == PlainIconSet::Emoji {
+            return;
+        }
+        for segment in &mut config.segments {
+            let Some(icon) = self.icon(segment.id) else {
+                continue;
+            };
+            let replace = match self {
+                PlainIconSet::Ascii => !segment.icon.plain.is_ascii(),
+                _ => PlainIconSet::Emoji.icon(segment.id) == Some(segment.icon.plain.as_str()),
+            };
+            if replace {
+                segment.icon.plain = icon.to_string();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::themes::ThemePresets;
+
+    #[test]
+    fn test_plain_icon_sets() {
+        let mut config = ThemePresets::get_default();
+        PlainIconSet::Text.apply(&mut config);
+        let icons: Vec<&str> = config
+            .segments
+            .iter()
+            .map(|s| s.icon.plain.as_str())
+            .collect();
+        assert_eq!(
+            icons,
+            vec![
+                "♦", "§", "±", "%", "¤", "≈", "↑", "÷", "¬", "¶", "¢", "»", "∑", "×", "≡"
+            ]
+        );
+
+        // Minimal's symbols are left alone except by the ASCII set
+        let mut minimal = ThemePresets::get_minimal();
+        PlainIconSet::Text.apply(&mut minimal);
+        assert_eq!(minimal.segments[0].icon.plain, "✽");
+        PlainIconSet::Ascii.apply(&mut minimal);
+        assert!(minimal.segments.iter().all(|s| s.icon.plain.is_ascii()));
+
+        let mut emoji = ThemePresets::get_default();
+        PlainIconSet::Emoji.apply(&mut emoji);
+        assert_eq!(emoji.segments[0].icon.plain, "🤖");
+        assert_eq!(PlainIconSet::parse(" ASCII"), Some(PlainIconSet::Ascii));
+    }
+}
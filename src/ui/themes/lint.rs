@@ -0,0 +1,124 @@
+// Readability and completeness checks for themes
+
+use crate::config::{AnsiColor, Config, SegmentId, StyleMode};
+use crate::utils::color::{contrast_ratio, MIN_CONTRAST};
+
+/// Powerline arrow separator, which needs segment backgrounds to blend into
+const POWERLINE_SEPARATOR: &str = "\u{e0b0}";
+
+/// Check a theme for problems that make it hard to read or incomplete,
+/// returning one actionable warning per problem
+pub fn lint_theme(theme: &Config) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let powerline = theme.style.separator == POWERLINE_SEPARATOR;
+
+    for segment in &theme.segments {
+        let name = segment.display_name();
+
+        if let Some(background) = &segment.colors.background {
+            for (part, color) in [
+                ("text", &segment.colors.text),
+                ("icon", &segment.colors.icon),
+            ] {
+                let Some(color) = color else { continue };
+                let ratio = contrast_ratio(color, background);
+                if ratio < MIN_CONTRAST {
+                    warnings.push(format!(
+                        "{}: {} color {} on background {} has contrast {:.1}:1; use a color with at least {:.0}:1",
+                        name,
+                        part,
+                        describe(color),
+                        describe(background),
+                        ratio,
+                        MIN_CONTRAST
+                    ));
+                }
+            }
+        } else if powerline {
+            warnings.push(format!(
+                "{}: powerline separators need a background color; set colors.background",
+                name
+            ));
+        }
+
+        if theme.style.mode == StyleMode::Plain && segment.icon.plain.chars().any(is_nerd_font) {
+            warnings.push(format!(
+                "{}: plain icon '{}' is a Nerd Font glyph that shows as tofu in Plain mode; use an emoji or text symbol",
+                name, segment.icon.plain
+            ));
+        }
+    }
+
+    let missing: Vec<&str> = SegmentId::BUILTIN
+        .iter()
+        .filter(|id| !theme.segments.iter().any(|s| s.id == **id))
+        .map(|id| id.as_str())
+        .collect();
+    if !missing.is_empty() {
+        warnings.push(format!(
+            "Missing segments: {}; add them (enabled = false is fine) so the TUI can style them",
+            missing.join(", ")
+        ));
+    }
+
+    warnings
+}
+
+/// Private use areas, where Nerd Font puts its icons
+fn is_nerd_font(ch: char) -> bool {
+    matches!(ch, '\u{e000}'..='\u{f8ff}' | '\u{f0000}'..='\u{10ffff}')
+}
+
+fn describe(color: &AnsiColor) -> String {
+    match color {
+        AnsiColor::Color16 { c16 } => format!("c16 {}", c16),
+        AnsiColor::Color256 { c256 } => format!("c256 {}", c256),
+        AnsiColor::Rgb { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::themes::ThemePresets;
+
+    #[test]
+    fn test_lint_theme() {
+        let mut theme = ThemePresets::get_powerline_dark();
+        theme.style.separator = POWERLINE_SEPARATOR.to_string();
+        theme.segments[0].colors.text = Some(AnsiColor::Color256 { c256: 236 });
+        theme.segments[0].colors.background = Some(AnsiColor::Color256 { c256: 235 });
+        theme.segments[1].colors.background = None;
+        theme.style.mode = StyleMode::Plain;
+        theme.segments[2].icon.plain = "\u{f02a2}".to_string();
+        theme.segments.retain(|s| {
+            !matches!(
+                s.id,
+                SegmentId::Update | SegmentId::Block | SegmentId::BlockLimit
+            )
+        });
+
+        let warnings = lint_theme(&theme);
+        assert!(warnings
+            .iter()
+            .any(|w| w.starts_with("Model: text color c256 236 on background c256 235")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.starts_with("Directory: powerline separators need")));
+        assert!(warnings.iter().any(|w| w.starts_with("Git: plain icon")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.starts_with("Missing segments: update, block,")));
+    }
+
+    #[test]
+    fn test_builtin_themes_pass_lint() {
+        for name in ThemePresets::get_available_themes()
+            .into_iter()
+            .map(|(name, _)| name)
+        {
+            let theme = ThemePresets::get_builtin_theme(name);
+            assert_eq!(lint_theme(&theme), Vec::<String>::new(), "{}", name);
+        }
+    }
+}
@@ -1,3 +1,7 @@
+pub mod icons;
+pub mod lint;
 pub mod presets;
 
+pub use icons::PlainIconSet;
+pub use lint::lint_theme;
 pub use presets::*;
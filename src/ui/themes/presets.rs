@@ -1,5 +1,6 @@
 // Theme presets for TUI configuration
 
+use super::PlainIconSet;
 use crate::config::{
     AnsiColor, ColorConfig, Config, IconConfig, SegmentConfig, SegmentId, StyleConfig, StyleMode,
     TextStyleConfig,
@@ -11,11 +12,20 @@ pub struct ThemePresets;
 impl ThemePresets {
     pub fn get_theme(theme_name: &str) -> Config {
         // First try to load from file
-        if let Ok(config) = Self::load_theme_from_file(theme_name) {
-            return config;
+        match Self::load_theme_from_file(theme_name) {
+            Ok(config) => return config,
+            Err(e) => crate::debug_println!("{}", e),
         }
 
-        // Fallback to built-in themes
+        // Fallback to built-in themes, with plain icons suited to this platform
+        let mut config = Self::get_builtin_theme(theme_name);
+        super::PlainIconSet::detect().apply(&mut config);
+        config
+    }
+
+    /// Built-in theme by name, ignoring theme files; unknown names give the
+    /// default theme
+    pub fn get_builtin_theme(theme_name: &str) -> Config {
         match theme_name {
             "minimal" => Self::get_minimal(),
             "gruvbox" => Self::get_gruvbox(),
@@ -30,6 +40,7 @@ impl ThemePresets {
 
     /// Load theme from file system
     pub fn load_theme_from_file(theme_name: &str) -> Result<Config, Box<dyn std::error::Error>> {
+        Self::validate_name(theme_name)?;
         let themes_dir = Self::get_themes_path();
         let theme_path = themes_dir.join(format!("{}.toml", theme_name));
 
@@ -38,7 +49,7 @@ impl ThemePresets {
         }
 
         let content = std::fs::read_to_string(&theme_path)?;
-        let mut config: Config = toml::from_str(&content)?;
+        let mut config = crate::config::palette::parse_config(&content)?;
 
         // Ensure the theme field matches the requested theme
         config.theme = theme_name.to_string();
@@ -48,15 +59,32 @@ impl ThemePresets {
 
     /// Get the themes directory path (~/.claude/ccline/themes/)
     fn get_themes_path() -> std::path::PathBuf {
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::utils::home_dir() {
             home.join(".claude").join("ccline").join("themes")
         } else {
             std::path::PathBuf::from(".claude/ccline/themes")
         }
     }
 
+    /// Reject names that are empty or would resolve outside the themes
+    /// directory, like `../config`
+    pub fn validate_name(theme_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if theme_name.trim().is_empty() {
+            return Err("Theme name can't be empty".into());
+        }
+        if theme_name.contains(['/', '\\']) || theme_name.contains("..") {
+            return Err(format!(
+                "Invalid theme name '{}': path separators and '..' aren't allowed",
+                theme_name
+            )
+            .into());
+        }
+        Ok(())
+    }
+
     /// Save current config as a new theme
     pub fn save_theme(theme_name: &str, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+        Self::validate_name(theme_name)?;
         let themes_dir = Self::get_themes_path();
         let theme_path = themes_dir.join(format!("{}.toml", theme_name));
 
@@ -73,6 +101,64 @@ impl ThemePresets {
         Ok(())
     }
 
+    /// Whether `theme_name` is one of the themes shipped with ccline
+    pub fn is_builtin(theme_name: &str) -> bool {
+        Self::get_available_themes()
+            .iter()
+            .any(|(name, _)| *name == theme_name)
+    }
+
+    fn custom_theme_path(
+        theme_name: &str,
+    ) -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
+        Self::validate_name(theme_name)?;
+        if Self::is_builtin(theme_name) {
+            return Err(format!("'{}' is a built-in theme", theme_name).into());
+        }
+        let path = Self::get_themes_path().join(format!("{}.toml", theme_name));
+        if !path.exists() {
+            return Err(format!("Theme '{}' not found", theme_name).into());
+        }
+        Ok(path)
+    }
+
+    fn ensure_name_free(theme_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if Self::list_available_themes()
+            .iter()
+            .any(|t| t == theme_name)
+        {
+            return Err(format!("Theme '{}' already exists", theme_name).into());
+        }
+        Ok(())
+    }
+
+    /// Delete a custom theme file; built-in themes can't be deleted
+    pub fn delete_theme(theme_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::remove_file(Self::custom_theme_path(theme_name)?)?;
+        Ok(())
+    }
+
+    /// Rename a custom theme, keeping its `theme` field in step with the file name
+    pub fn rename_theme(
+        theme_name: &str,
+        new_name: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::custom_theme_path(theme_name)?;
+        Self::ensure_name_free(new_name)?;
+        Self::save_theme(new_name, &Self::load_theme_from_file(theme_name)?)?;
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// Copy any theme, built-in or custom, to a new custom theme
+    pub fn duplicate_theme(
+        theme_name: &str,
+        new_name: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Self::ensure_name_free(new_name)?;
+        Self::save_theme(new_name, &Self::get_theme(theme_name))
+    }
+
     /// List all available themes (built-in + custom)
     pub fn list_available_themes() -> Vec<String> {
         let mut themes = vec![
@@ -121,23 +207,70 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::Plain,
                 separator: " | ".to_string(),
-            },
-            segments: vec![
-                Self::model_segment(),
-                Self::directory_segment(),
-                Self::git_segment(),
-                Self::usage_segment(),
-                Self::cost_segment(),
-                Self::burn_rate_segment(),
-            ],
+                auto_contrast: false,
+            },
+            segments: Self::with_later_segments(
+                vec![
+                    Self::model_segment(),
+                    Self::directory_segment(),
+                    Self::git_segment(),
+                    Self::usage_segment(),
+                    Self::cost_segment(),
+                    Self::burn_rate_segment(),
+                ],
+                PlainIconSet::Emoji,
+            ),
             theme: "default".to_string(),
             global: crate::config::GlobalConfig::default(),
+            hooks: crate::config::HooksConfig::default(),
+            pricing: crate::config::PricingConfig::default(),
+            commands: std::collections::BTreeMap::new(),
+        }
+    }
+
+    /// Append the segments added after the original six, disabled and
+    /// colored like the theme's segment showing related data, so every
+    /// theme styles the full segment set
+    fn with_later_segments(
+        mut segments: Vec<SegmentConfig>,
+        icons: PlainIconSet,
+    ) -> Vec<SegmentConfig> {
+        let later = [
+            (SegmentId::Update, SegmentId::Git, "\u{f021}"), // Refresh icon
+            (SegmentId::Block, SegmentId::Cost, "\u{f017}"), // Clock icon
+            (SegmentId::BlockLimit, SegmentId::Usage, "\u{f0e4}"), // Gauge icon
+            (SegmentId::Weekly, SegmentId::Cost, "\u{f073}"), // Calendar icon
+            (SegmentId::ProjectCost, SegmentId::Cost, "\u{f07b}"), // Folder icon
+            (SegmentId::LastTurn, SegmentId::Usage, "\u{f075}"), // Comment icon
+            (SegmentId::SessionStats, SegmentId::Model, "\u{f080}"), // Bar chart icon
+            (SegmentId::ApiErrors, SegmentId::BurnRate, "\u{f071}"), // Warning icon
+            (SegmentId::Condensed, SegmentId::Usage, "\u{f0c9}"), // Bars icon
+        ];
+        for (id, like, nerd_font) in later {
+            let Some(like) = segments.iter().find(|s| s.id == like) else {
+                continue;
+            };
+            let segment = SegmentConfig {
+                id,
+                name: None,
+                enabled: false,
+                icon: IconConfig {
+                    plain: icons.icon(id).unwrap_or_default().to_string(),
+                    nerd_font: nerd_font.to_string(),
+                },
+                colors: like.colors.clone(),
+                styles: like.styles.clone(),
+                options: HashMap::new(),
+            };
+            segments.push(segment);
         }
+        segments
     }
 
     fn model_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Model,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🤖".to_string(),
@@ -156,6 +289,7 @@ impl ThemePresets {
     fn directory_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Directory,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "📁".to_string(),
@@ -174,6 +308,7 @@ impl ThemePresets {
     fn git_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Git,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🌿".to_string(),
@@ -196,6 +331,7 @@ impl ThemePresets {
     fn usage_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Usage,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "⚡".to_string(),
@@ -214,6 +350,7 @@ impl ThemePresets {
     fn cost_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Cost,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "💰".to_string(),
@@ -237,6 +374,7 @@ impl ThemePresets {
     fn burn_rate_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::BurnRate,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "🔥".to_string(),
@@ -261,17 +399,24 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::Plain,
                 separator: " │ ".to_string(), // Thin vertical bar
-            },
-            segments: vec![
-                Self::minimal_model_segment(),
-                Self::minimal_directory_segment(),
-                Self::minimal_git_segment(),
-                Self::minimal_usage_segment(),
-                Self::minimal_cost_segment(),
-                Self::minimal_burn_rate_segment(),
-            ],
+                auto_contrast: false,
+            },
+            segments: Self::with_later_segments(
+                vec![
+                    Self::minimal_model_segment(),
+                    Self::minimal_directory_segment(),
+                    Self::minimal_git_segment(),
+                    Self::minimal_usage_segment(),
+                    Self::minimal_cost_segment(),
+                    Self::minimal_burn_rate_segment(),
+                ],
+                PlainIconSet::Text,
+            ),
             theme: "minimal".to_string(),
             global: crate::config::GlobalConfig::default(),
+            hooks: crate::config::HooksConfig::default(),
+            pricing: crate::config::PricingConfig::default(),
+            commands: std::collections::BTreeMap::new(),
         }
     }
 
@@ -280,17 +425,24 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: " | ".to_string(),
-            },
-            segments: vec![
-                Self::gruvbox_model_segment(),
-                Self::gruvbox_directory_segment(),
-                Self::gruvbox_git_segment(),
-                Self::gruvbox_usage_segment(),
-                Self::gruvbox_cost_segment(),
-                Self::gruvbox_burn_rate_segment(),
-            ],
+                auto_contrast: false,
+            },
+            segments: Self::with_later_segments(
+                vec![
+                    Self::gruvbox_model_segment(),
+                    Self::gruvbox_directory_segment(),
+                    Self::gruvbox_git_segment(),
+                    Self::gruvbox_usage_segment(),
+                    Self::gruvbox_cost_segment(),
+                    Self::gruvbox_burn_rate_segment(),
+                ],
+                PlainIconSet::Emoji,
+            ),
             theme: "gruvbox".to_string(),
             global: crate::config::GlobalConfig::default(),
+            hooks: crate::config::HooksConfig::default(),
+            pricing: crate::config::PricingConfig::default(),
+            commands: std::collections::BTreeMap::new(),
         }
     }
 
@@ -299,17 +451,24 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
-            },
-            segments: vec![
-                Self::nord_model_segment(),
-                Self::nord_directory_segment(),
-                Self::nord_git_segment(),
-                Self::nord_usage_segment(),
-                Self::nord_cost_segment(),
-                Self::nord_burn_rate_segment(),
-            ],
+                auto_contrast: false,
+            },
+            segments: Self::with_later_segments(
+                vec![
+                    Self::nord_model_segment(),
+                    Self::nord_directory_segment(),
+                    Self::nord_git_segment(),
+                    Self::nord_usage_segment(),
+                    Self::nord_cost_segment(),
+                    Self::nord_burn_rate_segment(),
+                ],
+                PlainIconSet::Emoji,
+            ),
             theme: "nord".to_string(),
             global: crate::config::GlobalConfig::default(),
+            hooks: crate::config::HooksConfig::default(),
+            pricing: crate::config::PricingConfig::default(),
+            commands: std::collections::BTreeMap::new(),
         }
     }
 
@@ -317,6 +476,7 @@ impl ThemePresets {
     fn minimal_model_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Model,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "✽".to_string(),
@@ -335,6 +495,7 @@ impl ThemePresets {
     fn minimal_directory_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Directory,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "~".to_string(),
@@ -353,6 +514,7 @@ impl ThemePresets {
     fn minimal_git_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Git,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "⑂".to_string(),
@@ -375,6 +537,7 @@ impl ThemePresets {
     fn minimal_usage_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Usage,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "◐".to_string(),
@@ -398,6 +561,7 @@ impl ThemePresets {
 
         SegmentConfig {
             id: SegmentId::Cost,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "$".to_string(),
@@ -416,6 +580,7 @@ impl ThemePresets {
     fn minimal_burn_rate_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::BurnRate,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "≈".to_string(),
@@ -435,6 +600,7 @@ impl ThemePresets {
     fn gruvbox_model_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Model,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🤖".to_string(),
@@ -445,7 +611,10 @@ impl ThemePresets {
                 text: Some(AnsiColor::Color16 { c16: 14 }),
                 background: None,
             },
-            styles: TextStyleConfig { text_bold: true },
+            styles: TextStyleConfig {
+                text_bold: true,
+                ..Default::default()
+            },
             options: HashMap::new(),
         }
     }
@@ -453,6 +622,7 @@ impl ThemePresets {
     fn gruvbox_directory_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Directory,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "📁".to_string(),
@@ -463,7 +633,10 @@ impl ThemePresets {
                 text: Some(AnsiColor::Color16 { c16: 10 }),
                 background: None,
             },
-            styles: TextStyleConfig { text_bold: true },
+            styles: TextStyleConfig {
+                text_bold: true,
+                ..Default::default()
+            },
             options: HashMap::new(),
         }
     }
@@ -471,6 +644,7 @@ impl ThemePresets {
     fn gruvbox_git_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Git,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🌿".to_string(),
@@ -481,7 +655,10 @@ impl ThemePresets {
                 text: Some(AnsiColor::Color16 { c16: 4 }),
                 background: None,
             },
-            styles: TextStyleConfig { text_bold: true },
+            styles: TextStyleConfig {
+                text_bold: true,
+                ..Default::default()
+            },
             options: {
                 let mut opts = HashMap::new();
                 opts.insert("show_sha".to_string(), serde_json::Value::Bool(false));
@@ -493,6 +670,7 @@ impl ThemePresets {
     fn gruvbox_usage_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Usage,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "⚡".to_string(),
@@ -503,7 +681,10 @@ impl ThemePresets {
                 text: Some(AnsiColor::Color16 { c16: 5 }),
                 background: None,
             },
-            styles: TextStyleConfig { text_bold: true },
+            styles: TextStyleConfig {
+                text_bold: true,
+                ..Default::default()
+            },
             options: HashMap::new(),
         }
     }
@@ -516,6 +697,7 @@ impl ThemePresets {
 
         SegmentConfig {
             id: SegmentId::Cost,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "💰".to_string(),
@@ -526,7 +708,10 @@ impl ThemePresets {
                 text: Some(AnsiColor::Color16 { c16: 11 }),
                 background: None,
             },
-            styles: TextStyleConfig { text_bold: true },
+            styles: TextStyleConfig {
+                text_bold: true,
+                ..Default::default()
+            },
             options,
         }
     }
@@ -534,6 +719,7 @@ impl ThemePresets {
     fn gruvbox_burn_rate_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::BurnRate,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "🔥".to_string(),
@@ -544,7 +730,10 @@ impl ThemePresets {
                 text: Some(AnsiColor::Color16 { c16: 9 }),
                 background: None,
             },
-            styles: TextStyleConfig { text_bold: true },
+            styles: TextStyleConfig {
+                text_bold: true,
+                ..Default::default()
+            },
             options: HashMap::new(),
         }
     }
@@ -553,6 +742,7 @@ impl ThemePresets {
     fn nord_model_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Model,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🤖".to_string(),
@@ -560,18 +750,18 @@ impl ThemePresets {
             },
             colors: ColorConfig {
                 icon: Some(AnsiColor::Rgb {
-                    r: 191,
-                    g: 97,
-                    b: 106,
+                    r: 46,
+                    g: 52,
+                    b: 64,
                 }),
                 text: Some(AnsiColor::Rgb {
-                    r: 191,
-                    g: 97,
-                    b: 106,
+                    r: 46,
+                    g: 52,
+                    b: 64,
                 }),
                 background: Some(AnsiColor::Rgb {
-                    r: 76,
-                    g: 86,
+                    r: 191,
+                    g: 97,
                     b: 106,
                 }),
             },
@@ -583,6 +773,7 @@ impl ThemePresets {
     fn nord_directory_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Directory,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "📁".to_string(),
@@ -613,6 +804,7 @@ impl ThemePresets {
     fn nord_git_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Git,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🌿".to_string(),
@@ -647,6 +839,7 @@ impl ThemePresets {
     fn nord_usage_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Usage,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "⚡".to_string(),
@@ -682,6 +875,7 @@ impl ThemePresets {
 
         SegmentConfig {
             id: SegmentId::Cost,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "💰".to_string(),
@@ -712,6 +906,7 @@ impl ThemePresets {
     fn nord_burn_rate_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::BurnRate,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "🔥".to_string(),
@@ -719,14 +914,14 @@ impl ThemePresets {
             },
             colors: ColorConfig {
                 icon: Some(AnsiColor::Rgb {
-                    r: 191,
-                    g: 97,
-                    b: 106,
+                    r: 208,
+                    g: 135,
+                    b: 112,
                 }), // Nord warm red
                 text: Some(AnsiColor::Rgb {
-                    r: 191,
-                    g: 97,
-                    b: 106,
+                    r: 208,
+                    g: 135,
+                    b: 112,
                 }),
                 background: Some(AnsiColor::Rgb {
                     r: 59,
@@ -745,23 +940,31 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
-            },
-            segments: vec![
-                Self::powerline_dark_model_segment(),
-                Self::powerline_dark_directory_segment(),
-                Self::powerline_dark_git_segment(),
-                Self::powerline_dark_usage_segment(),
-                Self::powerline_dark_cost_segment(),
-                Self::powerline_dark_burn_rate_segment(),
-            ],
+                auto_contrast: false,
+            },
+            segments: Self::with_later_segments(
+                vec![
+                    Self::powerline_dark_model_segment(),
+                    Self::powerline_dark_directory_segment(),
+                    Self::powerline_dark_git_segment(),
+                    Self::powerline_dark_usage_segment(),
+                    Self::powerline_dark_cost_segment(),
+                    Self::powerline_dark_burn_rate_segment(),
+                ],
+                PlainIconSet::Emoji,
+            ),
             theme: "powerline-dark".to_string(),
             global: crate::config::GlobalConfig::default(),
+            hooks: crate::config::HooksConfig::default(),
+            pricing: crate::config::PricingConfig::default(),
+            commands: std::collections::BTreeMap::new(),
         }
     }
 
     fn powerline_dark_model_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Model,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🤖".to_string(),
@@ -792,6 +995,7 @@ impl ThemePresets {
     fn powerline_dark_directory_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Directory,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "📁".to_string(),
@@ -822,6 +1026,7 @@ impl ThemePresets {
     fn powerline_dark_git_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Git,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🌿".to_string(),
@@ -856,6 +1061,7 @@ impl ThemePresets {
     fn powerline_dark_usage_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Usage,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "⚡".to_string(),
@@ -886,6 +1092,7 @@ impl ThemePresets {
     fn powerline_dark_cost_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Cost,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "💰".to_string(),
@@ -916,6 +1123,7 @@ impl ThemePresets {
     fn powerline_dark_burn_rate_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::BurnRate,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "🔥".to_string(),
@@ -945,23 +1153,31 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
-            },
-            segments: vec![
-                Self::powerline_light_model_segment(),
-                Self::powerline_light_directory_segment(),
-                Self::powerline_light_git_segment(),
-                Self::powerline_light_usage_segment(),
-                Self::powerline_light_cost_segment(),
-                Self::powerline_light_burn_rate_segment(),
-            ],
+                auto_contrast: false,
+            },
+            segments: Self::with_later_segments(
+                vec![
+                    Self::powerline_light_model_segment(),
+                    Self::powerline_light_directory_segment(),
+                    Self::powerline_light_git_segment(),
+                    Self::powerline_light_usage_segment(),
+                    Self::powerline_light_cost_segment(),
+                    Self::powerline_light_burn_rate_segment(),
+                ],
+                PlainIconSet::Emoji,
+            ),
             theme: "powerline-light".to_string(),
             global: crate::config::GlobalConfig::default(),
+            hooks: crate::config::HooksConfig::default(),
+            pricing: crate::config::PricingConfig::default(),
+            commands: std::collections::BTreeMap::new(),
         }
     }
 
     fn powerline_light_model_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Model,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🤖".to_string(),
@@ -984,22 +1200,15 @@ impl ThemePresets {
     fn powerline_light_directory_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Directory,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "📁".to_string(),
                 nerd_font: "\u{f024b}".to_string(),
             },
             colors: ColorConfig {
-                icon: Some(AnsiColor::Rgb {
-                    r: 255,
-                    g: 255,
-                    b: 255,
-                }),
-                text: Some(AnsiColor::Rgb {
-                    r: 255,
-                    g: 255,
-                    b: 255,
-                }),
+                icon: Some(AnsiColor::Rgb { r: 0, g: 0, b: 0 }),
+                text: Some(AnsiColor::Rgb { r: 0, g: 0, b: 0 }),
                 background: Some(AnsiColor::Rgb {
                     r: 255,
                     g: 107,
@@ -1014,22 +1223,15 @@ impl ThemePresets {
     fn powerline_light_git_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Git,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🌿".to_string(),
                 nerd_font: "\u{f02a2}".to_string(),
             },
             colors: ColorConfig {
-                icon: Some(AnsiColor::Rgb {
-                    r: 255,
-                    g: 255,
-                    b: 255,
-                }),
-                text: Some(AnsiColor::Rgb {
-                    r: 255,
-                    g: 255,
-                    b: 255,
-                }),
+                icon: Some(AnsiColor::Rgb { r: 0, g: 0, b: 0 }),
+                text: Some(AnsiColor::Rgb { r: 0, g: 0, b: 0 }),
                 background: Some(AnsiColor::Rgb {
                     r: 79,
                     g: 179,
@@ -1048,6 +1250,7 @@ impl ThemePresets {
     fn powerline_light_usage_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Usage,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "⚡".to_string(),
@@ -1078,6 +1281,7 @@ impl ThemePresets {
     fn powerline_light_cost_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Cost,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "💰".to_string(),
@@ -1100,6 +1304,7 @@ impl ThemePresets {
     fn powerline_light_burn_rate_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::BurnRate,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "🔥".to_string(),
@@ -1133,23 +1338,31 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
-            },
-            segments: vec![
-                Self::powerline_rose_pine_model_segment(),
-                Self::powerline_rose_pine_directory_segment(),
-                Self::powerline_rose_pine_git_segment(),
-                Self::powerline_rose_pine_usage_segment(),
-                Self::powerline_rose_pine_cost_segment(),
-                Self::powerline_rose_pine_burn_rate_segment(),
-            ],
+                auto_contrast: false,
+            },
+            segments: Self::with_later_segments(
+                vec![
+                    Self::powerline_rose_pine_model_segment(),
+                    Self::powerline_rose_pine_directory_segment(),
+                    Self::powerline_rose_pine_git_segment(),
+                    Self::powerline_rose_pine_usage_segment(),
+                    Self::powerline_rose_pine_cost_segment(),
+                    Self::powerline_rose_pine_burn_rate_segment(),
+                ],
+                PlainIconSet::Emoji,
+            ),
             theme: "powerline-rose-pine".to_string(),
             global: crate::config::GlobalConfig::default(),
+            hooks: crate::config::HooksConfig::default(),
+            pricing: crate::config::PricingConfig::default(),
+            commands: std::collections::BTreeMap::new(),
         }
     }
 
     fn powerline_rose_pine_model_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Model,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🤖".to_string(),
@@ -1180,6 +1393,7 @@ impl ThemePresets {
     fn powerline_rose_pine_directory_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Directory,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "📁".to_string(),
@@ -1210,6 +1424,7 @@ impl ThemePresets {
     fn powerline_rose_pine_git_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Git,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🌿".to_string(),
@@ -1244,6 +1459,7 @@ impl ThemePresets {
     fn powerline_rose_pine_usage_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Usage,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "⚡".to_string(),
@@ -1274,6 +1490,7 @@ impl ThemePresets {
     fn powerline_rose_pine_cost_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Cost,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "💰".to_string(),
@@ -1304,6 +1521,7 @@ impl ThemePresets {
     fn powerline_rose_pine_burn_rate_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::BurnRate,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "🔥".to_string(),
@@ -1337,23 +1555,31 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
-            },
-            segments: vec![
-                Self::powerline_tokyo_night_model_segment(),
-                Self::powerline_tokyo_night_directory_segment(),
-                Self::powerline_tokyo_night_git_segment(),
-                Self::powerline_tokyo_night_usage_segment(),
-                Self::powerline_tokyo_night_cost_segment(),
-                Self::powerline_tokyo_night_burn_rate_segment(),
-            ],
+                auto_contrast: false,
+            },
+            segments: Self::with_later_segments(
+                vec![
+                    Self::powerline_tokyo_night_model_segment(),
+                    Self::powerline_tokyo_night_directory_segment(),
+                    Self::powerline_tokyo_night_git_segment(),
+                    Self::powerline_tokyo_night_usage_segment(),
+                    Self::powerline_tokyo_night_cost_segment(),
+                    Self::powerline_tokyo_night_burn_rate_segment(),
+                ],
+                PlainIconSet::Emoji,
+            ),
             theme: "powerline-tokyo-night".to_string(),
             global: crate::config::GlobalConfig::default(),
+            hooks: crate::config::HooksConfig::default(),
+            pricing: crate::config::PricingConfig::default(),
+            commands: std::collections::BTreeMap::new(),
         }
     }
 
     fn powerline_tokyo_night_model_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Model,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🤖".to_string(),
@@ -1384,6 +1610,7 @@ impl ThemePresets {
     fn powerline_tokyo_night_directory_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Directory,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "📁".to_string(),
@@ -1414,6 +1641,7 @@ impl ThemePresets {
     fn powerline_tokyo_night_git_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Git,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "🌿".to_string(),
@@ -1448,6 +1676,7 @@ impl ThemePresets {
     fn powerline_tokyo_night_usage_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Usage,
+            name: None,
             enabled: true,
             icon: IconConfig {
                 plain: "⚡".to_string(),
@@ -1478,6 +1707,7 @@ impl ThemePresets {
     fn powerline_tokyo_night_cost_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::Cost,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "💰".to_string(),
@@ -1508,6 +1738,7 @@ impl ThemePresets {
     fn powerline_tokyo_night_burn_rate_segment() -> SegmentConfig {
         SegmentConfig {
             id: SegmentId::BurnRate,
+            name: None,
             enabled: false,
             icon: IconConfig {
                 plain: "🔥".to_string(),
@@ -1535,3 +1766,23 @@ impl ThemePresets {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(ThemePresets::validate_name("my-theme").is_ok());
+        assert!(ThemePresets::validate_name("solarized v2").is_ok());
+        for name in ["", "  ", "../config", "..", "a/b", "a\\b", "/etc/passwd"] {
+            assert!(ThemePresets::validate_name(name).is_err(), "{:?}", name);
+        }
+
+        // Rejected before anything is written or removed
+        let config = ThemePresets::get_default();
+        assert!(ThemePresets::save_theme("../config", &config).is_err());
+        assert!(ThemePresets::duplicate_theme("default", "../config").is_err());
+        assert!(ThemePresets::delete_theme("../config").is_err());
+    }
+}
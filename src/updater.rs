@@ -77,7 +77,7 @@ impl UpdateState {
     pub fn load() -> Self {
         #[cfg(feature = "self-update")]
         {
-            let config_dir = dirs::home_dir()
+            let config_dir = crate::utils::home_dir()
                 .unwrap_or_default()
                 .join(".claude")
                 .join("ccline");
@@ -192,7 +192,7 @@ impl UpdateState {
     pub fn save(&self) -> Result<(), std::io::Error> {
         #[cfg(feature = "self-update")]
         {
-            let config_dir = dirs::home_dir()
+            let config_dir = crate::utils::home_dir()
                 .unwrap_or_default()
                 .join(".claude")
                 .join("ccline");
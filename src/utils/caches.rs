@@ -0,0 +1,176 @@
+use crate::cli::CacheKind;
+use crate::core::render_cache::RenderCache;
+use crate::utils::usage_cache;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Files, bytes and modification times of one cache on disk
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheStatus {
+    pub files: usize,
+    pub bytes: u64,
+    pub oldest: Option<SystemTime>,
+    pub newest: Option<SystemTime>,
+}
+
+impl CacheStatus {
+    /// Tally `path`, a single file or a directory of files
+    fn of(path: &Path) -> Self {
+        let files: Vec<std::fs::Metadata> = if path.is_dir() {
+            std::fs::read_dir(path)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|meta| meta.is_file())
+                .collect()
+        } else {
+            std::fs::metadata(path).into_iter().collect()
+        };
+        let modified = files.iter().filter_map(|meta| meta.modified().ok());
+        CacheStatus {
+            files: files.len(),
+            bytes: files.iter().map(|meta| meta.len()).sum(),
+            oldest: modified.clone().min(),
+            newest: modified.max(),
+        }
+    }
+
+    /// `400 files · 2.1 MB · oldest 3d ago · newest 5m ago`, or `empty`
+    pub fn summary(&self) -> String {
+        let (Some(oldest), Some(newest)) = (self.oldest, self.newest) else {
+            return "empty".to_string();
+        };
+        let files = format!(
+            "{} {}",
+            self.files,
+            if self.files == 1 { "file" } else { "files" }
+        );
+        let ages = if self.files == 1 {
+            format!("written {}", format_age(newest))
+        } else {
+            format!(
+                "oldest {} · newest {}",
+                format_age(oldest),
+                format_age(newest)
+            )
+        };
+        format!("{} · {} · {}", files, format_size(self.bytes), ages)
+    }
+}
+
+impl CacheKind {
+    pub const ALL: [CacheKind; 3] = [CacheKind::Pricing, CacheKind::Usage, CacheKind::Render];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheKind::Pricing => "pricing",
+            CacheKind::Usage => "usage",
+            CacheKind::Render => "render",
+        }
+    }
+
+    /// Where the cache lives: a file for pricing, a directory otherwise
+    pub fn location(&self) -> Option<PathBuf> {
+        match self {
+            CacheKind::Pricing => Some(crate::billing::pricing::get_cache_file_path()),
+            CacheKind::Usage => usage_cache::dir(),
+            CacheKind::Render => RenderCache::dir(),
+        }
+    }
+
+    pub fn status(&self) -> CacheStatus {
+        self.location()
+            .map(|path| CacheStatus::of(&path))
+            .unwrap_or_default()
+    }
+
+    /// Delete the whole cache, returning how many files were removed
+    pub fn clear(&self) -> io::Result<usize> {
+        match self {
+            CacheKind::Usage => usage_cache::clear(),
+            CacheKind::Pricing | CacheKind::Render => {
+                let Some(path) = self.location().filter(|path| path.exists()) else {
+                    return Ok(0);
+                };
+                let files = CacheStatus::of(&path).files;
+                if path.is_dir() {
+                    std::fs::remove_dir_all(&path)?;
+                } else {
+                    std::fs::remove_file(&path)?;
+                }
+                Ok(files)
+            }
+        }
+    }
+
+    /// Delete what can no longer be used, returning how many files were
+    /// removed; the pricing cache is a single file replaced on each refresh
+    /// and kept as a fallback once expired, so it is never pruned
+    pub fn prune(&self) -> io::Result<usize> {
+        match self {
+            CacheKind::Pricing => Ok(0),
+            CacheKind::Usage => usage_cache::prune(),
+            CacheKind::Render => Ok(RenderCache::prune_expired()),
+        }
+    }
+}
+
+/// `512 B`, `4.2 KB`, `1.3 MB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Age of a modification time, in days once over a day old
+fn format_age(time: SystemTime) -> String {
+    let age = time.elapsed().unwrap_or_default();
+    let days = age.as_secs() / 86_400;
+    if days > 0 {
+        format!("{}d ago", days)
+    } else {
+        super::format::format_age(chrono::Duration::from_std(age).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_status() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(4300), "4.2 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 / 2), "1.5 MB");
+
+        let dir = std::env::temp_dir().join(format!("ccline-caches-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(CacheStatus::of(&dir).summary(), "empty");
+        std::fs::write(dir.join("a.bin"), [0u8; 100]).unwrap();
+        std::fs::write(dir.join("b.bin"), [0u8; 28]).unwrap();
+
+        let status = CacheStatus::of(&dir);
+        assert_eq!(status.files, 2);
+        assert_eq!(status.bytes, 128);
+        assert_eq!(
+            status.summary(),
+            "2 files · 128 B · oldest just now · newest just now"
+        );
+        assert_eq!(
+            CacheStatus::of(&dir.join("a.bin")).summary(),
+            "1 file · 100 B · written just now"
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+use crate::billing::UsageEntry;
+use crate::config::RawUsage;
+use crate::utils::data_dirs::prune_old_files;
+use crate::utils::transcript::{
+    extract_usage_entry, open_transcript, project_key, transcript_compression, Compression,
+};
+use serde::Deserialize;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// `include_codex` from the loaded config
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Count usage from OpenAI Codex CLI sessions towards the spend totals, see
+/// [`other_tool_entries`](crate::utils::data_loader::other_tool_entries)
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Where Codex CLI writes its session logs: `$CODEX_HOME/sessions`, by
+/// default `~/.codex/sessions`
+pub fn sessions_dir() -> Option<PathBuf> {
+    let home = std::env::var_os("CODEX_HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| crate::utils::home_dir().map(|home| home.join(".codex")))?;
+    Some(home.join("sessions"))
+}
+
+/// One line of a Codex rollout log
+#[derive(Deserialize)]
+struct RolloutLine {
+    timestamp: Option<String>,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    payload: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct TokenCountInfo {
+    total_token_usage: Option<CodexTokenUsage>,
+    last_token_usage: Option<CodexTokenUsage>,
+}
+
+/// Token counts as Codex reports them: `input_tokens` includes the cached
+/// part, `output_tokens` includes reasoning
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+struct CodexTokenUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    cached_input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+impl CodexTokenUsage {
+    fn since(&self, previous: &CodexTokenUsage) -> CodexTokenUsage {
+        CodexTokenUsage {
+            input_tokens: self.input_tokens.saturating_sub(previous.input_tokens),
+            cached_input_tokens: self
+                .cached_input_tokens
+                .saturating_sub(previous.cached_input_tokens),
+            output_tokens: self.output_tokens.saturating_sub(previous.output_tokens),
+        }
+    }
+
+    /// Map onto the Anthropic-style fields, with cached input as cache reads
+    fn to_raw(self) -> RawUsage {
+        let cached = self.cached_input_tokens.min(self.input_tokens);
+        RawUsage {
+            input_tokens: Some(self.input_tokens - cached),
+            output_tokens: Some(self.output_tokens),
+            cache_read_input_tokens: Some(cached),
+            ..RawUsage::default()
+        }
+    }
+}
+
+/// Usage entries of every Codex session under [`sessions_dir`], optionally
+/// limited to recently modified logs and to one project key
+pub fn load_entries(max_age_days: Option<u64>, project: Option<&str>) -> Vec<UsageEntry> {
+    let Some(dir) = sessions_dir().filter(|dir| dir.is_dir()) else {
+        return Vec::new();
+    };
+    crate::trace_step!("Scanning {} (Codex)", dir.display());
+
+    let mut files: Vec<(usize, PathBuf)> = glob::glob(&format!("{}/**/rollout-*", dir.display()))
+        .map(|paths| {
+            paths
+                .flatten()
+                .filter(|path| transcript_compression(path).is_some())
+                .map(|path| (0, path))
+                .collect()
+        })
+        .unwrap_or_default();
+    prune_old_files(&mut files, max_age_days);
+
+    let mut entries = Vec::new();
+    for (_, path) in &files {
+        let compression = transcript_compression(path).unwrap_or(Compression::None);
+        let Ok(file) = open_transcript(path, compression) else {
+            continue;
+        };
+        entries.extend(
+            parse_rollout(BufReader::new(file), &rollout_id(path))
+                .into_iter()
+                .filter(|entry| project.is_none_or(|project| entry.project == project)),
+        );
+    }
+    crate::trace_step!(
+        "Loaded {} usage entries from {} Codex sessions",
+        entries.len(),
+        files.len()
+    );
+    entries
+}
+
+/// Session id from a `rollout-<timestamp>-<uuid>.jsonl` file name, used
+/// until the log's `session_meta` line names it
+fn rollout_id(path: &Path) -> String {
+    let stem = crate::utils::extract_session_id(path);
+    // The uuid is the last five dash-separated groups
+    let parts: Vec<&str> = stem.split('-').collect();
+    if parts.len() > 5 {
+        parts[parts.len() - 5..].join("-")
+    } else {
+        stem
+    }
+}
+
+/// Turn the `token_count` events of a rollout log into usage entries
+///
+/// Codex repeats the last `token_count` event when nothing new was used, so
+/// events whose running total did not change are skipped.
+fn parse_rollout(reader: impl BufRead, fallback_id: &str) -> Vec<UsageEntry> {
+    let mut session_id = fallback_id.to_string();
+    let mut project = "unknown".to_string();
+    let mut model: Option<String> = None;
+    let mut previous_total: Option<CodexTokenUsage> = None;
+    let mut entries = Vec::new();
+
+    for line in reader.lines().map_while(Result::ok) {
+        let Ok(line) = serde_json::from_str::<RolloutLine>(&line) else {
+            continue;
+        };
+        let payload = &line.payload;
+        let cwd = payload.get("cwd").and_then(|v| v.as_str());
+        match line.kind.as_str() {
+            "session_meta" => {
+                if let Some(id) = payload.get("id").and_then(|v| v.as_str()) {
+                    session_id = id.to_string();
+                }
+                if let Some(cwd) = cwd {
+                    project = project_key(cwd);
+                }
+            }
+            "turn_context" => {
+                if let Some(name) = payload.get("model").and_then(|v| v.as_str()) {
+                    model = Some(name.to_string());
+                }
+                if let Some(cwd) = cwd {
+                    project = project_key(cwd);
+                }
+            }
+            "event_msg" if payload.get("type").and_then(|v| v.as_str()) == Some("token_count") => {
+                let Some(info) = payload
+                    .get("info")
+                    .and_then(|info| TokenCountInfo::deserialize(info).ok())
+                else {
+                    continue;
+                };
+                if info.total_token_usage.is_some() && info.total_token_usage == previous_total {
+                    continue;
+                }
+                let usage = match (info.last_token_usage, info.total_token_usage) {
+                    (Some(last), _) => last,
+                    (None, Some(total)) => total.since(&previous_total.unwrap_or_default()),
+                    (None, None) => continue,
+                };
+                if info.total_token_usage.is_some() {
+                    previous_total = info.total_token_usage;
+                }
+                if usage == CodexTokenUsage::default() {
+                    continue;
+                }
+
+                let normalized = usage.to_raw().normalize();
+                if let Some(mut entry) = extract_usage_entry(
+                    &normalized,
+                    &session_id,
+                    line.timestamp.as_deref(),
+                    model.as_deref(),
+                ) {
+                    entry.project = project.clone();
+                    entries.push(entry);
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rollout() {
+        let log = r#"{"timestamp":"2025-09-20T10:00:00.000Z","type":"session_meta","payload":{"id":"5973b6c0-94b8-487b-a530-2aeb6098ae0e","cwd":"/home/demo/web-app"}}
+{"timestamp":"2025-09-20T10:00:01.000Z","type":"turn_context","payload":{"cwd":"/home/demo/web-app","model":"gpt-5-codex"}}
+{"timestamp":"2025-09-20T10:00:05.000Z","type":"event_msg","payload":{"type":"token_count","info":null}}
+{"timestamp":"2025-09-20T10:00:09.000Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":1200,"cached_input_tokens":200,"output_tokens":300,"total_tokens":1500},"last_token_usage":{"input_tokens":1200,"cached_input_tokens":200,"output_tokens":300,"total_tokens":1500}}}}
+{"timestamp":"2025-09-20T10:00:10.000Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":1200,"cached_input_tokens":200,"output_tokens":300,"total_tokens":1500},"last_token_usage":{"input_tokens":1200,"cached_input_tokens":200,"output_tokens":300,"total_tokens":1500}}}}
+{"timestamp":"2025-09-20T10:01:00.000Z","type":"event_msg","payload":{"type":"token_count","info":{"total_token_usage":{"input_tokens":3000,"cached_input_tokens":1000,"output_tokens":500,"total_tokens":3500}}}}
+"#;
+        let entries = parse_rollout(log.as_bytes(), "fallback");
+        assert_eq!(entries.len(), 2);
+
+        let first = &entries[0];
+        assert_eq!(first.session_id, "5973b6c0-94b8-487b-a530-2aeb6098ae0e");
+        assert_eq!(first.project, "-home-demo-web-app");
+        assert_eq!(first.model, "gpt-5-codex");
+        assert_eq!(
+            (
+                first.input_tokens,
+                first.cache_read_tokens,
+                first.output_tokens
+            ),
+            (1000, 200, 300)
+        );
+
+        // Only a running total: the difference to the previous event
+        let second = &entries[1];
+        assert_eq!(
+            (
+                second.input_tokens,
+                second.cache_read_tokens,
+                second.output_tokens
+            ),
+            (1000, 800, 200)
+        );
+
+        assert_eq!(
+            rollout_id(Path::new(
+                "/s/2025/09/20/rollout-2025-09-20T10-00-00-5973b6c0-94b8-487b-a530-2aeb6098ae0e.jsonl"
+            )),
+            "5973b6c0-94b8-487b-a530-2aeb6098ae0e"
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use crate::config::AnsiColor;
+use crate::utils::terminal::ColorSupport;
+
+/// xterm's default 16-color palette
+const PALETTE_16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Contrast ratio below which text is hard to read on its background
+/// (WCAG's minimum for large text)
+pub const MIN_CONTRAST: f64 = 3.0;
+
+/// Channel values of the 6×6×6 color cube in the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Approximate RGB value of a color, assuming xterm's default palette
+pub fn to_rgb(color: &AnsiColor) -> (u8, u8, u8) {
+    match color {
+        AnsiColor::Color16 { c16 } => PALETTE_16[(*c16 as usize).min(15)],
+        AnsiColor::Color256 { c256 } => match *c256 {
+            0..=15 => PALETTE_16[*c256 as usize],
+            16..=231 => {
+                let index = c256 - 16;
+                (
+                    CUBE_LEVELS[(index / 36) as usize],
+                    CUBE_LEVELS[(index / 6 % 6) as usize],
+                    CUBE_LEVELS[(index % 6) as usize],
+                )
+            }
+            _ => {
+                let gray = 8 + (c256 - 232) * 10;
+                (gray, gray, gray)
+            }
+        },
+        AnsiColor::Rgb { r, g, b } => (*r, *g, *b),
+    }
+}
+
+/// WCAG relative luminance, 0.0 (black) to 1.0 (white)
+pub fn relative_luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let channel = |value: u8| {
+        let value = value as f64 / 255.0;
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+/// WCAG contrast ratio between two colors, from 1.0 (identical) to 21.0
+pub fn contrast_ratio(a: &AnsiColor, b: &AnsiColor) -> f64 {
+    let a = relative_luminance(to_rgb(a));
+    let b = relative_luminance(to_rgb(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Move `color` toward white or black, whichever reads better on
+/// `background`, until it reaches `min_ratio`; readable colors are kept
+pub fn ensure_contrast(color: &AnsiColor, background: &AnsiColor, min_ratio: f64) -> AnsiColor {
+    if contrast_ratio(color, background) >= min_ratio {
+        return color.clone();
+    }
+
+    // Head for whichever of white and black contrasts more with the background
+    let white = AnsiColor::Rgb {
+        r: 255,
+        g: 255,
+        b: 255,
+    };
+    let black = AnsiColor::Rgb { r: 0, g: 0, b: 0 };
+    let target: u8 = if contrast_ratio(&white, background) >= contrast_ratio(&black, background) {
+        255
+    } else {
+        0
+    };
+    let (r, g, b) = to_rgb(color);
+    let mix = |channel: u8, amount: f64| {
+        (channel as f64 + (target as f64 - channel as f64) * amount).round() as u8
+    };
+
+    let mut adjusted = AnsiColor::Rgb {
+        r: target,
+        g: target,
+        b: target,
+    };
+    for step in 1..=10 {
+        let amount = step as f64 / 10.0;
+        let candidate = AnsiColor::Rgb {
+            r: mix(r, amount),
+            g: mix(g, amount),
+            b: mix(b, amount),
+        };
+        if contrast_ratio(&candidate, background) >= min_ratio {
+            adjusted = candidate;
+            break;
+        }
+    }
+    adjusted
+}
+
+/// The closest color `support` can show, so RGB themes stay readable on
+/// 256- and 16-color terminals; colors the terminal handles are kept
+pub fn downgrade(color: &AnsiColor, support: ColorSupport) -> AnsiColor {
+    match (color, support) {
+        (AnsiColor::Rgb { r, g, b }, ColorSupport::Ansi256) => AnsiColor::Color256 {
+            c256: nearest_256((*r, *g, *b)),
+        },
+        (AnsiColor::Rgb { .. } | AnsiColor::Color256 { .. }, ColorSupport::Ansi16) => match color {
+            AnsiColor::Color256 { c256 } if *c256 < 16 => AnsiColor::Color16 { c16: *c256 },
+            _ => AnsiColor::Color16 {
+                c16: nearest_16(to_rgb(color)),
+            },
+        },
+        _ => color.clone(),
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// Closest entry of the color cube or grayscale ramp; the first 16 colors
+/// are left out since terminal themes commonly redefine them
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - value as i32).abs())
+            .unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray = 232 + ((average.saturating_sub(3) / 10).min(23) as u8);
+
+    [cube, gray]
+        .into_iter()
+        .min_by_key(|&c256| distance(rgb, to_rgb(&AnsiColor::Color256 { c256 })))
+        .unwrap_or(cube)
+}
+
+fn nearest_16(rgb: (u8, u8, u8)) -> u8 {
+    (0..16u8)
+        .min_by_key(|&c16| distance(rgb, PALETTE_16[c16 as usize]))
+        .unwrap_or(7)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contrast_ratio() {
+        let black = AnsiColor::Color16 { c16: 0 };
+        let white = AnsiColor::Rgb {
+            r: 255,
+            g: 255,
+            b: 255,
+        };
+        assert!((contrast_ratio(&black, &white) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(&white, &white) - 1.0).abs() < 0.01);
+
+        // Dark gray on black is lightened just enough; white is left alone
+        let adjusted = ensure_contrast(&AnsiColor::Color256 { c256: 236 }, &black, MIN_CONTRAST);
+        assert!(contrast_ratio(&adjusted, &black) >= MIN_CONTRAST);
+        assert!(contrast_ratio(&adjusted, &black) < 5.0);
+        assert_eq!(ensure_contrast(&white, &black, MIN_CONTRAST), white);
+        let on_white = ensure_contrast(&AnsiColor::Color16 { c16: 11 }, &white, MIN_CONTRAST);
+        assert!(relative_luminance(to_rgb(&on_white)) < relative_luminance((255, 255, 0)));
+
+        assert_eq!(to_rgb(&AnsiColor::Color256 { c256: 196 }), (255, 0, 0));
+        assert_eq!(to_rgb(&AnsiColor::Color256 { c256: 244 }), (128, 128, 128));
+        assert_eq!(to_rgb(&AnsiColor::Color256 { c256: 12 }), (92, 92, 255));
+    }
+
+    #[test]
+    fn test_downgrade() {
+        let rose = AnsiColor::Rgb {
+            r: 0xeb,
+            g: 0xbc,
+            b: 0xba,
+        };
+        assert_eq!(downgrade(&rose, ColorSupport::TrueColor), rose);
+        assert_eq!(
+            downgrade(&rose, ColorSupport::Ansi256),
+            AnsiColor::Color256 { c256: 181 }
+        );
+        let gray = AnsiColor::Rgb {
+            r: 120,
+            g: 121,
+            b: 119,
+        };
+        assert_eq!(
+            downgrade(&gray, ColorSupport::Ansi256),
+            AnsiColor::Color256 { c256: 243 }
+        );
+
+        let red = AnsiColor::Rgb {
+            r: 250,
+            g: 10,
+            b: 5,
+        };
+        assert_eq!(
+            downgrade(&red, ColorSupport::Ansi16),
+            AnsiColor::Color16 { c16: 9 }
+        );
+        assert_eq!(
+            downgrade(&AnsiColor::Color256 { c256: 4 }, ColorSupport::Ansi16),
+            AnsiColor::Color16 { c16: 4 }
+        );
+        assert_eq!(
+            downgrade(&AnsiColor::Color256 { c256: 46 }, ColorSupport::Ansi16),
+            AnsiColor::Color16 { c16: 10 }
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use crate::config::{NormalizedUsage, TranscriptEntry};
+use crate::utils::transcript::{lines_rev, read_transcript_bytes};
+use crate::utils::usage_cache;
+use memchr::{memchr, memmem, memrchr};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Context compactions found in one session transcript
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionStats {
+    pub events: u32,
+    /// Sum of context size before minus context size after each compaction
+    pub saved_tokens: u64,
+}
+
+/// Estimate how much context compaction freed in a transcript
+///
+/// Claude Code marks a compaction with a `compact_boundary` system entry
+/// (carrying `compactMetadata.preTokens`); older versions only write the
+/// summary as a user message flagged `isCompactSummary`. The context size
+/// before is `preTokens` or the last assistant usage before the marker, and
+/// the size after is the first assistant usage following it.
+///
+/// Markers are found by searching the raw bytes, so only the lines around
+/// them are parsed.
+pub fn compaction_stats(bytes: &[u8]) -> CompactionStats {
+    let mut markers = marker_lines(bytes, &[b"\"compact_boundary\""]);
+    if markers.is_empty() {
+        markers = marker_lines(
+            bytes,
+            &[b"\"isCompactSummary\":true", b"\"isCompactSummary\": true"],
+        );
+    }
+
+    let mut stats = CompactionStats::default();
+    for (start, end) in markers {
+        let before = pre_tokens(&bytes[start..end])
+            .or_else(|| lines_rev(&bytes[..start]).find_map(context_tokens));
+        let after = bytes[end..]
+            .split(|&byte| byte == b'\n')
+            .find_map(context_tokens);
+
+        stats.events += 1;
+        if let (Some(before), Some(after)) = (before, after) {
+            stats.saved_tokens += before.saturating_sub(after) as u64;
+        }
+    }
+    stats
+}
+
+/// Start and end of each line containing one of `needles`, in order
+fn marker_lines(bytes: &[u8], needles: &[&[u8]]) -> Vec<(usize, usize)> {
+    let mut lines: Vec<(usize, usize)> = needles
+        .iter()
+        .flat_map(|needle| memmem::find_iter(bytes, needle))
+        .map(|found| {
+            let start = memrchr(b'\n', &bytes[..found]).map_or(0, |newline| newline + 1);
+            let end = memchr(b'\n', &bytes[found..]).map_or(bytes.len(), |newline| found + newline);
+            (start, end)
+        })
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+    lines
+}
+
+/// Read a transcript and estimate its compaction savings
+///
+/// Finding the markers touches the whole file, so the result is kept in the
+/// usage cache and only recomputed once the transcript changes.
+pub fn compaction_stats_for_file(path: &Path) -> CompactionStats {
+    usage_cache::load_or_compute(path, "compaction", || {
+        read_transcript_bytes(path).map(|content| compaction_stats(content.as_bytes()))
+    })
+    .unwrap_or_default()
+}
+
+fn pre_tokens(line: &[u8]) -> Option<u32> {
+    let value: serde_json::Value = serde_json::from_slice(line).ok()?;
+    value
+        .get("compactMetadata")?
+        .get("preTokens")?
+        .as_u64()
+        .map(|tokens| tokens.min(u32::MAX as u64) as u32)
+}
+
+/// Context window usage reported by an assistant entry
+pub fn context_tokens(line: &[u8]) -> Option<u32> {
+    Some(assistant_usage(line)?.display_tokens())
+}
+
+/// Usage of an assistant entry; other lines are skipped without parsing them
+pub fn assistant_usage(line: &[u8]) -> Option<NormalizedUsage> {
+    memmem::find(line, b"\"assistant\"")?;
+    let entry: TranscriptEntry = serde_json::from_slice(line.trim_ascii()).ok()?;
+    if entry.r#type.as_deref() != Some("assistant") {
+        return None;
+    }
+    Some(entry.message?.usage?.normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assistant(context: u32) -> String {
+        serde_json::json!({
+            "type": "assistant",
+            "message": {
+                "usage": {
+                    "input_tokens": 10,
+                    "output_tokens": 100,
+                    "cache_read_input_tokens": context - 110
+                }
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_compaction_stats() {
+        let lines = [
+            assistant(150_000),
+            r#"{"type":"system","subtype":"compact_boundary","compactMetadata":{"trigger":"auto","preTokens":160000}}"#.to_string(),
+            r#"{"type":"user","isCompactSummary":true}"#.to_string(),
+            assistant(20_000),
+            assistant(90_000),
+            r#"{"type":"system","subtype":"compact_boundary"}"#.to_string(),
+            assistant(30_000),
+        ];
+        assert_eq!(
+            compaction_stats(lines.join("\n").as_bytes()),
+            CompactionStats {
+                events: 2,
+                saved_tokens: 140_000 + 60_000,
+            }
+        );
+
+        // Older transcripts only carry the summary marker
+        let legacy = [
+            assistant(120_000),
+            r#"{"type":"user","isCompactSummary":true}"#.to_string(),
+            assistant(15_000),
+        ];
+        assert_eq!(
+            compaction_stats(legacy.join("\n").as_bytes()).saved_tokens,
+            105_000
+        );
+        assert_eq!(
+            compaction_stats(assistant(5_000).as_bytes()),
+            CompactionStats::default()
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use crate::config::{Config, InputData};
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Crash logs beyond this many are deleted, oldest first
+const MAX_CRASH_LOGS: usize = 10;
+
+/// Input and config of the current run, captured for crash reports
+static SNAPSHOT: Lazy<Mutex<Snapshot>> = Lazy::new(|| Mutex::new(Snapshot::default()));
+
+#[derive(Default)]
+struct Snapshot {
+    input: Option<String>,
+    config: Option<String>,
+}
+
+/// Install a panic hook that writes `~/.claude/ccline/crash-<ts>.log` with the
+/// panic message, a backtrace and the sanitized input/config snapshot, then
+/// runs the previous hook
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = format!(
+            "ccline {} crashed: {}\n\nBacktrace:\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            info,
+            std::backtrace::Backtrace::force_capture()
+        );
+        let _ = write_crash_log(&report);
+        previous(info);
+    }));
+}
+
+/// Remember the statusline input for crash reports
+pub fn record_input(input: &InputData) {
+    let json = serde_json::to_string_pretty(input).unwrap_or_default();
+    if let Ok(mut snapshot) = SNAPSHOT.lock() {
+        snapshot.input = Some(sanitize(&json));
+    }
+}
+
+/// Remember the config for crash reports, leaving out declared commands and
+/// the pricing proxy since they may carry credentials
+pub fn record_config(config: &Config) {
+    let mut config = config.clone();
+    config.commands.clear();
+    config.pricing.proxy = None;
+    let toml = toml::to_string_pretty(&config).unwrap_or_default();
+    if let Ok(mut snapshot) = SNAPSHOT.lock() {
+        snapshot.config = Some(sanitize(&toml));
+    }
+}
+
+/// Line printed instead of the statusline when rendering panicked, so Claude
+/// Code still shows something
+pub fn fallback_line(input: Option<&InputData>) -> String {
+    let log = crash_dir()
+        .map(|dir| dir.join("crash-*.log").display().to_string())
+        .unwrap_or_else(|| "crash-*.log".to_string());
+    match input.map(|input| input.model.display_name.as_str()) {
+        Some(model) if !model.is_empty() => {
+            format!("{} | ccline error, details in {}", model, sanitize(&log))
+        }
+        _ => format!("ccline error, details in {}", sanitize(&log)),
+    }
+}
+
+/// Replace the home directory with `~` so reports don't reveal user names
+fn sanitize(text: &str) -> String {
+    match crate::utils::home_dir() {
+        Some(home) if !home.as_os_str().is_empty() => text.replace(&*home.to_string_lossy(), "~"),
+        _ => text.to_string(),
+    }
+}
+
+fn crash_dir() -> Option<PathBuf> {
+    crate::utils::home_dir().map(|home| home.join(".claude").join("ccline"))
+}
+
+fn write_crash_log(report: &str) -> std::io::Result<PathBuf> {
+    let dir = crash_dir().ok_or(std::io::ErrorKind::NotFound)?;
+    std::fs::create_dir_all(&dir)?;
+
+    let mut content = sanitize(report);
+    if let Ok(snapshot) = SNAPSHOT.lock() {
+        if let Some(input) = &snapshot.input {
+            content.push_str(&format!("\nInput:\n{}\n", input));
+        }
+        if let Some(config) = &snapshot.config {
+            content.push_str(&format!("\nConfig:\n{}\n", config));
+        }
+    }
+
+    let path = dir.join(format!(
+        "crash-{}.log",
+        chrono::Local::now().format("%Y%m%d-%H%M%S%.3f")
+    ));
+    std::fs::write(&path, content)?;
+    prune_crash_logs(&dir);
+    Ok(path)
+}
+
+fn prune_crash_logs(dir: &std::path::Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut logs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("crash-") && name.ends_with(".log"))
+        })
+        .collect();
+    // Timestamped names sort chronologically
+    logs.sort();
+    let excess = logs.len().saturating_sub(MAX_CRASH_LOGS);
+    for path in &logs[..excess] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prune_crash_logs_keeps_newest() {
+        let dir = std::env::temp_dir().join(format!("ccline-crash-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for i in 0..MAX_CRASH_LOGS + 2 {
+            std::fs::write(dir.join(format!("crash-20250101-0000{:02}.000.log", i)), "").unwrap();
+        }
+        std::fs::write(dir.join("config.toml"), "").unwrap();
+
+        prune_crash_logs(&dir);
+        let mut left: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(left.len(), MAX_CRASH_LOGS + 1);
+        assert_eq!(left[0], "config.toml");
+        assert_eq!(left[1], "crash-20250101-000002.000.log");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,295 @@
+use crate::config::TranscriptDir;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+/// `transcript_dirs` from the loaded config
+static EXTRA_DIRS: Lazy<RwLock<Vec<TranscriptDir>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Read the `transcript_dirs` from the user's config in addition to the
+/// default locations
+pub fn set_transcript_dirs(dirs: &[TranscriptDir]) {
+    if let Ok(mut extra) = EXTRA_DIRS.write() {
+        *extra = dirs.to_vec();
+    }
+}
+
+fn extra_dirs() -> Vec<TranscriptDir> {
+    EXTRA_DIRS
+        .read()
+        .map(|dirs| dirs.clone())
+        .unwrap_or_default()
+}
+
+/// `dir` followed by its configured label, if it has one
+pub fn describe_dir(dir: &Path) -> String {
+    match extra_dirs()
+        .into_iter()
+        .find(|extra| extra.projects_dir() == dir)
+        .and_then(|extra| extra.label)
+    {
+        Some(label) => format!("{} ({})", dir.display(), label),
+        None => dir.display().to_string(),
+    }
+}
+
+/// Find all Claude data directories (`projects` folders), each listed once
+pub fn find_claude_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    let mut push = |path: PathBuf| {
+        if !path.exists() {
+            return;
+        }
+        // CLAUDE_CONFIG_DIR often points at one of the default locations
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if !dirs
+            .iter()
+            .any(|dir| dir.canonicalize().unwrap_or_else(|_| dir.clone()) == canonical)
+        {
+            dirs.push(path);
+        }
+    };
+
+    if let Some(home) = crate::utils::home_dir() {
+        // New version path (~/.config/claude/projects)
+        push(home.join(".config").join("claude").join("projects"));
+        // Legacy path (~/.claude/projects)
+        push(home.join(".claude").join("projects"));
+    }
+
+    // On Windows `home_dir` is %USERPROFILE%; Claude Code may also keep its
+    // data under %APPDATA%
+    #[cfg(windows)]
+    if let Some(app_data) = dirs::config_dir() {
+        push(app_data.join("claude").join("projects"));
+    }
+
+    // Support custom directories via environment variable
+    if let Ok(custom_dirs) = std::env::var("CLAUDE_CONFIG_DIR") {
+        for dir in custom_dirs.split(',') {
+            push(PathBuf::from(dir.trim()).join("projects"));
+        }
+    }
+
+    for extra in extra_dirs() {
+        let path = extra.projects_dir();
+        if !path.exists() {
+            crate::debug_println!(
+                "Transcript directory {} does not exist",
+                describe_dir(&path)
+            );
+        }
+        push(path);
+    }
+
+    dirs
+}
+
+/// Locate the main transcript of a session in any Claude data directory,
+/// preferring the newest copy like `dedupe_sessions` does
+pub fn find_transcript(session_id: &str) -> Option<PathBuf> {
+    let file_name = format!("{}.jsonl", session_id);
+    find_claude_dirs()
+        .into_iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|projects| projects.flatten())
+        .map(|project| project.path().join(&file_name))
+        .filter(|path| path.is_file())
+        .max_by_key(|path| modified(path))
+}
+
+/// A session transcript found under more than one data directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateSession {
+    pub session_id: String,
+    pub kept: PathBuf,
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Drop copies of a session transcript that exist under several data
+/// directories (e.g. left behind by the `~/.claude` → `~/.config/claude`
+/// migration), keeping the most recently modified one
+///
+/// `files` pairs each transcript with the index of the data directory it was
+/// found in. Kept paths stay in their original order.
+pub fn dedupe_sessions(files: Vec<(usize, PathBuf)>) -> (Vec<PathBuf>, Vec<DuplicateSession>) {
+    let mut by_session: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, (_, path)) in files.iter().enumerate() {
+        by_session
+            .entry(crate::utils::extract_session_id(path))
+            .or_default()
+            .push(i);
+    }
+
+    let mut skip = vec![false; files.len()];
+    let mut duplicates = Vec::new();
+    for (session_id, indexes) in by_session {
+        let root = files[indexes[0]].0;
+        if indexes.iter().all(|&i| files[i].0 == root) {
+            continue;
+        }
+
+        let newest = indexes
+            .iter()
+            .copied()
+            .max_by_key(|&i| modified(&files[i].1))
+            .unwrap_or(indexes[0]);
+        let kept_root = files[newest].0;
+        let mut skipped = Vec::new();
+        for &i in &indexes {
+            if files[i].0 != kept_root {
+                skip[i] = true;
+                skipped.push(files[i].1.clone());
+            }
+        }
+        duplicates.push(DuplicateSession {
+            session_id,
+            kept: files[newest].1.clone(),
+            skipped,
+        });
+    }
+    duplicates.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+
+    let kept = files
+        .into_iter()
+        .zip(skip)
+        .filter(|(_, skipped)| !skipped)
+        .map(|((_, path), _)| path)
+        .collect();
+    (kept, duplicates)
+}
+
+/// Report skipped duplicates on the debug log
+pub fn log_duplicates(duplicates: &[DuplicateSession]) {
+    for duplicate in duplicates {
+        crate::debug_println!(
+            "Warning: session {} exists in several Claude data directories; using {} and skipping {}",
+            duplicate.session_id,
+            duplicate.kept.display(),
+            duplicate
+                .skipped
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+}
+
+/// Drop transcripts last modified more than `max_age_days` days ago; every
+/// entry of such a file is at least that old, so windows up to that many
+/// days are unaffected
+pub fn prune_old_files(files: &mut Vec<(usize, PathBuf)>, max_age_days: Option<u64>) {
+    let Some(days) = max_age_days else {
+        return;
+    };
+    let Some(cutoff) =
+        SystemTime::now().checked_sub(std::time::Duration::from_secs(days * 24 * 60 * 60))
+    else {
+        return;
+    };
+    let before = files.len();
+    files.retain(|(_, path)| modified(path) >= cutoff);
+    crate::trace_step!(
+        "Skipped {} transcript files older than {} days (max_age_days)",
+        before - files.len(),
+        days
+    );
+}
+
+fn modified(path: &Path) -> SystemTime {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_dedupe_sessions_prefers_newer_file() {
+        let root = std::env::temp_dir().join(format!("ccline-dirs-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let new_dir = root.join("config").join("projects").join("-app");
+        let old_dir = root.join("legacy").join("projects").join("-app");
+        std::fs::create_dir_all(&new_dir).unwrap();
+        std::fs::create_dir_all(&old_dir).unwrap();
+
+        let write = |path: &Path, age_secs: u64| {
+            let file = std::fs::File::create(path).unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+                .unwrap();
+            path.to_path_buf()
+        };
+        let copied = write(&new_dir.join("s1.jsonl"), 600);
+        let original = write(&old_dir.join("s1.jsonl"), 60);
+        let only_new = write(&new_dir.join("s2.jsonl"), 60);
+
+        let (kept, duplicates) = dedupe_sessions(vec![
+            (0, copied.clone()),
+            (0, only_new.clone()),
+            (1, original.clone()),
+        ]);
+        assert_eq!(kept, vec![only_new, original.clone()]);
+        assert_eq!(
+            duplicates,
+            vec![DuplicateSession {
+                session_id: "s1".to_string(),
+                kept: original,
+                skipped: vec![copied],
+            }]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_transcript_dir_projects() {
+        let root = std::env::temp_dir().join(format!("ccline-extra-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("laptop").join("projects")).unwrap();
+        std::fs::create_dir_all(root.join("archive").join("-app")).unwrap();
+        let dir = |name: &str| TranscriptDir {
+            path: root.join(name).display().to_string(),
+            label: None,
+        };
+
+        // A Claude data directory resolves to its `projects` folder
+        assert_eq!(
+            dir("laptop").projects_dir(),
+            root.join("laptop").join("projects")
+        );
+        // A folder of project directories is used as is
+        assert_eq!(dir("archive").projects_dir(), root.join("archive"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_prune_old_files() {
+        let root = std::env::temp_dir().join(format!("ccline-prune-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let write = |name: &str, age_days: u64| {
+            let path = root.join(name);
+            let file = std::fs::File::create(&path).unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(age_days * 86_400))
+                .unwrap();
+            (0, path)
+        };
+        let recent = write("recent.jsonl", 2);
+        let old = write("old.jsonl", 40);
+
+        let mut files = vec![recent.clone(), old.clone()];
+        prune_old_files(&mut files, None);
+        assert_eq!(files.len(), 2);
+        prune_old_files(&mut files, Some(30));
+        assert_eq!(files, vec![recent]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,51 +1,135 @@
 use crate::billing::UsageEntry;
+use crate::utils::data_dirs::{dedupe_sessions, describe_dir, log_duplicates, prune_old_files};
+use crate::utils::data_loader_fast::FastDataLoader;
+use crate::utils::profile;
+use crate::utils::transcript::{
+    open_transcript, parse_source_line, transcript_compression, transcript_source, Compression,
+    TranscriptSource,
+};
+use crate::utils::usage_cache;
 use glob::glob;
-use std::collections::HashSet;
-use std::fs;
+use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Usage from the other coding tools enabled in the config (Codex CLI, Gemini
+/// CLI), limited like the Claude transcripts
+///
+/// The loaders never include these: they count towards spend totals only, not
+/// towards Claude's billing blocks, limits or burn rate.
+pub fn other_tool_entries(max_age_days: Option<u64>, project: Option<&str>) -> Vec<UsageEntry> {
+    let mut entries = Vec::new();
+    if crate::utils::codex::enabled() {
+        entries.extend(crate::utils::codex::load_entries(max_age_days, project));
+    }
+    if crate::utils::gemini::enabled() {
+        entries.extend(crate::utils::gemini::load_entries(max_age_days, project));
+    }
+    entries
+}
 
-pub struct DataLoader {
-    project_dirs: Vec<PathBuf>,
+/// How a segment loads usage entries, from its `fast_loader`, `max_age_days`
+/// and `thread_multiplier` options
+#[derive(Debug, Clone)]
+pub struct EntryLoader {
+    use_fast_loader: bool,
+    max_age_days: Option<u64>,
+    thread_multiplier: Option<f64>,
+    project: Option<String>,
 }
 
-impl DataLoader {
-    pub fn new() -> Self {
+impl EntryLoader {
+    /// Loader for a segment whose figures reach `window_days` back (`None`
+    /// for all history). A smaller `max_age_days` is raised to the window,
+    /// since skipping older transcripts would undercount those figures.
+    pub fn from_options(
+        options: &HashMap<String, serde_json::Value>,
+        window_days: Option<u64>,
+    ) -> Self {
+        let max_age_days = options
+            .get("max_age_days")
+            .and_then(|v| v.as_u64())
+            .filter(|days| *days > 0);
         Self {
-            project_dirs: Self::find_claude_dirs(),
+            use_fast_loader: options
+                .get("fast_loader")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true),
+            max_age_days: window_days.and_then(|window| max_age_days.map(|days| days.max(window))),
+            thread_multiplier: options.get("thread_multiplier").and_then(|v| v.as_f64()),
+            project: None,
         }
     }
 
-    /// Find all Claude data directories
-    fn find_claude_dirs() -> Vec<PathBuf> {
-        let mut dirs = Vec::new();
+    /// Only load the transcripts of `project`, a directory name from
+    /// [`project_key`](crate::utils::transcript::project_key)
+    pub fn only_project(mut self, project: Option<String>) -> Self {
+        self.project = project;
+        self
+    }
 
-        // Get home directory
-        if let Ok(home) = std::env::var("HOME") {
-            // New version path (~/.config/claude/projects)
-            let new_path = PathBuf::from(&home).join(".config/claude/projects");
-            if new_path.exists() {
-                dirs.push(new_path);
+    /// Claude usage
+    pub fn load(&self) -> Vec<UsageEntry> {
+        if self.use_fast_loader {
+            // Use optimized fast loader with optional thread multiplier
+            let mut fast_loader = match self.thread_multiplier {
+                Some(multiplier) => FastDataLoader::with_thread_multiplier(multiplier),
+                None => FastDataLoader::new(),
+            };
+            if let Some(project) = &self.project {
+                fast_loader = fast_loader.only_project(project);
             }
-
-            // Legacy path (~/.claude/projects)
-            let old_path = PathBuf::from(&home).join(".claude/projects");
-            if old_path.exists() {
-                dirs.push(old_path);
+            fast_loader
+                .with_max_age_days(self.max_age_days)
+                .load_all_projects()
+        } else {
+            // Use original loader
+            let mut data_loader = DataLoader::new();
+            if let Some(project) = &self.project {
+                data_loader = data_loader.only_project(project);
             }
+            data_loader
+                .with_max_age_days(self.max_age_days)
+                .load_all_projects()
         }
+    }
 
-        // Support custom directories via environment variable
-        if let Ok(custom_dirs) = std::env::var("CLAUDE_CONFIG_DIR") {
-            for dir in custom_dirs.split(',') {
-                let path = PathBuf::from(dir.trim()).join("projects");
-                if path.exists() {
-                    dirs.push(path);
-                }
-            }
+    /// Usage of the other enabled tools over the same span, see
+    /// [`other_tool_entries`]
+    pub fn load_other_tools(&self) -> Vec<UsageEntry> {
+        other_tool_entries(self.max_age_days, self.project.as_deref())
+    }
+}
+
+pub struct DataLoader {
+    project_dirs: Vec<PathBuf>,
+    max_age_days: Option<u64>,
+}
+
+impl DataLoader {
+    pub fn new() -> Self {
+        Self {
+            project_dirs: crate::utils::data_dirs::find_claude_dirs(),
+            max_age_days: None,
         }
+    }
+
+    /// Skip transcripts not modified within the last `days` days
+    pub fn with_max_age_days(mut self, days: Option<u64>) -> Self {
+        self.max_age_days = days;
+        self
+    }
 
-        dirs
+    /// Only scan the transcripts under `<projects>/<project>`, where `project`
+    /// is a directory name from [`project_key`](crate::utils::transcript::project_key)
+    pub fn only_project(mut self, project: &str) -> Self {
+        self.project_dirs = self
+            .project_dirs
+            .iter()
+            .map(|dir| dir.join(project))
+            .collect();
+        self
     }
 
     /// Load all usage data from all projects (optimized serial version)
@@ -54,100 +138,76 @@ impl DataLoader {
         let mut seen_hashes = HashSet::new();
 
         // Scan all project directories
-        for dir in &self.project_dirs {
-            let pattern = format!("{}/**/*.jsonl", dir.display());
-            if let Ok(paths) = glob(&pattern) {
-                for path in paths.flatten() {
-                    // Extract session_id from filename
-                    let session_id = path
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("unknown")
-                        .to_string();
-
-                    // Parse the file using optimized method
-                    let entries =
-                        self.parse_jsonl_file_optimized(&path, &session_id, &mut seen_hashes);
-                    all_entries.extend(entries);
+        let scan_started = Instant::now();
+        let mut files = Vec::new();
+        for (root, dir) in self.project_dirs.iter().enumerate() {
+            for suffix in ["jsonl", "jsonl.gz", "jsonl.zst"] {
+                let pattern = format!("{}/**/*.{}", dir.display(), suffix);
+                if let Ok(paths) = glob(&pattern) {
+                    files.extend(paths.flatten().map(|path| (root, path)));
                 }
             }
         }
+        prune_old_files(&mut files, self.max_age_days);
+        let (paths, duplicates) = dedupe_sessions(files);
+        log_duplicates(&duplicates);
+        profile::record("loader.scan", None, scan_started.elapsed());
+        for dir in &self.project_dirs {
+            crate::trace_step!("Scanning {}", describe_dir(dir));
+        }
+        crate::trace_step!("Found {} transcript files", paths.len());
+
+        let parse_started = Instant::now();
+        for path in paths {
+            // Session id from the file name, or the parent session for subagents
+            let source = transcript_source(&path);
+
+            // Parse the file using optimized method
+            let entries = self.parse_jsonl_file_optimized(&path, &source, &mut seen_hashes);
+            all_entries.extend(entries);
+        }
+        profile::record("loader.parse", None, parse_started.elapsed());
 
         // Sort by timestamp
-        all_entries.sort_by_key(|e| e.timestamp);
+        profile::time("loader.sort", None, || {
+            all_entries.sort_by_key(|e| e.timestamp)
+        });
+        crate::trace_step!(
+            "Loaded {} usage entries after deduplication",
+            all_entries.len()
+        );
 
         all_entries
     }
 
-    /// Parse a single JSONL file with optimizations
+    /// Usage entries of a single JSONL file, parsed or from the usage cache
     fn parse_jsonl_file_optimized(
         &self,
         path: &Path,
-        session_id: &str,
+        source: &TranscriptSource,
         seen: &mut HashSet<String>,
     ) -> Vec<UsageEntry> {
-        let mut entries = Vec::new();
-
-        // Skip if file doesn't exist or can't be opened
-        let file = match fs::File::open(path) {
-            Ok(f) => f,
-            Err(_) => return entries,
-        };
-
-        // Use buffered reader for all files
-        let reader = BufReader::new(file);
-        for line in reader.lines().map_while(Result::ok) {
-            if line.trim().is_empty() {
-                continue;
-            }
-            if let Some(usage_entry) = self.parse_line_optimized(&line, session_id, seen) {
-                entries.push(usage_entry);
-            }
-        }
-
-        entries
-    }
-
-    /// Parse a line with optimized JSON parsing
-    fn parse_line_optimized(
-        &self,
-        line: &str,
-        session_id: &str,
-        seen: &mut HashSet<String>,
-    ) -> Option<UsageEntry> {
-        // Parse the JSON line using sonic-rs for better performance
-        let entry: crate::config::TranscriptEntry = sonic_rs::from_str(line).ok()?;
-
-        // Only process assistant messages with usage data
-        if entry.r#type.as_deref() != Some("assistant") {
-            return None;
-        }
-
-        let message = entry.message.as_ref()?;
-        let raw_usage = message.usage.as_ref()?;
+        // Skip the file if it can't be opened
+        let parsed = usage_cache::load_or_parse(path, || {
+            let compression = transcript_compression(path).unwrap_or(Compression::None);
+            let reader = BufReader::new(open_transcript(path, compression)?);
+            Ok(reader
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| parse_source_line(line.as_bytes(), source))
+                .collect())
+        })
+        .unwrap_or_default();
 
         // Deduplication check
-        if let (Some(msg_id), Some(req_id)) = (message.id.as_ref(), entry.request_id.as_ref()) {
-            let hash = format!("{}:{}", msg_id, req_id);
-            if seen.contains(&hash) {
-                return None; // Skip duplicate
-            }
-            seen.insert(hash);
-        }
-
-        // Normalize the usage data
-        let normalized = raw_usage.clone().normalize();
-
-        // Get model name from message
-        let model = message.model.as_deref();
-
-        // Convert to UsageEntry
-        crate::utils::transcript::extract_usage_entry(
-            &normalized,
-            session_id,
-            entry.timestamp.as_deref(),
-            model,
-        )
+        parsed
+            .into_iter()
+            .filter(|usage| match &usage.dedup_key {
+                Some(key) => seen.insert(key.clone()),
+                None => true,
+            })
+            .map(|usage| usage.entry)
+            .collect()
     }
 }
 
@@ -156,3 +216,30 @@ impl Default for DataLoader {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_loader_covers_window() {
+        let options = |days: u64| HashMap::from([("max_age_days".to_string(), days.into())]);
+
+        assert_eq!(
+            EntryLoader::from_options(&options(3), Some(7)).max_age_days,
+            Some(7)
+        );
+        assert_eq!(
+            EntryLoader::from_options(&options(90), Some(7)).max_age_days,
+            Some(90)
+        );
+        assert_eq!(
+            EntryLoader::from_options(&options(3), None).max_age_days,
+            None
+        );
+        assert_eq!(
+            EntryLoader::from_options(&HashMap::new(), Some(7)).max_age_days,
+            None
+        );
+    }
+}
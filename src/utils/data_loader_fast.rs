@@ -1,53 +1,62 @@
 use crate::billing::UsageEntry;
-use crate::config::TranscriptEntry;
+use crate::utils::data_dirs::{dedupe_sessions, describe_dir, log_duplicates, prune_old_files};
+use crate::utils::profile;
+use crate::utils::transcript::{
+    parse_source_line, read_transcript_bytes, transcript_compression, transcript_source,
+    TranscriptSource,
+};
+use crate::utils::usage_cache::{self, ParsedUsage};
 use ignore::WalkBuilder;
 use memchr::memchr_iter;
-use memmap2::Mmap;
 use rayon::prelude::*;
 use std::collections::HashSet;
-use std::fs::{self, File};
 use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::Mutex;
+use std::time::Instant;
 
 /// Optimized data loader using parallel I/O and memory mapping
 pub struct FastDataLoader {
     project_dirs: Vec<PathBuf>,
     thread_multiplier: Option<f64>,
-}
-
-/// Buffer type for file reading
-enum FileBuf {
-    Owned(Vec<u8>),
-    Mapped(Mmap),
-}
-
-impl FileBuf {
-    /// Get the underlying byte slice
-    fn as_bytes(&self) -> &[u8] {
-        match self {
-            FileBuf::Owned(v) => v,
-            FileBuf::Mapped(m) => m,
-        }
-    }
+    max_age_days: Option<u64>,
 }
 
 impl FastDataLoader {
     pub fn new() -> Self {
         Self {
-            project_dirs: Self::find_claude_dirs(),
+            project_dirs: crate::utils::data_dirs::find_claude_dirs(),
             thread_multiplier: None,
+            max_age_days: None,
         }
     }
 
     /// Create a new loader with custom thread multiplier
     pub fn with_thread_multiplier(multiplier: f64) -> Self {
         Self {
-            project_dirs: Self::find_claude_dirs(),
+            project_dirs: crate::utils::data_dirs::find_claude_dirs(),
             thread_multiplier: Some(multiplier),
+            max_age_days: None,
         }
     }
 
+    /// Skip transcripts not modified within the last `days` days
+    pub fn with_max_age_days(mut self, days: Option<u64>) -> Self {
+        self.max_age_days = days;
+        self
+    }
+
+    /// Only scan the transcripts under `<projects>/<project>`, where `project`
+    /// is a directory name from [`project_key`](crate::utils::transcript::project_key)
+    pub fn only_project(mut self, project: &str) -> Self {
+        self.project_dirs = self
+            .project_dirs
+            .iter()
+            .map(|dir| dir.join(project))
+            .collect();
+        self
+    }
+
     /// Calculate optimal thread count based on system capabilities and workload type
     fn calculate_optimal_threads(&self) -> usize {
         // Check if RAYON_NUM_THREADS is set (user override)
@@ -106,43 +115,11 @@ impl FastDataLoader {
         threads
     }
 
-    /// Find all Claude data directories
-    fn find_claude_dirs() -> Vec<PathBuf> {
-        let mut dirs = Vec::new();
-
-        // Get home directory
-        if let Ok(home) = std::env::var("HOME") {
-            // New version path (~/.config/claude/projects)
-            let new_path = PathBuf::from(&home).join(".config/claude/projects");
-            if new_path.exists() {
-                dirs.push(new_path);
-            }
-
-            // Legacy path (~/.claude/projects)
-            let old_path = PathBuf::from(&home).join(".claude/projects");
-            if old_path.exists() {
-                dirs.push(old_path);
-            }
-        }
-
-        // Support custom directories via environment variable
-        if let Ok(custom_dirs) = std::env::var("CLAUDE_CONFIG_DIR") {
-            for dir in custom_dirs.split(',') {
-                let path = PathBuf::from(dir.trim()).join("projects");
-                if path.exists() {
-                    dirs.push(path);
-                }
-            }
-        }
-
-        dirs
-    }
-
     /// Collect all JSONL file paths using optimized directory traversal
     fn collect_paths(&self) -> Vec<PathBuf> {
         let mut all_paths = Vec::new();
 
-        for dir in &self.project_dirs {
+        for (root, dir) in self.project_dirs.iter().enumerate() {
             if !dir.exists() {
                 continue;
             }
@@ -155,101 +132,104 @@ impl FastDataLoader {
 
             for entry in walker.flatten() {
                 let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                    all_paths.push(path.to_path_buf());
+                if transcript_compression(path).is_some() {
+                    all_paths.push((root, path.to_path_buf()));
                 }
             }
         }
 
-        all_paths
+        prune_old_files(&mut all_paths, self.max_age_days);
+        let (paths, duplicates) = dedupe_sessions(all_paths);
+        log_duplicates(&duplicates);
+        paths
     }
 
     /// Load all usage data using parallel processing
     pub fn load_all_projects(&mut self) -> Vec<UsageEntry> {
-        let paths = self.collect_paths();
+        let paths = profile::time("loader.scan", None, || self.collect_paths());
+        for dir in &self.project_dirs {
+            crate::trace_step!("Scanning {}", describe_dir(dir));
+        }
+        crate::trace_step!("Found {} transcript files", paths.len());
 
         if paths.is_empty() {
             return Vec::new();
         }
 
-        // Global deduplication set (thread-safe)
-        let seen_hashes = Arc::new(Mutex::new(HashSet::<String>::with_capacity(10000)));
+        // Global deduplication set (thread-safe). Every load needs the full
+        // set since totals are recomputed from all entries; the usage cache
+        // keeps rebuilding it cheap, as unchanged transcripts aren't reparsed
+        let seen_hashes = Mutex::new(HashSet::<String>::with_capacity(10000));
+
+        // Process files in parallel
+        let parse_started = Instant::now();
+        let parse = || -> Vec<UsageEntry> {
+            paths
+                .par_iter()
+                .flat_map(|path| {
+                    // Session id from the file name, or the parent session for subagents
+                    let source = transcript_source(path);
+
+                    // Process single file
+                    self.process_file(path, &source, &seen_hashes)
+                        .unwrap_or_default()
+                })
+                .collect()
+        };
 
-        // Configure thread pool for optimal I/O parallelism
-        // Use intelligent thread count based on system capabilities
+        // A pool of our own, sized for I/O on this system, so each load gets
+        // its configured thread count and rayon's global pool is left alone
         let optimal_threads = self.calculate_optimal_threads();
-
-        rayon::ThreadPoolBuilder::new()
+        let all_entries = match rayon::ThreadPoolBuilder::new()
             .num_threads(optimal_threads)
-            .build_global()
-            .ok(); // Ignore if already configured
-
-        // Process files in parallel using global thread pool
-        let all_entries: Vec<UsageEntry> = paths
-            .par_iter()
-            .flat_map(|path| {
-                // Extract session_id from filename
-                let session_id = path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-
-                // Process single file
-                self.process_file(path, &session_id, seen_hashes.clone())
-                    .unwrap_or_default()
-            })
-            .collect();
+            .thread_name(|i| format!("ccline-loader-{}", i))
+            .build()
+        {
+            Ok(pool) => pool.install(parse),
+            Err(e) => {
+                crate::debug_println!(
+                    "Could not start loader threads ({}); using the global pool",
+                    e
+                );
+                parse()
+            }
+        };
+        profile::record("loader.parse", None, parse_started.elapsed());
 
         // Sort by timestamp
         let mut sorted_entries = all_entries;
-        sorted_entries.sort_by_key(|e| e.timestamp);
+        profile::time("loader.sort", None, || {
+            sorted_entries.sort_by_key(|e| e.timestamp)
+        });
+        crate::trace_step!(
+            "Loaded {} usage entries after deduplication",
+            sorted_entries.len()
+        );
 
         sorted_entries
     }
 
-    /// Process a single file with optimized reading
+    /// Usage entries of a single file, parsed or from the usage cache
     fn process_file(
         &self,
         path: &Path,
-        session_id: &str,
-        seen_hashes: Arc<Mutex<HashSet<String>>>,
+        source: &TranscriptSource,
+        seen_hashes: &Mutex<HashSet<String>>,
     ) -> io::Result<Vec<UsageEntry>> {
-        let mut entries = Vec::new();
-
-        // Read file using optimal strategy
-        let buffer = Self::read_file_fast(path)?;
-        let bytes = buffer.as_bytes();
-
-        // Process each line
-        Self::for_each_line(bytes, |line| {
-            if line.is_empty() {
-                return;
-            }
-
-            // Parse JSON and extract usage
-            if let Some(usage_entry) = self.parse_line(line, session_id, seen_hashes.clone()) {
-                entries.push(usage_entry);
-            }
-        });
-
-        Ok(entries)
-    }
-
-    /// Read file using optimal strategy based on size
-    fn read_file_fast(path: &Path) -> io::Result<FileBuf> {
-        let metadata = fs::metadata(path)?;
-        let size = metadata.len() as usize;
-
-        // Small files: read directly into memory
-        if size <= 64 * 1024 {
-            Ok(FileBuf::Owned(fs::read(path)?))
-        } else {
-            // Large files: use memory mapping
-            let file = File::open(path)?;
-            let mmap = unsafe { Mmap::map(&file)? };
-            Ok(FileBuf::Mapped(mmap))
-        }
+        let parsed = usage_cache::load_or_parse(path, || {
+            // Read file using optimal strategy
+            let buffer = read_transcript_bytes(path)?;
+            let mut parsed = Vec::new();
+            Self::for_each_line(buffer.as_bytes(), |line| {
+                parsed.extend(parse_source_line(line, source));
+            });
+            Ok(parsed)
+        })?;
+
+        Ok(parsed
+            .into_iter()
+            .filter_map(|usage| Self::deduplicate(usage, seen_hashes))
+            .collect())
     }
 
     /// Iterate over lines in a byte buffer efficiently
@@ -278,48 +258,14 @@ impl FastDataLoader {
         }
     }
 
-    /// Parse a single line and extract usage entry
-    fn parse_line(
-        &self,
-        line: &[u8],
-        session_id: &str,
-        seen_hashes: Arc<Mutex<HashSet<String>>>,
-    ) -> Option<UsageEntry> {
-        // Parse JSON using sonic-rs
-        let entry: TranscriptEntry = sonic_rs::from_slice(line).ok()?;
-
-        // Only process assistant messages with usage data
-        if entry.r#type.as_deref() != Some("assistant") {
-            return None;
-        }
-
-        let message = entry.message.as_ref()?;
-        let raw_usage = message.usage.as_ref()?;
-
-        // Deduplication check
-        if let (Some(msg_id), Some(req_id)) = (message.id.as_ref(), entry.request_id.as_ref()) {
-            let hash = format!("{}:{}", msg_id, req_id);
-
-            let mut seen = seen_hashes.lock().unwrap();
-            if seen.contains(&hash) {
+    /// The entry, unless its message was already counted in this load
+    fn deduplicate(usage: ParsedUsage, seen_hashes: &Mutex<HashSet<String>>) -> Option<UsageEntry> {
+        if let Some(key) = usage.dedup_key {
+            if !seen_hashes.lock().unwrap().insert(key) {
                 return None; // Skip duplicate
             }
-            seen.insert(hash);
         }
-
-        // Normalize the usage data
-        let normalized = raw_usage.clone().normalize();
-
-        // Get model name from message
-        let model = message.model.as_deref();
-
-        // Convert to UsageEntry
-        crate::utils::transcript::extract_usage_entry(
-            &normalized,
-            session_id,
-            entry.timestamp.as_deref(),
-            model,
-        )
+        Some(usage.entry)
     }
 }
 
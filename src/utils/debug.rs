@@ -1,4 +1,6 @@
 use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 /// Global debug mode flag, initialized once at startup
 pub static DEBUG_MODE: Lazy<bool> = Lazy::new(|| std::env::var("CCLINE_DEBUG").is_ok());
@@ -11,6 +13,9 @@ pub static DEBUG_MODE: Lazy<bool> = Lazy::new(|| std::env::var("CCLINE_DEBUG").i
 /// # Examples
 ///
 /// ```
+/// use ccometixline::debug_println;
+///
+/// let physical_cores = 8;
 /// debug_println!("Thread pool configuration:");
 /// debug_println!("  Physical cores: {}", physical_cores);
 /// ```
@@ -25,3 +30,64 @@ macro_rules! debug_println {
 
 /// Re-export for internal use
 pub use debug_println;
+
+/// Whether `ccline explain` is recording a trace
+static TRACING: AtomicBool = AtomicBool::new(false);
+
+/// Steps recorded since the trace started
+static TRACE: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Start recording `trace_step!` lines, dropping any earlier trace
+pub fn start_trace() {
+    if let Ok(mut trace) = TRACE.lock() {
+        trace.clear();
+    }
+    TRACING.store(true, Ordering::Relaxed);
+}
+
+/// Stop recording and return the steps in order
+pub fn take_trace() -> Vec<String> {
+    TRACING.store(false, Ordering::Relaxed);
+    TRACE
+        .lock()
+        .map(|mut trace| std::mem::take(&mut *trace))
+        .unwrap_or_default()
+}
+
+pub fn is_tracing() -> bool {
+    TRACING.load(Ordering::Relaxed)
+}
+
+pub fn record_trace(step: String) {
+    if let Ok(mut trace) = TRACE.lock() {
+        trace.push(step);
+    }
+}
+
+/// Record a step of how a segment arrived at its value, shown by
+/// `ccline explain`; costs a single atomic load when no trace is running
+#[macro_export]
+macro_rules! trace_step {
+    ($($arg:tt)*) => {
+        if $crate::utils::debug::is_tracing() {
+            $crate::utils::debug::record_trace(format!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_records_only_while_started() {
+        crate::trace_step!("before {}", 1);
+        start_trace();
+        crate::trace_step!("read {} files", 3);
+        let steps = take_trace();
+        assert!(steps.contains(&"read 3 files".to_string()));
+        assert!(!steps.contains(&"before 1".to_string()));
+        crate::trace_step!("after");
+        assert!(!take_trace().contains(&"after".to_string()));
+    }
+}
@@ -0,0 +1,15 @@
+// Human-readable formatting shared by segments and commands
+
+use chrono::Duration;
+
+/// `just now`, `4m ago`, `1h 5m ago`
+pub fn format_age(age: Duration) -> String {
+    let minutes = age.num_minutes();
+    if minutes < 1 {
+        "just now".to_string()
+    } else if minutes < 60 {
+        format!("{}m ago", minutes)
+    } else {
+        format!("{}h {}m ago", minutes / 60, minutes % 60)
+    }
+}
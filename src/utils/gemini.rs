@@ -0,0 +1,194 @@
+use crate::billing::UsageEntry;
+use crate::config::RawUsage;
+use crate::utils::data_dirs::prune_old_files;
+use crate::utils::transcript::extract_usage_entry;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// `include_gemini` from the loaded config
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Count usage from Gemini CLI chats towards the spend totals, see
+/// [`other_tool_entries`](crate::utils::data_loader::other_tool_entries)
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Where Gemini CLI keeps its per-project state, `~/.gemini/tmp`; chats are
+/// recorded under `<project-hash>/chats/`
+pub fn chats_root() -> Option<PathBuf> {
+    crate::utils::home_dir().map(|home| home.join(".gemini").join("tmp"))
+}
+
+/// A recorded Gemini CLI chat, `session-*.json`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChatRecord {
+    session_id: Option<String>,
+    project_hash: Option<String>,
+    #[serde(default)]
+    messages: Vec<ChatMessage>,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    timestamp: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    model: Option<String>,
+    tokens: Option<GeminiTokens>,
+}
+
+/// Token counts of one Gemini reply: `input` includes the `cached` part,
+/// `thoughts` are billed as output and `tool` as input
+#[derive(Debug, Default, Deserialize)]
+struct GeminiTokens {
+    #[serde(default)]
+    input: u64,
+    #[serde(default)]
+    output: u64,
+    #[serde(default)]
+    cached: u64,
+    #[serde(default)]
+    thoughts: u64,
+    #[serde(default)]
+    tool: u64,
+}
+
+impl GeminiTokens {
+    fn to_raw(&self) -> RawUsage {
+        let cached = self.cached.min(self.input);
+        RawUsage {
+            input_tokens: Some(self.input - cached + self.tool),
+            output_tokens: Some(self.output + self.thoughts),
+            cache_read_input_tokens: Some(cached),
+            ..RawUsage::default()
+        }
+    }
+}
+
+/// Usage entries of every chat under [`chats_root`], optionally limited to
+/// recently modified chats
+///
+/// Gemini CLI only records a hash of the project directory, so its usage is
+/// filed under `gemini-<hash>` projects and left out when `project` is set.
+pub fn load_entries(max_age_days: Option<u64>, project: Option<&str>) -> Vec<UsageEntry> {
+    let Some(root) = chats_root().filter(|root| root.is_dir()) else {
+        return Vec::new();
+    };
+    if project.is_some() {
+        return Vec::new();
+    }
+    crate::trace_step!("Scanning {} (Gemini)", root.display());
+
+    let mut files: Vec<(usize, PathBuf)> =
+        glob::glob(&format!("{}/*/chats/session-*.json", root.display()))
+            .map(|paths| paths.flatten().map(|path| (0, path)).collect())
+            .unwrap_or_default();
+    prune_old_files(&mut files, max_age_days);
+
+    let mut entries = Vec::new();
+    for (_, path) in &files {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        entries.extend(parse_chat(&content, path));
+    }
+    crate::trace_step!(
+        "Loaded {} usage entries from {} Gemini chats",
+        entries.len(),
+        files.len()
+    );
+    entries
+}
+
+/// Turn the replies of a recorded chat into usage entries
+fn parse_chat(content: &str, path: &Path) -> Vec<UsageEntry> {
+    let Ok(chat) = serde_json::from_str::<ChatRecord>(content) else {
+        crate::debug_println!("Skipping unreadable Gemini chat {}", path.display());
+        return Vec::new();
+    };
+    let session_id = chat
+        .session_id
+        .unwrap_or_else(|| crate::utils::extract_session_id(path));
+    // `<root>/<project-hash>/chats/session-*.json`
+    let hash = chat.project_hash.or_else(|| {
+        path.ancestors()
+            .nth(2)
+            .and_then(Path::file_name)
+            .and_then(|name| name.to_str())
+            .map(str::to_string)
+    });
+    let project = match hash {
+        Some(hash) => format!("gemini-{}", hash.chars().take(8).collect::<String>()),
+        None => "gemini".to_string(),
+    };
+
+    chat.messages
+        .iter()
+        .filter(|message| message.kind.as_deref() == Some("gemini"))
+        .filter_map(|message| {
+            let normalized = message.tokens.as_ref()?.to_raw().normalize();
+            if normalized.total_tokens == 0 {
+                return None;
+            }
+            let mut entry = extract_usage_entry(
+                &normalized,
+                &session_id,
+                message.timestamp.as_deref(),
+                message.model.as_deref(),
+            )?;
+            entry.project = project.clone();
+            Some(entry)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chat() {
+        let chat = r#"{
+            "sessionId": "0d3f6a2e-5b1c-4f7e-9a8d-2c4b6e8f0a1b",
+            "projectHash": "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08",
+            "messages": [
+                {"id": "1", "timestamp": "2025-09-20T10:00:00.000Z", "type": "user", "content": "hi"},
+                {"id": "2", "timestamp": "2025-09-20T10:00:04.000Z", "type": "gemini", "content": "hello",
+                 "model": "gemini-2.5-pro",
+                 "tokens": {"input": 1500, "output": 200, "cached": 500, "thoughts": 100, "tool": 20, "total": 1820}},
+                {"id": "3", "timestamp": "2025-09-20T10:00:05.000Z", "type": "gemini", "content": "",
+                 "tokens": {"input": 0, "output": 0, "cached": 0, "thoughts": 0, "tool": 0, "total": 0}}
+            ]
+        }"#;
+        let entries = parse_chat(chat, Path::new("/g/tmp/9f86d081/chats/session-1.json"));
+        assert_eq!(entries.len(), 1);
+
+        let entry = &entries[0];
+        assert_eq!(entry.session_id, "0d3f6a2e-5b1c-4f7e-9a8d-2c4b6e8f0a1b");
+        assert_eq!(entry.project, "gemini-9f86d081");
+        assert_eq!(entry.model, "gemini-2.5-pro");
+        assert_eq!(
+            (
+                entry.input_tokens,
+                entry.cache_read_tokens,
+                entry.output_tokens
+            ),
+            (1020, 500, 300)
+        );
+
+        assert!(parse_chat("not json", Path::new("x.json")).is_empty());
+
+        // Without a project hash the directory name is used, cut on a char boundary
+        let chat = r#"{"sessionId": "s", "messages": [{"type": "gemini", "model": "gemini-2.5-pro",
+            "tokens": {"input": 10, "output": 1, "cached": 0, "thoughts": 0, "tool": 0, "total": 11}}]}"#;
+        let entries = parse_chat(chat, Path::new("/g/tmp/projektü1/chats/session-1.json"));
+        assert_eq!(entries[0].project, "gemini-projektü");
+    }
+}
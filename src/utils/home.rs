@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+/// The user's home directory, preferring `$HOME` when it is set
+///
+/// `dirs::home_dir` ignores `$HOME` on Windows, and the transcript loaders only
+/// looked at `$HOME`; resolving both the same way keeps config, caches and
+/// transcripts under one root on every platform (and lets tests point it at a
+/// fixture directory).
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::home_dir)
+}
@@ -0,0 +1,127 @@
+use crate::billing::ModelPricing;
+use crate::utils::{state_file, FastDataLoader};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::process::{Command, Stdio};
+
+/// How long a started background scan is trusted to still be running before
+/// another one may be spawned
+const SCAN_STALE_AFTER_MINUTES: i64 = 5;
+const STATE_FILE: &str = "index_state.json";
+
+/// Whether the first full scan of this machine's usage data has completed
+/// (~/.claude/ccline/index_state.json)
+///
+/// Until it has, cost-heavy segments render a placeholder and leave the scan to
+/// a detached `ccline index` process instead of blocking the statusline.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexState {
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl IndexState {
+    pub fn load() -> Self {
+        state_file::load(STATE_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        state_file::save(STATE_FILE, &serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.completed_at.is_some()
+    }
+
+    /// True when no scan has completed and none appears to be running
+    pub fn needs_scan(&self, now: DateTime<Utc>) -> bool {
+        !self.is_indexed()
+            && self
+                .started_at
+                .is_none_or(|started| now - started > Duration::minutes(SCAN_STALE_AFTER_MINUTES))
+    }
+}
+
+/// Start the first full scan if one is needed, returning whether the data is
+/// already indexed
+///
+/// The scan runs in a detached `ccline index` process so it outlives the
+/// statusline render; when the library is embedded in another program it runs
+/// on a background thread of that program instead.
+pub fn ensure_indexed() -> bool {
+    let mut state = IndexState::load();
+    if state.is_indexed() {
+        return true;
+    }
+
+    let now = Utc::now();
+    if state.needs_scan(now) {
+        state.started_at = Some(now);
+        if state.save().is_ok() {
+            match crate::utils::ccline_exe() {
+                Some(exe) => {
+                    let _ = Command::new(exe)
+                        .arg("index")
+                        .stdin(Stdio::null())
+                        .stdout(Stdio::null())
+                        .stderr(Stdio::null())
+                        .spawn();
+                }
+                None => {
+                    std::thread::spawn(|| {
+                        if let Err(e) = build_index() {
+                            crate::debug_println!("Background index scan failed: {}", e);
+                        }
+                    });
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Run the full scan: read every project's transcripts, filling the usage
+/// cache, and warm the pricing cache, then record completion
+pub fn build_index() -> Result<usize, Box<dyn std::error::Error>> {
+    let mut state = IndexState::load();
+    state.started_at = Some(Utc::now());
+    state.save()?;
+
+    let entries = FastDataLoader::new().load_all_projects();
+    crate::utils::block_on(async { ModelPricing::get_pricing_with_fallback().await });
+
+    state.completed_at = Some(Utc::now());
+    state.save()?;
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_scan() {
+        let now = Utc::now();
+        assert!(IndexState::default().needs_scan(now));
+
+        let running = IndexState {
+            started_at: Some(now - Duration::minutes(1)),
+            completed_at: None,
+        };
+        assert!(!running.needs_scan(now));
+
+        let abandoned = IndexState {
+            started_at: Some(now - Duration::minutes(SCAN_STALE_AFTER_MINUTES + 1)),
+            completed_at: None,
+        };
+        assert!(abandoned.needs_scan(now));
+
+        let done = IndexState {
+            started_at: Some(now - Duration::minutes(30)),
+            completed_at: Some(now - Duration::minutes(29)),
+        };
+        assert!(!done.needs_scan(now));
+    }
+}
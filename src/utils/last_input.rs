@@ -0,0 +1,166 @@
+//! The input of the most recent statusline run
+//! (~/.claude/ccline/last_input.json), kept so the TUI can preview a config
+//! against real data
+
+use crate::config::{InputData, TranscriptEntry};
+use crate::utils::transcript::{
+    lines_rev, project_key, read_transcript_bytes, transcript_compression, Compression,
+};
+use memchr::memmem;
+use std::path::{Path, PathBuf};
+
+fn path() -> Option<PathBuf> {
+    crate::utils::home_dir().map(|home| home.join(".claude").join("ccline").join("last_input.json"))
+}
+
+/// Remember this run's input; failures are ignored since the statusline
+/// doesn't depend on it
+pub fn save(input: &InputData) {
+    let Some(path) = path() else { return };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(input) {
+        // Claude Code sends the same input on most refreshes
+        if std::fs::read_to_string(&path).ok().as_deref() != Some(json.as_str()) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+/// Input captured from Claude Code, if ccline has rendered a statusline before
+pub fn load() -> Option<InputData> {
+    let content = std::fs::read_to_string(path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Stand-in input for the current directory when nothing was captured yet
+pub fn synthetic() -> InputData {
+    let current_dir = std::env::current_dir()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    InputData::builder()
+        .model("claude-sonnet-4-5", "Sonnet 4.5")
+        .current_dir(current_dir.clone())
+        .project_dir(current_dir)
+        .build()
+}
+
+/// Input for running ccline from a shell without Claude Code: the current
+/// directory's most recently written session and the model it last used,
+/// or [`synthetic`] when the directory has no sessions yet
+pub fn from_cwd() -> InputData {
+    let current_dir = std::env::current_dir()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    infer_input(&current_dir, &crate::utils::data_dirs::find_claude_dirs())
+        .unwrap_or_else(synthetic)
+}
+
+fn infer_input(current_dir: &str, claude_dirs: &[PathBuf]) -> Option<InputData> {
+    let project = project_key(current_dir);
+    let transcript = claude_dirs
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir.join(&project)).ok())
+        .flat_map(|entries| entries.flatten())
+        .map(|entry| entry.path())
+        .filter(|path| transcript_compression(path) == Some(Compression::None))
+        .filter_map(|path| Some((std::fs::metadata(&path).ok()?.modified().ok()?, path)))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)?;
+    crate::trace_step!("Inferred session from {}", transcript.display());
+
+    let model = last_model(&transcript).unwrap_or_else(|| "claude-sonnet-4-5".to_string());
+    Some(
+        InputData::builder()
+            .model(model.clone(), model_display_name(&model))
+            .current_dir(current_dir)
+            .project_dir(current_dir)
+            .session_id(crate::utils::extract_session_id(&transcript))
+            .transcript_path(transcript.display().to_string())
+            .build(),
+    )
+}
+
+/// Name Claude Code would show for a model id, e.g. `Opus 4.1` for
+/// `claude-opus-4-1-20250805`; ids of other vendors are kept as they are
+fn model_display_name(id: &str) -> String {
+    let Some(rest) = id.strip_prefix("claude-") else {
+        return id.to_string();
+    };
+    let parts: Vec<&str> = rest.split('-').collect();
+    let family = parts
+        .iter()
+        .find(|part| part.chars().all(|c| c.is_ascii_alphabetic()));
+    let version: Vec<&str> = parts
+        .iter()
+        .copied()
+        .filter(|part| part.len() <= 2 && part.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    match family {
+        Some(family) if !version.is_empty() => {
+            let mut name = family.to_string();
+            name[..1].make_ascii_uppercase();
+            format!("{} {}", name, version.join("."))
+        }
+        _ => id.to_string(),
+    }
+}
+
+/// Model of the last assistant message in a transcript
+fn last_model(transcript: &Path) -> Option<String> {
+    let content = read_transcript_bytes(transcript).ok()?;
+    let model = lines_rev(content.as_bytes()).find_map(|line| {
+        memmem::find(line, b"\"assistant\"")?;
+        let entry: TranscriptEntry = serde_json::from_slice(line).ok()?;
+        if entry.r#type.as_deref() != Some("assistant") {
+            return None;
+        }
+        entry
+            .message?
+            .model
+            .filter(|model| !model.is_empty() && model != "<synthetic>")
+    });
+    model
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_input() {
+        let root = std::env::temp_dir().join(format!("ccline-infer-{}", std::process::id()));
+        let project = root.join("-work-app");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(
+            project.join("abc.jsonl"),
+            concat!(
+                r#"{"type":"assistant","message":{"model":"claude-opus-4-1","usage":{}}}"#,
+                "\n",
+                r#"{"type":"user","message":{"role":"user"}}"#,
+                "\n"
+            ),
+        )
+        .unwrap();
+
+        let input = infer_input("/work/app", std::slice::from_ref(&root)).unwrap();
+        assert_eq!(input.model.id, "claude-opus-4-1");
+        assert_eq!(input.session_id.as_deref(), Some("abc"));
+        assert_eq!(input.workspace.current_dir, "/work/app");
+        assert_eq!(input.model.display_name, "Opus 4.1");
+        assert!(infer_input("/work/other", std::slice::from_ref(&root)).is_none());
+
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            model_display_name("claude-3-5-sonnet-20241022"),
+            "Sonnet 3.5"
+        );
+        assert_eq!(
+            model_display_name("claude-sonnet-4-5-20250929"),
+            "Sonnet 4.5"
+        );
+        assert_eq!(model_display_name("gpt-5"), "gpt-5");
+    }
+}
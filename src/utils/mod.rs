@@ -1,10 +1,31 @@
+pub mod caches;
+pub mod codex;
+pub mod color;
+pub mod compaction;
+pub mod crash;
+pub mod data_dirs;
 pub mod data_loader;
 pub mod data_loader_fast;
 pub mod debug;
+pub mod format;
+pub mod gemini;
+pub mod home;
+pub mod index_state;
+pub mod last_input;
+pub mod profile;
+pub mod recording;
 pub mod runtime;
+pub mod sanitize;
+pub mod state_file;
+pub mod terminal;
+pub mod timezone;
 pub mod transcript;
+pub mod usage_cache;
+pub mod width;
 
 pub use data_loader::DataLoader;
 pub use data_loader_fast::FastDataLoader;
-pub use runtime::{block_on, GLOBAL_RUNTIME};
+pub use home::home_dir;
+pub use runtime::{block_on, ccline_exe, GLOBAL_RUNTIME};
+pub use sanitize::sanitize_text;
 pub use transcript::{extract_session_id, extract_usage_entry};
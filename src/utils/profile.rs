@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Profiles beyond this many are deleted, oldest first
+const MAX_PROFILES: usize = 20;
+
+/// Whether `--profile` or `CCLINE_PROFILE=1` asked for a profile of this run
+static PROFILING: AtomicBool = AtomicBool::new(false);
+
+/// Spans recorded since profiling started
+static SPANS: Lazy<Mutex<Vec<Span>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// One timed stage of a statusline run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Span {
+    /// `collect` and `render` per segment, `loader.*` for the usage data
+    /// loaders and `cost.*` for the Cost segment's steps
+    pub stage: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment: Option<String>,
+    pub duration_ms: f64,
+    /// The segment reused its cached output instead of collecting
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+}
+
+/// Everything recorded for one invocation, as written to disk
+#[derive(Debug, Clone, Serialize)]
+pub struct Profile {
+    pub version: String,
+    pub started_at: DateTime<Utc>,
+    pub total_ms: f64,
+    pub spans: Vec<Span>,
+}
+
+/// Whether `CCLINE_PROFILE` is set to something other than `0` or empty
+pub fn enabled_by_env() -> bool {
+    std::env::var("CCLINE_PROFILE").is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Start recording spans, dropping any earlier ones
+pub fn start() {
+    if let Ok(mut spans) = SPANS.lock() {
+        spans.clear();
+    }
+    PROFILING.store(true, Ordering::Relaxed);
+}
+
+pub fn is_profiling() -> bool {
+    PROFILING.load(Ordering::Relaxed)
+}
+
+/// Record a finished stage; costs a single atomic load when not profiling
+pub fn record(stage: &str, segment: Option<&str>, elapsed: Duration) {
+    record_span(stage, segment, elapsed, false);
+}
+
+/// Record a segment's collect stage, noting whether its cache answered
+pub fn record_collect(segment: &str, elapsed: Duration, cached: bool) {
+    record_span("collect", Some(segment), elapsed, cached);
+}
+
+fn record_span(stage: &str, segment: Option<&str>, elapsed: Duration, cached: bool) {
+    if !is_profiling() {
+        return;
+    }
+    if let Ok(mut spans) = SPANS.lock() {
+        spans.push(Span {
+            stage: stage.to_string(),
+            segment: segment.map(str::to_string),
+            duration_ms: elapsed.as_secs_f64() * 1000.0,
+            cached,
+        });
+    }
+}
+
+/// Run `f`, recording how long it took as `stage`
+pub fn time<T>(stage: &str, segment: Option<&str>, f: impl FnOnce() -> T) -> T {
+    if !is_profiling() {
+        return f();
+    }
+    let started = Instant::now();
+    let result = f();
+    record(stage, segment, started.elapsed());
+    result
+}
+
+/// Stop recording and bundle the spans into a profile
+pub fn finish(started_at: DateTime<Utc>, total: Duration) -> Profile {
+    PROFILING.store(false, Ordering::Relaxed);
+    let spans = SPANS
+        .lock()
+        .map(|mut spans| std::mem::take(&mut *spans))
+        .unwrap_or_default();
+    Profile {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        started_at,
+        total_ms: total.as_secs_f64() * 1000.0,
+        spans,
+    }
+}
+
+/// Write the profile to `~/.claude/ccline/profiles/profile-<ts>.json`
+pub fn write(profile: &Profile) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dir = crate::utils::home_dir()
+        .ok_or("Could not determine home directory")?
+        .join(".claude")
+        .join("ccline")
+        .join("profiles");
+    write_to(&dir, profile)
+}
+
+fn write_to(dir: &Path, profile: &Profile) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!(
+        "profile-{}.json",
+        profile
+            .started_at
+            .with_timezone(&chrono::Local)
+            .format("%Y%m%d-%H%M%S%.3f")
+    ));
+    std::fs::write(&path, serde_json::to_string_pretty(profile)?)?;
+    prune_profiles(dir);
+    Ok(path)
+}
+
+fn prune_profiles(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut profiles: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("profile-") && name.ends_with(".json"))
+        })
+        .collect();
+    // Timestamped names sort chronologically
+    profiles.sort();
+    let excess = profiles.len().saturating_sub(MAX_PROFILES);
+    for path in &profiles[..excess] {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_records_only_while_started() {
+        record("loader.before", None, Duration::from_millis(1));
+        start();
+        let value = time("loader.test", None, || 42);
+        record_collect("profile-test", Duration::from_millis(5), true);
+        let profile = finish(Utc::now(), Duration::from_millis(10));
+
+        assert_eq!(value, 42);
+        assert_eq!(profile.total_ms, 10.0);
+        let stages: Vec<&str> = profile.spans.iter().map(|s| s.stage.as_str()).collect();
+        assert!(stages.contains(&"loader.test"));
+        assert!(!stages.contains(&"loader.before"));
+        let collect = profile
+            .spans
+            .iter()
+            .find(|s| s.segment.as_deref() == Some("profile-test"))
+            .unwrap();
+        assert_eq!(collect.stage, "collect");
+        assert!(collect.cached);
+
+        let dir = std::env::temp_dir().join(format!("ccline-profile-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = write_to(&dir, &profile).unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(
+            written["spans"].as_array().unwrap().len(),
+            profile.spans.len()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
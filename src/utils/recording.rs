@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A statusline input saved by `ccline --record`, with the line it rendered,
+/// for bug reports and for iterating on themes with `ccline --replay`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    /// ccline version that made the recording
+    pub version: String,
+    pub recorded_at: DateTime<Utc>,
+    /// The JSON Claude Code sent on stdin, unknown fields included
+    pub input: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl Recording {
+    pub fn new(input: serde_json::Value, output: String) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            recorded_at: Utc::now(),
+            input,
+            output: Some(output),
+        }
+    }
+
+    /// Read a recording, or a bare input payload such as one copied from a
+    /// bug report
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        Self::parse(&content).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    fn parse(content: &str) -> Result<Self, serde_json::Error> {
+        let value: serde_json::Value = serde_json::from_str(content)?;
+        if value.get("input").is_some() && value.get("recorded_at").is_some() {
+            return serde_json::from_value(value);
+        }
+        Ok(Self {
+            version: String::new(),
+            recorded_at: Utc::now(),
+            input: value,
+            output: None,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_recording() {
+        let input = serde_json::json!({
+            "model": {"id": "claude-sonnet-4", "display_name": "Sonnet 4"},
+            "workspace": {"current_dir": "/work/app"},
+            "transcript_path": "",
+            "future_field": 1
+        });
+        let recording = Recording::new(input.clone(), "line".to_string());
+        let json = serde_json::to_string(&recording).unwrap();
+
+        let parsed = Recording::parse(&json).unwrap();
+        assert_eq!(parsed.input, input);
+        assert_eq!(parsed.output.as_deref(), Some("line"));
+
+        // A bare payload replays without an expected output
+        let bare = Recording::parse(&input.to_string()).unwrap();
+        assert_eq!(bare.input, input);
+        assert_eq!(bare.output, None);
+    }
+}
@@ -1,4 +1,5 @@
 use once_cell::sync::Lazy;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
@@ -15,6 +16,18 @@ where
     GLOBAL_RUNTIME.block_on(future)
 }
 
+/// The running executable when it is ccline itself
+///
+/// Background work re-runs the binary with a subcommand (`index`,
+/// `run-command`); a program embedding the library gets `None` and must do
+/// that work in-process instead of re-executing itself with arguments it
+/// doesn't understand.
+pub fn ccline_exe() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let name = exe.file_stem()?.to_str()?;
+    matches!(name, "ccline" | "ccometixline").then_some(exe)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,79 @@
+/// Make externally supplied text (model names, branch names, directory names) safe
+/// to print: escape sequences and control characters are removed, line breaks and
+/// tabs become single spaces, and bidirectional overrides are dropped
+pub fn sanitize_text(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut last_was_space = false;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\x1b' => skip_escape_sequence(&mut chars),
+            // C1 CSI introducer, equivalent to ESC [
+            '\u{9b}' => skip_csi_body(&mut chars),
+            '\r' | '\n' | '\t' => {
+                if !last_was_space {
+                    output.push(' ');
+                    last_was_space = true;
+                }
+                continue;
+            }
+            c if c.is_control() || is_bidi_control(c) => {}
+            c => output.push(c),
+        }
+        last_was_space = false;
+    }
+
+    output
+}
+
+fn skip_escape_sequence(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    match chars.next() {
+        Some('[') => skip_csi_body(chars),
+        // OSC, DCS, SOS, PM and APC run until BEL or ST (ESC \)
+        Some(']' | 'P' | 'X' | '^' | '_') => {
+            while let Some(c) = chars.next() {
+                if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                    break;
+                }
+            }
+        }
+        // Two-character sequences (ESC 7, ESC c, ...) are fully consumed already
+        _ => {}
+    }
+}
+
+fn skip_csi_body(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    for c in chars.by_ref() {
+        if ('\x40'..='\x7e').contains(&c) {
+            break;
+        }
+    }
+}
+
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_escape_sequences() {
+        assert_eq!(sanitize_text("main\x1b[31mred\x1b[0m"), "mainred");
+        assert_eq!(
+            sanitize_text("x\x1b]0;pwned\x07y\x1b]8;;http://a\x1b\\z"),
+            "xyz"
+        );
+        assert_eq!(sanitize_text("a\u{9b}2Jb"), "ab");
+    }
+
+    #[test]
+    fn test_normalizes_whitespace_and_controls() {
+        assert_eq!(sanitize_text("line1\r\nline2\tend"), "line1 line2 end");
+        assert_eq!(sanitize_text("bell\x07\x08\u{7f}"), "bell");
+        assert_eq!(sanitize_text("evil\u{202e}txt.exe"), "eviltxt.exe");
+        assert_eq!(sanitize_text("Sonnet 4 ✓"), "Sonnet 4 ✓");
+    }
+}
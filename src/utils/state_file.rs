@@ -0,0 +1,60 @@
+//! Small JSON state files kept in ~/.claude/ccline between runs
+
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+
+/// Path of a state file kept in ~/.claude/ccline
+pub fn path(file: &str) -> Option<PathBuf> {
+    crate::utils::home_dir().map(|home| home.join(".claude").join("ccline").join(file))
+}
+
+/// Read a JSON state file, falling back to the default when it is missing or
+/// can't be parsed
+pub fn load<T: DeserializeOwned + Default>(file: &str) -> T {
+    path(file)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Save a state file in ~/.claude/ccline
+pub fn save(file: &str, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let path = path(file).ok_or("Could not find home directory")?;
+    write_atomic(&path, content.as_bytes())
+}
+
+/// Write through a temporary file and rename it into place, so concurrent
+/// renders never read a partially written file
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let temp = path.with_extension(format!("tmp{}", std::process::id()));
+    std::fs::write(&temp, content)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = std::env::temp_dir().join(format!("ccline-state-file-{}", std::process::id()));
+        let path = dir.join("nested").join("state.json");
+
+        write_atomic(&path, b"{\"a\":1}").unwrap();
+        write_atomic(&path, b"{\"a\":2}").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"a\":2}");
+
+        // Only the target is left behind, no temporary files
+        let files: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(files, vec![std::ffi::OsString::from("state.json")]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,165 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Let the console interpret ANSI escape sequences, so colors render in
+/// Windows Terminal and conhost instead of showing as raw `←[...m` codes
+///
+/// Turns on virtual terminal processing for stdout and stderr on Windows;
+/// elsewhere terminals already understand ANSI and this does nothing. Fails
+/// quietly when the stream is not a console, e.g. when piped to Claude Code.
+#[cfg(windows)]
+pub fn enable_ansi_support() {
+    use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+    use windows_sys::Win32::System::Console::{
+        GetConsoleMode, GetStdHandle, SetConsoleMode, ENABLE_VIRTUAL_TERMINAL_PROCESSING,
+        STD_ERROR_HANDLE, STD_OUTPUT_HANDLE,
+    };
+
+    for stream in [STD_OUTPUT_HANDLE, STD_ERROR_HANDLE] {
+        // SAFETY: the handle comes straight from GetStdHandle and is checked
+        // before use; the mode pointer is a valid local
+        unsafe {
+            let handle = GetStdHandle(stream);
+            if handle.is_null() || handle == INVALID_HANDLE_VALUE {
+                continue;
+            }
+            let mut mode = 0;
+            if GetConsoleMode(handle, &mut mode) != 0 {
+                SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING);
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn enable_ansi_support() {}
+
+/// `--no-color` from the command line
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+
+pub fn set_no_color(no_color: bool) {
+    NO_COLOR.store(no_color, Ordering::Relaxed);
+}
+
+/// Whether color output is disabled, by `--no-color` or the `NO_COLOR`
+/// convention
+pub fn no_color() -> bool {
+    NO_COLOR.load(Ordering::Relaxed) || std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty())
+}
+
+/// How many colors the terminal rendering the statusline can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// The 16 basic ANSI colors
+    Ansi16,
+    /// The xterm 256-color palette
+    Ansi256,
+    /// 24-bit RGB
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Support of the current terminal, overridable with
+    /// `CCLINE_COLOR=truecolor|256|16`
+    ///
+    /// Claude Code runs the statusline with stdout piped, so this relies on
+    /// the environment the terminal sets rather than probing it.
+    pub fn detect() -> Self {
+        if let Some(support) = std::env::var("CCLINE_COLOR")
+            .ok()
+            .and_then(|name| Self::parse(&name))
+        {
+            return support;
+        }
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        if var("COLORTERM").is_some_and(|v| matches!(v.as_str(), "truecolor" | "24bit")) {
+            return ColorSupport::TrueColor;
+        }
+        // Windows Terminal, also when it hosts WSL with TERM=xterm-256color
+        if var("WT_SESSION").is_some() {
+            return ColorSupport::TrueColor;
+        }
+        match var("TERM_PROGRAM").as_deref() {
+            Some("iTerm.app" | "WezTerm" | "vscode" | "ghostty") => return ColorSupport::TrueColor,
+            // Terminal.app before macOS 26 tops out at 256 colors
+            Some("Apple_Terminal") => return ColorSupport::Ansi256,
+            _ => {}
+        }
+
+        match var("TERM") {
+            Some(term) if term.contains("truecolor") || term.contains("direct") => {
+                ColorSupport::TrueColor
+            }
+            Some(term) if term.ends_with("256color") => ColorSupport::Ansi256,
+            Some(term) if term == "dumb" || term == "linux" || term.starts_with("vt") => {
+                ColorSupport::Ansi16
+            }
+            // Windows consoles with virtual terminal processing take RGB, as
+            // do most terminals with an unrecognized TERM
+            _ => ColorSupport::TrueColor,
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" | "rgb" => Some(ColorSupport::TrueColor),
+            "256" | "256color" | "ansi256" => Some(ColorSupport::Ansi256),
+            "16" | "ansi" | "ansi16" => Some(ColorSupport::Ansi16),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorSupport::Ansi16 => "16 colors",
+            ColorSupport::Ansi256 => "256 colors",
+            ColorSupport::TrueColor => "truecolor",
+        }
+    }
+}
+
+/// [`ColorSupport::detect`], worked out once per process
+pub fn color_support() -> ColorSupport {
+    static SUPPORT: OnceLock<ColorSupport> = OnceLock::new();
+    *SUPPORT.get_or_init(ColorSupport::detect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_color_support() {
+        let detect = |vars: &[(&str, &str)]| {
+            ColorSupport::from_env(|name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        assert_eq!(
+            detect(&[("COLORTERM", "truecolor"), ("TERM", "xterm-256color")]),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), ColorSupport::Ansi256);
+        assert_eq!(detect(&[("TERM", "tmux-256color")]), ColorSupport::Ansi256);
+        assert_eq!(
+            detect(&[
+                ("TERM_PROGRAM", "Apple_Terminal"),
+                ("TERM", "xterm-256color")
+            ]),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(
+            detect(&[("WT_SESSION", "1"), ("TERM", "xterm-256color")]),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(detect(&[("TERM", "linux")]), ColorSupport::Ansi16);
+        assert_eq!(detect(&[("TERM", "xterm-direct")]), ColorSupport::TrueColor);
+        assert_eq!(ColorSupport::parse("256"), Some(ColorSupport::Ansi256));
+        assert_eq!(ColorSupport::parse("bogus"), None);
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+/// Timezone for day, month and block-override boundaries, set from the
+/// loaded config; `None` follows the system's local time
+static TIMEZONE: Lazy<RwLock<Option<FixedOffset>>> = Lazy::new(|| RwLock::new(None));
+
+/// `local`, `utc`, or a fixed offset such as `+09:00`; `local` gives `None`
+pub fn parse_timezone(value: &str) -> Result<Option<FixedOffset>, String> {
+    match value.trim().to_lowercase().as_str() {
+        "local" | "" => Ok(None),
+        "utc" | "z" => Ok(FixedOffset::east_opt(0)),
+        other => other.parse().map(Some).map_err(|_| {
+            format!(
+                "Invalid timezone '{}': use local, utc or an offset such as +09:00",
+                value
+            )
+        }),
+    }
+}
+
+/// Use the `timezone` setting from the user's config for subsequent date
+/// grouping; invalid values fall back to local time
+pub fn set_timezone(value: Option<&str>) {
+    let offset = match value.map(parse_timezone) {
+        Some(Ok(offset)) => offset,
+        Some(Err(e)) => {
+            crate::debug_println!("{}", e);
+            None
+        }
+        None => None,
+    };
+    if let Ok(mut timezone) = TIMEZONE.write() {
+        *timezone = offset;
+    }
+}
+
+/// The configured fixed offset, or `None` for local time
+pub fn configured() -> Option<FixedOffset> {
+    TIMEZONE.read().ok().and_then(|timezone| *timezone)
+}
+
+/// `time` in the configured timezone
+pub fn to_local(time: DateTime<Utc>) -> DateTime<FixedOffset> {
+    match configured() {
+        Some(offset) => time.with_timezone(&offset),
+        None => time.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+pub fn now() -> DateTime<FixedOffset> {
+    to_local(Utc::now())
+}
+
+/// Calendar day `time` falls on in the configured timezone
+pub fn date_of(time: DateTime<Utc>) -> NaiveDate {
+    to_local(time).date_naive()
+}
+
+pub fn today() -> NaiveDate {
+    now().date_naive()
+}
+
+/// Interpret a wall-clock time in the configured timezone; `None` when it
+/// does not exist there (skipped by a DST change)
+pub fn from_local(time: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match configured() {
+        Some(offset) => offset
+            .from_local_datetime(&time)
+            .single()
+            .map(|local| local.with_timezone(&Utc)),
+        None => Local
+            .from_local_datetime(&time)
+            .single()
+            .map(|local| local.with_timezone(&Utc)),
+    }
+}
+
+/// Label for the configured timezone: `local`, `UTC` or an offset such as
+/// `+09:00`
+pub fn label() -> String {
+    match configured() {
+        Some(offset) if offset.local_minus_utc() == 0 => "UTC".to_string(),
+        Some(offset) => offset.to_string(),
+        None => "local".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone("local"), Ok(None));
+        assert_eq!(parse_timezone("UTC"), Ok(FixedOffset::east_opt(0)));
+        assert_eq!(
+            parse_timezone("+09:00"),
+            Ok(FixedOffset::east_opt(9 * 3600))
+        );
+        assert_eq!(
+            parse_timezone("-05:30"),
+            Ok(FixedOffset::west_opt(5 * 3600 + 1800))
+        );
+        assert!(parse_timezone("Mars/Olympus").is_err());
+    }
+}
@@ -1,16 +1,267 @@
 use crate::billing::UsageEntry;
 use crate::config::{NormalizedUsage, TranscriptEntry};
+use crate::utils::usage_cache::ParsedUsage;
 use chrono::{DateTime, Utc};
+use memchr::memrchr;
+use memmap2::Mmap;
 use std::collections::HashSet;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// How a transcript file is stored, going by its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+/// File name suffixes of transcripts, plain and compressed
+const TRANSCRIPT_SUFFIXES: [(&str, Compression); 3] = [
+    (".jsonl", Compression::None),
+    (".jsonl.gz", Compression::Gzip),
+    (".jsonl.zst", Compression::Zstd),
+];
+
+/// Compression of a `.jsonl`, `.jsonl.gz` or `.jsonl.zst` transcript, or
+/// `None` for any other file
+pub fn transcript_compression(path: &Path) -> Option<Compression> {
+    let name = path.file_name()?.to_str()?;
+    TRANSCRIPT_SUFFIXES
+        .iter()
+        .find(|(suffix, _)| name.ends_with(suffix))
+        .map(|(_, compression)| *compression)
+}
+
+/// Open a transcript for reading, decompressing it on the fly if needed
+pub fn open_transcript(path: &Path, compression: Compression) -> std::io::Result<Box<dyn Read>> {
+    let file = std::fs::File::open(path)?;
+    Ok(match compression {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(file)?),
+    })
+}
+
+/// Read a whole transcript into memory, decompressing it if needed
+pub fn read_transcript(path: &Path, compression: Compression) -> std::io::Result<Vec<u8>> {
+    let mut content = Vec::new();
+    open_transcript(path, compression)?.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// A transcript's bytes: read into memory when small or compressed, and
+/// memory-mapped otherwise so only the pages that are looked at get read
+pub enum FileBuf {
+    Owned(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl FileBuf {
+    /// Get the underlying byte slice
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            FileBuf::Owned(v) => v,
+            FileBuf::Mapped(m) => m,
+        }
+    }
+}
+
+/// Read a transcript using the best strategy for its size and compression
+pub fn read_transcript_bytes(path: &Path) -> std::io::Result<FileBuf> {
+    // Compressed transcripts are inflated into memory
+    match transcript_compression(path) {
+        Some(Compression::None) | None => {}
+        Some(compression) => return Ok(FileBuf::Owned(read_transcript(path, compression)?)),
+    }
+
+    let file = std::fs::File::open(path)?;
+    // Small files: read directly into memory
+    if file.metadata()?.len() <= 64 * 1024 {
+        let mut content = Vec::new();
+        (&file).read_to_end(&mut content)?;
+        Ok(FileBuf::Owned(content))
+    } else {
+        // Large files: use memory mapping
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(FileBuf::Mapped(mmap))
+    }
+}
+
+/// Non-blank lines of `bytes` from last to first, without line endings
+pub fn lines_rev(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut rest = bytes;
+    std::iter::from_fn(move || {
+        while !rest.is_empty() {
+            let (head, line) = match memrchr(b'\n', rest) {
+                Some(newline) => (&rest[..newline], &rest[newline + 1..]),
+                None => (&rest[..0], rest),
+            };
+            rest = head;
+            let line = line.trim_ascii();
+            if !line.is_empty() {
+                return Some(line);
+            }
+        }
+        None
+    })
+}
+
+/// Complete lines within the last `max_bytes` of a plain transcript, oldest
+/// first; a line cut off by the limit is dropped
+pub fn read_tail_lines(path: &Path, max_bytes: u64) -> std::io::Result<Vec<String>> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)?;
+
+    let mut text = String::from_utf8_lossy(&content).into_owned();
+    if start > 0 {
+        text = text
+            .split_once('\n')
+            .map(|(_, rest)| rest.to_string())
+            .unwrap_or_default();
+    }
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
 
 /// Extract session ID from file path (the UUID part)
 pub fn extract_session_id(path: &std::path::Path) -> String {
-    path.file_stem()
-        .and_then(|s| s.to_str())
+    let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
+        return "unknown".to_string();
+    };
+    TRANSCRIPT_SUFFIXES
+        .iter()
+        .find_map(|(suffix, _)| name.strip_suffix(suffix))
+        .or_else(|| path.file_stem().and_then(|s| s.to_str()))
         .unwrap_or("unknown")
         .to_string()
 }
 
+/// Whether Claude Code named a transcript that hasn't been written yet, which
+/// happens before the first reply of a brand-new session
+///
+/// Unlike a transcript that exists but has no usage entries, this means the
+/// session really is at zero rather than that its usage couldn't be read.
+pub fn is_new_session(transcript_path: &str) -> bool {
+    !transcript_path.is_empty()
+        && transcript_path != "mock_preview"
+        && matches!(
+            std::fs::metadata(transcript_path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound
+        )
+}
+
+/// The session and project a transcript file belongs to, and whether a
+/// subagent wrote it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptSource {
+    pub session_id: String,
+    pub subagent: bool,
+    /// Name of the directory under `projects/` holding the session
+    pub project: String,
+}
+
+/// Directory name Claude Code stores a workspace's transcripts under: the
+/// path with every character other than letters and digits replaced by `-`
+pub fn project_key(workspace: &str) -> String {
+    workspace
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Classify a transcript file by its location
+///
+/// Subagent runs are written as `agent-*.jsonl`, either next to the session
+/// transcripts or under `<session-id>/subagents/`. In the latter layout the
+/// parent session is known from the path; otherwise it comes from the entries.
+pub fn transcript_source(path: &Path) -> TranscriptSource {
+    let stem = extract_session_id(path);
+    let in_subagents_dir = path
+        .parent()
+        .and_then(Path::file_name)
+        .is_some_and(|name| name == "subagents");
+    let subagent = in_subagents_dir || stem.starts_with("agent-");
+
+    let parent_session = in_subagents_dir
+        .then(|| path.parent()?.parent()?.file_name()?.to_str())
+        .flatten()
+        .map(str::to_string);
+
+    // `<project>/<session>.jsonl` or `<project>/<session>/subagents/agent-*.jsonl`
+    let project_dir = if in_subagents_dir {
+        path.ancestors().nth(3)
+    } else {
+        path.parent()
+    };
+    let project = project_dir
+        .and_then(Path::file_name)
+        .and_then(|name| name.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    TranscriptSource {
+        session_id: parent_session.unwrap_or(stem),
+        subagent,
+        project,
+    }
+}
+
+/// Tag usage with its project, flag usage from subagents and move it onto
+/// the session that launched them
+pub fn attribute_source(
+    usage: &mut UsageEntry,
+    entry: &TranscriptEntry,
+    source: &TranscriptSource,
+) {
+    usage.project = source.project.clone();
+    usage.subagent = source.subagent || entry.is_sidechain == Some(true);
+    if source.subagent {
+        if let Some(parent) = entry.session_id.as_deref().filter(|id| !id.is_empty()) {
+            usage.session_id = parent.to_string();
+        }
+    }
+}
+
+/// Usage of an assistant transcript line attributed to `source`, with the
+/// key to deduplicate it by
+pub fn parse_source_line(line: &[u8], source: &TranscriptSource) -> Option<ParsedUsage> {
+    let entry: TranscriptEntry = sonic_rs::from_slice(line).ok()?;
+
+    // Only process assistant messages with usage data
+    if entry.r#type.as_deref() != Some("assistant") {
+        return None;
+    }
+
+    let message = entry.message.as_ref()?;
+    let normalized = message.usage.clone()?.normalize();
+    let dedup_key = message
+        .id
+        .as_ref()
+        .zip(entry.request_id.as_ref())
+        .map(|(msg_id, req_id)| format!("{}:{}", msg_id, req_id));
+
+    let mut usage = extract_usage_entry(
+        &normalized,
+        &source.session_id,
+        entry.timestamp.as_deref(),
+        message.model.as_deref(),
+    )?;
+    attribute_source(&mut usage, &entry, source);
+    Some(ParsedUsage {
+        entry: usage,
+        dedup_key,
+    })
+}
+
 /// Parse a JSONL line and extract usage entry if valid
 pub fn parse_line_to_usage(
     line: &str,
@@ -46,7 +297,10 @@ pub fn parse_line_to_usage(
     let model = message.model.as_deref();
 
     // Convert to UsageEntry
-    extract_usage_entry(&normalized, session_id, entry.timestamp.as_deref(), model)
+    let mut usage =
+        extract_usage_entry(&normalized, session_id, entry.timestamp.as_deref(), model)?;
+    usage.subagent = entry.is_sidechain == Some(true);
+    Some(usage)
 }
 
 /// Convert NormalizedUsage to UsageEntry
@@ -68,19 +322,22 @@ pub fn extract_usage_entry(
 
     Some(UsageEntry {
         timestamp,
-        input_tokens: normalized.input_tokens,
-        output_tokens: normalized.output_tokens,
-        cache_creation_tokens: normalized.cache_creation_input_tokens,
-        cache_read_tokens: normalized.cache_read_input_tokens,
+        input_tokens: u64::from(normalized.input_tokens),
+        output_tokens: u64::from(normalized.output_tokens),
+        cache_creation_tokens: u64::from(normalized.cache_creation_input_tokens),
+        cache_read_tokens: u64::from(normalized.cache_read_input_tokens),
         model: model.unwrap_or("").to_string(),
         cost: None, // Will be calculated later with pricing data
         session_id: session_id.to_string(),
+        subagent: false,
+        project: String::new(),
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::RawUsage;
 
     #[test]
     fn test_extract_session_id() {
@@ -91,6 +348,112 @@ mod tests {
             extract_session_id(path),
             "c040b0ba-658d-4188-befa-0d2dad1f0ea5"
         );
+        assert_eq!(
+            extract_session_id(Path::new("/p/-app/c040b0ba.jsonl.zst")),
+            "c040b0ba"
+        );
+    }
+
+    #[test]
+    fn test_read_compressed_transcripts() {
+        use std::io::Write;
+
+        let dir = std::env::temp_dir().join(format!("ccline-compressed-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let content = b"{\"type\":\"user\"}\n{\"type\":\"assistant\"}\n";
+
+        let gz = dir.join("s1.jsonl.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(std::fs::File::create(&gz).unwrap(), Default::default());
+        encoder.write_all(content).unwrap();
+        encoder.finish().unwrap();
+        let zst = dir.join("s2.jsonl.zst");
+        std::fs::write(&zst, zstd::encode_all(&content[..], 3).unwrap()).unwrap();
+
+        assert_eq!(transcript_compression(&gz), Some(Compression::Gzip));
+        assert_eq!(transcript_compression(&zst), Some(Compression::Zstd));
+        assert_eq!(transcript_compression(Path::new("notes.txt")), None);
+        assert_eq!(read_transcript(&gz, Compression::Gzip).unwrap(), content);
+        assert_eq!(read_transcript(&zst, Compression::Zstd).unwrap(), content);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_tail_lines() {
+        let path = std::env::temp_dir().join(format!("ccline-tail-{}.jsonl", std::process::id()));
+        std::fs::write(&path, "first line\nsecond\n\nthird\n").unwrap();
+
+        assert_eq!(
+            read_tail_lines(&path, 1024).unwrap(),
+            vec!["first line", "second", "third"]
+        );
+        // The cut-off "line" of "first line" is dropped
+        assert_eq!(read_tail_lines(&path, 18).unwrap(), vec!["second", "third"]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_lines_rev() {
+        let lines: Vec<&[u8]> = lines_rev(b"first\nsecond\r\n\n  \nthird\n").collect();
+        assert_eq!(lines, [&b"third"[..], b"second", b"first"]);
+        assert_eq!(lines_rev(b"only").collect::<Vec<_>>(), [&b"only"[..]]);
+        assert_eq!(lines_rev(b"\n\n").count(), 0);
+    }
+
+    #[test]
+    fn test_is_new_session() {
+        let dir = std::env::temp_dir().join(format!("ccline-new-session-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("existing.jsonl");
+        std::fs::write(&existing, "").unwrap();
+
+        assert!(is_new_session(&dir.join("missing.jsonl").to_string_lossy()));
+        assert!(!is_new_session(&existing.to_string_lossy()));
+        assert!(!is_new_session(""));
+        assert!(!is_new_session("mock_preview"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_transcript_source() {
+        let main = transcript_source(Path::new("/p/-app/c040b0ba.jsonl"));
+        assert_eq!(main.session_id, "c040b0ba");
+        assert!(!main.subagent);
+
+        let nested = transcript_source(Path::new("/p/-app/c040b0ba/subagents/agent-1f2e.jsonl"));
+        assert_eq!(nested.session_id, "c040b0ba");
+        assert!(nested.subagent);
+        assert_eq!(main.project, "-app");
+        assert_eq!(nested.project, "-app");
+        assert_eq!(project_key("/home/me/my.app"), "-home-me-my-app");
+
+        let sidecar = transcript_source(Path::new("/p/-app/agent-1f2e.jsonl"));
+        assert_eq!(sidecar.session_id, "agent-1f2e");
+        assert!(sidecar.subagent);
+
+        // Sidecar files name their parent session in each entry
+        let entry: TranscriptEntry = serde_json::from_str(
+            r#"{"type":"assistant","sessionId":"c040b0ba","isSidechain":true}"#,
+        )
+        .unwrap();
+        let normalized = NormalizedUsage {
+            input_tokens: 1,
+            output_tokens: 1,
+            total_tokens: 2,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+            calculation_source: "test".to_string(),
+            raw_data_available: vec![],
+        };
+        let mut usage = extract_usage_entry(&normalized, &sidecar.session_id, None, None).unwrap();
+        attribute_source(&mut usage, &entry, &sidecar);
+        assert_eq!(usage.session_id, "c040b0ba");
+        assert!(usage.subagent);
+        assert_eq!(usage.project, "-app");
     }
 
     #[test]
@@ -116,4 +479,22 @@ mod tests {
         assert_eq!(entry.model, "claude-3-5-sonnet");
         assert!(entry.cost.is_none());
     }
+
+    #[test]
+    fn test_oversized_usage_saturates() {
+        let raw: RawUsage = serde_json::from_str(
+            r#"{"input_tokens": 5000000000, "output_tokens": 10, "cache_read_input_tokens": 4294967295}"#,
+        )
+        .unwrap();
+        let normalized = raw.normalize();
+        assert_eq!(normalized.input_tokens, u32::MAX);
+        assert_eq!(normalized.total_tokens, u32::MAX);
+        assert_eq!(normalized.context_tokens(), u32::MAX);
+
+        // Entries add up past u32::MAX without wrapping
+        let entry = extract_usage_entry(&normalized, "s", None, None).unwrap();
+        let blocks = crate::billing::block::identify_session_blocks(&[entry.clone(), entry]);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].total_tokens, 4 * u32::MAX as u64 + 20);
+    }
 }
@@ -0,0 +1,238 @@
+use crate::billing::UsageEntry;
+use crate::utils::state_file;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Bump when `UsageEntry` or how transcript lines are parsed changes, so
+/// entries parsed the old way are not reused
+const CACHE_VERSION: u32 = 1;
+
+/// A usage entry parsed from a transcript line, before deduplication
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedUsage {
+    pub entry: UsageEntry,
+    /// `message_id:request_id`, when the message has both
+    pub dedup_key: Option<String>,
+}
+
+/// The entries of one transcript, or a value derived from it, as of its
+/// length and modification time; `E` is borrowed for writing and owned for
+/// reading
+#[derive(Serialize, Deserialize)]
+struct CachedFile<E> {
+    version: u32,
+    path: PathBuf,
+    len: u64,
+    modified: SystemTime,
+    entries: E,
+}
+
+/// Directory of the parsed usage cache
+/// (~/.claude/ccline/cache/usage/<hash of the transcript path>.bin)
+///
+/// Each file holds a transcript's parsed entries (or its compaction history)
+/// in bincode and is reused while the transcript's length and modification
+/// time are unchanged, so repeat renders only parse the transcripts that grew.
+///
+/// `CCLINE_USAGE_CACHE_DIR` moves the cache elsewhere; unit tests use a
+/// directory under the system temp dir so they never write into the real
+/// home.
+pub fn dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("CCLINE_USAGE_CACHE_DIR").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    if cfg!(test) {
+        return Some(std::env::temp_dir().join("ccline-test-usage-cache"));
+    }
+    crate::utils::home_dir().map(|home| {
+        home.join(".claude")
+            .join("ccline")
+            .join("cache")
+            .join("usage")
+    })
+}
+
+/// `<hash>.bin` for the parsed entries, `<hash>.<kind>.bin` for other values
+/// derived from the same transcript
+fn cache_path(path: &Path, kind: Option<&str>) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    let name = match kind {
+        Some(kind) => format!("{:016x}.{}.bin", hasher.finish(), kind),
+        None => format!("{:016x}.bin", hasher.finish()),
+    };
+    dir().map(|dir| dir.join(name))
+}
+
+/// The entries of the transcript at `path`: from the cache while the file is
+/// unchanged, otherwise parsed with `parse` and cached for the next load
+pub fn load_or_parse(
+    path: &Path,
+    parse: impl FnOnce() -> io::Result<Vec<ParsedUsage>>,
+) -> io::Result<Vec<ParsedUsage>> {
+    cached_or(path, None, parse)
+}
+
+/// A value computed from the whole transcript at `path`, like its compaction
+/// history, cached next to its entries under the same rules
+pub fn load_or_compute<T: Serialize + DeserializeOwned>(
+    path: &Path,
+    kind: &str,
+    compute: impl FnOnce() -> io::Result<T>,
+) -> io::Result<T> {
+    cached_or(path, Some(kind), compute)
+}
+
+fn cached_or<T: Serialize + DeserializeOwned>(
+    path: &Path,
+    kind: Option<&str>,
+    compute: impl FnOnce() -> io::Result<T>,
+) -> io::Result<T> {
+    let stamp = std::fs::metadata(path)
+        .ok()
+        .and_then(|meta| Some((meta.len(), meta.modified().ok()?)));
+    let cache_file = cache_path(path, kind);
+
+    if let (Some((len, modified)), Some(cache_file)) = (stamp, &cache_file) {
+        if let Some(value) = read(cache_file, path, len, modified) {
+            return Ok(value);
+        }
+    }
+
+    let value = compute()?;
+    if let (Some((len, modified)), Some(cache_file)) = (stamp, &cache_file) {
+        let cached = CachedFile {
+            version: CACHE_VERSION,
+            path: path.to_path_buf(),
+            len,
+            modified,
+            entries: &value,
+        };
+        if let Err(e) = write(cache_file, &cached) {
+            crate::debug_println!("Could not cache usage of {}: {}", path.display(), e);
+        }
+    }
+    Ok(value)
+}
+
+fn read<T: DeserializeOwned>(
+    cache_file: &Path,
+    path: &Path,
+    len: u64,
+    modified: SystemTime,
+) -> Option<T> {
+    let bytes = std::fs::read(cache_file).ok()?;
+    let cached: CachedFile<T> = bincode::deserialize(&bytes).ok()?;
+    (cached.version == CACHE_VERSION
+        && cached.path == path
+        && cached.len == len
+        && cached.modified == modified)
+        .then_some(cached.entries)
+}
+
+fn write<T: Serialize>(
+    cache_file: &Path,
+    cached: &CachedFile<&T>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    state_file::write_atomic(cache_file, &bincode::serialize(cached)?)
+}
+
+/// The leading fields of a cache file, enough to tell whether it can still be
+/// used
+#[derive(Deserialize)]
+struct CachedHeader {
+    version: u32,
+    path: PathBuf,
+}
+
+/// Delete cached usage of transcripts that no longer exist or written by a
+/// different cache version, returning how many files were removed
+pub fn prune() -> io::Result<usize> {
+    let Some(dir) = dir().filter(|dir| dir.exists()) else {
+        return Ok(0);
+    };
+    let mut removed = 0;
+    for entry in std::fs::read_dir(&dir)?.flatten() {
+        let header = std::fs::read(entry.path())
+            .ok()
+            .and_then(|bytes| bincode::deserialize::<CachedHeader>(&bytes).ok());
+        let usable =
+            header.is_some_and(|header| header.version == CACHE_VERSION && header.path.exists());
+        if !usable && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Delete all cached usage, returning how many files were removed
+pub fn clear() -> io::Result<usize> {
+    let Some(dir) = dir().filter(|dir| dir.exists()) else {
+        return Ok(0);
+    };
+    let removed = std::fs::read_dir(&dir)?.flatten().count();
+    std::fs::remove_dir_all(&dir)?;
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_cached_file_round_trip() {
+        let entries = vec![ParsedUsage {
+            entry: UsageEntry {
+                timestamp: Utc::now(),
+                input_tokens: 10,
+                output_tokens: 20,
+                cache_creation_tokens: 0,
+                cache_read_tokens: 5,
+                model: "claude-sonnet-4".to_string(),
+                cost: None,
+                session_id: "s1".to_string(),
+                subagent: false,
+                project: "-home-me-app".to_string(),
+            },
+            dedup_key: Some("msg_1:req_1".to_string()),
+        }];
+        let dir = std::env::temp_dir().join(format!("ccline-usage-cache-{}", std::process::id()));
+        let cache_file = dir.join("entry.bin");
+        let path = Path::new("/projects/app/s1.jsonl");
+        let modified = SystemTime::now();
+        let cached = CachedFile {
+            version: CACHE_VERSION,
+            path: path.to_path_buf(),
+            len: 42,
+            modified,
+            entries: &entries,
+        };
+        write(&cache_file, &cached).unwrap();
+
+        let read_back: Vec<ParsedUsage> = read(&cache_file, path, 42, modified).unwrap();
+        assert_eq!(read_back.len(), 1);
+        assert_eq!(read_back[0].entry.output_tokens, 20);
+        assert_eq!(read_back[0].dedup_key.as_deref(), Some("msg_1:req_1"));
+        // The transcript grew, or a different transcript hashed to this file
+        assert!(read::<Vec<ParsedUsage>>(&cache_file, path, 43, modified).is_none());
+        assert!(read::<Vec<ParsedUsage>>(
+            &cache_file,
+            Path::new("/projects/app/s2.jsonl"),
+            42,
+            modified
+        )
+        .is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache_dir_outside_home() {
+        assert!(super::dir().unwrap().starts_with(std::env::temp_dir()));
+    }
+}
@@ -0,0 +1,76 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Remove ANSI escape sequences, leaving only the visible text
+///
+/// Handles CSI sequences (`\x1b[...m` colors and styles), OSC sequences such
+/// as `\x1b]8;;url\x1b\\` hyperlinks ended by BEL or ST, and two-character
+/// escapes.
+pub fn strip_ansi(text: &str) -> String {
+    let mut visible = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            visible.push(ch);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates up to a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: until BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' {
+                        chars.next_if_eq(&'\\');
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    visible
+}
+
+/// Terminal columns taken by `text` once escape sequences are removed, with
+/// wide characters (CJK, emoji) counting as two columns
+pub fn display_width(text: &str) -> usize {
+    if text.contains('\x1b') {
+        strip_ansi(text).width()
+    } else {
+        text.width()
+    }
+}
+
+/// Columns taken by a single character, zero for control characters
+pub fn char_width(ch: char) -> usize {
+    ch.width().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("\x1b[1;38;5;14mSonnet 4\x1b[0m"), 8);
+        assert_eq!(display_width("项目"), 4);
+        assert_eq!(display_width("🤖 ok"), 5);
+        // Nerd Font glyphs are private use characters drawn one column wide
+        assert_eq!(display_width("\u{f0c9} 42%"), 5);
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07 \x1b[2Kdone"),
+            "link done"
+        );
+    }
+}
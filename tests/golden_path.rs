@@ -0,0 +1,26 @@
+use ccometixline::selftest::run_checks;
+use std::path::Path;
+
+#[test]
+fn golden_path_checks_pass() {
+    let root = std::env::temp_dir().join(format!("ccline-golden-path-{}", std::process::id()));
+    let results = run_checks(Path::new(env!("CARGO_BIN_EXE_ccometixline")), &root);
+    let _ = std::fs::remove_dir_all(&root);
+
+    let failures: Vec<String> = results
+        .iter()
+        .filter_map(|check| {
+            check
+                .result
+                .as_ref()
+                .err()
+                .map(|reason| format!("{}: {}", check.name, reason))
+        })
+        .collect();
+    assert!(results.len() >= 4);
+    assert!(
+        failures.is_empty(),
+        "failed checks:\n{}",
+        failures.join("\n")
+    );
+}